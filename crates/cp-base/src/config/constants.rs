@@ -11,13 +11,32 @@ pub const API_ENDPOINT: &str = "https://api.anthropic.com/v1/messages";
 /// Anthropic API version
 pub const API_VERSION: &str = "2023-06-01";
 
+/// Smallest extended-thinking budget the API accepts (below this, thinking is off)
+pub const THINKING_BUDGET_MIN: u32 = 1_024;
+
+/// Thinking budget step for the config overlay bar
+pub const THINKING_BUDGET_STEP: u32 = 4_096;
+
+/// Largest thinking budget selectable from the config overlay
+pub const THINKING_BUDGET_MAX: u32 = 65_536;
+
 // =============================================================================
 // CONTEXT & TOKEN MANAGEMENT
 // =============================================================================
 
-/// Average characters per token for token estimation
+/// Average characters per token (heuristic tokenizer only)
 pub const CHARS_PER_TOKEN: f32 = 3.3;
 
+/// Weight of each API-reported prompt size in the tokenizer calibration
+pub const TOKENIZER_CALIBRATION_RATE: f32 = 0.3;
+
+/// Prompts smaller than this (as reported by the API) don't update the calibration
+pub const TOKENIZER_CALIBRATION_MIN_TOKENS: usize = 1_000;
+
+/// Bounds of the tokenizer calibration factor
+pub const TOKENIZER_CALIBRATION_MIN: f32 = 0.5;
+pub const TOKENIZER_CALIBRATION_MAX: f32 = 2.0;
+
 /// Minimum active messages in a chunk before it can be detached.
 pub const DETACH_CHUNK_MIN_MESSAGES: usize = 25;
 
@@ -91,6 +110,13 @@ pub const PERF_STATS_REFRESH_MS: u64 = 500;
 /// Maximum number of retries for API errors
 pub const MAX_API_RETRIES: u32 = 3;
 
+/// Share of a provider's rate-limit budget kept for the main worker; reveries are
+/// deferred while less than this is left
+pub const RATE_LIMIT_RESERVE: f64 = 0.1;
+
+/// How long a rate-limit report without a reset time is trusted, in ms
+pub const RATE_LIMIT_STALE_MS: u64 = 60_000;
+
 // =============================================================================
 // PERSISTENCE
 // =============================================================================
@@ -123,9 +149,35 @@ pub const PANEL_MAX_LOAD_BYTES: usize = 5 * 1024 * 1024; // 5 MB
 /// Tokens per page when paginating (also serves as the soft cap — panels exceeding this get paginated)
 pub const PANEL_PAGE_TOKENS: usize = 25_000;
 
+/// Default per-type token budgets (editable in the config overlay, persisted in config.json)
+pub const DEFAULT_PANEL_BUDGETS: &[(&str, usize)] =
+    &[("console", 8_000), ("git_result", 10_000), ("github_result", 10_000)];
+
+/// Panel types listed in the config overlay's budget editor
+pub const BUDGETED_PANEL_TYPES: &[&str] =
+    &["console", "git_result", "github_result", "file", "tree", "brave_result", "firecrawl_result"];
+
+/// Panel types whose over-budget content keeps its tail (newest output) instead of being paginated
+pub const TAIL_BUDGET_PANEL_TYPES: &[&str] = &["console"];
+
+/// Panel metadata key overriding its type's token budget
+pub const PANEL_BUDGET_META: &str = "token_budget";
+
+/// Step when adjusting a panel budget in the config overlay (tokens)
+pub const PANEL_BUDGET_STEP: usize = 2_000;
+
 /// Maximum size for command output cached in result panels (bytes)
 pub const MAX_RESULT_CONTENT_BYTES: usize = 1_000_000; // 1 MB
 
+/// Largest image file that can be opened as vision input (API limit per image)
+pub const IMAGE_MAX_BYTES: usize = 5 * 1024 * 1024; // 5 MB
+
+/// Images are downscaled by the API so the long edge fits this many pixels
+pub const IMAGE_MAX_EDGE_PX: usize = 1568;
+
+/// Approximate pixels per vision token (width × height / 750)
+pub const IMAGE_PIXELS_PER_TOKEN: usize = 750;
+
 // =============================================================================
 // SHARED DIRECTORY (version-controlled part of .context-pilot)
 // =============================================================================
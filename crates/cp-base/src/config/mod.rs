@@ -1,4 +1,4 @@
-//! YAML configuration loader for prompts, icons, and UI strings.
+//! YAML configuration loader for prompts, icons, UI strings, and the model registry.
 use std::sync::LazyLock;
 
 use serde::Deserialize;
@@ -162,6 +162,7 @@ fn parse_yaml<T: for<'de> Deserialize<'de>>(name: &str, content: &str) -> T {
 // ============================================================================
 
 pub mod constants;
+pub mod models;
 
 pub static PROMPTS: LazyLock<PromptsConfig> =
     LazyLock::new(|| parse_yaml("prompts.yaml", include_str!("../../../../yamls/prompts.yaml")));
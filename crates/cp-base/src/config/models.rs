@@ -0,0 +1,340 @@
+//! Model registry loaded from `yamls/models.yaml`.
+//!
+//! The embedded file is the baseline; `.context-pilot/models.yaml` in the
+//! project is deep-merged over it, so prices, context windows, aliases and
+//! entirely new models can be changed without recompiling.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use serde::Deserialize;
+use serde_yaml::{Mapping, Value};
+
+use crate::llm_types::{LlmProvider, ModelInfo};
+
+/// Per-project override file, relative to the working directory
+pub const MODELS_OVERRIDE_PATH: &str = "./.context-pilot/models.yaml";
+
+/// One model entry. `id` is the registry key (the value persisted in config.json).
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModelSpec {
+    #[serde(skip)]
+    pub id: String,
+    pub api_name: String,
+    pub display_name: String,
+    pub context_window: usize,
+    pub input_price: f32,
+    pub output_price: f32,
+    #[serde(default)]
+    pub cache_hit_price: Option<f32>,
+    #[serde(default)]
+    pub cache_miss_price: Option<f32>,
+    pub max_output_tokens: u32,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Accepts image input. OpenAI-compatible providers only send images to
+    /// these models; the others see the image panel's text description.
+    #[serde(default)]
+    pub vision: bool,
+}
+
+impl ModelInfo for ModelSpec {
+    fn api_name(&self) -> &str {
+        &self.api_name
+    }
+
+    fn display_name(&self) -> &str {
+        &self.display_name
+    }
+
+    fn context_window(&self) -> usize {
+        self.context_window
+    }
+
+    fn input_price_per_mtok(&self) -> f32 {
+        self.input_price
+    }
+
+    fn output_price_per_mtok(&self) -> f32 {
+        self.output_price
+    }
+
+    fn cache_hit_price_per_mtok(&self) -> f32 {
+        self.cache_hit_price.unwrap_or(self.input_price * 0.1)
+    }
+
+    fn cache_miss_price_per_mtok(&self) -> f32 {
+        self.cache_miss_price.unwrap_or(self.input_price * 1.25)
+    }
+
+    fn max_output_tokens(&self) -> u32 {
+        self.max_output_tokens
+    }
+}
+
+/// Models offered by one provider, in display order
+#[derive(Debug, Clone, Default)]
+pub struct ProviderModels {
+    /// Model selected when nothing (or an unknown ID) is persisted
+    pub default: String,
+    /// Default for the secondary (reverie) model
+    pub secondary_default: String,
+    pub models: Vec<ModelSpec>,
+}
+
+/// One step of the failover chain: a provider/model pair to re-send a failed request to
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Fallback {
+    pub provider: LlmProvider,
+    /// Registry ID, alias or API name (local models: the ID the server reports)
+    pub model: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawRegistry {
+    providers: HashMap<String, RawProvider>,
+    #[serde(default)]
+    fallbacks: Vec<Fallback>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawProvider {
+    #[serde(default)]
+    default: Option<String>,
+    #[serde(default)]
+    secondary_default: Option<String>,
+    /// Kept as a mapping to preserve declaration order
+    #[serde(default)]
+    models: Mapping,
+}
+
+/// All known providers and models
+#[derive(Debug, Default)]
+pub struct ModelRegistry {
+    providers: HashMap<String, ProviderModels>,
+    /// Failover chain for the main worker, tried in order once retries are exhausted
+    pub fallbacks: Vec<Fallback>,
+    /// Why the per-project override was ignored, if it was
+    pub override_error: Option<String>,
+}
+
+/// Registry section a provider reads its models from.
+/// Claude Code variants serve the Anthropic models.
+pub fn registry_key(provider: LlmProvider) -> &'static str {
+    match provider {
+        LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey => "anthropic",
+        LlmProvider::Grok => "grok",
+        LlmProvider::Groq => "groq",
+        LlmProvider::DeepSeek => "deepseek",
+        LlmProvider::OpenAi => "openai",
+        LlmProvider::Local => "local",
+        LlmProvider::Replay => "replay",
+    }
+}
+
+impl ModelRegistry {
+    /// Parse a registry, deep-merging `overlay` (if any) over `base`.
+    pub fn from_yaml(base: &str, overlay: Option<&str>) -> Result<Self, String> {
+        let mut value: Value = serde_yaml::from_str(base).map_err(|e| e.to_string())?;
+        if let Some(overlay) = overlay {
+            let overlay: Value = serde_yaml::from_str(overlay).map_err(|e| e.to_string())?;
+            merge_yaml(&mut value, overlay);
+        }
+        let raw: RawRegistry = serde_yaml::from_value(value).map_err(|e| e.to_string())?;
+
+        let mut providers = HashMap::new();
+        for (key, raw_provider) in raw.providers {
+            let mut models = Vec::with_capacity(raw_provider.models.len());
+            for (id, spec) in raw_provider.models {
+                let id = id.as_str().ok_or_else(|| format!("{}: model keys must be strings", key))?.to_string();
+                let mut spec: ModelSpec = serde_yaml::from_value(spec).map_err(|e| format!("{}.{}: {}", key, id, e))?;
+                spec.id = id;
+                models.push(spec);
+            }
+            let default = raw_provider
+                .default
+                .filter(|d| models.iter().any(|m| &m.id == d))
+                .or_else(|| models.first().map(|m| m.id.clone()))
+                .unwrap_or_default();
+            let secondary_default = raw_provider
+                .secondary_default
+                .filter(|d| models.iter().any(|m| &m.id == d))
+                .unwrap_or_else(|| default.clone());
+            providers.insert(key, ProviderModels { default, secondary_default, models });
+        }
+        Ok(Self { providers, fallbacks: raw.fallbacks, override_error: None })
+    }
+
+    /// Load the embedded registry plus the project override.
+    /// A broken override is reported via `override_error` instead of aborting startup.
+    fn load() -> Self {
+        let base = include_str!("../../../../yamls/models.yaml");
+        let overlay = std::fs::read_to_string(MODELS_OVERRIDE_PATH).ok();
+        match overlay {
+            Some(overlay) => Self::from_yaml(base, Some(&overlay)).unwrap_or_else(|e| {
+                let mut registry = Self::from_yaml(base, None).expect("Failed to parse models.yaml");
+                registry.override_error = Some(format!("{}: {}", MODELS_OVERRIDE_PATH, e));
+                registry
+            }),
+            None => Self::from_yaml(base, None).expect("Failed to parse models.yaml"),
+        }
+    }
+
+    /// Models of a provider, in display order
+    pub fn models(&self, provider: LlmProvider) -> &[ModelSpec] {
+        self.providers.get(registry_key(provider)).map(|p| p.models.as_slice()).unwrap_or(&[])
+    }
+
+    /// Exact lookup by registry ID, API name, or alias
+    pub fn get(&self, provider: LlmProvider, id: &str) -> Option<&ModelSpec> {
+        let models = self.models(provider);
+        models
+            .iter()
+            .find(|m| m.id == id)
+            .or_else(|| models.iter().find(|m| m.api_name == id || m.aliases.iter().any(|a| a == id)))
+    }
+
+    /// Lookup that falls back to the provider's default model (for stale persisted IDs)
+    pub fn resolve(&self, provider: LlmProvider, id: &str) -> Option<&ModelSpec> {
+        self.get(provider, id).or_else(|| self.get(provider, self.default_id(provider)))
+    }
+
+    /// Default model ID for a provider (empty if it has none, e.g. Local)
+    pub fn default_id(&self, provider: LlmProvider) -> &str {
+        self.providers.get(registry_key(provider)).map(|p| p.default.as_str()).unwrap_or("")
+    }
+
+    /// Default secondary (reverie) model ID for a provider
+    pub fn secondary_default_id(&self, provider: LlmProvider) -> &str {
+        self.providers.get(registry_key(provider)).map(|p| p.secondary_default.as_str()).unwrap_or("")
+    }
+
+    /// Map any known name (ID, alias, or API name) to the API model identifier.
+    /// Unknown names pass through unchanged.
+    pub fn canonical_api_name<'a>(&'a self, provider: LlmProvider, name: &'a str) -> &'a str {
+        self.get(provider, name).map(|m| m.api_name.as_str()).unwrap_or(name)
+    }
+}
+
+/// Recursively merge `overlay` into `base`: mappings merge key by key, anything else replaces.
+fn merge_yaml(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Mapping(base), Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_yaml(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Global model registry (embedded YAML + project override, loaded on first use)
+pub static MODELS: LazyLock<ModelRegistry> = LazyLock::new(ModelRegistry::load);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = include_str!("../../../../yamls/models.yaml");
+
+    #[test]
+    fn embedded_registry_parses() {
+        let registry = ModelRegistry::from_yaml(BASE, None).unwrap();
+        for provider in [LlmProvider::Anthropic, LlmProvider::Grok, LlmProvider::Groq, LlmProvider::DeepSeek] {
+            assert!(!registry.models(provider).is_empty());
+            assert!(registry.get(provider, registry.default_id(provider)).is_some());
+        }
+        assert!(registry.models(LlmProvider::Local).is_empty());
+        assert_eq!(registry.secondary_default_id(LlmProvider::Anthropic), "claude-haiku45");
+        assert!(registry.get(LlmProvider::OpenAi, "gpt-4o").is_some_and(|m| m.vision));
+        assert!(!registry.get(LlmProvider::DeepSeek, "deepseek-chat").is_some_and(|m| m.vision));
+    }
+
+    #[test]
+    fn legacy_persisted_ids_resolve() {
+        // IDs written by the former enum-based config must keep loading
+        let registry = ModelRegistry::from_yaml(BASE, None).unwrap();
+        let cases = [
+            (LlmProvider::Anthropic, "claude-opus45", "claude-opus-4-6"),
+            (LlmProvider::ClaudeCode, "claude-sonnet45", "claude-sonnet-4-5-20250929"),
+            (LlmProvider::Grok, "grok41-fast", "grok-4-1-fast"),
+            (LlmProvider::Groq, "llama33-70b", "llama-3.3-70b-versatile"),
+            (LlmProvider::DeepSeek, "deepseek-reasoner", "deepseek-reasoner"),
+            (LlmProvider::OpenAi, "o4-mini", "o4-mini"),
+        ];
+        for (provider, id, api_name) in cases {
+            assert_eq!(registry.get(provider, id).map(|m| m.api_name.as_str()), Some(api_name), "{}", id);
+        }
+    }
+
+    #[test]
+    fn aliases_map_to_api_name() {
+        let registry = ModelRegistry::from_yaml(BASE, None).unwrap();
+        assert_eq!(registry.canonical_api_name(LlmProvider::ClaudeCode, "claude-opus-4-5"), "claude-opus-4-6");
+        assert_eq!(
+            registry.canonical_api_name(LlmProvider::ClaudeCode, "claude-haiku-4-5"),
+            "claude-haiku-4-5-20251001"
+        );
+        assert_eq!(registry.canonical_api_name(LlmProvider::ClaudeCode, "unknown-model"), "unknown-model");
+    }
+
+    #[test]
+    fn override_merges_fields_and_adds_models() {
+        let overlay = r#"
+providers:
+  anthropic:
+    models:
+      claude-sonnet45:
+        input_price: 2.5
+      claude-next:
+        api_name: claude-next-1
+        display_name: Next
+        context_window: 400000
+        input_price: 4.0
+        output_price: 20.0
+        max_output_tokens: 64000
+"#;
+        let registry = ModelRegistry::from_yaml(BASE, Some(overlay)).unwrap();
+        let sonnet = registry.get(LlmProvider::Anthropic, "claude-sonnet45").unwrap();
+        assert_eq!(sonnet.input_price, 2.5);
+        assert_eq!(sonnet.output_price, 15.0);
+        let models = registry.models(LlmProvider::Anthropic);
+        assert_eq!(models.last().map(|m| m.id.as_str()), Some("claude-next"));
+        assert_eq!(models[0].id, "claude-opus45");
+        // Unset cache prices fall back to the ModelInfo defaults
+        let next = registry.get(LlmProvider::Anthropic, "claude-next-1").unwrap();
+        assert!((next.cache_hit_price_per_mtok() - 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn unknown_id_resolves_to_default() {
+        let registry = ModelRegistry::from_yaml(BASE, None).unwrap();
+        assert_eq!(registry.resolve(LlmProvider::Grok, "grok-0").map(|m| m.id.as_str()), Some("grok41-fast"));
+        assert!(registry.resolve(LlmProvider::Local, "llama3").is_none());
+    }
+
+    #[test]
+    fn override_replaces_failover_chain() {
+        assert!(ModelRegistry::from_yaml(BASE, None).unwrap().fallbacks.is_empty());
+        let overlay = r#"
+fallbacks:
+  - provider: deepseek
+    model: deepseek-chat
+  - provider: local
+    model: qwen2.5-coder
+"#;
+        let registry = ModelRegistry::from_yaml(BASE, Some(overlay)).unwrap();
+        assert_eq!(
+            registry.fallbacks,
+            vec![
+                Fallback { provider: LlmProvider::DeepSeek, model: "deepseek-chat".to_string() },
+                Fallback { provider: LlmProvider::Local, model: "qwen2.5-coder".to_string() },
+            ]
+        );
+    }
+}
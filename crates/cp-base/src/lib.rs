@@ -3,6 +3,7 @@ pub mod llm_types;
 pub mod modules;
 pub mod panels;
 pub mod state;
+pub mod tokenizer;
 pub mod tools;
 pub mod ui;
 pub mod watchers {
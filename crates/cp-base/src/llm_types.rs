@@ -12,6 +12,10 @@ pub enum StreamEvent {
     Chunk(String),
     /// Tool use request from the LLM
     ToolUse(ToolUse),
+    /// Completed extended-thinking block. The signature must be replayed verbatim.
+    Thinking { thinking: String, signature: String },
+    /// Thinking block encrypted by the API's safety systems — opaque, replayed as-is
+    RedactedThinking { data: String },
     /// Stream completed with token usage
     Done {
         input_tokens: usize,
@@ -20,10 +24,60 @@ pub enum StreamEvent {
         cache_miss_tokens: usize,
         stop_reason: Option<String>,
     },
+    /// The request failed with a retryable error and is being re-sent to the next
+    /// failover model. Anything streamed before this event belongs to the failed attempt.
+    Failover {
+        /// Position of the new model in the failover chain
+        fallback: usize,
+        provider: LlmProvider,
+        model: String,
+        /// Error that triggered the switch
+        error: String,
+    },
+    /// Rate-limit budget reported in the response headers
+    RateLimit(RateLimitInfo),
     /// Error occurred
     Error(String),
 }
 
+/// Request and token budget from a provider's rate-limit response headers.
+/// Durations are relative to when the response arrived.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RateLimitInfo {
+    pub provider: LlmProvider,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_limit: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_remaining: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_limit: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_remaining: Option<u64>,
+    /// Time until the budgets above refill, in ms (the later of the two)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reset_after_ms: Option<u64>,
+    /// How long the server asked us to wait before retrying (`retry-after`), in ms
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_ms: Option<u64>,
+}
+
+impl RateLimitInfo {
+    /// Whether the remaining requests or tokens have dropped below `reserve` (0.0–1.0) of the limit
+    pub fn is_low(&self, reserve: f64) -> bool {
+        let low = |remaining: Option<u64>, limit: Option<u64>| match (remaining, limit) {
+            (Some(remaining), Some(limit)) if limit > 0 => (remaining as f64) < limit as f64 * reserve,
+            (Some(remaining), _) => remaining == 0,
+            _ => false,
+        };
+        low(self.requests_remaining, self.requests_limit) || low(self.tokens_remaining, self.tokens_limit)
+    }
+
+    /// Whether either budget is fully spent
+    pub fn is_exhausted(&self) -> bool {
+        self.requests_remaining == Some(0) || self.tokens_remaining == Some(0)
+    }
+}
+
 /// Result of API check
 #[derive(Debug, Clone)]
 pub struct ApiCheckResult {
@@ -39,12 +93,13 @@ impl ApiCheckResult {
     }
 }
 
-/// Model metadata trait for context window and pricing info
+/// Model metadata trait for context window and pricing info.
+/// Implemented by registry entries (`config::models::ModelSpec`).
 pub trait ModelInfo {
     /// API model identifier
-    fn api_name(&self) -> &'static str;
+    fn api_name(&self) -> &str;
     /// Human-readable display name
-    fn display_name(&self) -> &'static str;
+    fn display_name(&self) -> &str;
     /// Maximum context window in tokens
     fn context_window(&self) -> usize;
     /// Input price per million tokens in USD (used for cache miss / uncached input)
@@ -64,7 +119,7 @@ pub trait ModelInfo {
 }
 
 /// Available LLM providers
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum LlmProvider {
     #[default]
@@ -76,235 +131,15 @@ pub enum LlmProvider {
     Grok,
     Groq,
     DeepSeek,
+    OpenAi,
+    /// Local OpenAI-compatible server (Ollama, llama.cpp). Models are discovered at runtime.
+    Local,
+    /// Plays back recorded streams from a cassette file (deterministic sessions and tests)
+    Replay,
 }
 
-/// Available models for Anthropic
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
-#[serde(rename_all = "kebab-case")]
-pub enum AnthropicModel {
-    #[default]
-    ClaudeOpus45,
-    ClaudeSonnet45,
-    ClaudeHaiku45,
-}
+/// Context window assumed for local models not listed in models.yaml
+pub const LOCAL_CONTEXT_WINDOW: usize = 32_768;
 
-impl ModelInfo for AnthropicModel {
-    fn api_name(&self) -> &'static str {
-        match self {
-            AnthropicModel::ClaudeOpus45 => "claude-opus-4-6",
-            AnthropicModel::ClaudeSonnet45 => "claude-sonnet-4-5-20250929",
-            AnthropicModel::ClaudeHaiku45 => "claude-haiku-4-5-20251001",
-        }
-    }
-
-    fn display_name(&self) -> &'static str {
-        match self {
-            AnthropicModel::ClaudeOpus45 => "Opus 4.6",
-            AnthropicModel::ClaudeSonnet45 => "Sonnet 4.5",
-            AnthropicModel::ClaudeHaiku45 => "Haiku 4.5",
-        }
-    }
-
-    fn context_window(&self) -> usize {
-        200_000 // All current Anthropic models: 200K context window
-    }
-
-    fn input_price_per_mtok(&self) -> f32 {
-        match self {
-            AnthropicModel::ClaudeOpus45 => 5.0,
-            AnthropicModel::ClaudeSonnet45 => 3.0,
-            AnthropicModel::ClaudeHaiku45 => 1.0,
-        }
-    }
-
-    fn output_price_per_mtok(&self) -> f32 {
-        match self {
-            AnthropicModel::ClaudeOpus45 => 25.0,
-            AnthropicModel::ClaudeSonnet45 => 15.0,
-            AnthropicModel::ClaudeHaiku45 => 5.0,
-        }
-    }
-
-    fn cache_hit_price_per_mtok(&self) -> f32 {
-        match self {
-            AnthropicModel::ClaudeOpus45 => 0.50,
-            AnthropicModel::ClaudeSonnet45 => 0.30,
-            AnthropicModel::ClaudeHaiku45 => 0.10,
-        }
-    }
-
-    fn cache_miss_price_per_mtok(&self) -> f32 {
-        match self {
-            AnthropicModel::ClaudeOpus45 => 6.25,
-            AnthropicModel::ClaudeSonnet45 => 3.75,
-            AnthropicModel::ClaudeHaiku45 => 1.25,
-        }
-    }
-
-    fn max_output_tokens(&self) -> u32 {
-        match self {
-            AnthropicModel::ClaudeOpus45 => 128_000,
-            AnthropicModel::ClaudeSonnet45 => 64_000,
-            AnthropicModel::ClaudeHaiku45 => 64_000,
-        }
-    }
-}
-
-/// Available models for Grok (fast models optimized for tool calling)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
-#[serde(rename_all = "kebab-case")]
-pub enum GrokModel {
-    #[default]
-    Grok41Fast,
-    Grok4Fast,
-}
-
-impl ModelInfo for GrokModel {
-    fn api_name(&self) -> &'static str {
-        match self {
-            GrokModel::Grok41Fast => "grok-4-1-fast",
-            GrokModel::Grok4Fast => "grok-4-fast",
-        }
-    }
-
-    fn display_name(&self) -> &'static str {
-        match self {
-            GrokModel::Grok41Fast => "Grok 4.1 Fast",
-            GrokModel::Grok4Fast => "Grok 4 Fast",
-        }
-    }
-
-    fn context_window(&self) -> usize {
-        match self {
-            GrokModel::Grok41Fast => 2_000_000,
-            GrokModel::Grok4Fast => 2_000_000,
-        }
-    }
-
-    fn input_price_per_mtok(&self) -> f32 {
-        match self {
-            GrokModel::Grok41Fast => 0.20, // $0.20/1M input
-            GrokModel::Grok4Fast => 0.20,
-        }
-    }
-
-    fn output_price_per_mtok(&self) -> f32 {
-        match self {
-            GrokModel::Grok41Fast => 0.50, // $0.50/1M output
-            GrokModel::Grok4Fast => 0.50,
-        }
-    }
-
-    fn max_output_tokens(&self) -> u32 {
-        128_000
-    }
-}
-/// - GPT-OSS models: Support BOTH custom tools AND built-in tools (browser search, code exec)
-/// - Llama models: Custom tools only
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
-#[serde(rename_all = "kebab-case")]
-pub enum GroqModel {
-    #[default]
-    GptOss120b,
-    GptOss20b,
-    Llama33_70b,
-    Llama31_8b,
-}
-
-impl ModelInfo for GroqModel {
-    fn api_name(&self) -> &'static str {
-        match self {
-            GroqModel::GptOss120b => "openai/gpt-oss-120b",
-            GroqModel::GptOss20b => "openai/gpt-oss-20b",
-            GroqModel::Llama33_70b => "llama-3.3-70b-versatile",
-            GroqModel::Llama31_8b => "llama-3.1-8b-instant",
-        }
-    }
-
-    fn display_name(&self) -> &'static str {
-        match self {
-            GroqModel::GptOss120b => "GPT-OSS 120B (+web)",
-            GroqModel::GptOss20b => "GPT-OSS 20B (+web)",
-            GroqModel::Llama33_70b => "Llama 3.3 70B",
-            GroqModel::Llama31_8b => "Llama 3.1 8B",
-        }
-    }
-
-    fn context_window(&self) -> usize {
-        131_072 // All models have 131K context
-    }
-
-    fn input_price_per_mtok(&self) -> f32 {
-        match self {
-            GroqModel::GptOss120b => 1.20,
-            GroqModel::GptOss20b => 0.20,
-            GroqModel::Llama33_70b => 0.59,
-            GroqModel::Llama31_8b => 0.05,
-        }
-    }
-
-    fn output_price_per_mtok(&self) -> f32 {
-        match self {
-            GroqModel::GptOss120b => 1.20,
-            GroqModel::GptOss20b => 0.20,
-            GroqModel::Llama33_70b => 0.79,
-            GroqModel::Llama31_8b => 0.08,
-        }
-    }
-
-    fn max_output_tokens(&self) -> u32 {
-        128_000
-    }
-}
-
-/// Available models for DeepSeek
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
-#[serde(rename_all = "kebab-case")]
-pub enum DeepSeekModel {
-    #[default]
-    DeepseekChat,
-    DeepseekReasoner,
-}
-
-impl ModelInfo for DeepSeekModel {
-    fn api_name(&self) -> &'static str {
-        match self {
-            DeepSeekModel::DeepseekChat => "deepseek-chat",
-            DeepSeekModel::DeepseekReasoner => "deepseek-reasoner",
-        }
-    }
-
-    fn display_name(&self) -> &'static str {
-        match self {
-            DeepSeekModel::DeepseekChat => "DeepSeek Chat",
-            DeepSeekModel::DeepseekReasoner => "DeepSeek Reasoner",
-        }
-    }
-
-    fn context_window(&self) -> usize {
-        128_000
-    }
-
-    fn input_price_per_mtok(&self) -> f32 {
-        0.28
-    }
-
-    fn output_price_per_mtok(&self) -> f32 {
-        0.42
-    }
-
-    fn cache_hit_price_per_mtok(&self) -> f32 {
-        0.028
-    }
-
-    fn cache_miss_price_per_mtok(&self) -> f32 {
-        0.28
-    }
-
-    fn max_output_tokens(&self) -> u32 {
-        match self {
-            DeepSeekModel::DeepseekChat => 8_192,
-            DeepSeekModel::DeepseekReasoner => 16_384,
-        }
-    }
-}
+/// Max output tokens requested from local models not listed in models.yaml
+pub const LOCAL_MAX_OUTPUT_TOKENS: u32 = 8_192;
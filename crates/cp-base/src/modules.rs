@@ -37,6 +37,27 @@ pub fn truncate_output(output: &str, max_bytes: usize) -> String {
     }
 }
 
+/// "./src/a.rs", "src/a.rs" and its absolute path are all "src/a.rs"
+pub fn project_path(path: &str) -> String {
+    let path = path.strip_prefix("./").unwrap_or(path);
+    let relative = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| std::path::Path::new(path).strip_prefix(cwd).ok().map(|p| p.to_string_lossy().to_string()))
+        .unwrap_or_else(|| path.to_string());
+    let relative = relative.trim_end_matches('/');
+    if relative.is_empty() { ".".to_string() } else { relative.to_string() }
+}
+
+/// Where `path` ends up after `from` was moved to `to` (`None`: deleted).
+/// `from` may be a directory, which takes everything under it along.
+/// Returns `None` when the move does not concern `path`, `Some(None)` when it
+/// was deleted. All paths are compared and returned as `project_path`s.
+pub fn follow_move(path: &str, from: &str, to: Option<&str>) -> Option<Option<String>> {
+    let (path, from) = (project_path(path), project_path(from));
+    let rest = if path == from { "" } else { path.strip_prefix(&from)?.strip_prefix('/')? };
+    Some(to.map(|to| if rest.is_empty() { project_path(to) } else { format!("{}/{}", project_path(to), rest) }))
+}
+
 /// A module that provides tools, panels, and configuration to the TUI.
 ///
 /// Modules are stateless — all runtime state lives in `State`.
@@ -191,6 +212,11 @@ pub trait Module: Send + Sync {
     /// Modules can update their state to reflect the stop.
     fn on_stream_stop(&self, _state: &mut State) {}
 
+    /// Called after a tool moved (`to` is the new path) or deleted (`to` is
+    /// `None`) a project file or directory. Modules that keep paths in their
+    /// state make them follow (see `follow_move`). Paths are project-relative.
+    fn on_path_moved(&self, _from: &str, _to: Option<&str>, _state: &mut State) {}
+
     // === File watcher delegation ===
 
     /// Return filesystem paths this module wants the file watcher to monitor.
@@ -220,3 +246,26 @@ pub trait Module: Send + Sync {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follow_move_handles_files_and_directories() {
+        assert_eq!(follow_move("./src/a.rs", "src/a.rs", Some("src/b.rs")), Some(Some("src/b.rs".to_string())));
+        assert_eq!(follow_move("src/ui/a.rs", "src/ui/", Some("src/view")), Some(Some("src/view/a.rs".to_string())));
+        assert_eq!(follow_move("src/uix/a.rs", "src/ui", Some("src/view")), None);
+        assert_eq!(follow_move("src/ui/a.rs", "src/ui", None), Some(None));
+        assert_eq!(follow_move("src", "src/ui", None), None);
+    }
+
+    #[test]
+    fn follow_move_matches_absolute_paths() {
+        let cwd = std::env::current_dir().unwrap();
+        let absolute = cwd.join("src/ui/a.rs").to_string_lossy().to_string();
+        assert_eq!(follow_move(&absolute, "src/ui", Some("src/view")), Some(Some("src/view/a.rs".to_string())));
+        assert_eq!(follow_move(&absolute, "./src/ui/a.rs", None), Some(None));
+        assert_eq!(follow_move("src/ui/a.rs", &absolute, Some("b.rs")), Some(Some("b.rs".to_string())));
+    }
+}
@@ -122,13 +122,13 @@ pub fn mark_panels_dirty(state: &mut State, context_type: ContextType) {
 /// Otherwise slices by approximate token offset, snaps to line boundaries,
 /// and prepends a page header.
 pub fn paginate_content(full_content: &str, current_page: usize, total_pages: usize) -> String {
-    use crate::config::constants::{CHARS_PER_TOKEN, PANEL_PAGE_TOKENS};
-
     if total_pages <= 1 {
         return full_content.to_string();
     }
 
-    let chars_per_page = PANEL_PAGE_TOKENS as f32 * CHARS_PER_TOKEN;
+    // Equal slices, so a panel split into more pages than PANEL_PAGE_TOKENS needs
+    // (a token budget) gets proportionally smaller pages
+    let chars_per_page = full_content.len() as f32 / total_pages as f32;
     let start_char = (current_page as f32 * chars_per_page) as usize;
 
     // Snap start to next line boundary
@@ -153,6 +153,44 @@ pub fn paginate_content(full_content: &str, current_page: usize, total_pages: us
     format!("[Page {}/{} — use panel_goto_page to navigate]\n{}", current_page + 1, total_pages, page_content)
 }
 
+/// Enforce a token budget on a panel's freshly applied content. Over-budget
+/// content is either cut down to its tail (newest output) or split into pages
+/// of roughly `budget` tokens. `full_token_count` keeps the unbudgeted size, so
+/// the overflow is `full_token_count - token_count`.
+pub fn apply_token_budget(ctx: &mut ContextElement, budget: usize, keep_tail: bool) {
+    let Some(content) = ctx.cached_content.as_deref() else {
+        return;
+    };
+    let full = crate::state::estimate_tokens(content);
+    if budget == 0 || full <= budget {
+        return;
+    }
+
+    if keep_tail {
+        // Keep about `budget` tokens' worth of bytes from the end, snapped to a line start
+        let keep_bytes = (content.len() as f32 * budget as f32 / full as f32) as usize;
+        let cut = content.ceil_char_boundary(content.len() - keep_bytes.min(content.len()));
+        let start = if cut == 0 || content[..cut].ends_with('\n') {
+            cut
+        } else {
+            content[cut..].find('\n').map(|pos| cut + pos + 1).unwrap_or(content.len())
+        };
+        let tail = &content[start..];
+        let dropped = full.saturating_sub(crate::state::estimate_tokens(tail));
+        let truncated =
+            format!("[… {} earlier tokens truncated to fit the {}-token budget …]\n{}", dropped, budget, tail);
+        ctx.token_count = crate::state::estimate_tokens(&truncated);
+        ctx.cached_content = Some(truncated);
+        ctx.total_pages = 1;
+        ctx.current_page = 0;
+    } else {
+        ctx.total_pages = full.div_ceil(budget);
+        ctx.current_page = ctx.current_page.min(ctx.total_pages - 1);
+        ctx.token_count = crate::state::estimate_tokens(&paginate_content(content, ctx.current_page, ctx.total_pages));
+    }
+    ctx.full_token_count = full;
+}
+
 /// A single context item to be sent to the LLM
 #[derive(Debug, Clone)]
 pub struct ContextItem {
@@ -164,6 +202,17 @@ pub struct ContextItem {
     pub content: String,
     /// Last refresh timestamp in milliseconds since UNIX epoch (for sorting panels)
     pub last_refresh_ms: u64,
+    /// Image sent alongside the text content to providers with vision support
+    pub image: Option<ImageAttachment>,
+}
+
+/// Image file attached to a context item. Read and encoded at prompt-assembly
+/// time (off the UI thread) rather than held in state.
+#[derive(Debug, Clone)]
+pub struct ImageAttachment {
+    pub path: String,
+    /// MIME type, e.g. "image/png"
+    pub media_type: String,
 }
 
 impl ContextItem {
@@ -173,7 +222,13 @@ impl ContextItem {
         content: impl Into<String>,
         last_refresh_ms: u64,
     ) -> Self {
-        Self { id: id.into(), header: header.into(), content: content.into(), last_refresh_ms }
+        Self { id: id.into(), header: header.into(), content: content.into(), last_refresh_ms, image: None }
+    }
+
+    /// Attach an image to this item
+    pub fn with_image(mut self, image: ImageAttachment) -> Self {
+        self.image = Some(image);
+        self
     }
 }
 
@@ -302,4 +357,45 @@ mod tests {
         update_if_changed(&mut ctx, "hello");
         assert!(update_if_changed(&mut ctx, "world"));
     }
+
+    // ── apply_token_budget ─────────────────────────────────────────
+
+    fn numbered_lines(n: usize) -> String {
+        (0..n).map(|i| format!("line {} of the command output\n", i)).collect()
+    }
+
+    #[test]
+    fn budget_keeps_the_tail() {
+        let mut ctx = test_ctx("P9", ContextType::new(ContextType::CONSOLE));
+        ctx.cached_content = Some(numbered_lines(400));
+        apply_token_budget(&mut ctx, 200, true);
+
+        let content = ctx.cached_content.as_deref().unwrap();
+        assert!(content.starts_with("[… "), "{}", &content[..40]);
+        assert!(content.ends_with("line 399 of the command output\n"));
+        assert!(!content.contains("line 0 "));
+        assert!(ctx.token_count <= 230, "{}", ctx.token_count);
+        assert!(ctx.full_token_count > ctx.token_count);
+        assert_eq!(ctx.total_pages, 1);
+    }
+
+    #[test]
+    fn budget_paginates_and_leaves_small_content_alone() {
+        let mut ctx = test_ctx("P9", ContextType::new(ContextType::FILE));
+        let full = numbered_lines(400);
+        ctx.cached_content = Some(full.clone());
+        let full_tokens = crate::state::estimate_tokens(&full);
+        apply_token_budget(&mut ctx, full_tokens / 3, false);
+
+        assert_eq!(ctx.cached_content.as_deref(), Some(full.as_str()), "content stays whole");
+        assert!(ctx.total_pages >= 3);
+        assert!(ctx.token_count < full_tokens / 2);
+        assert_eq!(ctx.full_token_count, full_tokens);
+
+        let mut small = test_ctx("P10", ContextType::new(ContextType::FILE));
+        small.cached_content = Some("tiny".to_string());
+        small.token_count = 1;
+        apply_token_budget(&mut small, 100, false);
+        assert_eq!((small.total_pages, small.token_count), (1, 1));
+    }
 }
@@ -17,6 +17,8 @@ pub enum Action {
     SelectNextContext,
     SelectPrevContext,
     AppendChars(String),
+    /// Attach a completed thinking block to the streaming assistant message
+    AppendThinking(crate::state::message::ThinkingBlock),
     StreamDone {
         _input_tokens: usize,
         output_tokens: usize,
@@ -35,10 +37,8 @@ pub enum Action {
     TogglePerfMonitor,
     ToggleConfigView,
     ConfigSelectProvider(crate::llm_types::LlmProvider),
-    ConfigSelectAnthropicModel(crate::llm_types::AnthropicModel),
-    ConfigSelectGrokModel(crate::llm_types::GrokModel),
-    ConfigSelectGroqModel(crate::llm_types::GroqModel),
-    ConfigSelectDeepSeekModel(crate::llm_types::DeepSeekModel),
+    /// Select the main model for the current provider (registry ID, or server ID for Local)
+    ConfigSelectModel(String),
     ConfigSelectNextBar,
     ConfigSelectPrevBar,
     ConfigIncreaseSelectedBar,
@@ -47,14 +47,40 @@ pub enum Action {
     ConfigPrevTheme,
     ConfigToggleAutoContinue,
     ConfigSelectSecondaryProvider(crate::llm_types::LlmProvider),
-    ConfigSelectSecondaryAnthropicModel(crate::llm_types::AnthropicModel),
-    ConfigSelectSecondaryGrokModel(crate::llm_types::GrokModel),
-    ConfigSelectSecondaryGroqModel(crate::llm_types::GroqModel),
-    ConfigSelectSecondaryDeepSeekModel(crate::llm_types::DeepSeekModel),
+    /// Select the secondary model for the secondary provider
+    ConfigSelectSecondaryModel(String),
     ConfigToggleReverie,
+    ConfigToggleHistorySummaries,
+    ConfigToggleAutoCheckpoints,
+    /// Cycle the panel type edited by the config overlay's budget bar
+    ConfigNextBudgetType,
     ConfigToggleSecondaryMode,
     OpenCommandPalette,
     ResetSessionCosts,
+    /// Pin or unpin the panel selected in the sidebar
+    TogglePinSelected,
+    /// Save the working context under an auto-generated snapshot name
+    SnapshotContext,
+    /// Restore a context snapshot by name
+    RestoreSnapshot(String),
+    /// Fork the conversation after a message (display ID) into a new auto-named branch
+    ForkConversation(String),
+    /// Switch to a conversation branch, optionally restoring its panels
+    SwitchBranch(String, bool),
+    /// Checkpoint files, conversation position and panels under an auto label
+    CreateCheckpoint,
+    /// Describe what restoring a checkpoint (by ID) would change
+    PreviewCheckpoint(String),
+    /// Roll files, conversation and panels back to a checkpoint (by ID)
+    RestoreCheckpoint(String),
+    /// Restore the checkpoint taken at the start of the last user turn
+    UndoLastTurn,
+    /// Revert the last recorded AI edit of a file (by path)
+    UndoFileEdit(String),
+    /// Re-apply the last undone edit of a file (by path)
+    RedoFileEdit(String),
+    /// Open the prompt inspector in a mode ("next", "last" or "diff")
+    OpenPromptInspector(String),
     SelectContextById(String),
     None,
 }
@@ -38,6 +38,9 @@ pub struct SharedConfig {
     /// Cursor position in draft input
     #[serde(default)]
     pub draft_cursor: usize,
+    /// Token budget per panel type (e.g. "console" → 8000); missing types are unlimited
+    #[serde(default = "default_panel_budgets")]
+    pub panel_budgets: HashMap<String, usize>,
 
     // === Module data (keyed by module ID) ===
     #[serde(default)]
@@ -54,6 +57,7 @@ impl Default for SharedConfig {
             selected_context: 0,
             draft_input: String::new(),
             draft_cursor: 0,
+            panel_budgets: default_panel_budgets(),
             modules: HashMap::new(),
         }
     }
@@ -152,6 +156,10 @@ fn default_theme() -> String {
     crate::config::DEFAULT_THEME.to_string()
 }
 
+pub fn default_panel_budgets() -> HashMap<String, usize> {
+    crate::config::constants::DEFAULT_PANEL_BUDGETS.iter().map(|(t, b)| (t.to_string(), *b)).collect()
+}
+
 fn default_one() -> usize {
     1
 }
@@ -4,7 +4,6 @@ use std::sync::OnceLock;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
-use crate::config::constants::CHARS_PER_TOKEN;
 use crate::config::{active_theme, normalize_icon};
 
 // =============================================================================
@@ -225,9 +224,9 @@ impl ContextElement {
     }
 }
 
-/// Estimate tokens from text (uses CHARS_PER_TOKEN constant)
+/// Estimate tokens from text with the active (calibrated) tokenizer
 pub fn estimate_tokens(text: &str) -> usize {
-    (text.len() as f32 / CHARS_PER_TOKEN).ceil() as usize
+    crate::tokenizer::count_tokens(text)
 }
 
 /// Compute total pages for a given token count using PANEL_PAGE_TOKENS
@@ -268,7 +267,7 @@ pub fn make_default_context_element(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::constants::{CHARS_PER_TOKEN, PANEL_PAGE_TOKENS};
+    use crate::config::constants::PANEL_PAGE_TOKENS;
 
     /// Initialize a minimal registry for tests.
     fn init_test_registry() {
@@ -358,9 +357,8 @@ mod tests {
 
     #[test]
     fn estimate_tokens_short_text() {
-        let text = "hello world";
-        let expected = (text.len() as f32 / CHARS_PER_TOKEN).ceil() as usize;
-        assert_eq!(estimate_tokens(text), expected);
+        // Two BPE tokens — the old 3.3 chars/token heuristic said 4
+        assert!((2..4).contains(&estimate_tokens("hello world")));
     }
 
     #[test]
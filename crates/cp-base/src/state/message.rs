@@ -26,6 +26,15 @@ pub struct ToolUseRecord {
     pub input: serde_json::Value,
 }
 
+/// Extended-thinking block attached to an assistant message.
+/// Stored exactly as received so it can be replayed with a valid signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ThinkingBlock {
+    Thinking { thinking: String, signature: String },
+    RedactedThinking { data: String },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolResultRecord {
     pub tool_use_id: String,
@@ -59,6 +68,12 @@ pub struct Message {
     /// Tool results in this message (for ToolResult messages)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tool_results: Vec<ToolResultRecord>,
+    /// Extended-thinking blocks that preceded this response (assistant messages)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub thinking_blocks: Vec<ThinkingBlock>,
+    /// Estimated tokens of the (non-redacted) thinking text
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub thinking_token_count: usize,
     /// Input tokens used for this response (from API, for assistant messages)
     #[serde(default)]
     pub input_tokens: usize,
@@ -67,6 +82,10 @@ pub struct Message {
     pub timestamp_ms: u64,
 }
 
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
 impl Message {
     /// Create a new user text message with the given ID, UID, and content.
     pub fn new_user(id: String, uid: String, content: String, token_count: usize) -> Self {
@@ -80,6 +99,8 @@ impl Message {
             status: MessageStatus::Full,
             tool_uses: Vec::new(),
             tool_results: Vec::new(),
+            thinking_blocks: Vec::new(),
+            thinking_token_count: 0,
             input_tokens: 0,
             timestamp_ms: crate::panels::now_ms(),
         }
@@ -97,6 +118,8 @@ impl Message {
             status: MessageStatus::Full,
             tool_uses: Vec::new(),
             tool_results: Vec::new(),
+            thinking_blocks: Vec::new(),
+            thinking_token_count: 0,
             input_tokens: 0,
             timestamp_ms: crate::panels::now_ms(),
         }
@@ -127,6 +150,8 @@ pub mod test_helpers {
                     status: MessageStatus::Full,
                     tool_uses: Vec::new(),
                     tool_results: Vec::new(),
+                    thinking_blocks: Vec::new(),
+                    thinking_token_count: 0,
                     input_tokens: 0,
                     timestamp_ms: 0,
                 },
@@ -175,6 +200,11 @@ pub mod test_helpers {
             b
         }
 
+        pub fn thinking(mut self, block: ThinkingBlock) -> Self {
+            self.msg.thinking_blocks.push(block);
+            self
+        }
+
         pub fn status(mut self, s: MessageStatus) -> Self {
             self.msg.status = s;
             self
@@ -263,4 +293,24 @@ mod tests {
         let chunk = format_messages_to_chunk(&msgs);
         assert!(chunk.contains("file contents here\n"));
     }
+
+    #[test]
+    fn thinking_blocks_round_trip() {
+        let msg = MessageBuilder::assistant("answer")
+            .thinking(ThinkingBlock::Thinking { thinking: "hmm".into(), signature: "sig".into() })
+            .thinking(ThinkingBlock::RedactedThinking { data: "opaque".into() })
+            .build();
+        let json = serde_json::to_value(&msg).unwrap();
+        assert_eq!(json["thinking_blocks"][0]["type"], "thinking");
+        assert_eq!(json["thinking_blocks"][1]["type"], "redacted_thinking");
+        let back: Message = serde_json::from_value(json).unwrap();
+        assert_eq!(back.thinking_blocks, msg.thinking_blocks);
+    }
+
+    #[test]
+    fn messages_without_thinking_omit_the_field() {
+        let json = serde_json::to_value(MessageBuilder::assistant("plain").build()).unwrap();
+        assert!(json.get("thinking_blocks").is_none());
+        assert!(json.get("thinking_token_count").is_none());
+    }
 }
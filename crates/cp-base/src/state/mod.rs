@@ -13,8 +13,13 @@ pub use context::{
     ContextElement, ContextType, ContextTypeMeta, compute_total_pages, estimate_tokens, fixed_panel_order,
     get_context_type_meta, init_context_type_registry, make_default_context_element,
 };
-pub use message::{Message, MessageStatus, MessageType, ToolResultRecord, ToolUseRecord, format_messages_to_chunk};
-pub use runtime::{FullContentCache, InputRenderCache, MessageRenderCache, State, hash_values};
+pub use message::{
+    Message, MessageStatus, MessageType, ThinkingBlock, ToolResultRecord, ToolUseRecord, format_messages_to_chunk,
+};
+pub use runtime::{
+    FallbackUsage, FullContentCache, InputRenderCache, MessageRenderCache, RateLimitStatus, State, StreamTarget,
+    hash_values,
+};
 
 // ─── Reverie State ──────────────────────────────────────────────────────────
 // Ephemeral sub-agent state — lives as Option<ReverieState> on the main State.
@@ -9,9 +9,63 @@ use ratatui::text::Line;
 use super::context::{ContextElement, ContextType};
 use super::message::Message;
 
-use crate::llm_types::ModelInfo;
+use crate::config::constants::{PANEL_BUDGET_META, RATE_LIMIT_RESERVE, RATE_LIMIT_STALE_MS};
+use crate::config::models::{MODELS, ModelSpec};
+use crate::llm_types::{LlmProvider, ModelInfo, RateLimitInfo};
 use crate::tools::ToolDefinition;
 
+/// Provider and model settings of one main-worker stream
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamTarget {
+    pub provider: LlmProvider,
+    /// API model name
+    pub model: String,
+    pub max_output_tokens: u32,
+    pub thinking_budget: Option<u32>,
+    /// Position in the failover chain (None = the selected main model)
+    pub fallback: Option<usize>,
+}
+
+/// Tokens served by failover models, and what they cost at those models' prices
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct FallbackUsage {
+    pub cache_hit_tokens: usize,
+    pub cache_miss_tokens: usize,
+    pub output_tokens: usize,
+    pub cost: f64,
+}
+
+/// Latest rate-limit report from a provider, anchored to when it arrived
+#[derive(Debug, Clone)]
+pub struct RateLimitStatus {
+    pub info: RateLimitInfo,
+    pub received_ms: u64,
+}
+
+impl RateLimitStatus {
+    /// When the server will accept another request: the `retry-after` it sent, or the
+    /// budget reset once a budget is spent. None when it gave no such hint.
+    pub fn retry_at_ms(&self) -> Option<u64> {
+        let after = match self.info.retry_after_ms {
+            Some(ms) => ms,
+            None if self.info.is_exhausted() => self.info.reset_after_ms?,
+            None => return None,
+        };
+        Some(self.received_ms + after)
+    }
+
+    /// Whether the budget is nearly spent and has not refilled since the report
+    pub fn nearly_exhausted(&self, now_ms: u64) -> bool {
+        let refill_ms = self.received_ms + self.info.reset_after_ms.unwrap_or(RATE_LIMIT_STALE_MS);
+        now_ms < refill_ms && self.info.is_low(RATE_LIMIT_RESERVE)
+    }
+}
+
+/// Registry lookup for a selection: exact match for local models, default fallback otherwise
+fn lookup_spec(provider: LlmProvider, id: &str) -> Option<&'static ModelSpec> {
+    if provider == LlmProvider::Local { MODELS.get(provider, id) } else { MODELS.resolve(provider, id) }
+}
+
 // =============================================================================
 // Render Caches
 // =============================================================================
@@ -121,28 +175,50 @@ pub struct State {
     pub active_theme: String,
     /// Selected LLM provider
     pub llm_provider: crate::llm_types::LlmProvider,
-    /// Selected Anthropic model
-    pub anthropic_model: crate::llm_types::AnthropicModel,
-    /// Selected Grok model
-    pub grok_model: crate::llm_types::GrokModel,
-    /// Selected Groq model
-    pub groq_model: crate::llm_types::GroqModel,
-    /// Selected DeepSeek model
-    pub deepseek_model: crate::llm_types::DeepSeekModel,
+    /// Selected Anthropic model (registry ID from models.yaml)
+    pub anthropic_model: String,
+    /// Selected Grok model (registry ID from models.yaml)
+    pub grok_model: String,
+    /// Selected Groq model (registry ID from models.yaml)
+    pub groq_model: String,
+    /// Selected DeepSeek model (registry ID from models.yaml)
+    pub deepseek_model: String,
+    /// Selected OpenAI model (registry ID from models.yaml)
+    pub openai_model: String,
+    /// Selected local model ID (as reported by the local server; empty = none picked yet)
+    pub local_model: String,
     /// Whether config overlay is showing secondary model selection (Tab toggles)
     pub config_secondary_mode: bool,
     /// Secondary LLM provider (for reveries / sub-agents)
     pub secondary_provider: crate::llm_types::LlmProvider,
     /// Secondary Anthropic model (for reveries / sub-agents)
-    pub secondary_anthropic_model: crate::llm_types::AnthropicModel,
+    pub secondary_anthropic_model: String,
     /// Secondary Grok model
-    pub secondary_grok_model: crate::llm_types::GrokModel,
+    pub secondary_grok_model: String,
     /// Secondary Groq model
-    pub secondary_groq_model: crate::llm_types::GroqModel,
+    pub secondary_groq_model: String,
     /// Secondary DeepSeek model
-    pub secondary_deepseek_model: crate::llm_types::DeepSeekModel,
+    pub secondary_deepseek_model: String,
+    /// Secondary OpenAI model
+    pub secondary_openai_model: String,
+    /// Secondary local model ID
+    pub secondary_local_model: String,
+    /// Model IDs discovered on the local server (runtime-only, refreshed from the config overlay)
+    pub local_models: Vec<String>,
+    /// Why local model discovery failed (None while discovering or on success)
+    pub local_models_error: Option<String>,
     /// Whether the reverie system is enabled (auto-trigger on threshold breach)
     pub reverie_enabled: bool,
+    /// Whether detached history chunks are summarized by the secondary model
+    pub summarize_history: bool,
+    /// Whether a checkpoint is taken automatically at the start of every user turn
+    pub auto_checkpoints: bool,
+    /// Token budget per panel type (persisted in SharedConfig)
+    pub panel_budgets: HashMap<String, usize>,
+    /// Index into BUDGETED_PANEL_TYPES selected in the config overlay's budget editor
+    pub config_budget_type: usize,
+    /// Extended-thinking budget in tokens (0 = thinking disabled)
+    pub thinking_budget: u32,
     /// Active reverie session (None when no reverie is running).
     /// Ephemeral — not persisted, discarded after each run.
     pub reverie: Option<super::reverie::ReverieState>,
@@ -164,6 +240,13 @@ pub struct State {
     pub tick_cache_miss_tokens: usize,
     /// Last tick output tokens (runtime-only, set per StreamDone)
     pub tick_output_tokens: usize,
+    /// Estimated prompt size of the last main-worker request, compared with the
+    /// API-reported size to calibrate the tokenizer (runtime-only)
+    pub prompt_token_estimate: usize,
+    /// Share of the totals above served by failover models (persisted)
+    pub fallback_usage: FallbackUsage,
+    /// Share of the stream totals served by failover models (runtime-only, reset per user input)
+    pub stream_fallback_usage: FallbackUsage,
     /// Cleaning threshold (0.0 - 1.0), triggers auto-cleaning when exceeded
     pub cleaning_threshold: f32,
     /// Cleaning target as proportion of threshold (0.0 - 1.0)
@@ -179,6 +262,11 @@ pub struct State {
 
     /// Current API retry count (reset on success)
     pub api_retry_count: u32,
+    /// Latest rate-limit report per provider (runtime-only)
+    pub rate_limits: HashMap<LlmProvider, RateLimitStatus>,
+    /// Failover chain entry standing in for the main model (index into `MODELS.fallbacks`).
+    /// Cleared when the user sends a message or picks another model.
+    pub active_fallback: Option<usize>,
     /// Guard rail block reason (set when spine blocks, cleared when streaming starts)
     pub guard_rail_blocked: Option<String>,
     /// Reload pending flag (set by system_reload tool, triggers reload after tool result is saved)
@@ -246,17 +334,28 @@ impl Default for State {
             config_selected_bar: 0,
             active_theme: crate::config::DEFAULT_THEME.to_string(),
             llm_provider: crate::llm_types::LlmProvider::default(),
-            anthropic_model: crate::llm_types::AnthropicModel::default(),
-            grok_model: crate::llm_types::GrokModel::default(),
-            groq_model: crate::llm_types::GroqModel::default(),
-            deepseek_model: crate::llm_types::DeepSeekModel::default(),
+            anthropic_model: MODELS.default_id(LlmProvider::Anthropic).to_string(),
+            grok_model: MODELS.default_id(LlmProvider::Grok).to_string(),
+            groq_model: MODELS.default_id(LlmProvider::Groq).to_string(),
+            deepseek_model: MODELS.default_id(LlmProvider::DeepSeek).to_string(),
+            openai_model: MODELS.default_id(LlmProvider::OpenAi).to_string(),
+            local_model: String::new(),
             config_secondary_mode: false,
             secondary_provider: crate::llm_types::LlmProvider::Anthropic,
-            secondary_anthropic_model: crate::llm_types::AnthropicModel::ClaudeHaiku45,
-            secondary_grok_model: crate::llm_types::GrokModel::default(),
-            secondary_groq_model: crate::llm_types::GroqModel::default(),
-            secondary_deepseek_model: crate::llm_types::DeepSeekModel::default(),
+            secondary_anthropic_model: MODELS.secondary_default_id(LlmProvider::Anthropic).to_string(),
+            secondary_grok_model: MODELS.secondary_default_id(LlmProvider::Grok).to_string(),
+            secondary_groq_model: MODELS.secondary_default_id(LlmProvider::Groq).to_string(),
+            secondary_deepseek_model: MODELS.secondary_default_id(LlmProvider::DeepSeek).to_string(),
+            secondary_openai_model: MODELS.secondary_default_id(LlmProvider::OpenAi).to_string(),
+            secondary_local_model: String::new(),
+            local_models: vec![],
+            local_models_error: None,
             reverie_enabled: true,
+            summarize_history: false,
+            auto_checkpoints: true,
+            panel_budgets: super::config::default_panel_budgets(),
+            config_budget_type: 0,
+            thinking_budget: 0,
             reverie: None,
             cache_hit_tokens: 0,
             cache_miss_tokens: 0,
@@ -267,12 +366,17 @@ impl Default for State {
             tick_cache_hit_tokens: 0,
             tick_cache_miss_tokens: 0,
             tick_output_tokens: 0,
+            prompt_token_estimate: 0,
+            fallback_usage: FallbackUsage::default(),
+            stream_fallback_usage: FallbackUsage::default(),
             cleaning_threshold: 0.70,
             cleaning_target_proportion: 0.70,
             context_budget: None,
             api_check_in_progress: false,
             api_check_result: None,
             api_retry_count: 0,
+            active_fallback: None,
+            rate_limits: HashMap::new(),
             guard_rail_blocked: None,
             reload_pending: false,
             waiting_for_panels: false,
@@ -323,43 +427,162 @@ impl State {
         format!("P{}", id)
     }
 
+    /// Registry ID of the selected model for a provider
+    fn model_id_for(&self, provider: LlmProvider, secondary: bool) -> &str {
+        match (provider, secondary) {
+            (LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey, false) => {
+                &self.anthropic_model
+            }
+            (LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey, true) => {
+                &self.secondary_anthropic_model
+            }
+            (LlmProvider::Grok, false) => &self.grok_model,
+            (LlmProvider::Grok, true) => &self.secondary_grok_model,
+            (LlmProvider::Groq, false) => &self.groq_model,
+            (LlmProvider::Groq, true) => &self.secondary_groq_model,
+            (LlmProvider::DeepSeek, false) => &self.deepseek_model,
+            (LlmProvider::DeepSeek, true) => &self.secondary_deepseek_model,
+            (LlmProvider::OpenAi, false) => &self.openai_model,
+            (LlmProvider::OpenAi, true) => &self.secondary_openai_model,
+            (LlmProvider::Local, false) => &self.local_model,
+            (LlmProvider::Local, true) => &self.secondary_local_model,
+            // The replay provider has a single placeholder model
+            (LlmProvider::Replay, _) => MODELS.default_id(provider),
+        }
+    }
+
+    /// Set the selected model for a provider (main or secondary)
+    pub fn set_model_id(&mut self, provider: LlmProvider, secondary: bool, id: String) {
+        let slot = match (provider, secondary) {
+            (LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey, false) => {
+                &mut self.anthropic_model
+            }
+            (LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey, true) => {
+                &mut self.secondary_anthropic_model
+            }
+            (LlmProvider::Grok, false) => &mut self.grok_model,
+            (LlmProvider::Grok, true) => &mut self.secondary_grok_model,
+            (LlmProvider::Groq, false) => &mut self.groq_model,
+            (LlmProvider::Groq, true) => &mut self.secondary_groq_model,
+            (LlmProvider::DeepSeek, false) => &mut self.deepseek_model,
+            (LlmProvider::DeepSeek, true) => &mut self.secondary_deepseek_model,
+            (LlmProvider::OpenAi, false) => &mut self.openai_model,
+            (LlmProvider::OpenAi, true) => &mut self.secondary_openai_model,
+            (LlmProvider::Local, false) => &mut self.local_model,
+            (LlmProvider::Local, true) => &mut self.secondary_local_model,
+            (LlmProvider::Replay, _) => return,
+        };
+        *slot = id;
+    }
+
+    /// Selectable model IDs for a provider, in display order
+    /// (registry entries, or the models discovered on the local server)
+    pub fn model_choices(&self, provider: LlmProvider) -> Vec<String> {
+        if provider == LlmProvider::Local {
+            self.local_models.clone()
+        } else {
+            MODELS.models(provider).iter().map(|m| m.id.clone()).collect()
+        }
+    }
+
+    /// Registry ID of the main model for the current provider
+    pub fn current_model_id(&self) -> &str {
+        self.model_id_for(self.llm_provider, false)
+    }
+
+    /// Registry ID of the secondary model for the secondary provider
+    pub fn secondary_model_id(&self) -> &str {
+        self.model_id_for(self.secondary_provider, true)
+    }
+
+    /// Registry entry for the main model. Stale IDs fall back to the provider default;
+    /// local models only match if listed in models.yaml.
+    pub fn current_model_spec(&self) -> Option<&'static ModelSpec> {
+        lookup_spec(self.llm_provider, self.current_model_id())
+    }
+
+    /// Registry entry for the secondary model
+    pub fn secondary_model_spec(&self) -> Option<&'static ModelSpec> {
+        lookup_spec(self.secondary_provider, self.secondary_model_id())
+    }
+
     /// Get the API model string for the current provider/model selection
     pub fn current_model(&self) -> String {
-        use crate::llm_types::LlmProvider;
-        match self.llm_provider {
-            LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey => {
-                self.anthropic_model.api_name().to_string()
-            }
-            LlmProvider::Grok => self.grok_model.api_name().to_string(),
-            LlmProvider::Groq => self.groq_model.api_name().to_string(),
-            LlmProvider::DeepSeek => self.deepseek_model.api_name().to_string(),
+        match self.current_model_spec() {
+            Some(spec) => spec.api_name().to_string(),
+            None => self.current_model_id().to_string(),
+        }
+    }
+
+    /// Get the API model string for the secondary provider/model selection
+    pub fn secondary_model(&self) -> String {
+        match self.secondary_model_spec() {
+            Some(spec) => spec.api_name().to_string(),
+            None => self.secondary_model_id().to_string(),
         }
     }
 
     /// Get the max output tokens for the current provider/model selection
     pub fn current_max_output_tokens(&self) -> u32 {
-        use crate::llm_types::LlmProvider;
-        match self.llm_provider {
-            LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey => {
-                self.anthropic_model.max_output_tokens()
-            }
-            LlmProvider::Grok => self.grok_model.max_output_tokens(),
-            LlmProvider::Groq => self.groq_model.max_output_tokens(),
-            LlmProvider::DeepSeek => self.deepseek_model.max_output_tokens(),
+        self.current_model_spec().map(|m| m.max_output_tokens()).unwrap_or(crate::llm_types::LOCAL_MAX_OUTPUT_TOKENS)
+    }
+
+    /// Thinking budget to request from the main model, if thinking is enabled and supported.
+    /// Only Anthropic-family providers accept it; the budget must stay below max_tokens.
+    pub fn effective_thinking_budget(&self) -> Option<u32> {
+        self.thinking_budget_for(self.llm_provider, self.current_max_output_tokens())
+    }
+
+    fn thinking_budget_for(&self, provider: LlmProvider, max_output_tokens: u32) -> Option<u32> {
+        use crate::config::constants::THINKING_BUDGET_MIN;
+        let supported =
+            matches!(provider, LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey);
+        if !supported || self.thinking_budget < THINKING_BUDGET_MIN {
+            return None;
+        }
+        let ceiling = max_output_tokens.saturating_sub(1);
+        (ceiling >= THINKING_BUDGET_MIN).then(|| self.thinking_budget.min(ceiling))
+    }
+
+    /// Target of the next main-worker stream: the selected model, or the
+    /// failover model currently standing in for it
+    pub fn stream_target(&self) -> StreamTarget {
+        if let Some(target) = self.active_fallback.and_then(|i| self.fallback_target(i)) {
+            return target;
+        }
+        StreamTarget {
+            provider: self.llm_provider,
+            model: self.current_model(),
+            max_output_tokens: self.current_max_output_tokens(),
+            thinking_budget: self.effective_thinking_budget(),
+            fallback: None,
         }
     }
 
+    /// Failover chain entries after the one currently in use, in order
+    pub fn remaining_fallbacks(&self) -> Vec<StreamTarget> {
+        let start = self.active_fallback.map_or(0, |i| i + 1);
+        (start..MODELS.fallbacks.len()).filter_map(|i| self.fallback_target(i)).collect()
+    }
+
+    fn fallback_target(&self, index: usize) -> Option<StreamTarget> {
+        let fallback = MODELS.fallbacks.get(index)?;
+        // Exact lookup: an unknown ID is sent as-is rather than swapped for the provider default
+        let spec = MODELS.get(fallback.provider, &fallback.model);
+        let max_output_tokens =
+            spec.map(|m| m.max_output_tokens()).unwrap_or(crate::llm_types::LOCAL_MAX_OUTPUT_TOKENS);
+        Some(StreamTarget {
+            provider: fallback.provider,
+            model: spec.map(|m| m.api_name.clone()).unwrap_or_else(|| fallback.model.clone()),
+            max_output_tokens,
+            thinking_budget: self.thinking_budget_for(fallback.provider, max_output_tokens),
+            fallback: Some(index),
+        })
+    }
+
     /// Get the max output tokens for the secondary provider/model selection
     pub fn secondary_max_output_tokens(&self) -> u32 {
-        use crate::llm_types::LlmProvider;
-        match self.secondary_provider {
-            LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey => {
-                self.secondary_anthropic_model.max_output_tokens()
-            }
-            LlmProvider::Grok => self.secondary_grok_model.max_output_tokens(),
-            LlmProvider::Groq => self.secondary_groq_model.max_output_tokens(),
-            LlmProvider::DeepSeek => self.secondary_deepseek_model.max_output_tokens(),
-        }
+        self.secondary_model_spec().map(|m| m.max_output_tokens()).unwrap_or(crate::llm_types::LOCAL_MAX_OUTPUT_TOKENS)
     }
 
     /// Get the cleaning target as absolute proportion (threshold * target_proportion)
@@ -369,15 +592,7 @@ impl State {
 
     /// Get the current model's context window
     pub fn model_context_window(&self) -> usize {
-        use crate::llm_types::LlmProvider;
-        match self.llm_provider {
-            LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey => {
-                self.anthropic_model.context_window()
-            }
-            LlmProvider::Grok => self.grok_model.context_window(),
-            LlmProvider::Groq => self.groq_model.context_window(),
-            LlmProvider::DeepSeek => self.deepseek_model.context_window(),
-        }
+        self.current_model_spec().map(|m| m.context_window()).unwrap_or(crate::llm_types::LOCAL_CONTEXT_WINDOW)
     }
 
     /// Get effective context budget (custom or model's full context)
@@ -390,48 +605,33 @@ impl State {
         (self.effective_context_budget() as f32 * self.cleaning_threshold) as usize
     }
 
+    /// Token budget for a panel: its own override, else its type's budget (None = unlimited)
+    pub fn panel_budget(&self, ctx: &ContextElement) -> Option<usize> {
+        match ctx.get_meta_usize(PANEL_BUDGET_META) {
+            Some(budget) => Some(budget),
+            None => self.panel_budgets.get(ctx.context_type.as_str()).copied(),
+        }
+        .filter(|&b| b > 0)
+    }
+
     /// Get cleaning target in tokens
     pub fn cleaning_target_tokens(&self) -> usize {
         (self.effective_context_budget() as f32 * self.cleaning_target()) as usize
     }
 
-    /// Get cache hit price per million tokens for the current model
+    /// Get cache hit price per million tokens for the current model (local models are free)
     pub fn cache_hit_price_per_mtok(&self) -> f32 {
-        use crate::llm_types::LlmProvider;
-        match self.llm_provider {
-            LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey => {
-                self.anthropic_model.cache_hit_price_per_mtok()
-            }
-            LlmProvider::Grok => self.grok_model.cache_hit_price_per_mtok(),
-            LlmProvider::Groq => self.groq_model.cache_hit_price_per_mtok(),
-            LlmProvider::DeepSeek => self.deepseek_model.cache_hit_price_per_mtok(),
-        }
+        self.current_model_spec().map(|m| m.cache_hit_price_per_mtok()).unwrap_or(0.0)
     }
 
     /// Get cache miss price per million tokens for the current model
     pub fn cache_miss_price_per_mtok(&self) -> f32 {
-        use crate::llm_types::LlmProvider;
-        match self.llm_provider {
-            LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey => {
-                self.anthropic_model.cache_miss_price_per_mtok()
-            }
-            LlmProvider::Grok => self.grok_model.cache_miss_price_per_mtok(),
-            LlmProvider::Groq => self.groq_model.cache_miss_price_per_mtok(),
-            LlmProvider::DeepSeek => self.deepseek_model.cache_miss_price_per_mtok(),
-        }
+        self.current_model_spec().map(|m| m.cache_miss_price_per_mtok()).unwrap_or(0.0)
     }
 
     /// Get output price per million tokens for the current model
     pub fn output_price_per_mtok(&self) -> f32 {
-        use crate::llm_types::LlmProvider;
-        match self.llm_provider {
-            LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey => {
-                self.anthropic_model.output_price_per_mtok()
-            }
-            LlmProvider::Grok => self.grok_model.output_price_per_mtok(),
-            LlmProvider::Groq => self.groq_model.output_price_per_mtok(),
-            LlmProvider::DeepSeek => self.deepseek_model.output_price_per_mtok(),
-        }
+        self.current_model_spec().map(|m| m.output_price_per_mtok()).unwrap_or(0.0)
     }
 
     /// Calculate cost in USD for a given token count and price per MTok
@@ -439,6 +639,78 @@ impl State {
         tokens as f64 * price_per_mtok as f64 / 1_000_000.0
     }
 
+    /// Add one API call's usage to the tick, stream and session counters.
+    /// Calls served by a failover model are also priced at that model.
+    pub fn record_usage(&mut self, cache_hit_tokens: usize, cache_miss_tokens: usize, output_tokens: usize) {
+        self.tick_cache_hit_tokens = cache_hit_tokens;
+        self.tick_cache_miss_tokens = cache_miss_tokens;
+        self.tick_output_tokens = output_tokens;
+        self.stream_cache_hit_tokens += cache_hit_tokens;
+        self.stream_cache_miss_tokens += cache_miss_tokens;
+        self.stream_output_tokens += output_tokens;
+        self.cache_hit_tokens += cache_hit_tokens;
+        self.cache_miss_tokens += cache_miss_tokens;
+        self.total_output_tokens += output_tokens;
+
+        let Some(fallback) = self.active_fallback.and_then(|i| MODELS.fallbacks.get(i)) else { return };
+        let cost = MODELS.get(fallback.provider, &fallback.model).map_or(0.0, |m| {
+            Self::token_cost(cache_hit_tokens, m.cache_hit_price_per_mtok())
+                + Self::token_cost(cache_miss_tokens, m.cache_miss_price_per_mtok())
+                + Self::token_cost(output_tokens, m.output_price_per_mtok())
+        });
+        for usage in [&mut self.fallback_usage, &mut self.stream_fallback_usage] {
+            usage.cache_hit_tokens += cache_hit_tokens;
+            usage.cache_miss_tokens += cache_miss_tokens;
+            usage.output_tokens += output_tokens;
+            usage.cost += cost;
+        }
+    }
+
+    /// Store a provider's rate-limit report
+    pub fn record_rate_limit(&mut self, info: RateLimitInfo) {
+        let status = RateLimitStatus { received_ms: crate::panels::now_ms(), info };
+        self.rate_limits.insert(status.info.provider, status);
+    }
+
+    /// Milliseconds left before `provider` accepts a retry (0 = go ahead)
+    pub fn retry_wait_ms(&self, provider: LlmProvider) -> u64 {
+        let retry_at = self.rate_limits.get(&provider).and_then(RateLimitStatus::retry_at_ms);
+        retry_at.map_or(0, |at| at.saturating_sub(crate::panels::now_ms()))
+    }
+
+    /// Whether `provider`'s rate-limit budget is (nearly) spent
+    pub fn rate_limit_low(&self, provider: LlmProvider) -> bool {
+        self.retry_wait_ms(provider) > 0
+            || self.rate_limits.get(&provider).is_some_and(|s| s.nearly_exhausted(crate::panels::now_ms()))
+    }
+
+    /// Session cost in USD: tokens served by the main model at its prices, plus failover costs
+    pub fn session_cost(&self) -> f64 {
+        self.cost_with_fallback(
+            self.cache_hit_tokens,
+            self.cache_miss_tokens,
+            self.total_output_tokens,
+            &self.fallback_usage,
+        )
+    }
+
+    /// Cost in USD of the current stream (since the last user message)
+    pub fn stream_cost(&self) -> f64 {
+        self.cost_with_fallback(
+            self.stream_cache_hit_tokens,
+            self.stream_cache_miss_tokens,
+            self.stream_output_tokens,
+            &self.stream_fallback_usage,
+        )
+    }
+
+    fn cost_with_fallback(&self, hit: usize, miss: usize, output: usize, fallback: &FallbackUsage) -> f64 {
+        Self::token_cost(hit.saturating_sub(fallback.cache_hit_tokens), self.cache_hit_price_per_mtok())
+            + Self::token_cost(miss.saturating_sub(fallback.cache_miss_tokens), self.cache_miss_price_per_mtok())
+            + Self::token_cost(output.saturating_sub(fallback.output_tokens), self.output_price_per_mtok())
+            + fallback.cost
+    }
+
     // === Message Creation Helpers ===
 
     /// Allocate the next user message ID and UID, returning (id, uid).
@@ -0,0 +1,250 @@
+//! Token counting behind `estimate_tokens`.
+//!
+//! The active `Tokenizer` is chosen per model family: OpenAI models use their own
+//! BPE vocabulary (o200k or cl100k); every other family is counted with cl100k,
+//! the closest public vocabulary. Counts are then scaled by a calibration factor
+//! learned from the prompt sizes the API reports, so estimates converge on what
+//! the provider actually bills over a session.
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+
+use tiktoken_rs::CoreBPE;
+
+use crate::config::constants::{
+    CHARS_PER_TOKEN, TOKENIZER_CALIBRATION_MAX, TOKENIZER_CALIBRATION_MIN, TOKENIZER_CALIBRATION_MIN_TOKENS,
+    TOKENIZER_CALIBRATION_RATE,
+};
+use crate::llm_types::LlmProvider;
+
+/// Counts tokens in a piece of text
+pub trait Tokenizer: Send + Sync {
+    /// Short name shown in the statistics panel (e.g. "cl100k")
+    fn name(&self) -> &str;
+
+    /// Raw token count, before calibration
+    fn count(&self, text: &str) -> usize;
+}
+
+/// Character-ratio fallback (`CHARS_PER_TOKEN`)
+pub struct HeuristicTokenizer;
+
+impl Tokenizer for HeuristicTokenizer {
+    fn name(&self) -> &str {
+        "heuristic"
+    }
+
+    fn count(&self, text: &str) -> usize {
+        (text.len() as f32 / CHARS_PER_TOKEN).ceil() as usize
+    }
+}
+
+/// Bundled BPE vocabularies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BpeVocab {
+    Cl100k,
+    O200k,
+}
+
+/// Local BPE tokenizer (vocabularies embedded at build time)
+pub struct BpeTokenizer {
+    vocab: BpeVocab,
+}
+
+impl BpeTokenizer {
+    pub fn new(vocab: BpeVocab) -> Self {
+        Self { vocab }
+    }
+
+    fn bpe(&self) -> &'static CoreBPE {
+        match self.vocab {
+            BpeVocab::Cl100k => tiktoken_rs::cl100k_base_singleton(),
+            BpeVocab::O200k => tiktoken_rs::o200k_base_singleton(),
+        }
+    }
+}
+
+impl Tokenizer for BpeTokenizer {
+    fn name(&self) -> &str {
+        match self.vocab {
+            BpeVocab::Cl100k => "cl100k",
+            BpeVocab::O200k => "o200k",
+        }
+    }
+
+    fn count(&self, text: &str) -> usize {
+        self.bpe().encode_ordinary(text).len()
+    }
+}
+
+/// Vocabulary used to count tokens for a provider's model
+pub fn vocab_for(provider: LlmProvider, api_model: &str) -> BpeVocab {
+    use tiktoken_rs::tokenizer::{Tokenizer as Encoding, get_tokenizer};
+    match provider {
+        LlmProvider::OpenAi => match get_tokenizer(api_model) {
+            Some(Encoding::O200kBase | Encoding::O200kHarmony) => BpeVocab::O200k,
+            Some(_) => BpeVocab::Cl100k,
+            // Newer model names tiktoken doesn't know yet are o200k-era
+            None => BpeVocab::O200k,
+        },
+        _ => BpeVocab::Cl100k,
+    }
+}
+
+/// Multiplier learned from API-reported prompt sizes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Calibration {
+    pub factor: f32,
+    /// Number of reports folded in so far
+    pub samples: u32,
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Self { factor: 1.0, samples: 0 }
+    }
+}
+
+impl Calibration {
+    /// Fold in one request: `estimated` is our (calibrated) estimate of the prompt,
+    /// `reported` what the API counted. Small prompts are too noisy to learn from.
+    pub fn observe(&mut self, estimated: usize, reported: usize) {
+        if estimated == 0 || reported < TOKENIZER_CALIBRATION_MIN_TOKENS {
+            return;
+        }
+        let target = self.factor * reported as f32 / estimated as f32;
+        // The first report replaces the default outright; later ones are smoothed
+        let rate = if self.samples == 0 { 1.0 } else { TOKENIZER_CALIBRATION_RATE };
+        self.factor =
+            (self.factor + (target - self.factor) * rate).clamp(TOKENIZER_CALIBRATION_MIN, TOKENIZER_CALIBRATION_MAX);
+        self.samples += 1;
+    }
+
+    fn apply(&self, raw: usize) -> usize {
+        (raw as f32 * self.factor).ceil() as usize
+    }
+}
+
+struct Active {
+    tokenizer: Arc<dyn Tokenizer>,
+    calibration: Calibration,
+}
+
+static ACTIVE: LazyLock<RwLock<Active>> = LazyLock::new(|| {
+    RwLock::new(Active {
+        tokenizer: Arc::new(BpeTokenizer::new(BpeVocab::Cl100k)),
+        calibration: Calibration::default(),
+    })
+});
+
+/// Raw counts of recently seen large texts (system prompt, open files), keyed by
+/// tokenizer name and content hash — the same text is re-estimated on every render.
+static MEMO: LazyLock<Mutex<HashMap<(String, u64), usize>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Texts at least this long go through the memo
+const MEMO_MIN_BYTES: usize = 1_024;
+
+/// Memo entries kept before it is cleared
+const MEMO_CAPACITY: usize = 512;
+
+fn raw_count(tokenizer: &dyn Tokenizer, text: &str) -> usize {
+    if text.len() < MEMO_MIN_BYTES {
+        return tokenizer.count(text);
+    }
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    let key = (tokenizer.name().to_string(), hasher.finish());
+    if let Some(&count) = MEMO.lock().unwrap_or_else(|e| e.into_inner()).get(&key) {
+        return count;
+    }
+    let count = tokenizer.count(text);
+    let mut memo = MEMO.lock().unwrap_or_else(|e| e.into_inner());
+    if memo.len() >= MEMO_CAPACITY {
+        memo.clear();
+    }
+    memo.insert(key, count);
+    count
+}
+
+/// Calibrated token estimate for `text` using the active tokenizer
+pub fn count_tokens(text: &str) -> usize {
+    if text.is_empty() {
+        return 0;
+    }
+    let (tokenizer, calibration) = {
+        let active = ACTIVE.read().unwrap_or_else(|e| e.into_inner());
+        (Arc::clone(&active.tokenizer), active.calibration)
+    };
+    calibration.apply(raw_count(tokenizer.as_ref(), text)).max(1)
+}
+
+/// Replace the active tokenizer. Calibration restarts unless it is the same kind.
+pub fn install(tokenizer: Arc<dyn Tokenizer>) {
+    let mut active = ACTIVE.write().unwrap_or_else(|e| e.into_inner());
+    if active.tokenizer.name() != tokenizer.name() {
+        active.calibration = Calibration::default();
+    }
+    active.tokenizer = tokenizer;
+}
+
+/// Install the bundled tokenizer for a provider's model
+pub fn select_for_model(provider: LlmProvider, api_model: &str) {
+    install(Arc::new(BpeTokenizer::new(vocab_for(provider, api_model))));
+}
+
+/// Fold an API-reported prompt size into the active calibration
+pub fn calibrate(estimated: usize, reported: usize) {
+    ACTIVE.write().unwrap_or_else(|e| e.into_inner()).calibration.observe(estimated, reported);
+}
+
+/// Name of the active tokenizer and its current calibration
+pub fn describe() -> (String, Calibration) {
+    let active = ACTIVE.read().unwrap_or_else(|e| e.into_inner());
+    (active.tokenizer.name().to_string(), active.calibration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bpe_counts_code_denser_than_the_heuristic() {
+        let code = r#"{"id": 42, "items": [1, 2, 3], "ok": true}"#;
+        let bpe = BpeTokenizer::new(BpeVocab::Cl100k).count(code);
+        assert!(bpe > HeuristicTokenizer.count(code), "{}", bpe);
+        assert_eq!(BpeTokenizer::new(BpeVocab::Cl100k).count("hello world"), 2);
+    }
+
+    #[test]
+    fn vocab_follows_the_model_family() {
+        assert_eq!(vocab_for(LlmProvider::OpenAi, "gpt-4o-mini"), BpeVocab::O200k);
+        assert_eq!(vocab_for(LlmProvider::OpenAi, "gpt-4-0613"), BpeVocab::Cl100k);
+        assert_eq!(vocab_for(LlmProvider::Anthropic, "claude-sonnet-4-5"), BpeVocab::Cl100k);
+    }
+
+    #[test]
+    fn calibration_converges_on_reported_sizes() {
+        let mut calibration = Calibration::default();
+        calibration.observe(500, 800);
+        assert_eq!(calibration, Calibration::default(), "small prompts are ignored");
+
+        // API consistently counts 20% more than the raw estimate
+        calibration.observe(10_000, 12_000);
+        assert!((calibration.factor - 1.2).abs() < 1e-4);
+        for _ in 0..20 {
+            let estimated = calibration.apply(10_000);
+            calibration.observe(estimated, 12_000);
+        }
+        assert!((calibration.factor - 1.2).abs() < 1e-3);
+        assert_eq!(calibration.apply(10_000), 12_000);
+    }
+
+    #[test]
+    fn calibration_is_bounded() {
+        let mut calibration = Calibration::default();
+        calibration.observe(1_000, 100_000);
+        assert_eq!(calibration.factor, TOKENIZER_CALIBRATION_MAX);
+    }
+}
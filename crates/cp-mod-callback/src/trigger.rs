@@ -1,6 +1,6 @@
 //! Callback trigger engine: collect changed files, match patterns, partition callbacks.
 //!
-//! Called from tool_pipeline.rs after a batch of Edit/Write tools completes.
+//! Called from tool_pipeline.rs after a batch of file-changing tools completes.
 //! Firing logic lives in firing.rs.
 
 use std::path::Path;
@@ -29,39 +29,76 @@ pub struct ChangedFile {
     pub skip_callbacks: Vec<String>,
 }
 
+/// Tools that change files. Their results carry the callback summary.
+pub const FILE_TOOLS: &[&str] = &[
+    "Edit",
+    "Write",
+    "MultiEdit",
+    "Patch",
+    "apply_preview",
+    "file_undo",
+    "file_redo",
+    "file_move",
+    "file_copy",
+    "file_delete",
+];
+
+/// Paths a file-changing tool call names, as given in its input.
+/// Dry runs change nothing and name no paths.
+pub fn tool_file_paths<'a>(tool_name: &str, input: &'a serde_json::Value) -> Vec<&'a str> {
+    if input.get("dry_run").and_then(|v| v.as_bool()).unwrap_or(false) {
+        return Vec::new();
+    }
+    match tool_name {
+        "Edit" | "Write" | "apply_preview" | "file_undo" | "file_redo" => {
+            input.get("file_path").and_then(|v| v.as_str()).into_iter().collect()
+        }
+        "MultiEdit" => input
+            .get("edits")
+            .and_then(|v| v.as_array())
+            .map(|edits| edits.iter().filter_map(|e| e.get("file_path").and_then(|v| v.as_str())).collect())
+            .unwrap_or_default(),
+        // A move changes both paths; a copy only creates its destination
+        "file_move" => {
+            ["source", "destination"].iter().filter_map(|k| input.get(*k).and_then(|v| v.as_str())).collect()
+        }
+        "file_copy" => input.get("destination").and_then(|v| v.as_str()).into_iter().collect(),
+        "file_delete" => input.get("path").and_then(|v| v.as_str()).into_iter().collect(),
+        // Both sides of every file section: renamed and deleted paths count as touched
+        "Patch" => {
+            input.get("patch").and_then(|v| v.as_str()).map(cp_mod_files::patch::patch_paths).unwrap_or_default()
+        }
+        _ => Vec::new(),
+    }
+}
+
 /// Collect changed file paths from a batch of tool uses.
-/// Extracts `file_path` from Edit and Write tool inputs.
+/// Extracts the paths named by file-changing tools (see `tool_file_paths`).
 /// Also collects `skip_callbacks` names per tool for selective skipping.
 pub fn collect_changed_files(tools: &[cp_base::tools::ToolUse]) -> Vec<ChangedFile> {
     let mut hull: Vec<ChangedFile> = Vec::new();
     let project_root = std::env::current_dir().unwrap_or_default().to_string_lossy().to_string();
     for tool in tools {
-        match tool.name.as_str() {
-            "Edit" | "Write" => {
-                if let Some(path) = tool.input.get("file_path").and_then(|v| v.as_str()) {
-                    // Normalize: strip leading ./ if present, strip absolute project root prefix
-                    let mut anchor_path = path.strip_prefix("./").unwrap_or(path);
-                    if let Some(relative) = anchor_path.strip_prefix(&project_root) {
-                        anchor_path = relative.strip_prefix('/').unwrap_or(relative);
-                    }
-                    let anchor_str = anchor_path.to_string();
-
-                    // Parse skip_callbacks: string array of callback names
-                    let skip_names = parse_skip_callbacks(&tool.input);
-
-                    // Merge: if file already in hull, union the skip lists
-                    if let Some(existing) = hull.iter_mut().find(|f| f.path == anchor_str) {
-                        for name in &skip_names {
-                            if !existing.skip_callbacks.contains(name) {
-                                existing.skip_callbacks.push(name.clone());
-                            }
-                        }
-                    } else {
-                        hull.push(ChangedFile { path: anchor_str, skip_callbacks: skip_names });
+        // Parse skip_callbacks: string array of callback names
+        let skip_names = parse_skip_callbacks(&tool.input);
+        for path in tool_file_paths(&tool.name, &tool.input) {
+            // Normalize: strip leading ./ if present, strip absolute project root prefix
+            let mut anchor_path = path.strip_prefix("./").unwrap_or(path);
+            if let Some(relative) = anchor_path.strip_prefix(&project_root) {
+                anchor_path = relative.strip_prefix('/').unwrap_or(relative);
+            }
+            let anchor_str = anchor_path.to_string();
+
+            // Merge: if file already in hull, union the skip lists
+            if let Some(existing) = hull.iter_mut().find(|f| f.path == anchor_str) {
+                for name in &skip_names {
+                    if !existing.skip_callbacks.contains(name) {
+                        existing.skip_callbacks.push(name.clone());
                     }
                 }
+            } else {
+                hull.push(ChangedFile { path: anchor_str, skip_callbacks: skip_names.clone() });
             }
-            _ => {}
         }
     }
     hull
@@ -228,6 +265,30 @@ mod tests {
         assert_eq!(paths(&files), vec!["src/main.rs"]);
     }
 
+    #[test]
+    fn test_collect_patch_paths() {
+        let patch = "--- a/src/a.rs\n+++ b/src/a.rs\n@@ -1 +1 @@\n-x\n+y\n\
+                     diff --git a/old.rs b/new.rs\nrename from old.rs\nrename to new.rs\n";
+        let tools = vec![cp_base::tools::ToolUse {
+            id: "test".to_string(),
+            name: "Patch".to_string(),
+            input: json!({ "patch": patch, "skip_callbacks": ["fmt"] }),
+        }];
+        let files = collect_changed_files(&tools);
+        assert_eq!(paths(&files), vec!["src/a.rs", "old.rs", "new.rs"]);
+        assert_eq!(files[0].skip_callbacks, vec!["fmt"]);
+    }
+
+    #[test]
+    fn test_collect_move_paths() {
+        let tools = vec![cp_base::tools::ToolUse {
+            id: "test".to_string(),
+            name: "file_move".to_string(),
+            input: json!({ "source": "src/old.rs", "destination": "src/new.rs" }),
+        }];
+        assert_eq!(paths(&collect_changed_files(&tools)), vec!["src/old.rs", "src/new.rs"]);
+    }
+
     #[test]
     fn test_collect_ignores_non_file_tools() {
         let tools = vec![
@@ -242,6 +303,39 @@ mod tests {
         assert_eq!(paths(&files), vec!["src/main.rs"]);
     }
 
+    #[test]
+    fn test_collect_multi_edit_files() {
+        let tools = vec![cp_base::tools::ToolUse {
+            id: "test".to_string(),
+            name: "MultiEdit".to_string(),
+            input: json!({
+                "edits": [{ "file_path": "src/a.rs" }, { "file_path": "./src/b.rs" }, { "file_path": "src/a.rs" }],
+                "skip_callbacks": ["rust-check"],
+            }),
+        }];
+        let files = collect_changed_files(&tools);
+        assert_eq!(paths(&files), vec!["src/a.rs", "src/b.rs"]);
+        assert!(files.iter().all(|f| f.skip_callbacks == vec!["rust-check"]));
+    }
+
+    #[test]
+    fn test_collect_skips_dry_runs() {
+        let tools = vec![
+            cp_base::tools::ToolUse {
+                id: "test".to_string(),
+                name: "Edit".to_string(),
+                input: json!({ "file_path": "src/a.rs", "dry_run": true }),
+            },
+            cp_base::tools::ToolUse {
+                id: "test".to_string(),
+                name: "apply_preview".to_string(),
+                input: json!({ "preview_id": "V1", "file_path": "src/b.rs" }),
+            },
+        ];
+        let files = collect_changed_files(&tools);
+        assert_eq!(paths(&files), vec!["src/b.rs"]);
+    }
+
     #[test]
     fn test_collect_parses_skip_callbacks() {
         let tools = vec![
@@ -0,0 +1,264 @@
+//! Per-file undo/redo history of AI edits.
+//!
+//! Every `Edit`, `Write`, `MultiEdit`, `Patch` and `apply_preview` records the file's
+//! previous content in `.context-pilot/file-history/<hash>.json`, newest last,
+//! up to `MAX_HISTORY` entries per file. Each entry also stores the hash of the
+//! content the file must have for it to apply, so undo and redo refuse when
+//! something else changed the file since. Files outside the project have no
+//! history.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use cp_base::config::constants::STORE_DIR;
+use cp_base::panels::hash_content;
+use cp_base::state::{ContextElement, ContextType, State};
+
+use crate::paths::{in_project, project_path};
+use crate::tools::diff::generate_file_diff;
+
+/// Subdirectory of STORE_DIR holding one history file per edited path
+pub const HISTORY_DIR: &str = "file-history";
+/// Undo entries kept per file (oldest dropped first)
+pub const MAX_HISTORY: usize = 20;
+/// File panel metadata: number of edits that can be undone / redone
+pub const UNDO_DEPTH_META: &str = "undo_depth";
+pub const REDO_DEPTH_META: &str = "redo_depth";
+
+/// Unchanged lines kept around each change in undo/redo diffs
+const DIFF_CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    /// Content to put back (`None`: the file did not exist)
+    content: Option<String>,
+    /// Hash of the content the file must have now (`None`: it must not exist)
+    expect: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct History {
+    path: String,
+    undo: Vec<Entry>,
+    redo: Vec<Entry>,
+}
+
+/// Result of an undo or redo
+#[derive(Debug)]
+struct Step {
+    before: Option<String>,
+    after: Option<String>,
+    undo_depth: usize,
+    redo_depth: usize,
+}
+
+fn history_dir() -> PathBuf {
+    PathBuf::from(STORE_DIR).join(HISTORY_DIR)
+}
+
+fn history_file(dir: &Path, path: &str) -> PathBuf {
+    dir.join(format!("{}.json", &hash_content(&project_path(path))[..16]))
+}
+
+fn load(dir: &Path, path: &str) -> History {
+    fs::read_to_string(history_file(dir, path))
+        .ok()
+        .and_then(|s| serde_json::from_str::<History>(&s).ok())
+        .filter(|h| h.path == project_path(path))
+        .unwrap_or_else(|| History { path: project_path(path), ..Default::default() })
+}
+
+fn save(dir: &Path, history: &History) -> Result<(), String> {
+    let file = history_file(dir, &history.path);
+    if history.undo.is_empty() && history.redo.is_empty() {
+        let _ = fs::remove_file(file);
+        return Ok(());
+    }
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create '{}': {}", dir.display(), e))?;
+    let json = serde_json::to_string(history).map_err(|e| e.to_string())?;
+    fs::write(&file, json).map_err(|e| format!("Failed to write '{}': {}", file.display(), e))
+}
+
+fn read_current(path: &str) -> Option<String> {
+    if Path::new(path).exists() { fs::read_to_string(path).ok() } else { None }
+}
+
+fn record_in(dir: &Path, path: &str, before: Option<&str>, after: Option<&str>) -> Result<(usize, usize), String> {
+    let mut history = load(dir, path);
+    history.undo.push(Entry { content: before.map(|s| s.to_string()), expect: after.map(hash_content) });
+    if history.undo.len() > MAX_HISTORY {
+        history.undo.remove(0);
+    }
+    // A new edit forks the timeline: what was undone can no longer be redone
+    history.redo.clear();
+    save(dir, &history)?;
+    Ok((history.undo.len(), 0))
+}
+
+fn step_in(dir: &Path, path: &str, undo: bool) -> Result<Step, String> {
+    let mut history = load(dir, path);
+    let (verb, stack) = if undo { ("undo", &mut history.undo) } else { ("redo", &mut history.redo) };
+    let Some(entry) = stack.pop() else {
+        return Err(format!("Nothing to {} for '{}'", verb, path));
+    };
+
+    let current = read_current(path);
+    if current.as_deref().map(hash_content) != entry.expect {
+        return Err(format!(
+            "'{}' was changed outside the recorded edits since the last one; {} refused. Edit it directly instead.",
+            path, verb
+        ));
+    }
+
+    match &entry.content {
+        Some(content) => fs::write(path, content).map_err(|e| format!("Failed to write '{}': {}", path, e))?,
+        None => fs::remove_file(path).map_err(|e| format!("Failed to remove '{}': {}", path, e))?,
+    }
+    let inverse = Entry { content: current.clone(), expect: entry.content.as_deref().map(hash_content) };
+    if undo {
+        history.redo.push(inverse);
+    } else {
+        history.undo.push(inverse);
+    }
+    save(dir, &history)?;
+    Ok(Step { before: current, after: entry.content, undo_depth: history.undo.len(), redo_depth: history.redo.len() })
+}
+
+/// Undo and redo depth recorded for a file
+pub fn depth(path: &str) -> (usize, usize) {
+    if !in_project(path) {
+        return (0, 0);
+    }
+    let history = load(&history_dir(), path);
+    (history.undo.len(), history.redo.len())
+}
+
+/// Store the depth on a file panel so its header can show it
+pub fn set_depth_meta(ctx: &mut ContextElement, (undo, redo): (usize, usize)) {
+    ctx.set_meta(UNDO_DEPTH_META, &undo);
+    ctx.set_meta(REDO_DEPTH_META, &redo);
+}
+
+fn sync_panels(state: &mut State, path: &str, depth: (usize, usize), changed: bool) {
+    for ctx in state.context.iter_mut() {
+        if ctx.context_type == ContextType::FILE
+            && ctx.get_meta_str("file_path").map(project_path) == Some(project_path(path))
+        {
+            set_depth_meta(ctx, depth);
+            ctx.cache_deprecated |= changed;
+        }
+    }
+}
+
+/// Record an AI edit of `path` (`before` is `None` when the edit created it,
+/// `after` is `None` when it deleted it).
+/// History is best-effort: a failure to store it never fails the edit.
+pub fn record(state: &mut State, path: &str, before: Option<&str>, after: Option<&str>) {
+    if before == after || !in_project(path) {
+        return;
+    }
+    if let Ok(depth) = record_in(&history_dir(), path, before, after) {
+        sync_panels(state, path, depth, false);
+    }
+}
+
+fn step(state: &mut State, path: &str, undo: bool) -> Result<String, String> {
+    if !in_project(path) {
+        return Err(format!("No edit history for '{}': it is outside the project", path));
+    }
+    let step = step_in(&history_dir(), path, undo)?;
+    sync_panels(state, path, (step.undo_depth, step.redo_depth), true);
+    let action = match (undo, &step.after) {
+        (true, None) => "Undid the creation of",
+        (true, Some(_)) => "Undid last edit of",
+        (false, None) => "Redid the deletion of",
+        (false, Some(_)) => "Redid edit of",
+    };
+    let diff = generate_file_diff(
+        step.before.as_deref().unwrap_or(""),
+        step.after.as_deref().unwrap_or(""),
+        DIFF_CONTEXT_LINES,
+    );
+    Ok(format!("{} '{}' ({} undo, {} redo left)\n```diff\n{}```", action, path, step.undo_depth, step.redo_depth, diff))
+}
+
+/// Restore the content `path` had before its last recorded edit
+pub fn undo(state: &mut State, path: &str) -> Result<String, String> {
+    step(state, path, true)
+}
+
+/// Re-apply the last undone edit of `path`
+pub fn redo(state: &mut State, path: &str) -> Result<String, String> {
+    step(state, path, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> (PathBuf, String) {
+        let dir = std::env::temp_dir().join(format!("cp_history_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("a.txt").to_string_lossy().to_string();
+        (dir.join("history"), file)
+    }
+
+    #[test]
+    fn undo_redo_round_trip() {
+        let (dir, file) = scratch("round_trip");
+        // Created, then edited twice
+        fs::write(&file, "v1").unwrap();
+        record_in(&dir, &file, None, Some("v1")).unwrap();
+        fs::write(&file, "v2").unwrap();
+        record_in(&dir, &file, Some("v1"), Some("v2")).unwrap();
+        fs::write(&file, "v3").unwrap();
+        assert_eq!(record_in(&dir, &file, Some("v2"), Some("v3")).unwrap(), (3, 0));
+
+        let step = step_in(&dir, &file, true).unwrap();
+        assert_eq!((step.before.as_deref(), step.after.as_deref()), (Some("v3"), Some("v2")));
+        assert_eq!(fs::read_to_string(&file).unwrap(), "v2");
+        step_in(&dir, &file, true).unwrap();
+        let step = step_in(&dir, &file, true).unwrap();
+        assert_eq!((step.undo_depth, step.redo_depth), (0, 3));
+        assert!(!Path::new(&file).exists());
+        assert!(step_in(&dir, &file, true).unwrap_err().starts_with("Nothing to undo"));
+
+        step_in(&dir, &file, false).unwrap();
+        step_in(&dir, &file, false).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "v2");
+
+        // A new edit drops the redo entries
+        fs::write(&file, "other").unwrap();
+        assert_eq!(record_in(&dir, &file, Some("v2"), Some("other")).unwrap(), (3, 0));
+        assert!(step_in(&dir, &file, false).is_err());
+        let _ = fs::remove_dir_all(dir.parent().unwrap());
+    }
+
+    #[test]
+    fn refuses_after_external_change() {
+        let (dir, file) = scratch("external");
+        fs::write(&file, "new").unwrap();
+        record_in(&dir, &file, Some("old"), Some("new")).unwrap();
+        fs::write(&file, "changed by hand").unwrap();
+        assert!(step_in(&dir, &file, true).unwrap_err().contains("changed outside the recorded edits"));
+        assert_eq!(fs::read_to_string(&file).unwrap(), "changed by hand");
+        // The entry is still there once the file is back
+        fs::write(&file, "new").unwrap();
+        assert!(step_in(&dir, &file, true).is_ok());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "old");
+        let _ = fs::remove_dir_all(dir.parent().unwrap());
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let (dir, file) = scratch("bounded");
+        for i in 0..MAX_HISTORY + 5 {
+            record_in(&dir, &file, Some(&i.to_string()), Some(&(i + 1).to_string())).unwrap();
+        }
+        assert_eq!(load(&dir, &file).undo.len(), MAX_HISTORY);
+        let _ = fs::remove_dir_all(dir.parent().unwrap());
+    }
+}
@@ -0,0 +1,131 @@
+//! Image files opened as vision input.
+//!
+//! The panel content is a short text description (format, dimensions, size) —
+//! that is all providers without vision support receive. The image itself is
+//! attached to the panel's `ContextItem` and encoded at prompt-assembly time.
+
+use std::path::Path;
+
+use cp_base::config::constants::{IMAGE_MAX_EDGE_PX, IMAGE_PIXELS_PER_TOKEN};
+
+/// MIME type for a supported image file, by extension
+pub fn media_type_for(path: &str) -> Option<&'static str> {
+    let ext = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Estimate vision tokens for an image of the given size.
+/// Mirrors the API's downscaling: the long edge is capped at `IMAGE_MAX_EDGE_PX`,
+/// then roughly one token per `IMAGE_PIXELS_PER_TOKEN` pixels.
+pub fn estimate_image_tokens(width: usize, height: usize) -> usize {
+    if width == 0 || height == 0 {
+        return 0;
+    }
+    let long_edge = width.max(height);
+    let (w, h) = if long_edge > IMAGE_MAX_EDGE_PX {
+        (width * IMAGE_MAX_EDGE_PX / long_edge, height * IMAGE_MAX_EDGE_PX / long_edge)
+    } else {
+        (width, height)
+    };
+    (w * h).div_ceil(IMAGE_PIXELS_PER_TOKEN).max(1)
+}
+
+/// Text description of an image, used as panel content and as the fallback
+/// for providers that can't see the image.
+pub fn describe_image(path: &str, bytes: &[u8]) -> (String, Option<(usize, usize)>) {
+    let format = media_type_for(path).and_then(|m| m.strip_prefix("image/")).unwrap_or("unknown").to_uppercase();
+    let dims = imagesize::blob_size(bytes).ok().map(|s| (s.width, s.height));
+    let dims_str = dims.map(|(w, h)| format!("{}×{} px", w, h)).unwrap_or_else(|| "unknown size".to_string());
+    let description = format!(
+        "Image: {}\nFormat: {} · {} · {}\n[The image is attached as vision input. If you cannot see it, this description is all you have.]",
+        path,
+        format,
+        dims_str,
+        format_bytes(bytes.len())
+    );
+    (description, dims)
+}
+
+/// If pasted text is a single path to an existing image file, return that path.
+/// Handles what terminals produce on drag-and-drop: quotes, `file://` URLs, escaped spaces.
+pub fn image_path_from_paste(text: &str) -> Option<String> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() || trimmed.contains('\n') {
+        return None;
+    }
+    let unquoted = trimmed
+        .strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+        .or_else(|| trimmed.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+        .unwrap_or(trimmed);
+    let path = unquoted.strip_prefix("file://").unwrap_or(unquoted).replace("\\ ", " ");
+    media_type_for(&path)?;
+    Path::new(&path).is_file().then_some(path)
+}
+
+fn format_bytes(n: usize) -> String {
+    if n >= 1024 * 1024 {
+        format!("{:.1} MB", n as f64 / (1024.0 * 1024.0))
+    } else if n >= 1024 {
+        format!("{} KB", n / 1024)
+    } else {
+        format!("{} B", n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn media_types_by_extension() {
+        assert_eq!(media_type_for("shot.PNG"), Some("image/png"));
+        assert_eq!(media_type_for("a/b.jpeg"), Some("image/jpeg"));
+        assert_eq!(media_type_for("anim.gif"), Some("image/gif"));
+        assert_eq!(media_type_for("x.webp"), Some("image/webp"));
+        assert_eq!(media_type_for("main.rs"), None);
+        assert_eq!(media_type_for("Makefile"), None);
+    }
+
+    #[test]
+    fn token_estimate_caps_long_edge() {
+        assert_eq!(estimate_image_tokens(750, 1), 1);
+        assert_eq!(estimate_image_tokens(1000, 1000), 1334);
+        // 4000×2000 is downscaled to 1568×784
+        assert_eq!(estimate_image_tokens(4000, 2000), 1568 * 784 / 750 + 1);
+        assert_eq!(estimate_image_tokens(0, 100), 0);
+    }
+
+    #[test]
+    fn describes_png_dimensions() {
+        // Minimal PNG header: signature + IHDR with 2×3 px
+        let mut png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 13];
+        png.extend_from_slice(b"IHDR");
+        png.extend_from_slice(&2u32.to_be_bytes());
+        png.extend_from_slice(&3u32.to_be_bytes());
+        png.extend_from_slice(&[8, 6, 0, 0, 0]);
+        let (desc, dims) = describe_image("shot.png", &png);
+        assert_eq!(dims, Some((2, 3)));
+        assert!(desc.contains("PNG · 2×3 px"));
+    }
+
+    #[test]
+    fn paste_paths_are_unwrapped() {
+        assert_eq!(image_path_from_paste("notes.txt"), None);
+        assert_eq!(image_path_from_paste("missing.png"), None);
+        assert_eq!(image_path_from_paste("a.png\nb.png"), None);
+
+        let file = std::env::temp_dir().join("cp paste test.png");
+        std::fs::write(&file, b"").unwrap();
+        let path = file.to_string_lossy().to_string();
+        assert_eq!(image_path_from_paste(&format!("'{}'", path)), Some(path.clone()));
+        assert_eq!(image_path_from_paste(&format!("file://{}", path.replace(' ', "\\ "))), Some(path.clone()));
+        let _ = std::fs::remove_file(&file);
+    }
+}
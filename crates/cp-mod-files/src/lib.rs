@@ -1,4 +1,10 @@
+pub mod history;
+pub mod image;
 mod panel;
+pub mod patch;
+pub mod paths;
+mod preview;
+pub mod slice;
 mod tools;
 
 use cp_base::modules::ToolVisualizer;
@@ -7,9 +13,14 @@ use cp_base::state::{ContextType, State};
 use cp_base::tools::{ParamType, ToolDefinition, ToolParam};
 use cp_base::tools::{ToolResult, ToolUse};
 
-use self::panel::FilePanel;
+use self::panel::{FilePanel, ImagePanel};
 use cp_base::modules::Module;
 
+/// Context type of image panels (opened via `Open` on PNG/JPEG/GIF/WebP files)
+pub const IMAGE_CONTEXT_TYPE: &str = "image";
+
+pub use self::tools::file::open_image;
+
 pub struct FilesModule;
 
 impl Module for FilesModule {
@@ -29,13 +40,18 @@ impl Module for FilesModule {
         true
     }
 
+    fn init_state(&self, state: &mut State) {
+        state.set_ext(self::preview::PreviewState::new());
+    }
+
     fn dynamic_panel_types(&self) -> Vec<ContextType> {
-        vec![ContextType::new(ContextType::FILE)]
+        vec![ContextType::new(ContextType::FILE), ContextType::new(IMAGE_CONTEXT_TYPE)]
     }
 
     fn create_panel(&self, context_type: &ContextType) -> Option<Box<dyn Panel>> {
         match context_type.as_str() {
             ContextType::FILE => Some(Box::new(FilePanel)),
+            IMAGE_CONTEXT_TYPE => Some(Box::new(ImagePanel)),
             _ => None,
         }
     }
@@ -46,11 +62,17 @@ impl Module for FilesModule {
                 id: "Open".to_string(),
                 name: "Open File".to_string(),
                 short_desc: "Read file into context".to_string(),
-                description: "Opens a file and adds it to context so you can see its content. ALWAYS use this BEFORE file_edit to see current content - you need exact text for edits.".to_string(),
+                description: "Opens a file and adds it to context so you can see its content. ALWAYS use this BEFORE file_edit to see current content - you need exact text for edits. PNG, JPEG, GIF and WebP files open as images (screenshots, diagrams) you can look at. For large files, pass start_line/end_line or a symbol to open just that slice (shown with line numbers); re-open the same path with a different range to widen or narrow it, or with no range to show the whole file.".to_string(),
                 params: vec![
                     ToolParam::new("path", ParamType::String)
                         .desc("Path to the file to open")
                         .required(),
+                    ToolParam::new("start_line", ParamType::Integer)
+                        .desc("First line to show (1-based). Defaults to 1 when only end_line is given"),
+                    ToolParam::new("end_line", ParamType::Integer)
+                        .desc("Last line to show (inclusive). Defaults to the end of the file"),
+                    ToolParam::new("symbol", ParamType::String)
+                        .desc("Show only this definition, with its doc comments (e.g. 'assemble_prompt', 'fn assemble_prompt', 'impl State'). The range follows the symbol as the file changes"),
                 ],
                 enabled: true,
                 reverie_allowed: true,
@@ -73,6 +95,8 @@ impl Module for FilesModule {
                         .required(),
                     ToolParam::new("replace_all", ParamType::Boolean)
                         .desc("Replace all occurrences (default: false)"),
+                    ToolParam::new("dry_run", ParamType::Boolean)
+                        .desc("Don't write: return the diff and a preview ID for apply_preview (default: false). No callbacks run."),
                     ToolParam::new("skip_callbacks", ParamType::Array(Box::new(ParamType::String)))
                         .desc("List of callback names to skip for this edit. Use sparingly — only when you KNOW the callback will fail (e.g. mid-refactor) or when actively debugging. Callbacks exist to help you; prefer letting them run."),
                 ],
@@ -92,6 +116,8 @@ impl Module for FilesModule {
                     ToolParam::new("contents", ParamType::String)
                         .desc("Complete file contents to write")
                         .required(),
+                    ToolParam::new("dry_run", ParamType::Boolean)
+                        .desc("Don't write: return the diff and a preview ID for apply_preview (default: false). No callbacks run."),
                     ToolParam::new("skip_callbacks", ParamType::Array(Box::new(ParamType::String)))
                         .desc("List of callback names to skip for this write. Use sparingly — only when you KNOW the callback will fail (e.g. mid-refactor) or when actively debugging. Callbacks exist to help you; prefer letting them run."),
                 ],
@@ -99,8 +125,178 @@ impl Module for FilesModule {
                 reverie_allowed: false,
                 category: "File".to_string(),
             },
-
-
+            ToolDefinition {
+                id: "apply_preview".to_string(),
+                name: "Apply Preview".to_string(),
+                short_desc: "Write a dry-run change".to_string(),
+                description: "Writes a change previously previewed with file_edit or file_write dry_run=true, by its preview ID (e.g. 'V3'). Refuses if the file changed since the preview was made. Callbacks run as for a normal edit.".to_string(),
+                params: vec![
+                    ToolParam::new("preview_id", ParamType::String)
+                        .desc("Preview ID returned by the dry run")
+                        .required(),
+                    ToolParam::new("file_path", ParamType::String)
+                        .desc("Path of the previewed file (must match the preview)")
+                        .required(),
+                    ToolParam::new("skip_callbacks", ParamType::Array(Box::new(ParamType::String)))
+                        .desc("List of callback names to skip for this write. Use sparingly — only when you KNOW the callback will fail (e.g. mid-refactor) or when actively debugging. Callbacks exist to help you; prefer letting them run."),
+                ],
+                enabled: true,
+                reverie_allowed: false,
+                category: "File".to_string(),
+            },
+            ToolDefinition {
+                id: "file_undo".to_string(),
+                name: "Undo File Edit".to_string(),
+                short_desc: "Revert the last edit of a file".to_string(),
+                description: "Restores the content a file had before its last Edit/Write/MultiEdit/Patch (up to 20 edits back per file; undoing a file's creation deletes it). Refuses if the file was changed by something else since that edit. Returns the diff of what was reverted.".to_string(),
+                params: vec![
+                    ToolParam::new("file_path", ParamType::String)
+                        .desc("Path of the file to revert")
+                        .required(),
+                    ToolParam::new("skip_callbacks", ParamType::Array(Box::new(ParamType::String)))
+                        .desc("List of callback names to skip for this change. Use sparingly — callbacks exist to help you; prefer letting them run."),
+                ],
+                enabled: true,
+                reverie_allowed: false,
+                category: "File".to_string(),
+            },
+            ToolDefinition {
+                id: "file_redo".to_string(),
+                name: "Redo File Edit".to_string(),
+                short_desc: "Re-apply an undone edit".to_string(),
+                description: "Re-applies the last edit of a file undone with file_undo. A new edit to the file clears what can be redone.".to_string(),
+                params: vec![
+                    ToolParam::new("file_path", ParamType::String)
+                        .desc("Path of the file")
+                        .required(),
+                    ToolParam::new("skip_callbacks", ParamType::Array(Box::new(ParamType::String)))
+                        .desc("List of callback names to skip for this change. Use sparingly — callbacks exist to help you; prefer letting them run."),
+                ],
+                enabled: true,
+                reverie_allowed: false,
+                category: "File".to_string(),
+            },
+            ToolDefinition {
+                id: "MultiEdit".to_string(),
+                name: "Multi Edit".to_string(),
+                short_desc: "Apply several edits atomically".to_string(),
+                description: "Applies a list of exact-text replacements across one or more files as a single change: every edit is matched first (same rules as file_edit), and if any fails to match nothing is written. Edits to the same file apply in order, each seeing the result of the previous ones. Callbacks fire once for the whole change set. Use for coordinated refactors instead of many file_edit calls. Every file must be open in context.".to_string(),
+                params: vec![
+                    ToolParam::new("edits", ParamType::Array(Box::new(ParamType::Object(vec![
+                        ToolParam::new("file_path", ParamType::String)
+                            .desc("Path to the file to edit")
+                            .required(),
+                        ToolParam::new("old_string", ParamType::String)
+                            .desc("Exact text to find and replace (copy from file context)")
+                            .required(),
+                        ToolParam::new("new_string", ParamType::String)
+                            .desc("Replacement text")
+                            .required(),
+                        ToolParam::new("replace_all", ParamType::Boolean)
+                            .desc("Replace all occurrences (default: false)"),
+                    ]))))
+                    .desc("Edits to apply, in order")
+                    .required(),
+                    ToolParam::new("skip_callbacks", ParamType::Array(Box::new(ParamType::String)))
+                        .desc("List of callback names to skip for this change set. Use sparingly — only when you KNOW the callback will fail (e.g. mid-refactor) or when actively debugging. Callbacks exist to help you; prefer letting them run."),
+                ],
+                enabled: true,
+                reverie_allowed: false,
+                category: "File".to_string(),
+            },
+            ToolDefinition {
+                id: "file_move".to_string(),
+                name: "Move File".to_string(),
+                short_desc: "Move or rename a file or directory".to_string(),
+                description: "Moves or renames a file or directory, creating missing parent directories. Open panels, tree descriptions and typst watchlist entries follow the new path. Refuses to replace an existing path unless overwrite is set (file over file only). .git, .context-pilot and paths outside the project are protected. Callbacks fire for both the old and the new path.".to_string(),
+                params: vec![
+                    ToolParam::new("source", ParamType::String)
+                        .desc("Current path")
+                        .required(),
+                    ToolParam::new("destination", ParamType::String)
+                        .desc("New path (the full target path, not its parent directory)")
+                        .required(),
+                    ToolParam::new("overwrite", ParamType::Boolean)
+                        .desc("Replace an existing destination file")
+                        .default_val("false"),
+                    ToolParam::new("skip_callbacks", ParamType::Array(Box::new(ParamType::String)))
+                        .desc("List of callback names to skip for this change. Use sparingly — callbacks exist to help you; prefer letting them run."),
+                ],
+                enabled: true,
+                reverie_allowed: false,
+                category: "File".to_string(),
+            },
+            ToolDefinition {
+                id: "file_copy".to_string(),
+                name: "Copy File".to_string(),
+                short_desc: "Copy a file or directory".to_string(),
+                description: "Copies a file, or a directory recursively, creating missing parent directories. Refuses to replace an existing path unless overwrite is set (file over file only). .git, .context-pilot and paths outside the project are protected. Callbacks fire for the new path.".to_string(),
+                params: vec![
+                    ToolParam::new("source", ParamType::String)
+                        .desc("Path to copy")
+                        .required(),
+                    ToolParam::new("destination", ParamType::String)
+                        .desc("Path of the copy")
+                        .required(),
+                    ToolParam::new("overwrite", ParamType::Boolean)
+                        .desc("Replace an existing destination file")
+                        .default_val("false"),
+                    ToolParam::new("skip_callbacks", ParamType::Array(Box::new(ParamType::String)))
+                        .desc("List of callback names to skip for this change. Use sparingly — callbacks exist to help you; prefer letting them run."),
+                ],
+                enabled: true,
+                reverie_allowed: false,
+                category: "File".to_string(),
+            },
+            ToolDefinition {
+                id: "file_delete".to_string(),
+                name: "Delete File".to_string(),
+                short_desc: "Delete a file or directory".to_string(),
+                description: "Deletes a file, or a directory (non-empty directories need recursive). A deleted file can be restored with file_undo. Tree descriptions and typst watchlist entries for it are dropped. .git, .context-pilot and paths outside the project are protected. Callbacks fire for the deleted path.".to_string(),
+                params: vec![
+                    ToolParam::new("path", ParamType::String)
+                        .desc("Path to delete")
+                        .required(),
+                    ToolParam::new("recursive", ParamType::Boolean)
+                        .desc("Delete a non-empty directory with everything in it")
+                        .default_val("false"),
+                    ToolParam::new("skip_callbacks", ParamType::Array(Box::new(ParamType::String)))
+                        .desc("List of callback names to skip for this change. Use sparingly — callbacks exist to help you; prefer letting them run."),
+                ],
+                enabled: true,
+                reverie_allowed: false,
+                category: "File".to_string(),
+            },
+            ToolDefinition {
+                id: "file_mkdir".to_string(),
+                name: "Make Directory".to_string(),
+                short_desc: "Create a directory".to_string(),
+                description: "Creates a directory and any missing parents. Succeeds if it already exists.".to_string(),
+                params: vec![
+                    ToolParam::new("path", ParamType::String)
+                        .desc("Directory to create")
+                        .required(),
+                ],
+                enabled: true,
+                reverie_allowed: false,
+                category: "File".to_string(),
+            },
+            ToolDefinition {
+                id: "Patch".to_string(),
+                name: "Patch".to_string(),
+                short_desc: "Apply a unified diff".to_string(),
+                description: "Applies a unified diff (git diff or diff -u output) that may span several files, including created (/dev/null), deleted and renamed files. Hunks that moved are found at the nearest matching offset; if the context still differs, up to 2 outer context lines per side may be ignored (fuzz). The result reports every hunk (applied, offset, fuzz or rejected with the closest matching line). If any hunk is rejected, or any path is protected (.git, .context-pilot, outside the project), nothing is written. Files do not need to be open in context. Callbacks fire for every touched path.".to_string(),
+                params: vec![
+                    ToolParam::new("patch", ParamType::String)
+                        .desc("Unified diff text with ---/+++ file headers and @@ hunks (a/ and b/ prefixes are stripped)")
+                        .required(),
+                    ToolParam::new("skip_callbacks", ParamType::Array(Box::new(ParamType::String)))
+                        .desc("List of callback names to skip for this change set. Use sparingly — callbacks exist to help you; prefer letting them run."),
+                ],
+                enabled: true,
+                reverie_allowed: false,
+                category: "File".to_string(),
+            },
         ]
     }
 
@@ -109,30 +305,59 @@ impl Module for FilesModule {
             "Open" => Some(self::tools::file::execute_open(tool, state)),
             "Edit" => Some(self::tools::edit_file::execute_edit(tool, state)),
             "Write" => Some(self::tools::write::execute(tool, state)),
-
+            "MultiEdit" => Some(self::tools::multi_edit::execute(tool, state)),
+            "Patch" => Some(self::tools::patch::execute(tool, state)),
+            "file_move" => Some(self::tools::manage::execute_move(tool, state)),
+            "file_copy" => Some(self::tools::manage::execute_copy(tool, state)),
+            "file_delete" => Some(self::tools::manage::execute_delete(tool, state)),
+            "file_mkdir" => Some(self::tools::manage::execute_mkdir(tool, state)),
+            "apply_preview" => Some(self::tools::apply_preview::execute(tool, state)),
+            "file_undo" => Some(self::tools::undo::execute(tool, state, true)),
+            "file_redo" => Some(self::tools::undo::execute(tool, state, false)),
             _ => None,
         }
     }
 
     fn tool_visualizers(&self) -> Vec<(&'static str, ToolVisualizer)> {
-        vec![("Edit", visualize_diff as ToolVisualizer), ("Write", visualize_diff as ToolVisualizer)]
+        vec![
+            ("Edit", visualize_diff as ToolVisualizer),
+            ("Write", visualize_diff as ToolVisualizer),
+            ("MultiEdit", visualize_diff as ToolVisualizer),
+            ("Patch", visualize_diff as ToolVisualizer),
+            ("apply_preview", visualize_diff as ToolVisualizer),
+            ("file_undo", visualize_diff as ToolVisualizer),
+            ("file_redo", visualize_diff as ToolVisualizer),
+        ]
     }
 
     fn context_type_metadata(&self) -> Vec<cp_base::state::ContextTypeMeta> {
-        vec![cp_base::state::ContextTypeMeta {
-            context_type: "file",
-            icon_id: "file",
-            is_fixed: false,
-            needs_cache: true,
-            fixed_order: None,
-            display_name: "file",
-            short_name: "file",
-            needs_async_wait: true,
-        }]
+        vec![
+            cp_base::state::ContextTypeMeta {
+                context_type: "file",
+                icon_id: "file",
+                is_fixed: false,
+                needs_cache: true,
+                fixed_order: None,
+                display_name: "file",
+                short_name: "file",
+                needs_async_wait: true,
+            },
+            cp_base::state::ContextTypeMeta {
+                context_type: IMAGE_CONTEXT_TYPE,
+                icon_id: "image",
+                is_fixed: false,
+                needs_cache: true,
+                fixed_order: None,
+                display_name: "image",
+                short_name: "image",
+                needs_async_wait: true,
+            },
+        ]
     }
 
     fn context_detail(&self, ctx: &cp_base::state::ContextElement) -> Option<String> {
-        if ctx.context_type.as_str() == cp_base::state::ContextType::FILE {
+        let ct = ctx.context_type.as_str();
+        if ct == cp_base::state::ContextType::FILE || ct == IMAGE_CONTEXT_TYPE {
             Some(ctx.get_meta_str("file_path").unwrap_or("").to_string())
         } else {
             None
@@ -143,11 +368,32 @@ impl Module for FilesModule {
         vec![("File", "Read, write, and search files in the project")]
     }
 
+    /// File and image panels follow their file; a deleted one shows the error on refresh
+    fn on_path_moved(&self, from: &str, to: Option<&str>, state: &mut State) {
+        for ctx in state.context.iter_mut() {
+            let ct = ctx.context_type.as_str();
+            if ct != ContextType::FILE && ct != IMAGE_CONTEXT_TYPE {
+                continue;
+            }
+            let Some(moved) = ctx.get_meta_str("file_path").and_then(|p| cp_base::modules::follow_move(p, from, to))
+            else {
+                continue;
+            };
+            if let Some(new_path) = moved {
+                ctx.name = std::path::Path::new(&new_path)
+                    .file_name()
+                    .map_or_else(|| new_path.clone(), |n| n.to_string_lossy().to_string());
+                ctx.set_meta("file_path", &new_path);
+            }
+            ctx.cache_deprecated = true;
+        }
+    }
+
     fn watch_paths(&self, state: &State) -> Vec<cp_base::panels::WatchSpec> {
         state
             .context
             .iter()
-            .filter(|c| c.context_type.as_str() == ContextType::FILE)
+            .filter(|c| c.context_type.as_str() == ContextType::FILE || c.context_type.as_str() == IMAGE_CONTEXT_TYPE)
             .filter_map(|c| c.get_meta_str("file_path").map(|p| cp_base::panels::WatchSpec::File(p.to_string())))
             .collect()
     }
@@ -161,11 +407,12 @@ impl Module for FilesModule {
         if is_dir_event {
             return false;
         }
-        ctx.context_type.as_str() == ContextType::FILE && ctx.get_meta_str("file_path") == Some(changed_path)
+        let ct = ctx.context_type.as_str();
+        (ct == ContextType::FILE || ct == IMAGE_CONTEXT_TYPE) && ctx.get_meta_str("file_path") == Some(changed_path)
     }
 }
 
-/// Visualizer for Edit and Write tool results.
+/// Visualizer for Edit, Write, MultiEdit, Patch, apply_preview and undo/redo tool results.
 /// Also reused by cp-mod-prompt for Edit_prompt.
 /// Parses ```diff blocks and renders deleted lines in red, added lines in green.
 /// Callback summary blocks get compact styled rendering (only status word colored).
@@ -4,17 +4,34 @@ use std::path::PathBuf;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::prelude::*;
 
-use cp_base::config::constants::{PANEL_MAX_LOAD_BYTES, SCROLL_ARROW_AMOUNT, SCROLL_PAGE_AMOUNT};
+use cp_base::config::constants::{IMAGE_MAX_BYTES, PANEL_MAX_LOAD_BYTES, SCROLL_ARROW_AMOUNT, SCROLL_PAGE_AMOUNT};
 use cp_base::config::theme;
 use cp_base::panels::{CacheRequest, CacheUpdate, hash_content};
-use cp_base::panels::{ContextItem, Panel, paginate_content, update_if_changed};
+use cp_base::panels::{ContextItem, ImageAttachment, Panel, paginate_content, update_if_changed};
 use cp_base::state::Action;
 use cp_base::state::{ContextElement, ContextType, State, compute_total_pages, estimate_tokens};
 
+use crate::IMAGE_CONTEXT_TYPE;
+use crate::history::{REDO_DEPTH_META, UNDO_DEPTH_META};
+use crate::image::{describe_image, estimate_image_tokens, media_type_for};
+use crate::slice::{
+    LINE_END_META, LINE_START_META, SYMBOL_META, extract, find_symbol, number_lines, parse_slice_header,
+};
+
 pub struct FileCacheRequest {
     pub context_id: String,
     pub file_path: String,
     pub current_source_hash: Option<String>,
+    /// Explicit line range of a partial panel (1-based, inclusive)
+    pub range: Option<(usize, usize)>,
+    /// Symbol whose definition a partial panel shows
+    pub symbol: Option<String>,
+}
+
+/// Split a partial panel's content into its range header and body
+fn split_slice(content: &str) -> Option<((usize, usize, usize), &str)> {
+    let (header, body) = content.split_once('\n').unwrap_or((content, ""));
+    parse_slice_header(header).map(|range| (range, body))
 }
 
 pub struct FilePanel;
@@ -36,18 +53,31 @@ impl Panel for FilePanel {
         false
     }
 
-    fn handle_key(&self, key: &KeyEvent, _state: &State) -> Option<Action> {
+    fn handle_key(&self, key: &KeyEvent, state: &State) -> Option<Action> {
+        let path = || state.context.get(state.selected_context)?.get_meta_str("file_path").map(|p| p.to_string());
         match key.code {
             KeyCode::Up => Some(Action::ScrollUp(SCROLL_ARROW_AMOUNT)),
             KeyCode::Down => Some(Action::ScrollDown(SCROLL_ARROW_AMOUNT)),
             KeyCode::PageUp => Some(Action::ScrollUp(SCROLL_PAGE_AMOUNT)),
             KeyCode::PageDown => Some(Action::ScrollDown(SCROLL_PAGE_AMOUNT)),
+            KeyCode::Char('u') => path().map(Action::UndoFileEdit),
+            KeyCode::Char('r') => path().map(Action::RedoFileEdit),
             _ => None,
         }
     }
 
     fn title(&self, state: &State) -> String {
-        state.context.get(state.selected_context).map(|ctx| ctx.name.clone()).unwrap_or_else(|| "File".to_string())
+        let Some(ctx) = state.context.get(state.selected_context) else {
+            return "File".to_string();
+        };
+        // Edit history depth, with the keys that walk it
+        let undo = ctx.get_meta_usize(UNDO_DEPTH_META).unwrap_or(0);
+        let redo = ctx.get_meta_usize(REDO_DEPTH_META).unwrap_or(0);
+        match (undo, redo) {
+            (0, 0) => ctx.name.clone(),
+            (undo, 0) => format!("{} · {} undo (u)", ctx.name, undo),
+            (undo, redo) => format!("{} · {} undo (u) · {} redo (r)", ctx.name, undo, redo),
+        }
     }
 
     fn build_cache_request(&self, ctx: &ContextElement, _state: &State) -> Option<CacheRequest> {
@@ -58,6 +88,8 @@ impl Panel for FilePanel {
                 context_id: ctx.id.clone(),
                 file_path: path.to_string(),
                 current_source_hash: ctx.source_hash.clone(),
+                range: ctx.get_meta_usize(LINE_START_META).zip(ctx.get_meta_usize(LINE_END_META)),
+                symbol: ctx.get_meta_str(SYMBOL_META).map(|s| s.to_string()),
             }),
         })
     }
@@ -66,6 +98,11 @@ impl Panel for FilePanel {
         let CacheUpdate::Content { content, token_count, .. } = update else {
             return false;
         };
+        // Symbol panels learn where their definition currently is
+        if let Some(((start, end, _), _)) = split_slice(&content).filter(|_| ctx.get_meta_str(SYMBOL_META).is_some()) {
+            ctx.set_meta(LINE_START_META, &start);
+            ctx.set_meta(LINE_END_META, &end);
+        }
         ctx.source_hash = Some(cp_base::panels::hash_content(&content));
         ctx.cached_content = Some(content);
         ctx.full_token_count = token_count;
@@ -91,7 +128,7 @@ impl Panel for FilePanel {
 
     fn refresh_cache(&self, request: CacheRequest) -> Option<CacheUpdate> {
         let req = request.data.downcast::<FileCacheRequest>().ok()?;
-        let FileCacheRequest { context_id, file_path, current_source_hash } = *req;
+        let FileCacheRequest { context_id, file_path, current_source_hash, range, symbol } = *req;
         let path = PathBuf::from(&file_path);
         if !path.exists() {
             return None;
@@ -108,12 +145,27 @@ impl Panel for FilePanel {
             let token_count = estimate_tokens(&msg);
             return Some(CacheUpdate::Content { context_id, content: msg, token_count });
         }
-        let content = fs::read_to_string(&path).ok()?;
+        let mut content = fs::read_to_string(&path).ok()?;
+        if let Some(symbol) = &symbol {
+            content = match find_symbol(&content, symbol) {
+                Some((start, end)) => extract(&content, Some(symbol), start, end),
+                None => format!(
+                    "[Symbol '{}' not found in {}. Re-open the file with a line range instead.]",
+                    symbol, file_path
+                ),
+            };
+        } else if let Some((start, end)) = range {
+            content = extract(&content, None, start, end);
+        }
         let new_hash = hash_content(&content);
         if current_source_hash.as_ref() == Some(&new_hash) {
             return Some(CacheUpdate::Unchanged { context_id });
         }
-        let token_count = estimate_tokens(&content);
+        // Partial panels are sent with a line-number gutter
+        let token_count = match split_slice(&content) {
+            Some(((start, ..), body)) => estimate_tokens(&number_lines(body, start)),
+            None => estimate_tokens(&content),
+        };
         Some(CacheUpdate::Content { context_id, content, token_count })
     }
 
@@ -126,6 +178,12 @@ impl Panel for FilePanel {
                 let path = c.get_meta_str("file_path")?;
                 // Use cached content only - no blocking file reads
                 let content = c.cached_content.as_ref()?;
+                if let Some(((start, end, total), body)) = split_slice(content) {
+                    let numbered = number_lines(body, start);
+                    let output = paginate_content(&numbered, c.current_page, c.total_pages);
+                    let header = format!("File: {} (lines {}-{} of {})", path, start, end, total);
+                    return Some(ContextItem::new(&c.id, header, output, c.last_refresh_ms));
+                }
                 let output = paginate_content(content, c.current_page, c.total_pages);
                 Some(ContextItem::new(&c.id, format!("File: {}", path), output, c.last_refresh_ms))
             })
@@ -135,7 +193,7 @@ impl Panel for FilePanel {
     fn content(&self, state: &State, base_style: Style) -> Vec<Line<'static>> {
         let selected = state.context.get(state.selected_context);
 
-        let (content, file_path) = if let Some(ctx) = selected {
+        let (mut content, file_path) = if let Some(ctx) = selected {
             let path = ctx.get_meta_str("file_path").unwrap_or("");
             // Use cached content only - no blocking file reads
             let content = ctx.cached_content.clone().unwrap_or_else(|| {
@@ -146,6 +204,19 @@ impl Panel for FilePanel {
             (String::new(), String::new())
         };
 
+        let mut text: Vec<Line> = Vec::new();
+
+        // Partial panels: range banner, then line numbers counted from the range start
+        let mut first_line = 1;
+        if let Some(((start, end, total), body)) = split_slice(&content) {
+            first_line = start;
+            text.push(Line::from(Span::styled(
+                format!(" lines {}-{} of {}", start, end, total),
+                Style::default().fg(theme::text_muted()).italic(),
+            )));
+            content = body.to_string();
+        }
+
         // Get syntax highlighting
         let highlighted = if !file_path.is_empty() {
             state.highlight_fn.map(|f| f(&file_path, &content)).unwrap_or_else(|| std::sync::Arc::new(Vec::new()))
@@ -153,11 +224,9 @@ impl Panel for FilePanel {
             std::sync::Arc::new(Vec::new())
         };
 
-        let mut text: Vec<Line> = Vec::new();
-
         if highlighted.is_empty() {
             for (i, line) in content.lines().enumerate() {
-                let line_num = i + 1;
+                let line_num = first_line + i;
                 text.push(Line::from(vec![
                     Span::styled(
                         format!(" {:4} ", line_num),
@@ -169,7 +238,7 @@ impl Panel for FilePanel {
             }
         } else {
             for (i, spans) in highlighted.iter().enumerate() {
-                let line_num = i + 1;
+                let line_num = first_line + i;
                 let mut line_spans = vec![
                     Span::styled(
                         format!(" {:4} ", line_num),
@@ -189,3 +258,133 @@ impl Panel for FilePanel {
         text
     }
 }
+
+/// Panel content prefix for images over `IMAGE_MAX_BYTES` (sent as text only)
+const IMAGE_TOO_LARGE: &str = "[Image too large to attach:";
+
+pub struct ImageCacheRequest {
+    pub context_id: String,
+    pub file_path: String,
+    pub current_source_hash: Option<String>,
+}
+
+/// Image file opened as vision input. Content is a text description; the
+/// image itself travels as an attachment on the panel's `ContextItem`.
+pub struct ImagePanel;
+
+impl Panel for ImagePanel {
+    fn needs_cache(&self) -> bool {
+        true
+    }
+
+    fn suicide(&self, ctx: &ContextElement, _state: &State) -> bool {
+        if ctx.cached_content.is_some() {
+            return false;
+        }
+        if let Some(path) = ctx.get_meta_str("file_path") {
+            return !PathBuf::from(path).exists();
+        }
+        false
+    }
+
+    fn title(&self, state: &State) -> String {
+        state.context.get(state.selected_context).map(|ctx| ctx.name.clone()).unwrap_or_else(|| "Image".to_string())
+    }
+
+    fn build_cache_request(&self, ctx: &ContextElement, _state: &State) -> Option<CacheRequest> {
+        let path = ctx.get_meta_str("file_path")?;
+        Some(CacheRequest {
+            context_type: ContextType::new(IMAGE_CONTEXT_TYPE),
+            data: Box::new(ImageCacheRequest {
+                context_id: ctx.id.clone(),
+                file_path: path.to_string(),
+                current_source_hash: ctx.source_hash.clone(),
+            }),
+        })
+    }
+
+    fn apply_cache_update(&self, update: CacheUpdate, ctx: &mut ContextElement, _state: &mut State) -> bool {
+        let CacheUpdate::Content { content, token_count, .. } = update else {
+            return false;
+        };
+        ctx.source_hash = Some(hash_content(&content));
+        ctx.cached_content = Some(content);
+        ctx.token_count = token_count;
+        ctx.full_token_count = token_count;
+        ctx.total_pages = 1;
+        ctx.current_page = 0;
+        ctx.cache_deprecated = false;
+        let content_ref = ctx.cached_content.clone().unwrap_or_default();
+        update_if_changed(ctx, &content_ref);
+        true
+    }
+
+    fn refresh(&self, _state: &mut State) {
+        // Image refresh is handled by background cache system via refresh_cache
+    }
+
+    fn refresh_cache(&self, request: CacheRequest) -> Option<CacheUpdate> {
+        let req = request.data.downcast::<ImageCacheRequest>().ok()?;
+        let ImageCacheRequest { context_id, file_path, current_source_hash } = *req;
+        let path = PathBuf::from(&file_path);
+        let meta = fs::metadata(&path).ok()?;
+        if meta.len() as usize > IMAGE_MAX_BYTES {
+            let msg = format!(
+                "{} {} bytes (limit: {} bytes). Close this panel.]",
+                IMAGE_TOO_LARGE,
+                meta.len(),
+                IMAGE_MAX_BYTES
+            );
+            let token_count = estimate_tokens(&msg);
+            return Some(CacheUpdate::Content { context_id, content: msg, token_count });
+        }
+        let bytes = fs::read(&path).ok()?;
+        let (description, dims) = describe_image(&file_path, &bytes);
+        if current_source_hash.as_ref() == Some(&hash_content(&description)) {
+            return Some(CacheUpdate::Unchanged { context_id });
+        }
+        let image_tokens = dims.map(|(w, h)| estimate_image_tokens(w, h)).unwrap_or(0);
+        let token_count = estimate_tokens(&description) + image_tokens;
+        Some(CacheUpdate::Content { context_id, content: description, token_count })
+    }
+
+    fn context(&self, state: &State) -> Vec<ContextItem> {
+        state
+            .context
+            .iter()
+            .filter(|c| c.context_type == IMAGE_CONTEXT_TYPE)
+            .filter_map(|c| {
+                let path = c.get_meta_str("file_path")?;
+                let content = c.cached_content.as_ref()?;
+                let item = ContextItem::new(&c.id, format!("Image: {}", path), content.clone(), c.last_refresh_ms);
+                // Oversized images keep only their text notice
+                match media_type_for(path) {
+                    Some(media_type) if !content.starts_with(IMAGE_TOO_LARGE) => Some(
+                        item.with_image(ImageAttachment { path: path.to_string(), media_type: media_type.to_string() }),
+                    ),
+                    _ => Some(item),
+                }
+            })
+            .collect()
+    }
+
+    fn content(&self, state: &State, _base_style: Style) -> Vec<Line<'static>> {
+        let Some(ctx) = state.context.get(state.selected_context) else {
+            return Vec::new();
+        };
+        let content = ctx
+            .cached_content
+            .clone()
+            .unwrap_or_else(|| if ctx.cache_deprecated { "Loading...".to_string() } else { "No content".to_string() });
+        let mut text: Vec<Line> = content
+            .lines()
+            .map(|line| Line::from(Span::styled(format!(" {}", line), Style::default().fg(theme::text()))))
+            .collect();
+        text.push(Line::from(""));
+        text.push(Line::from(Span::styled(
+            format!(" ~{} tokens as vision input", ctx.token_count),
+            Style::default().fg(theme::text_muted()).italic(),
+        )));
+        text
+    }
+}
@@ -0,0 +1,451 @@
+//! Unified diff parsing and hunk application for the `Patch` tool.
+//!
+//! Accepts `git diff` output as well as plain `diff -u`: `a/` and `b/` path
+//! prefixes are dropped, `/dev/null` marks a created or deleted file, and git's
+//! `rename from`/`rename to` lines rename. Hunk line counts are only a hint, so
+//! hand-written hunks with wrong counts still parse.
+//!
+//! Hunks are located like GNU patch does: at the line the header names
+//! (shifted by the hunks applied before it), else at the nearest offset where
+//! the context matches, else with up to `MAX_FUZZ` outer context lines ignored.
+//! Lines are compared without trailing whitespace.
+
+use crate::tools::edit_file::{Replacement, find_closest_match};
+
+/// Most context lines ignored at each end of a hunk that does not match as-is
+pub const MAX_FUZZ: usize = 2;
+
+/// One file's section of a patch
+#[derive(Debug)]
+pub struct FilePatch<'a> {
+    /// Path before the change (`None`: the patch creates the file)
+    pub old_path: Option<&'a str>,
+    /// Path after the change (`None`: the patch deletes the file)
+    pub new_path: Option<&'a str>,
+    pub hunks: Vec<Hunk<'a>>,
+}
+
+#[derive(Debug)]
+pub struct Hunk<'a> {
+    /// "@@ -12,5 +12,6 @@" (without the trailing section heading)
+    pub header: &'a str,
+    /// First old line (1-based; 0 for a hunk that adds to an empty file)
+    pub old_start: usize,
+    /// (' ' | '-' | '+', text)
+    pub lines: Vec<(char, &'a str)>,
+    /// "\ No newline at end of file" follows the last old / new line
+    pub old_no_eol: bool,
+    pub new_no_eol: bool,
+}
+
+/// How a hunk went
+#[derive(Debug, Clone, PartialEq)]
+pub enum HunkStatus {
+    Applied,
+    /// Found this many lines away from where the header says
+    Offset(isize),
+    /// Matched only once `fuzz` outer context lines were ignored
+    Fuzz {
+        fuzz: usize,
+        offset: isize,
+    },
+    Rejected(String),
+}
+
+impl std::fmt::Display for HunkStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HunkStatus::Applied => write!(f, "applied"),
+            HunkStatus::Offset(offset) => write!(f, "applied at offset {:+} lines", offset),
+            HunkStatus::Fuzz { fuzz, offset: 0 } => write!(f, "applied with fuzz {}", fuzz),
+            HunkStatus::Fuzz { fuzz, offset } => write!(f, "applied with fuzz {} at offset {:+} lines", fuzz, offset),
+            HunkStatus::Rejected(reason) => write!(f, "rejected: {}", reason),
+        }
+    }
+}
+
+/// Result of applying one file's hunks in memory
+pub(crate) struct Applied {
+    pub content: String,
+    pub statuses: Vec<HunkStatus>,
+    /// One per applied hunk, in order, for keeping line-range panels aligned
+    pub replacements: Vec<Replacement>,
+}
+
+impl Applied {
+    pub fn rejected(&self) -> usize {
+        self.statuses.iter().filter(|s| matches!(s, HunkStatus::Rejected(_))).count()
+    }
+}
+
+/// Path from a `---`/`+++` line: timestamps cut, `/dev/null` is `None`
+fn header_path(raw: &str) -> Option<&str> {
+    let path = raw.split('\t').next().unwrap_or(raw).trim_end();
+    (path != "/dev/null").then_some(path)
+}
+
+/// Drop git's `a/` and `b/` prefixes when both sides use them
+fn strip_prefixes<'a>(old: Option<&'a str>, new: Option<&'a str>) -> (Option<&'a str>, Option<&'a str>) {
+    let git_style = old.is_none_or(|p| p.starts_with("a/")) && new.is_none_or(|p| p.starts_with("b/"));
+    if git_style && (old.is_some() || new.is_some()) {
+        (old.map(|p| &p[2..]), new.map(|p| &p[2..]))
+    } else {
+        (old, new)
+    }
+}
+
+/// "-12,5" / "+12" → 12
+fn range_start(range: &str) -> Option<usize> {
+    range[1..].split(',').next()?.parse().ok()
+}
+
+fn is_file_header(lines: &[&str], i: usize) -> bool {
+    lines[i].starts_with("--- ") && lines.get(i + 1).is_some_and(|n| n.starts_with("+++ "))
+}
+
+/// Parse a unified diff into per-file sections
+pub fn parse_patch(text: &str) -> Result<Vec<FilePatch<'_>>, String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut files: Vec<FilePatch<'_>> = Vec::new();
+    // A `diff --git` header was seen and its `---`/`+++` lines may still follow
+    let mut git_header_open = false;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            let (old, new) = match rest.split_once(" b/") {
+                Some((old, new)) => (Some(old), Some(&rest[old.len() + 1..old.len() + 3 + new.len()])),
+                None => (None, None),
+            };
+            let (old_path, new_path) = strip_prefixes(old, new);
+            files.push(FilePatch { old_path, new_path, hunks: Vec::new() });
+            git_header_open = true;
+            i += 1;
+        } else if is_file_header(&lines, i) {
+            let (old_path, new_path) = strip_prefixes(header_path(&line[4..]), header_path(&lines[i + 1][4..]));
+            // Lines naming other paths start a new section even right after a git header
+            let same_file = |f: &FilePatch<'_>| {
+                old_path.is_none_or(|p| Some(p) == f.old_path) && new_path.is_none_or(|p| Some(p) == f.new_path)
+            };
+            match files.last_mut() {
+                Some(file) if git_header_open && same_file(file) => {
+                    (file.old_path, file.new_path) = (old_path, new_path)
+                }
+                _ => files.push(FilePatch { old_path, new_path, hunks: Vec::new() }),
+            }
+            git_header_open = false;
+            i += 2;
+        } else if line.starts_with("@@ ") {
+            let Some(file) = files.last_mut() else {
+                return Err(format!("Hunk on line {} has no ---/+++ file header before it", i + 1));
+            };
+            git_header_open = false;
+            let (hunk, next) = parse_hunk(&lines, i)?;
+            file.hunks.push(hunk);
+            i = next;
+        } else {
+            if let Some(file) = files.last_mut().filter(|_| git_header_open) {
+                if let Some(path) = line.strip_prefix("rename from ") {
+                    file.old_path = Some(path);
+                } else if let Some(path) = line.strip_prefix("rename to ") {
+                    file.new_path = Some(path);
+                } else if line.starts_with("new file mode") {
+                    file.old_path = None;
+                } else if line.starts_with("deleted file mode") {
+                    file.new_path = None;
+                }
+            }
+            if line.starts_with("GIT binary patch") || (line.starts_with("Binary files ") && line.ends_with(" differ"))
+            {
+                return Err(format!("Line {}: binary patches are not supported", i + 1));
+            }
+            i += 1;
+        }
+    }
+    files.retain(|f| f.old_path.is_some() || f.new_path.is_some());
+    if files.is_empty() {
+        return Err("No file changes found: expected a unified diff with ---/+++ headers and @@ hunks".to_string());
+    }
+    Ok(files)
+}
+
+/// Parse the hunk whose header is `lines[start]`; returns it and the index after it.
+/// Counts from the header decide where a hunk ends only while they last: a
+/// body line after that still belongs to the hunk unless it starts the next
+/// hunk or file, or is blank.
+fn parse_hunk<'a>(lines: &[&'a str], start: usize) -> Result<(Hunk<'a>, usize), String> {
+    let header_line = lines[start];
+    let bad = || format!("Line {}: malformed hunk header '{}'", start + 1, header_line);
+    let mut parts = header_line.split_whitespace().skip(1);
+    let old_range = parts.next().filter(|r| r.starts_with('-')).ok_or_else(bad)?;
+    let new_range = parts.next().filter(|r| r.starts_with('+')).ok_or_else(bad)?;
+    let old_start = range_start(old_range).ok_or_else(bad)?;
+    range_start(new_range).ok_or_else(bad)?;
+    let count = |range: &str| range.split_once(',').map_or(Some(1), |(_, n)| n.parse::<usize>().ok());
+    let (mut old_left, mut new_left) = (count(old_range).unwrap_or(0), count(new_range).unwrap_or(0));
+    let header_end = header_line[2..].find("@@").map_or(header_line.len(), |p| p + 4);
+
+    let mut hunk =
+        Hunk { header: &header_line[..header_end], old_start, lines: Vec::new(), old_no_eol: false, new_no_eol: false };
+    let mut i = start + 1;
+    while i < lines.len() {
+        let line = lines[i];
+        let counted = old_left > 0 || new_left > 0;
+        if !counted
+            && (line.is_empty() || line.starts_with("@@") || line.starts_with("diff ") || is_file_header(lines, i))
+        {
+            break;
+        }
+        let (kind, text) = match line.chars().next() {
+            None => (' ', ""),
+            Some(c @ (' ' | '-' | '+')) => (c, &line[1..]),
+            Some('\\') => {
+                match hunk.lines.last().map(|(k, _)| *k) {
+                    Some('-') => hunk.old_no_eol = true,
+                    Some('+') => hunk.new_no_eol = true,
+                    _ => (hunk.old_no_eol, hunk.new_no_eol) = (true, true),
+                }
+                i += 1;
+                continue;
+            }
+            Some(_) => break,
+        };
+        if kind != '+' {
+            old_left = old_left.saturating_sub(1);
+        }
+        if kind != '-' {
+            new_left = new_left.saturating_sub(1);
+        }
+        hunk.lines.push((kind, text));
+        i += 1;
+    }
+    // Blank lines that only pad the end of a hunk are not context
+    while hunk.lines.last() == Some(&(' ', "")) && lines.get(i - 1).is_some_and(|l| l.is_empty()) {
+        hunk.lines.pop();
+        i -= 1;
+    }
+    if hunk.lines.is_empty() {
+        return Err(format!("Line {}: hunk '{}' has no lines", start + 1, hunk.header));
+    }
+    Ok((hunk, i))
+}
+
+/// Every path a patch touches (old and new sides), in order, without repeats.
+/// Unparseable patches name no paths.
+pub fn patch_paths(text: &str) -> Vec<&str> {
+    let mut paths: Vec<&str> = Vec::new();
+    for file in parse_patch(text).unwrap_or_default() {
+        for path in [file.old_path, file.new_path].into_iter().flatten() {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
+
+/// Where `hunk` applies in `lines`: (position, context lines ignored at the
+/// front and back, fuzz). Positions below `floor` belong to earlier hunks.
+fn locate(lines: &[String], hunk: &Hunk<'_>, expected: isize, floor: usize) -> Option<(usize, usize, usize, usize)> {
+    let leading = hunk.lines.iter().take_while(|(k, _)| *k == ' ').count();
+    let trailing = hunk.lines.iter().rev().take_while(|(k, _)| *k == ' ').count();
+    let mut tried = Vec::new();
+    for fuzz in 0..=MAX_FUZZ {
+        let (front, back) = (fuzz.min(leading), fuzz.min(trailing));
+        if tried.contains(&(front, back)) {
+            continue;
+        }
+        tried.push((front, back));
+        let old: Vec<&str> = hunk.lines[front..hunk.lines.len() - back]
+            .iter()
+            .filter(|(k, _)| *k != '+')
+            .map(|(_, t)| t.trim_end())
+            .collect();
+        if old.is_empty() || old.len() > lines.len() {
+            continue;
+        }
+        let last = lines.len() - old.len();
+        let matches_at = |pos: usize| old.iter().zip(&lines[pos..]).all(|(want, have)| *want == have.trim_end());
+        // Nearest match to the expected line first, looking both ways
+        let expected = (expected + front as isize).clamp(0, last as isize) as usize;
+        for distance in 0..=lines.len() {
+            let candidates = [expected.checked_add(distance), expected.checked_sub(distance).filter(|_| distance > 0)];
+            for pos in candidates.into_iter().flatten() {
+                if pos >= floor && pos <= last && matches_at(pos) {
+                    return Some((pos, front, back, fuzz));
+                }
+            }
+            if expected + distance > last && expected < floor + distance {
+                break;
+            }
+        }
+    }
+    None
+}
+
+/// Why a hunk did not match, naming the closest line
+fn rejection(lines: &[String], hunk: &Hunk<'_>) -> String {
+    let needle: Vec<&str> =
+        hunk.lines.iter().filter(|(k, _)| *k != '+').map(|(_, t)| *t).skip_while(|t| t.trim().is_empty()).collect();
+    let hint = find_closest_match(&lines.join("\n"), &needle.join("\n"))
+        .map(|(line, preview)| format!(" (closest match at line {}: \"{}\")", line, preview))
+        .unwrap_or_default();
+    format!("context not found{}", hint)
+}
+
+/// Apply `hunks` to `original` in memory. A rejected hunk is skipped and the
+/// following ones still try, so every hunk gets a status.
+pub(crate) fn apply_hunks(original: &str, hunks: &[Hunk<'_>]) -> Applied {
+    let newline = if original.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut lines: Vec<String> = original.lines().map(|l| l.to_string()).collect();
+    let mut trailing_newline = original.is_empty() || original.ends_with('\n');
+    let mut statuses = Vec::new();
+    let mut replacements = Vec::new();
+    // Lines added minus lines removed by the hunks applied so far
+    let mut delta: isize = 0;
+    let mut floor = 0;
+
+    for hunk in hunks {
+        let expected = hunk.old_start.saturating_sub(1) as isize + delta;
+        let pure_insert = hunk.lines.iter().all(|(k, _)| *k == '+');
+        let found = if pure_insert {
+            // "-N,0": insert after line N
+            let at = hunk.old_start as isize + delta;
+            (at >= floor as isize && at <= lines.len() as isize).then_some((at as usize, 0, 0, 0))
+        } else {
+            locate(&lines, hunk, expected, floor)
+        };
+        let Some((pos, front, back, fuzz)) = found else {
+            statuses.push(HunkStatus::Rejected(if pure_insert {
+                format!("line {} is past the end of the file ({} lines)", hunk.old_start, lines.len())
+            } else {
+                rejection(&lines, hunk)
+            }));
+            continue;
+        };
+
+        // Context lines keep the file's own text; only -/+ lines come from the patch
+        let mut cursor = pos;
+        let (mut old, mut new) = (Vec::new(), Vec::new());
+        for (kind, text) in &hunk.lines[front..hunk.lines.len() - back] {
+            match kind {
+                ' ' => {
+                    old.push(lines[cursor].clone());
+                    new.push(lines[cursor].clone());
+                    cursor += 1;
+                }
+                '-' => {
+                    old.push(lines[cursor].clone());
+                    cursor += 1;
+                }
+                _ => new.push(text.to_string()),
+            }
+        }
+        let at_end = cursor == lines.len();
+        lines.splice(pos..cursor, new.iter().cloned());
+        if at_end && hunk.new_no_eol {
+            trailing_newline = false;
+        } else if at_end && hunk.old_no_eol {
+            trailing_newline = true;
+        }
+
+        let wanted = if pure_insert { hunk.old_start as isize + delta } else { expected };
+        let offset = pos as isize - front as isize - wanted;
+        statuses.push(match (fuzz, offset) {
+            (0, 0) => HunkStatus::Applied,
+            (0, offset) => HunkStatus::Offset(offset),
+            (fuzz, offset) => HunkStatus::Fuzz { fuzz, offset },
+        });
+        delta += new.len() as isize - old.len() as isize;
+        floor = pos + new.len();
+        replacements.push(Replacement { old: old.join("\n"), new: new.join("\n"), count: 1, line: Some(pos + 1) });
+    }
+
+    let mut content = lines.join(newline);
+    if !lines.is_empty() && trailing_newline {
+        content.push_str(newline);
+    }
+    Applied { content, statuses, replacements }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATCH: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,4 +1,4 @@
+ fn a() {}
+-fn b() {}
++fn b2() {}
+ fn c() {}
+ fn d() {}
+diff --git a/old.rs b/new.rs
+similarity index 100%
+rename from old.rs
+rename to new.rs
+--- /dev/null
++++ b/added.txt
+@@ -0,0 +1,2 @@
++one
++two
+\\ No newline at end of file
+";
+
+    #[test]
+    fn parses_git_and_plain_sections() {
+        let files = parse_patch(PATCH).unwrap();
+        let paths: Vec<_> = files.iter().map(|f| (f.old_path, f.new_path, f.hunks.len())).collect();
+        assert_eq!(
+            paths,
+            vec![
+                (Some("src/lib.rs"), Some("src/lib.rs"), 1),
+                (Some("old.rs"), Some("new.rs"), 0),
+                (None, Some("added.txt"), 1),
+            ]
+        );
+        assert_eq!(files[0].hunks[0].header, "@@ -1,4 +1,4 @@");
+        assert!(files[2].hunks[0].new_no_eol);
+        assert_eq!(patch_paths(PATCH), vec!["src/lib.rs", "old.rs", "new.rs", "added.txt"]);
+
+        let created = apply_hunks("", &files[2].hunks);
+        assert_eq!(created.content, "one\ntwo");
+    }
+
+    #[test]
+    fn offset_fuzz_and_rejection() {
+        let patch = "\
+--- a.rs
++++ a.rs
+@@ -1,3 +1,3 @@
+ x
+-y
++Y
+ z
+@@ -10,5 +10,5 @@
+ stale
+ k
+-l
++L
+ m
+ stale
+@@ -20,3 +20,3 @@
+ nothing
+-like
++this
+";
+        let files = parse_patch(patch).unwrap();
+        // Two lines were added at the top since the patch was made
+        let original = "new1\nnew2\nx\ny\nz\ni\nj\nk\nl\nm\nn\n";
+        let applied = apply_hunks(original, &files[0].hunks);
+        assert_eq!(applied.statuses[0], HunkStatus::Offset(2));
+        assert_eq!(applied.statuses[1], HunkStatus::Fuzz { fuzz: 1, offset: -3 });
+        assert!(matches!(&applied.statuses[2], HunkStatus::Rejected(r) if r.starts_with("context not found")));
+        assert_eq!(applied.rejected(), 1);
+        assert_eq!(applied.content, "new1\nnew2\nx\nY\nz\ni\nj\nk\nL\nm\nn\n");
+        assert_eq!(applied.replacements[0].line, Some(3));
+    }
+}
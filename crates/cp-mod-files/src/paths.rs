@@ -0,0 +1,100 @@
+//! Project-relative paths, the protected-path list, and the moves a tool call made.
+
+use std::path::Path;
+
+use crate::patch::parse_patch;
+
+/// Paths the file-management tools refuse to move, delete or write into:
+/// version control and Context Pilot's own store. The project root and
+/// anything outside it are refused too.
+pub const PROTECTED_PATHS: &[&str] = &[".git", ".context-pilot"];
+
+pub use cp_base::modules::project_path;
+
+/// Relative paths, or absolute ones under the working directory
+pub fn in_project(path: &str) -> bool {
+    let path = Path::new(path);
+    !path.is_absolute() || std::env::current_dir().is_ok_and(|cwd| path.starts_with(cwd))
+}
+
+/// Refuse a path that is, contains or lies inside a protected one
+pub fn check_protected(path: &str) -> Result<(), String> {
+    if !in_project(path) {
+        return Err(format!("'{}' is outside the project", path));
+    }
+    let rel = project_path(path);
+    if rel == "." || rel.split('/').any(|c| c == "..") {
+        return Err(format!("'{}' is the project root or leaves it", path));
+    }
+    let under = |a: &str, b: &str| a == b || a.starts_with(&format!("{}/", b));
+    match PROTECTED_PATHS.iter().find(|p| under(&rel, p) || under(p, &rel)) {
+        Some(protected) => Err(format!("'{}' is protected ({} is off limits to file tools)", path, protected)),
+        None => Ok(()),
+    }
+}
+
+/// (from, to) for every path a successful tool call moved (`to` is `None`
+/// for a deletion), project-relative
+pub fn moved_paths(tool_name: &str, input: &serde_json::Value) -> Vec<(String, Option<String>)> {
+    let field = |name: &str| input.get(name).and_then(|v| v.as_str());
+    match tool_name {
+        "file_move" => match (field("source"), field("destination")) {
+            (Some(from), Some(to)) => vec![(project_path(from), Some(project_path(to)))],
+            _ => vec![],
+        },
+        "file_delete" => field("path").map(|p| vec![(project_path(p), None)]).unwrap_or_default(),
+        "Patch" => {
+            let files = field("patch").and_then(|text| parse_patch(text).ok()).unwrap_or_default();
+            files
+                .iter()
+                .filter_map(|f| match (f.old_path, f.new_path) {
+                    (Some(old), new) if new != Some(old) => Some((project_path(old), new.map(project_path))),
+                    _ => None,
+                })
+                .collect()
+        }
+        _ => vec![],
+    }
+}
+
+/// Run `f` with the working directory switched to a fresh temp dir, so
+/// tests can use project-relative paths. Serialized: the cwd is process-wide.
+#[cfg(test)]
+pub(crate) fn in_temp_project<R>(name: &str, f: impl FnOnce() -> R) -> R {
+    use std::sync::Mutex;
+
+    static CWD_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Restores the cwd and removes the temp dir even when `f` panics
+    struct Restore(std::path::PathBuf, std::path::PathBuf);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let _ = std::env::set_current_dir(&self.0);
+            let _ = std::fs::remove_dir_all(&self.1);
+        }
+    }
+
+    let _lock = CWD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = std::env::temp_dir().join(format!("cp_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let _restore = Restore(std::env::current_dir().unwrap(), dir.clone());
+    std::env::set_current_dir(&dir).unwrap();
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn protected_paths_are_refused() {
+        assert!(check_protected("src/main.rs").is_ok());
+        assert!(check_protected("./.git/config").is_err());
+        assert!(check_protected(".context-pilot").is_err());
+        assert!(check_protected(".").is_err());
+        assert!(check_protected("../elsewhere").is_err());
+        assert!(check_protected("/etc/passwd").is_err());
+        assert!(check_protected(".github/workflows").is_ok());
+    }
+}
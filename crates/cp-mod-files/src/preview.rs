@@ -0,0 +1,70 @@
+//! Dry-run previews of `Edit` and `Write`.
+//!
+//! A dry run computes the new file content and its diff without writing
+//! anything, and parks the result here under an ID like `V3`. `apply_preview`
+//! writes it later, provided the file still has the content the preview was
+//! computed from. Previews live in memory only.
+
+use cp_base::state::State;
+
+use crate::tools::edit_file::Replacement;
+
+/// Oldest previews are dropped beyond this many
+pub const MAX_PREVIEWS: usize = 20;
+
+/// What produced a preview (decides how panels are updated on apply)
+pub(crate) enum PreviewKind {
+    /// Replacements, with their replace_all flag, for keeping line ranges aligned
+    Edit(Vec<(Replacement, bool)>),
+    Write,
+}
+
+pub(crate) struct Preview {
+    pub id: String,
+    pub path: String,
+    /// File content the preview was computed from (`None`: file did not exist)
+    pub original: Option<String>,
+    pub content: String,
+    /// Rendered result (header + diff block) shown again on apply
+    pub diff: String,
+    pub kind: PreviewKind,
+}
+
+pub(crate) struct PreviewState {
+    pub previews: Vec<Preview>,
+    pub next_id: usize,
+}
+
+impl PreviewState {
+    pub fn new() -> Self {
+        Self { previews: Vec::new(), next_id: 1 }
+    }
+
+    pub fn get_mut(state: &mut State) -> &mut Self {
+        state.get_ext_mut::<Self>().expect("PreviewState not initialized")
+    }
+
+    /// Store a preview; returns its ID
+    pub fn record(
+        &mut self,
+        path: &str,
+        original: Option<String>,
+        content: String,
+        diff: String,
+        kind: PreviewKind,
+    ) -> String {
+        let id = format!("V{}", self.next_id);
+        self.next_id += 1;
+        self.previews.push(Preview { id: id.clone(), path: path.to_string(), original, content, diff, kind });
+        if self.previews.len() > MAX_PREVIEWS {
+            self.previews.remove(0);
+        }
+        id
+    }
+
+    /// Remove and return a preview
+    pub fn take(&mut self, id: &str) -> Option<Preview> {
+        let idx = self.previews.iter().position(|p| p.id == id)?;
+        Some(self.previews.remove(idx))
+    }
+}
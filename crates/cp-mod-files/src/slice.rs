@@ -0,0 +1,234 @@
+//! Partial file views: a line range or a symbol's definition instead of the whole file.
+//!
+//! A partial panel's cached content starts with a header line carrying the
+//! resolved range (`[lines 120-185 of 3012]`), followed by the raw lines. The
+//! LLM sees them with a line-number gutter; the TUI numbers them from the
+//! range start.
+
+/// Panel metadata: first line shown (1-based)
+pub const LINE_START_META: &str = "line_start";
+/// Panel metadata: last line shown (1-based, inclusive)
+pub const LINE_END_META: &str = "line_end";
+/// Panel metadata: symbol whose definition is shown (range re-resolved on every refresh)
+pub const SYMBOL_META: &str = "symbol";
+
+/// Words that introduce a definition, across the languages we commonly open
+const DEFINITION_KEYWORDS: &[&str] = &[
+    "fn",
+    "struct",
+    "enum",
+    "trait",
+    "impl",
+    "mod",
+    "type",
+    "const",
+    "static",
+    "union",
+    "macro_rules!",
+    "class",
+    "def",
+    "function",
+    "interface",
+    "func",
+];
+
+/// Comment and attribute lines that belong to the definition below them
+const LEADING_PREFIXES: &[&str] = &["///", "//!", "#[", "@", "/**", "* ", "*/"];
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Byte offset of `name` in `line` as a whole word
+fn find_word(line: &str, name: &str) -> Option<usize> {
+    line.match_indices(name).map(|(i, _)| i).find(|&i| {
+        let before = line[..i].chars().next_back();
+        let after = line[i + name.len()..].chars().next();
+        !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+    })
+}
+
+/// Whether `line` defines `name`, optionally with the given keywords (e.g. ["fn"])
+fn defines(line: &str, name: &str, keywords: &[&str]) -> bool {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("//") || trimmed.starts_with('#') && !trimmed.starts_with("#[") {
+        return false;
+    }
+    let Some(pos) = find_word(line, name) else {
+        return false;
+    };
+    let words: Vec<&str> =
+        line[..pos].split(|c: char| !is_word_char(c) && c != '!').filter(|w| !w.is_empty()).collect();
+    if keywords.is_empty() {
+        words.iter().any(|w| DEFINITION_KEYWORDS.contains(w))
+    } else {
+        keywords.iter().all(|k| words.contains(k))
+    }
+}
+
+/// Last line (0-based) of the block opened at `start`: brace matching, or
+/// indentation for `:`-terminated headers (Python)
+fn block_end(lines: &[&str], start: usize) -> usize {
+    let header = lines[start].trim_end();
+    if header.ends_with(':') {
+        let indent = lines[start].len() - lines[start].trim_start().len();
+        let mut end = start;
+        for (i, line) in lines.iter().enumerate().skip(start + 1) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if line.len() - line.trim_start().len() <= indent {
+                break;
+            }
+            end = i;
+        }
+        return end;
+    }
+
+    let mut depth = 0usize;
+    let mut opened = false;
+    for (i, line) in lines.iter().enumerate().skip(start) {
+        let code = line.split("//").next().unwrap_or("");
+        let mut in_string = false;
+        let mut prev = '\0';
+        for c in code.chars() {
+            if c == '"' && prev != '\\' {
+                in_string = !in_string;
+            } else if !in_string {
+                match c {
+                    '{' => {
+                        depth += 1;
+                        opened = true;
+                    }
+                    '}' => depth = depth.saturating_sub(1),
+                    // `struct Foo;`, `type X = Y;`, trait method declarations
+                    ';' if !opened => return i,
+                    _ => {}
+                }
+            }
+            prev = c;
+        }
+        if opened && depth == 0 {
+            return i;
+        }
+    }
+    lines.len().saturating_sub(1)
+}
+
+/// Lines (1-based, inclusive) of the definition of `symbol` — "assemble_prompt",
+/// "fn assemble_prompt", "impl State" — including its doc comments and attributes
+pub fn find_symbol(content: &str, symbol: &str) -> Option<(usize, usize)> {
+    let words: Vec<&str> = symbol.split_whitespace().collect();
+    let (name, keywords) = words.split_last()?;
+    let lines: Vec<&str> = content.lines().collect();
+    let def = lines.iter().position(|l| defines(l, name, keywords))?;
+    let end = block_end(&lines, def);
+    let mut start = def;
+    while start > 0 && LEADING_PREFIXES.iter().any(|p| lines[start - 1].trim_start().starts_with(p)) {
+        start -= 1;
+    }
+    Some((start + 1, end + 1))
+}
+
+/// Header line of a partial panel's content
+pub fn slice_header(symbol: Option<&str>, start: usize, end: usize, total: usize) -> String {
+    match symbol {
+        Some(symbol) => format!("[{}: lines {}-{} of {}]", symbol, start, end, total),
+        None => format!("[lines {}-{} of {}]", start, end, total),
+    }
+}
+
+/// (start, end, total) from a header written by `slice_header`
+pub fn parse_slice_header(line: &str) -> Option<(usize, usize, usize)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let range = inner.rsplit_once("lines ")?.1;
+    let (span, total) = range.split_once(" of ")?;
+    let (start, end) = span.split_once('-')?;
+    Some((start.parse().ok()?, end.parse().ok()?, total.parse().ok()?))
+}
+
+/// Build a partial panel's content: header, then lines `start..=end` (clamped)
+pub fn extract(content: &str, symbol: Option<&str>, start: usize, end: usize) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let total = lines.len();
+    let start = start.clamp(1, total.max(1));
+    let end = end.clamp(start, total.max(1));
+    let body = lines.get(start - 1..end).map(|s| s.join("\n")).unwrap_or_default();
+    format!("{}\n{}", slice_header(symbol, start, end, total), body)
+}
+
+/// Prefix each line with its number, starting at `first_line`
+pub fn number_lines(text: &str, first_line: usize) -> String {
+    let mut out = String::with_capacity(text.len() + text.lines().count() * 8);
+    for (i, line) in text.lines().enumerate() {
+        out.push_str(&format!("{:>5} | {}\n", first_line + i, line));
+    }
+    out
+}
+
+/// `text` without the gutter added by `number_lines`, if every line carries one
+/// (an edit's old_string copied straight from a partial panel)
+pub fn strip_line_numbers(text: &str) -> Option<String> {
+    let stripped: Option<Vec<&str>> = text
+        .lines()
+        .map(|line| {
+            let (num, rest) = line.split_once(" | ").or_else(|| line.strip_suffix(" |").map(|n| (n, "")))?;
+            num.trim().parse::<usize>().ok().map(|_| rest)
+        })
+        .collect();
+    stripped.filter(|s| !s.is_empty()).map(|s| s.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RUST: &str = "use std::fs;\n\
+        \n\
+        /// Adds one.\n\
+        #[inline]\n\
+        pub fn add_one(x: u32) -> u32 {\n\
+        \x20   let s = \"}\";\n\
+        \x20   x + 1\n\
+        }\n\
+        \n\
+        pub struct Unit;\n\
+        \n\
+        impl Display for Unit {\n\
+        \x20   fn fmt(&self) {}\n\
+        }\n";
+
+    #[test]
+    fn finds_definitions_with_docs_and_attributes() {
+        assert_eq!(find_symbol(RUST, "add_one"), Some((3, 8)));
+        assert_eq!(find_symbol(RUST, "fn add_one"), Some((3, 8)));
+        assert_eq!(find_symbol(RUST, "Unit"), Some((10, 10)));
+        assert_eq!(find_symbol(RUST, "impl Display"), Some((12, 14)));
+        assert_eq!(find_symbol(RUST, "struct add_one"), None);
+        assert_eq!(find_symbol(RUST, "missing"), None);
+    }
+
+    #[test]
+    fn python_blocks_end_at_dedent() {
+        let py = "import os\n\n@cached\ndef load(path):\n    data = 1\n\n    return data\n\nprint(load)\n";
+        assert_eq!(find_symbol(py, "def load"), Some((3, 7)));
+    }
+
+    #[test]
+    fn header_round_trip_and_clamping() {
+        let content = "a\nb\nc\nd\n";
+        let sliced = extract(content, None, 2, 9);
+        assert_eq!(sliced, "[lines 2-4 of 4]\nb\nc\nd");
+        assert_eq!(parse_slice_header("[lines 2-4 of 4]"), Some((2, 4, 4)));
+        assert_eq!(parse_slice_header("[fn add_one: lines 3-8 of 14]"), Some((3, 8, 14)));
+        assert_eq!(parse_slice_header("fn main() {"), None);
+    }
+
+    #[test]
+    fn line_number_gutter_round_trip() {
+        let numbered = number_lines("fn a() {\n    b();\n}", 41);
+        assert_eq!(numbered, "   41 | fn a() {\n   42 |     b();\n   43 | }\n");
+        assert_eq!(strip_line_numbers(&numbered).as_deref(), Some("fn a() {\n    b();\n}"));
+        assert_eq!(strip_line_numbers("fn a() {\n    b();"), None);
+    }
+}
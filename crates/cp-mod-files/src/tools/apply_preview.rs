@@ -0,0 +1,137 @@
+use std::fs;
+use std::path::Path;
+
+use cp_base::state::State;
+use cp_base::tools::{ToolResult, ToolUse};
+
+use super::edit_file::{Replacement, update_panel};
+use super::write::write_file;
+use crate::history;
+use crate::preview::{PreviewKind, PreviewState};
+
+pub fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let Some(preview_id) = tool.input.get("preview_id").and_then(|v| v.as_str()) else {
+        return ToolResult::new(tool.id.clone(), "Missing required parameter: preview_id".to_string(), true);
+    };
+    let Some(path_str) = tool.input.get("file_path").and_then(|v| v.as_str()) else {
+        return ToolResult::new(tool.id.clone(), "Missing required parameter: file_path".to_string(), true);
+    };
+
+    let previews = PreviewState::get_mut(state);
+    let preview = match previews.previews.iter().find(|p| p.id == preview_id) {
+        None => {
+            let known: Vec<&str> = previews.previews.iter().map(|p| p.id.as_str()).collect();
+            let known = if known.is_empty() { "none".to_string() } else { known.join(", ") };
+            return ToolResult::new(
+                tool.id.clone(),
+                format!("Unknown preview '{}' (available: {})", preview_id, known),
+                true,
+            );
+        }
+        Some(p) if p.path != path_str => {
+            return ToolResult::new(
+                tool.id.clone(),
+                format!("Preview {} is for '{}', not '{}'", preview_id, p.path, path_str),
+                true,
+            );
+        }
+        Some(p) => p,
+    };
+
+    // The preview only holds if the file is exactly as it was when it was made.
+    // A rejected apply keeps the preview; it is only spent once written.
+    let current = if Path::new(path_str).exists() { fs::read_to_string(path_str).ok() } else { None };
+    if current != preview.original {
+        return ToolResult::new(
+            tool.id.clone(),
+            format!(
+                "'{}' changed since preview {} was made; nothing written. Re-run the edit (dry_run again to re-check).",
+                path_str, preview_id
+            ),
+            true,
+        );
+    }
+    let Some(preview) = previews.take(preview_id) else {
+        return ToolResult::new(tool.id.clone(), format!("Unknown preview '{}'", preview_id), true);
+    };
+
+    let written = match &preview.kind {
+        PreviewKind::Edit(edits) => {
+            fs::write(path_str, &preview.content).map_err(|e| format!("Failed to write file: {}", e)).map(|()| {
+                let edits: Vec<(&Replacement, bool)> = edits.iter().map(|(e, all)| (e, *all)).collect();
+                update_panel(state, path_str, &preview.content, &edits);
+                history::record(state, path_str, preview.original.as_deref(), Some(&preview.content));
+            })
+        }
+        PreviewKind::Write => write_file(state, path_str, &preview.content),
+    };
+    match written {
+        Ok(()) => ToolResult::new(tool.id.clone(), format!("Applied preview {}\n{}", preview_id, preview.diff), false),
+        Err(e) => ToolResult::new(tool.id.clone(), e, true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cp_base::state::{ContextType, make_default_context_element};
+
+    fn tool(name: &str, input: serde_json::Value) -> ToolUse {
+        ToolUse { id: "t1".to_string(), name: name.to_string(), input }
+    }
+
+    fn preview_id(result: &ToolResult) -> String {
+        result.content.strip_prefix("Preview ").and_then(|r| r.split_whitespace().next()).unwrap().to_string()
+    }
+
+    #[test]
+    fn dry_run_then_apply() {
+        let dir = std::env::temp_dir().join(format!("cp_preview_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("a.txt").to_string_lossy().to_string();
+        fs::write(&path, "one\ntwo\n").unwrap();
+        let mut state = State::default();
+        state.set_ext(PreviewState::new());
+        let mut ctx = make_default_context_element("P10", ContextType::new(ContextType::FILE), "a.txt", false);
+        ctx.set_meta("file_path", &path);
+        state.context.push(ctx);
+
+        // Dry-run edit: nothing written
+        let edit = tool(
+            "Edit",
+            serde_json::json!({ "file_path": path, "old_string": "two", "new_string": "2", "dry_run": true }),
+        );
+        let result = super::super::edit_file::execute_edit(&edit, &mut state);
+        assert!(!result.is_error, "{}", result.content);
+        assert!(result.content.contains("- two\n+ 2\n"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "one\ntwo\n");
+        let id = preview_id(&result);
+
+        // Wrong path, then a successful apply, then the ID is spent
+        let apply = |p: &str| tool("apply_preview", serde_json::json!({ "preview_id": id, "file_path": p }));
+        assert!(execute(&apply("other.txt"), &mut state).is_error);
+        let applied = execute(&apply(&path), &mut state);
+        assert!(!applied.is_error, "{}", applied.content);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "one\n2\n");
+        assert!(execute(&apply(&path), &mut state).content.starts_with("Unknown preview"));
+
+        // A dry-run write goes stale when the file changes underneath it
+        let write = tool("Write", serde_json::json!({ "file_path": path, "contents": "new\n", "dry_run": true }));
+        let result = super::super::write::execute(&write, &mut state);
+        let id = preview_id(&result);
+        fs::write(&path, "external\n").unwrap();
+        let stale =
+            execute(&tool("apply_preview", serde_json::json!({ "preview_id": id, "file_path": path })), &mut state);
+        assert!(stale.is_error);
+        assert!(stale.content.contains("changed since preview"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "external\n");
+
+        // The rejected apply did not spend the preview: it applies once the file is back
+        fs::write(&path, "one\n2\n").unwrap();
+        let retried =
+            execute(&tool("apply_preview", serde_json::json!({ "preview_id": id, "file_path": path })), &mut state);
+        assert!(!retried.is_error, "{}", retried.content);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new\n");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
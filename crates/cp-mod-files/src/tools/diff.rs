@@ -23,6 +23,67 @@ pub fn generate_unified_diff(old: &str, new: &str) -> String {
     result
 }
 
+/// Above this many LCS cells (old lines × new lines) the changed middle of a
+/// file diff is shown as a block replacement instead of a line-level diff
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// Diff of two whole files in the same format as `generate_unified_diff`.
+/// Unchanged lines more than `context` lines away from a change are folded
+/// into a single "  ... (N unchanged lines)" marker.
+pub fn generate_file_diff(old: &str, new: &str, context: usize) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    // Only the middle between the common prefix and suffix needs an LCS
+    let prefix = old_lines.iter().zip(&new_lines).take_while(|(a, b)| a == b).count();
+    let max_suffix = old_lines.len().min(new_lines.len()) - prefix;
+    let suffix =
+        old_lines.iter().rev().zip(new_lines.iter().rev()).take(max_suffix).take_while(|(a, b)| a == b).count();
+    let old_mid = &old_lines[prefix..old_lines.len() - suffix];
+    let new_mid = &new_lines[prefix..new_lines.len() - suffix];
+
+    let mut ops: Vec<DiffOp> = old_lines[..prefix].iter().map(|l| DiffOp::Equal(l)).collect();
+    if old_mid.len().saturating_mul(new_mid.len()) <= MAX_LCS_CELLS {
+        ops.extend(compute_diff(old_mid, new_mid));
+    } else {
+        ops.extend(old_mid.iter().map(|l| DiffOp::Delete(l)));
+        ops.extend(new_mid.iter().map(|l| DiffOp::Insert(l)));
+    }
+    ops.extend(old_lines[old_lines.len() - suffix..].iter().map(|l| DiffOp::Equal(l)));
+
+    // Keep every change plus `context` lines on each side of it
+    let mut keep = vec![false; ops.len()];
+    for (i, op) in ops.iter().enumerate() {
+        if !matches!(op, DiffOp::Equal(_)) {
+            let from = i.saturating_sub(context);
+            let to = (i + context + 1).min(ops.len());
+            keep[from..to].iter_mut().for_each(|k| *k = true);
+        }
+    }
+
+    let mut result = String::new();
+    let mut folded = 0;
+    for (op, keep) in ops.iter().zip(&keep) {
+        if !keep {
+            folded += 1;
+            continue;
+        }
+        if folded > 0 {
+            result.push_str(&format!("  ... ({} unchanged lines)\n", folded));
+            folded = 0;
+        }
+        match op {
+            DiffOp::Equal(line) => result.push_str(&format!("  {}\n", line)),
+            DiffOp::Delete(line) => result.push_str(&format!("- {}\n", line)),
+            DiffOp::Insert(line) => result.push_str(&format!("+ {}\n", line)),
+        }
+    }
+    if folded > 0 {
+        result.push_str(&format!("  ... ({} unchanged lines)\n", folded));
+    }
+    result
+}
+
 #[derive(Debug, Clone, PartialEq)]
 enum DiffOp<'a> {
     Equal(&'a str),
@@ -158,6 +219,18 @@ mod tests {
         assert!(change1_add_pos < keep2_pos);
     }
 
+    #[test]
+    fn test_file_diff_folds_unchanged_lines() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh";
+        let new = "a\nb\nc\nd\nE\nf\ng\nh";
+        assert_eq!(
+            generate_file_diff(old, new, 1),
+            "  ... (3 unchanged lines)\n  d\n- e\n+ E\n  f\n  ... (2 unchanged lines)\n"
+        );
+        assert_eq!(generate_file_diff("", "x\ny", 3), "+ x\n+ y\n");
+        assert_eq!(generate_file_diff("same", "same", 3), "  ... (1 unchanged lines)\n");
+    }
+
     #[test]
     fn test_unified_diff_pure_addition() {
         let old = "line1";
@@ -5,6 +5,9 @@ use cp_base::state::{ContextType, State, estimate_tokens};
 use cp_base::tools::{ToolResult, ToolUse};
 
 use super::diff::generate_unified_diff;
+use crate::history;
+use crate::preview::{PreviewKind, PreviewState};
+use crate::slice::{LINE_END_META, LINE_START_META, SYMBOL_META, strip_line_numbers};
 
 /// Normalize a string for matching: trim trailing whitespace per line, normalize line endings
 fn normalize_for_match(s: &str) -> String {
@@ -58,7 +61,7 @@ fn find_normalized_match<'a>(haystack: &'a str, needle: &str) -> Option<&'a str>
 }
 
 /// Find closest match for error reporting (returns line number and preview)
-fn find_closest_match(haystack: &str, needle: &str) -> Option<(usize, String)> {
+pub(crate) fn find_closest_match(haystack: &str, needle: &str) -> Option<(usize, String)> {
     let norm_needle = normalize_for_match(needle);
     let first_needle_line = norm_needle.lines().next()?;
 
@@ -95,6 +98,119 @@ fn find_closest_match(haystack: &str, needle: &str) -> Option<(usize, String)> {
     best_match.map(|(line, _, preview)| (line, preview))
 }
 
+/// New (start, end) of a line-range view after replacing `old` with `new` at `line`:
+/// edits above the range shift it, edits inside it grow or shrink its end
+fn shift_range(start: usize, end: usize, line: usize, old: &str, new: &str) -> (usize, usize) {
+    let delta = new.lines().count() as isize - old.lines().count() as isize;
+    let shift = |n: usize| n.saturating_add_signed(delta).max(1);
+    if line < start {
+        (shift(start), if end == usize::MAX { end } else { shift(end) })
+    } else if line <= end && end != usize::MAX {
+        (start, shift(end).max(start))
+    } else {
+        (start, end)
+    }
+}
+
+/// One successful replacement inside a file's content
+pub(crate) struct Replacement {
+    /// Text actually searched for (line-number gutter stripped if needed)
+    pub old: String,
+    pub new: String,
+    /// Number of occurrences replaced
+    pub count: usize,
+    /// First line (1-based) of the first match, before replacing
+    pub line: Option<usize>,
+}
+
+/// Replace `old_string` with `new_string` in `content` using normalized matching.
+/// `partial` panels show a line-number gutter, so text copied with it is accepted.
+/// On failure `content` is untouched and the error names the closest match.
+pub(crate) fn replace_in(
+    content: &mut String,
+    old_string: &str,
+    new_string: &str,
+    replace_all: bool,
+    partial: bool,
+) -> Result<Replacement, String> {
+    let (old_string, new_string) = match (partial && find_normalized_match(content, old_string).is_none())
+        .then(|| strip_line_numbers(old_string))
+        .flatten()
+    {
+        Some(old) => (old, strip_line_numbers(new_string).unwrap_or_else(|| new_string.to_string())),
+        None => (old_string.to_string(), new_string.to_string()),
+    };
+
+    // Try normalized matching (handles trailing whitespace differences)
+    let Some(actual_match) = find_normalized_match(content, &old_string).map(|m| m.to_string()) else {
+        // Provide helpful error with closest match
+        let hint = if let Some((line, preview)) = find_closest_match(content, &old_string) {
+            format!(" (closest match at line {}: \"{}\")", line, preview)
+        } else {
+            String::new()
+        };
+
+        let needle_preview = if old_string.len() > 50 {
+            format!("{}...", &old_string[..old_string.floor_char_boundary(50)])
+        } else {
+            old_string.clone()
+        };
+
+        return Err(format!("No match found for \"{}\"{}", needle_preview, hint));
+    };
+
+    let line = content.find(&actual_match).map(|pos| content[..pos].matches('\n').count() + 1);
+    let count = if replace_all {
+        let count = content.matches(&actual_match).count();
+        *content = content.replace(&actual_match, &new_string);
+        count
+    } else {
+        *content = content.replacen(&actual_match, &new_string, 1);
+        1
+    };
+    Ok(Replacement { old: old_string, new: new_string, count, line })
+}
+
+/// Whether the file panel for `path_str` shows only part of the file:
+/// (explicit line range, shows a symbol)
+pub(crate) fn panel_view(state: &State, path_str: &str) -> Option<(Option<(usize, usize)>, bool)> {
+    let panel = state
+        .context
+        .iter()
+        .find(|c| c.context_type == ContextType::FILE && c.get_meta_str("file_path") == Some(path_str))?;
+    // Symbol panels re-resolve their range on refresh; only explicit ranges need shifting
+    let symbol = panel.get_meta_str(SYMBOL_META).is_some();
+    let range = panel.get_meta_usize(LINE_START_META).zip(panel.get_meta_usize(LINE_END_META)).filter(|_| !symbol);
+    Some((range, symbol))
+}
+
+/// Bring the file panel for `path_str` up to date after `edits` were written
+pub(crate) fn update_panel(state: &mut State, path_str: &str, content: &str, edits: &[(&Replacement, bool)]) {
+    let Some((range, symbol)) = panel_view(state, path_str) else { return };
+    let Some(ctx) = state
+        .context
+        .iter_mut()
+        .find(|c| c.context_type == ContextType::FILE && c.get_meta_str("file_path") == Some(path_str))
+    else {
+        return;
+    };
+    if range.is_none() && !symbol {
+        ctx.token_count = estimate_tokens(content);
+        return;
+    }
+    // Keep a line-range panel on the same code as lines move around it
+    if let Some((mut start, mut end)) = range {
+        for (edit, replace_all) in edits {
+            if let (Some(line), false) = (edit.line, replace_all) {
+                (start, end) = shift_range(start, end, line, &edit.old, &edit.new);
+            }
+        }
+        ctx.set_meta(LINE_START_META, &start);
+        ctx.set_meta(LINE_END_META, &end);
+    }
+    ctx.cache_deprecated = true;
+}
+
 pub fn execute_edit(tool: &ToolUse, state: &mut State) -> ToolResult {
     // Get file_path (required)
     let path_str = match tool.input.get("file_path").and_then(|v| v.as_str()) {
@@ -124,18 +240,14 @@ pub fn execute_edit(tool: &ToolUse, state: &mut State) -> ToolResult {
     let replace_all = tool.input.get("replace_all").and_then(|v| v.as_bool()).unwrap_or(false);
 
     // Check if file is open in context
-    let is_open = state
-        .context
-        .iter()
-        .any(|c| c.context_type == ContextType::FILE && c.get_meta_str("file_path") == Some(path_str));
-
-    if !is_open {
+    let Some((range, symbol)) = panel_view(state, path_str) else {
         return ToolResult::new(
             tool.id.clone(),
             format!("File '{}' is not open in context. Use file_open first.", path_str),
             true,
         );
-    }
+    };
+    let partial = range.is_some() || symbol;
 
     let path = Path::new(path_str);
 
@@ -147,35 +259,23 @@ pub fn execute_edit(tool: &ToolUse, state: &mut State) -> ToolResult {
         }
     };
 
-    // Try normalized matching (handles trailing whitespace differences)
-    let replaced = if let Some(actual_match) = find_normalized_match(&content, old_string) {
-        if replace_all {
-            let count = content.matches(actual_match).count();
-            content = content.replace(actual_match, new_string);
-            count
-        } else {
-            content = content.replacen(actual_match, new_string, 1);
-            1
-        }
-    } else {
-        0
+    let original = content.clone();
+    let edit = match replace_in(&mut content, old_string, new_string, replace_all, partial) {
+        Ok(edit) => edit,
+        Err(e) => return ToolResult::new(tool.id.clone(), e, true),
     };
-
-    if replaced == 0 {
-        // Provide helpful error with closest match
-        let hint = if let Some((line, preview)) = find_closest_match(&content, old_string) {
-            format!(" (closest match at line {}: \"{}\")", line, preview)
-        } else {
-            String::new()
-        };
-
-        let needle_preview = if old_string.len() > 50 {
-            format!("{}...", &old_string[..old_string.floor_char_boundary(50)])
-        } else {
-            old_string.to_string()
-        };
-
-        return ToolResult::new(tool.id.clone(), format!("No match found for \"{}\"{}", needle_preview, hint), true);
+    let result_msg = edit_message(path_str, &edit, replace_all);
+
+    // Dry run: park the change for apply_preview instead of writing it
+    if tool.input.get("dry_run").and_then(|v| v.as_bool()).unwrap_or(false) {
+        let id = PreviewState::get_mut(state).record(
+            path_str,
+            Some(original),
+            content,
+            result_msg.clone(),
+            PreviewKind::Edit(vec![(edit, replace_all)]),
+        );
+        return ToolResult::new(tool.id.clone(), preview_message(&id, &result_msg), false);
     }
 
     // Write file
@@ -184,25 +284,30 @@ pub fn execute_edit(tool: &ToolUse, state: &mut State) -> ToolResult {
     }
 
     // Update the context element's token count
-    if let Some(ctx) = state
-        .context
-        .iter_mut()
-        .find(|c| c.context_type == ContextType::FILE && c.get_meta_str("file_path") == Some(path_str))
-    {
-        ctx.token_count = estimate_tokens(&content);
-    }
+    update_panel(state, path_str, &content, &[(&edit, replace_all)]);
+    history::record(state, path_str, Some(&original), Some(&content));
 
+    ToolResult::new(tool.id.clone(), result_msg, false)
+}
+
+/// "Preview V3 (not written; apply_preview with this ID to write it)" + the result it would give
+pub(crate) fn preview_message(id: &str, result_msg: &str) -> String {
+    format!("Preview {} (dry run, nothing written; apply_preview with this ID to write it)\n{}", id, result_msg)
+}
+
+/// Header and diff block reported for an edit
+fn edit_message(path_str: &str, edit: &Replacement, replace_all: bool) -> String {
     // Count approximate lines changed
-    let lines_changed = new_string.lines().count().max(old_string.lines().count());
+    let lines_changed = edit.new.lines().count().max(edit.old.lines().count());
 
     // Format result as a unified diff for UI display
     let mut result_msg = String::new();
 
     // Header line
-    if replace_all && replaced > 1 {
+    if replace_all && edit.count > 1 {
         result_msg.push_str(&format!(
             "Edited '{}': {} replacements (~{} lines changed each)\n",
-            path_str, replaced, lines_changed
+            path_str, edit.count, lines_changed
         ));
     } else {
         result_msg.push_str(&format!("Edited '{}': ~{} lines changed\n", path_str, lines_changed));
@@ -212,12 +317,11 @@ pub fn execute_edit(tool: &ToolUse, state: &mut State) -> ToolResult {
     result_msg.push_str("```diff\n");
 
     // Generate unified diff by comparing old and new line by line
-    let diff_lines = generate_unified_diff(old_string, new_string);
+    let diff_lines = generate_unified_diff(&edit.old, &edit.new);
     result_msg.push_str(&diff_lines);
 
     result_msg.push_str("```");
-
-    ToolResult::new(tool.id.clone(), result_msg, false)
+    result_msg
 }
 
 #[cfg(test)]
@@ -254,6 +358,18 @@ mod tests {
         assert!(matched.unwrap().contains("let y = 2"));
     }
 
+    #[test]
+    fn test_shift_range() {
+        // Two lines inserted above the range
+        assert_eq!(shift_range(10, 20, 3, "a", "a\nb\nc"), (12, 22));
+        // One line removed inside the range
+        assert_eq!(shift_range(10, 20, 15, "a\nb", "a"), (10, 19));
+        // Edits below the range leave it alone
+        assert_eq!(shift_range(10, 20, 30, "a", "a\nb"), (10, 20));
+        // Open-ended ranges keep their open end
+        assert_eq!(shift_range(10, usize::MAX, 3, "a", "a\nb"), (11, usize::MAX));
+    }
+
     #[test]
     fn test_diff_format_structure() {
         // Test that verifies the expected structure of a diff-formatted result message
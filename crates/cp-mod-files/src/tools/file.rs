@@ -3,6 +3,80 @@ use std::path::Path;
 use cp_base::state::{ContextElement, ContextType, State};
 use cp_base::tools::{ToolResult, ToolUse};
 
+use crate::IMAGE_CONTEXT_TYPE;
+use crate::history;
+use crate::image::media_type_for;
+use crate::slice::{LINE_END_META, LINE_START_META, SYMBOL_META};
+
+/// Requested partial view of a file
+enum View {
+    Whole,
+    Lines(usize, usize),
+    Symbol(String),
+}
+
+impl View {
+    fn from_input(tool: &ToolUse) -> Result<Self, String> {
+        let start = tool.input.get("start_line").and_then(|v| v.as_u64()).map(|v| v as usize);
+        let end = tool.input.get("end_line").and_then(|v| v.as_u64()).map(|v| v as usize);
+        let symbol = tool.input.get("symbol").and_then(|v| v.as_str()).map(str::trim).filter(|s| !s.is_empty());
+        match (symbol, start, end) {
+            (Some(_), Some(_), _) | (Some(_), _, Some(_)) => {
+                Err("Pass either a symbol or start_line/end_line, not both".to_string())
+            }
+            (Some(symbol), None, None) => Ok(View::Symbol(symbol.to_string())),
+            (None, None, None) => Ok(View::Whole),
+            (None, start, end) => {
+                let start = start.unwrap_or(1);
+                let end = end.unwrap_or(usize::MAX);
+                if start == 0 || end < start {
+                    return Err(format!("Invalid line range {}-{} (lines are 1-based, start <= end)", start, end));
+                }
+                Ok(View::Lines(start, end))
+            }
+        }
+    }
+
+    /// Panel name for a file with this view
+    fn panel_name(&self, file_name: &str) -> String {
+        match self {
+            View::Whole => file_name.to_string(),
+            View::Lines(start, usize::MAX) => format!("{}:{}-", file_name, start),
+            View::Lines(start, end) => format!("{}:{}-{}", file_name, start, end),
+            View::Symbol(symbol) => format!("{}::{}", file_name, symbol.split_whitespace().last().unwrap_or(symbol)),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            View::Whole => "whole file".to_string(),
+            View::Lines(start, usize::MAX) => format!("lines {}-end", start),
+            View::Lines(start, end) => format!("lines {}-{}", start, end),
+            View::Symbol(symbol) => format!("symbol '{}'", symbol),
+        }
+    }
+
+    /// Store the view in the panel's metadata
+    fn apply(&self, ctx: &mut ContextElement, file_name: &str) {
+        for key in [LINE_START_META, LINE_END_META, SYMBOL_META] {
+            ctx.metadata.remove(key);
+        }
+        match self {
+            View::Whole => {}
+            View::Lines(start, end) => {
+                ctx.set_meta(LINE_START_META, start);
+                ctx.set_meta(LINE_END_META, end);
+            }
+            View::Symbol(symbol) => ctx.set_meta(SYMBOL_META, symbol),
+        }
+        ctx.name = self.panel_name(file_name);
+    }
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| path.to_string())
+}
+
 pub fn execute_open(tool: &ToolUse, state: &mut State) -> ToolResult {
     let path = match tool.input.get("path").and_then(|v| v.as_str()) {
         Some(p) => p,
@@ -11,9 +85,24 @@ pub fn execute_open(tool: &ToolUse, state: &mut State) -> ToolResult {
         }
     };
 
-    // Check if file is already open
-    if state.context.iter().any(|c| c.get_meta_str("file_path") == Some(path)) {
-        return ToolResult::new(tool.id.clone(), format!("File '{}' is already open in context", path), false);
+    let view = match View::from_input(tool) {
+        Ok(view) => view,
+        Err(e) => return ToolResult::new(tool.id.clone(), e, true),
+    };
+
+    // Already open: re-opening changes the visible slice (or restores the whole file)
+    if let Some(ctx) = state.context.iter_mut().find(|c| c.get_meta_str("file_path") == Some(path)) {
+        let partial = ctx.get_meta_usize(LINE_START_META).is_some() || ctx.get_meta_str(SYMBOL_META).is_some();
+        if ctx.context_type.as_str() != ContextType::FILE || (matches!(view, View::Whole) && !partial) {
+            return ToolResult::new(tool.id.clone(), format!("File '{}' is already open in context", path), false);
+        }
+        view.apply(ctx, &file_name(path));
+        ctx.cache_deprecated = true;
+        return ToolResult::new(
+            tool.id.clone(),
+            format!("Panel {} for '{}' now shows {}", ctx.id, path, view.describe()),
+            false,
+        );
     }
 
     // Check if file exists (quick metadata check, not a full read)
@@ -26,19 +115,50 @@ pub fn execute_open(tool: &ToolUse, state: &mut State) -> ToolResult {
         return ToolResult::new(tool.id.clone(), format!("'{}' is not a file", path), true);
     }
 
-    let file_name = path_obj.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| path.to_string());
+    if media_type_for(path).is_some() {
+        let context_id = push_panel(state, path, IMAGE_CONTEXT_TYPE);
+        return ToolResult::new(tool.id.clone(), format!("Opened image '{}' as {}", path, context_id), false);
+    }
+
+    let context_id = push_panel(state, path, ContextType::FILE);
+    if matches!(view, View::Whole) {
+        return ToolResult::new(tool.id.clone(), format!("Opened '{}' as {}", path, context_id), false);
+    }
+    if let Some(ctx) = state.context.iter_mut().find(|c| c.id == context_id) {
+        view.apply(ctx, &file_name(path));
+    }
+    ToolResult::new(tool.id.clone(), format!("Opened '{}' ({}) as {}", path, view.describe(), context_id), false)
+}
+
+/// Open an image file as a panel (e.g. from a pasted path). Returns the panel ID,
+/// or the existing panel's ID if the file is already open.
+pub fn open_image(state: &mut State, path: &str) -> Result<String, String> {
+    if let Some(existing) = state.context.iter().find(|c| c.get_meta_str("file_path") == Some(path)) {
+        return Ok(existing.id.clone());
+    }
+    if media_type_for(path).is_none() {
+        return Err(format!("'{}' is not a PNG, JPEG, GIF or WebP image", path));
+    }
+    if !Path::new(path).is_file() {
+        return Err(format!("File '{}' not found", path));
+    }
+    Ok(push_panel(state, path, IMAGE_CONTEXT_TYPE))
+}
+
+/// Create a file-backed panel WITHOUT reading its content.
+/// The background cache system populates it.
+fn push_panel(state: &mut State, path: &str, context_type: &str) -> String {
+    let file_name = file_name(path);
 
     // Generate context ID (fills gaps) and UID
     let context_id = state.next_available_context_id();
     let uid = format!("UID_{}_P", state.global_next_uid);
     state.global_next_uid += 1;
 
-    // Create context element WITHOUT reading file content
-    // Background cache system will populate it
     let mut elem = ContextElement {
         id: context_id.clone(),
         uid: Some(uid),
-        context_type: ContextType::new(ContextType::FILE),
+        context_type: ContextType::new(context_type),
         name: file_name,
         token_count: 0, // Will be updated by cache
         metadata: std::collections::HashMap::new(),
@@ -56,7 +176,9 @@ pub fn execute_open(tool: &ToolUse, state: &mut State) -> ToolResult {
         panel_total_cost: 0.0,
     };
     elem.set_meta("file_path", &path.to_string());
+    if context_type == ContextType::FILE {
+        history::set_depth_meta(&mut elem, history::depth(path));
+    }
     state.context.push(elem);
-
-    ToolResult::new(tool.id.clone(), format!("Opened '{}' as {}", path, context_id), false)
+    context_id
 }
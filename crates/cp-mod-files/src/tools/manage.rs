@@ -0,0 +1,238 @@
+use std::fs;
+use std::path::Path;
+
+use cp_base::state::{ContextType, State};
+use cp_base::tools::{ToolResult, ToolUse};
+
+use crate::history;
+use crate::paths::check_protected;
+
+fn param<'a>(tool: &'a ToolUse, name: &str) -> Result<&'a str, String> {
+    tool.input.get(name).and_then(|v| v.as_str()).ok_or_else(|| format!("Missing required parameter: {}", name))
+}
+
+fn flag(tool: &ToolUse, name: &str) -> bool {
+    tool.input.get(name).and_then(|v| v.as_bool()).unwrap_or(false)
+}
+
+fn result(tool: &ToolUse, outcome: Result<String, String>) -> ToolResult {
+    match outcome {
+        Ok(msg) => ToolResult::new(tool.id.clone(), msg, false),
+        Err(e) => ToolResult::new(tool.id.clone(), e, true),
+    }
+}
+
+/// Number of files under `path` (1 for a file)
+fn count_files(path: &Path) -> usize {
+    if !path.is_dir() {
+        return 1;
+    }
+    fs::read_dir(path).map(|entries| entries.flatten().map(|e| count_files(&e.path())).sum()).unwrap_or(0)
+}
+
+fn copy_recursive(from: &Path, to: &Path) -> std::io::Result<()> {
+    if !from.is_dir() {
+        return fs::copy(from, to).map(|_| ());
+    }
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+/// Shared checks for move and copy; returns the content of the file being replaced, if any
+fn check_transfer(source: &str, destination: &str, overwrite: bool) -> Result<Option<String>, String> {
+    check_protected(source)?;
+    check_protected(destination)?;
+    let (from, to) = (Path::new(source), Path::new(destination));
+    if !from.exists() {
+        return Err(format!("'{}' does not exist", source));
+    }
+    if from.is_dir() && to.starts_with(from) {
+        return Err(format!("Cannot put '{}' inside itself", source));
+    }
+    if to.exists() {
+        if !overwrite || to.is_dir() || from.is_dir() {
+            return Err(format!("'{}' already exists (overwrite only replaces a file with a file)", destination));
+        }
+        // Keep what is overwritten for the undo history
+        return Ok(fs::read_to_string(to).ok());
+    }
+    if let Some(parent) = to.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create '{}': {}", parent.display(), e))?;
+    }
+    Ok(None)
+}
+
+/// `file_move`
+fn move_path(tool: &ToolUse, state: &mut State) -> Result<String, String> {
+    let (source, destination) = (param(tool, "source")?, param(tool, "destination")?);
+    let replaced = check_transfer(source, destination, flag(tool, "overwrite"))?;
+    let is_dir = Path::new(source).is_dir();
+    let content = if is_dir { None } else { fs::read_to_string(source).ok() };
+    let files = count_files(Path::new(source));
+    fs::rename(source, destination).map_err(|e| format!("Failed to move '{}' to '{}': {}", source, destination, e))?;
+
+    if let Some(content) = &content {
+        history::record(state, source, Some(content), None);
+        history::record(state, destination, replaced.as_deref(), Some(content));
+    }
+    cp_base::panels::mark_panels_dirty(state, ContextType::new(ContextType::TREE));
+    Ok(if is_dir {
+        format!("Moved directory '{}' → '{}' ({} files)", source, destination, files)
+    } else {
+        format!("Moved '{}' → '{}'", source, destination)
+    })
+}
+
+pub fn execute_move(tool: &ToolUse, state: &mut State) -> ToolResult {
+    result(tool, move_path(tool, state))
+}
+
+/// `file_copy`
+fn copy_path(tool: &ToolUse, state: &mut State) -> Result<String, String> {
+    let (source, destination) = (param(tool, "source")?, param(tool, "destination")?);
+    let replaced = check_transfer(source, destination, flag(tool, "overwrite"))?;
+    copy_recursive(Path::new(source), Path::new(destination))
+        .map_err(|e| format!("Failed to copy '{}' to '{}': {}", source, destination, e))?;
+
+    if let Ok(content) = fs::read_to_string(destination) {
+        history::record(state, destination, replaced.as_deref(), Some(&content));
+    }
+    cp_base::panels::mark_panels_dirty(state, ContextType::new(ContextType::TREE));
+    Ok(if Path::new(source).is_dir() {
+        format!("Copied directory '{}' → '{}' ({} files)", source, destination, count_files(Path::new(source)))
+    } else {
+        format!("Copied '{}' → '{}'", source, destination)
+    })
+}
+
+pub fn execute_copy(tool: &ToolUse, state: &mut State) -> ToolResult {
+    result(tool, copy_path(tool, state))
+}
+
+/// `file_delete`
+fn delete_path(tool: &ToolUse, state: &mut State) -> Result<String, String> {
+    let path_str = param(tool, "path")?;
+    check_protected(path_str)?;
+    let path = Path::new(path_str);
+    if !path.exists() {
+        return Err(format!("'{}' does not exist", path_str));
+    }
+    let msg = if path.is_dir() {
+        let files = count_files(path);
+        if files > 0 && !flag(tool, "recursive") {
+            return Err(format!(
+                "'{}' is a directory with {} files; set recursive to delete it with its contents",
+                path_str, files
+            ));
+        }
+        fs::remove_dir_all(path).map_err(|e| format!("Failed to delete '{}': {}", path_str, e))?;
+        format!("Deleted directory '{}' ({} files)", path_str, files)
+    } else {
+        let content = fs::read_to_string(path).ok();
+        fs::remove_file(path).map_err(|e| format!("Failed to delete '{}': {}", path_str, e))?;
+        match content {
+            Some(content) => {
+                history::record(state, path_str, Some(&content), None);
+                format!("Deleted '{}' (file_undo restores it)", path_str)
+            }
+            None => format!("Deleted '{}'", path_str),
+        }
+    };
+    cp_base::panels::mark_panels_dirty(state, ContextType::new(ContextType::TREE));
+    Ok(msg)
+}
+
+pub fn execute_delete(tool: &ToolUse, state: &mut State) -> ToolResult {
+    result(tool, delete_path(tool, state))
+}
+
+/// `file_mkdir`
+fn make_dir(tool: &ToolUse, state: &mut State) -> Result<String, String> {
+    let path_str = param(tool, "path")?;
+    check_protected(path_str)?;
+    let path = Path::new(path_str);
+    if path.is_dir() {
+        return Ok(format!("Directory '{}' already exists", path_str));
+    }
+    if path.exists() {
+        return Err(format!("'{}' exists and is not a directory", path_str));
+    }
+    fs::create_dir_all(path).map_err(|e| format!("Failed to create '{}': {}", path_str, e))?;
+    cp_base::panels::mark_panels_dirty(state, ContextType::new(ContextType::TREE));
+    Ok(format!("Created directory '{}'", path_str))
+}
+
+pub fn execute_mkdir(tool: &ToolUse, state: &mut State) -> ToolResult {
+    result(tool, make_dir(tool, state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::in_temp_project;
+
+    fn tool(name: &str, input: serde_json::Value) -> ToolUse {
+        ToolUse { id: "t1".to_string(), name: name.to_string(), input }
+    }
+
+    #[test]
+    fn move_copy_delete_mkdir() {
+        // Relative paths (the tools refuse anything outside the project), in a temp project
+        in_temp_project("manage", || {
+            let mut state = State::default();
+
+            let made = execute_mkdir(&tool("file_mkdir", serde_json::json!({ "path": "a/b" })), &mut state);
+            assert!(!made.is_error, "{}", made.content);
+            fs::write("a/b/x.txt", "x").unwrap();
+
+            let copied =
+                execute_copy(&tool("file_copy", serde_json::json!({ "source": "a", "destination": "c" })), &mut state);
+            assert!(copied.content.ends_with("(1 files)"), "{}", copied.content);
+            assert_eq!(fs::read_to_string("c/b/x.txt").unwrap(), "x");
+
+            // No silent overwrite, and nothing moves into itself
+            let clash = serde_json::json!({ "source": "a/b/x.txt", "destination": "c/b/x.txt" });
+            assert!(execute_move(&tool("file_move", clash), &mut state).content.contains("already exists"));
+            let inside = serde_json::json!({ "source": "a", "destination": "a/b/a" });
+            assert!(execute_move(&tool("file_move", inside), &mut state).is_error);
+
+            let moved = execute_move(
+                &tool("file_move", serde_json::json!({ "source": "a/b", "destination": "d/b" })),
+                &mut state,
+            );
+            assert!(!moved.is_error, "{}", moved.content);
+            assert_eq!(fs::read_to_string("d/b/x.txt").unwrap(), "x");
+            assert!(!Path::new("a/b").exists());
+
+            // A moved file can be undone back into place
+            let renamed = execute_move(
+                &tool("file_move", serde_json::json!({ "source": "d/b/x.txt", "destination": "y.txt" })),
+                &mut state,
+            );
+            assert!(!renamed.is_error, "{}", renamed.content);
+            assert!(history::undo(&mut state, "d/b/x.txt").is_ok());
+            assert_eq!(fs::read_to_string("d/b/x.txt").unwrap(), "x");
+
+            let non_empty = execute_delete(&tool("file_delete", serde_json::json!({ "path": "c" })), &mut state);
+            assert!(non_empty.is_error);
+            let deleted =
+                execute_delete(&tool("file_delete", serde_json::json!({ "path": "c", "recursive": true })), &mut state);
+            assert!(!deleted.is_error, "{}", deleted.content);
+            assert!(!Path::new("c").exists());
+
+            let deleted_file = execute_delete(&tool("file_delete", serde_json::json!({ "path": "y.txt" })), &mut state);
+            assert!(deleted_file.content.contains("file_undo restores it"), "{}", deleted_file.content);
+            assert!(history::undo(&mut state, "y.txt").is_ok());
+            assert_eq!(fs::read_to_string("y.txt").unwrap(), "x");
+
+            let protected = execute_delete(&tool("file_delete", serde_json::json!({ "path": ".git" })), &mut state);
+            assert!(protected.content.contains("protected"));
+        });
+    }
+}
@@ -1,4 +1,9 @@
+pub mod apply_preview;
 pub mod diff;
 pub mod edit_file;
 pub mod file;
+pub mod manage;
+pub mod multi_edit;
+pub mod patch;
+pub mod undo;
 pub mod write;
@@ -0,0 +1,224 @@
+use std::fs;
+use std::path::Path;
+
+use cp_base::state::State;
+use cp_base::tools::{ToolResult, ToolUse};
+
+use super::diff::generate_file_diff;
+use super::edit_file::{Replacement, panel_view, replace_in, update_panel};
+use crate::history;
+use crate::paths::project_path;
+
+/// One requested replacement
+struct EditOp<'a> {
+    file_path: &'a str,
+    old_string: &'a str,
+    new_string: &'a str,
+    replace_all: bool,
+}
+
+/// Unchanged lines kept around each change in the result diff
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// A file with all of its edits applied in memory
+struct PendingFile<'a> {
+    /// First spelling of the path in the edits, used for reading and writing
+    path: &'a str,
+    /// Every spelling the edits used ("a.rs", "./a.rs", ...), one panel each at most
+    spellings: Vec<&'a str>,
+    original: String,
+    content: String,
+    edits: Vec<(Replacement, bool)>,
+}
+
+fn parse_ops(input: &serde_json::Value) -> Result<Vec<EditOp<'_>>, String> {
+    let edits = input
+        .get("edits")
+        .and_then(|v| v.as_array())
+        .filter(|a| !a.is_empty())
+        .ok_or_else(|| "Missing required parameter: edits (non-empty array)".to_string())?;
+    edits
+        .iter()
+        .enumerate()
+        .map(|(i, e)| {
+            let field = |name: &str| {
+                e.get(name).and_then(|v| v.as_str()).ok_or_else(|| format!("Edit {}: missing '{}'", i + 1, name))
+            };
+            Ok(EditOp {
+                file_path: field("file_path")?,
+                old_string: field("old_string")?,
+                new_string: field("new_string")?,
+                replace_all: e.get("replace_all").and_then(|v| v.as_bool()).unwrap_or(false),
+            })
+        })
+        .collect()
+}
+
+/// Apply every edit in memory, in order. Edits to the same file see the
+/// result of the earlier ones. Nothing touches disk here.
+fn plan<'a>(ops: &[EditOp<'a>], state: &State) -> Result<Vec<PendingFile<'a>>, String> {
+    let mut files: Vec<PendingFile<'a>> = Vec::new();
+    for (i, op) in ops.iter().enumerate() {
+        let Some((range, symbol)) = panel_view(state, op.file_path) else {
+            return Err(format!(
+                "Edit {}: file '{}' is not open in context. Use file_open first.",
+                i + 1,
+                op.file_path
+            ));
+        };
+        // Group by file, not by spelling: two spellings must not each write their own copy
+        let idx = match files.iter().position(|f| project_path(f.path) == project_path(op.file_path)) {
+            Some(idx) => idx,
+            None => {
+                let original = fs::read_to_string(op.file_path)
+                    .map_err(|e| format!("Edit {}: failed to read '{}': {}", i + 1, op.file_path, e))?;
+                files.push(PendingFile {
+                    path: op.file_path,
+                    spellings: vec![],
+                    content: original.clone(),
+                    original,
+                    edits: vec![],
+                });
+                files.len() - 1
+            }
+        };
+        let file = &mut files[idx];
+        if !file.spellings.contains(&op.file_path) {
+            file.spellings.push(op.file_path);
+        }
+        let edit =
+            replace_in(&mut file.content, op.old_string, op.new_string, op.replace_all, range.is_some() || symbol)
+                .map_err(|e| format!("Edit {} ('{}'): {}", i + 1, op.file_path, e))?;
+        file.edits.push((edit, op.replace_all));
+    }
+    Ok(files)
+}
+
+/// Write all files; if one write fails, put back the ones already written
+fn commit(files: &[PendingFile<'_>]) -> Result<(), String> {
+    for (i, file) in files.iter().enumerate() {
+        if let Err(e) = fs::write(Path::new(file.path), &file.content) {
+            for done in &files[..i] {
+                let _ = fs::write(Path::new(done.path), &done.original);
+            }
+            return Err(format!("Failed to write '{}': {}. No file was changed.", file.path, e));
+        }
+    }
+    Ok(())
+}
+
+pub fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let ops = match parse_ops(&tool.input) {
+        Ok(ops) => ops,
+        Err(e) => return ToolResult::new(tool.id.clone(), e, true),
+    };
+    let files = match plan(&ops, state) {
+        Ok(files) => files,
+        Err(e) => return ToolResult::new(tool.id.clone(), format!("{}\nNo file was changed.", e), true),
+    };
+    if let Err(e) = commit(&files) {
+        return ToolResult::new(tool.id.clone(), e, true);
+    }
+
+    let mut diff = String::new();
+    for file in &files {
+        let edits: Vec<(&Replacement, bool)> = file.edits.iter().map(|(e, all)| (e, *all)).collect();
+        for spelling in &file.spellings {
+            update_panel(state, spelling, &file.content, &edits);
+        }
+        history::record(state, file.path, Some(&file.original), Some(&file.content));
+        diff.push_str(&format!("@@ {} @@\n", file.path));
+        diff.push_str(&generate_file_diff(&file.original, &file.content, DIFF_CONTEXT_LINES));
+    }
+
+    let replacements: usize = files.iter().flat_map(|f| &f.edits).map(|(e, _)| e.count).sum();
+    let result_msg = format!(
+        "Edited {} file(s) with {} edit(s) ({} replacements)\n```diff\n{}```",
+        files.len(),
+        ops.len(),
+        replacements,
+        diff
+    );
+    ToolResult::new(tool.id.clone(), result_msg, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::in_temp_project;
+    use cp_base::state::{ContextType, make_default_context_element};
+
+    fn open(state: &mut State, path: &str) {
+        let mut ctx = make_default_context_element(
+            &format!("P{}", state.context.len() + 10),
+            ContextType::new(ContextType::FILE),
+            path,
+            false,
+        );
+        ctx.set_meta("file_path", &path.to_string());
+        state.context.push(ctx);
+    }
+
+    fn tool(edits: serde_json::Value) -> ToolUse {
+        ToolUse { id: "t1".to_string(), name: "MultiEdit".to_string(), input: serde_json::json!({ "edits": edits }) }
+    }
+
+    #[test]
+    fn all_or_nothing() {
+        let dir = std::env::temp_dir().join(format!("cp_multi_edit_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let a = dir.join("a.rs").to_string_lossy().to_string();
+        let b = dir.join("b.rs").to_string_lossy().to_string();
+        fs::write(&a, "fn old() {}\nold();\nold();\n").unwrap();
+        fs::write(&b, "use a::old;\n").unwrap();
+        let mut state = State::default();
+        open(&mut state, &a);
+        open(&mut state, &b);
+
+        // The second edit to b fails: neither file changes
+        let failing = tool(serde_json::json!([
+            { "file_path": a, "old_string": "old();", "new_string": "new();", "replace_all": true },
+            { "file_path": b, "old_string": "use a::old;", "new_string": "use a::new;" },
+            { "file_path": b, "old_string": "missing", "new_string": "x" },
+        ]));
+        let result = execute(&failing, &mut state);
+        assert!(result.is_error);
+        assert!(result.content.starts_with("Edit 3 ("), "{}", result.content);
+        assert_eq!(fs::read_to_string(&a).unwrap(), "fn old() {}\nold();\nold();\n");
+        assert_eq!(fs::read_to_string(&b).unwrap(), "use a::old;\n");
+
+        // Later edits see earlier ones in the same file
+        let ok = tool(serde_json::json!([
+            { "file_path": a, "old_string": "old();", "new_string": "new();", "replace_all": true },
+            { "file_path": a, "old_string": "fn old() {}\nnew();", "new_string": "pub fn new() {}\nnew();" },
+            { "file_path": b, "old_string": "use a::old;", "new_string": "use a::new;" },
+        ]));
+        let result = execute(&ok, &mut state);
+        assert!(!result.is_error, "{}", result.content);
+        assert!(result.content.starts_with("Edited 2 file(s) with 3 edit(s) (4 replacements)"));
+        assert_eq!(fs::read_to_string(&a).unwrap(), "pub fn new() {}\nnew();\nnew();\n");
+        assert_eq!(fs::read_to_string(&b).unwrap(), "use a::new;\n");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn spellings_of_one_file_share_its_edits() {
+        in_temp_project("multi_edit_spellings", || {
+            fs::write("a.rs", "one\ntwo\n").unwrap();
+            let mut state = State::default();
+            open(&mut state, "a.rs");
+            open(&mut state, "./a.rs");
+
+            let edits = tool(serde_json::json!([
+                { "file_path": "a.rs", "old_string": "one", "new_string": "1" },
+                { "file_path": "./a.rs", "old_string": "two", "new_string": "2" },
+            ]));
+            let result = execute(&edits, &mut state);
+            assert!(result.content.starts_with("Edited 1 file(s) with 2 edit(s)"), "{}", result.content);
+            assert_eq!(fs::read_to_string("a.rs").unwrap(), "1\n2\n");
+            // One diff for the file, from its original to its final content
+            assert_eq!(result.content.matches("@@ ").count(), 1, "{}", result.content);
+            assert!(result.content.contains("+ 1\n") && result.content.contains("+ 2\n"), "{}", result.content);
+        });
+    }
+}
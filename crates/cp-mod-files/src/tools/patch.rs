@@ -0,0 +1,297 @@
+use std::fs;
+use std::path::Path;
+
+use cp_base::state::{ContextType, State};
+use cp_base::tools::{ToolResult, ToolUse};
+
+use super::diff::generate_file_diff;
+use super::edit_file::{Replacement, update_panel};
+use crate::history;
+use crate::patch::{FilePatch, apply_hunks, parse_patch};
+use crate::paths::check_protected;
+
+/// Unchanged lines kept around each change in the reported diff
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// One file of the patch, fully applied in memory
+struct PendingFile<'a> {
+    old_path: Option<&'a str>,
+    new_path: Option<&'a str>,
+    /// Content of `old_path` before the patch
+    original: Option<String>,
+    /// Content of `new_path` after the patch
+    content: Option<String>,
+    replacements: Vec<Replacement>,
+}
+
+/// "'path': modified" / "'old' → 'new': renamed" line heading a file's report
+fn file_heading(file: &FilePatch<'_>) -> String {
+    match (file.old_path, file.new_path) {
+        (Some(old), Some(new)) if old != new => format!("'{}' → '{}': renamed", old, new),
+        (Some(path), Some(_)) => format!("'{}': modified", path),
+        (None, Some(path)) => format!("'{}': created", path),
+        (Some(path), None) => format!("'{}': deleted", path),
+        (None, None) => String::new(),
+    }
+}
+
+/// Read and patch one file in memory. Errors are file-level problems;
+/// hunk results go to `report` either way.
+fn plan_file<'a>(file: &FilePatch<'a>, report: &mut String) -> Result<(PendingFile<'a>, usize), String> {
+    for path in [file.old_path, file.new_path].into_iter().flatten() {
+        check_protected(path)?;
+    }
+    let original = match file.old_path {
+        Some(path) => Some(fs::read_to_string(path).map_err(|e| format!("cannot read '{}': {}", path, e))?),
+        None => None,
+    };
+    if let Some(new) = file.new_path.filter(|new| Some(*new) != file.old_path)
+        && Path::new(new).exists()
+    {
+        return Err(format!("'{}' already exists", new));
+    }
+
+    let applied = apply_hunks(original.as_deref().unwrap_or(""), &file.hunks);
+    for (hunk, status) in file.hunks.iter().zip(&applied.statuses) {
+        report.push_str(&format!("  {}: {}\n", hunk.header, status));
+    }
+    if file.new_path.is_none() && applied.rejected() == 0 && !applied.content.trim().is_empty() {
+        return Err("the deletion does not remove the whole file".to_string());
+    }
+    let rejected = applied.rejected();
+    let pending = PendingFile {
+        old_path: file.old_path,
+        new_path: file.new_path,
+        original,
+        content: file.new_path.map(|_| applied.content),
+        replacements: applied.replacements,
+    };
+    Ok((pending, rejected))
+}
+
+/// Write every file; if one fails, put back what was already done,
+/// including whatever the failing file got through
+fn commit(files: &[PendingFile<'_>]) -> Result<(), String> {
+    for (i, file) in files.iter().enumerate() {
+        if let Err(e) = commit_file(file) {
+            files[..=i].iter().rev().for_each(rollback_file);
+            return Err(format!("{}. No file was changed.", e));
+        }
+    }
+    Ok(())
+}
+
+fn rollback_file(file: &PendingFile<'_>) {
+    // `plan_file` checked that a new path did not exist yet
+    if let Some(new) = file.new_path.filter(|new| file.old_path != Some(*new)) {
+        let _ = fs::remove_file(new);
+    }
+    if let (Some(old), Some(original)) = (file.old_path, &file.original) {
+        let _ = fs::write(old, original);
+    }
+}
+
+fn commit_file(file: &PendingFile<'_>) -> Result<(), String> {
+    if let (Some(path), Some(content)) = (file.new_path, &file.content) {
+        if let Some(parent) = Path::new(path).parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create '{}': {}", parent.display(), e))?;
+        }
+        fs::write(path, content).map_err(|e| format!("Failed to write '{}': {}", path, e))?;
+    }
+    if let Some(old) = file.old_path.filter(|old| file.new_path != Some(*old)) {
+        fs::remove_file(old).map_err(|e| format!("Failed to remove '{}': {}", old, e))?;
+    }
+    Ok(())
+}
+
+/// Bring panels and edit history up to date with a written file
+fn sync_state(state: &mut State, file: &PendingFile<'_>) {
+    let original = file.original.as_deref();
+    let content = file.content.as_deref();
+    match (file.old_path, file.new_path) {
+        (Some(old), Some(new)) if old == new => {
+            let edits: Vec<(&Replacement, bool)> = file.replacements.iter().map(|r| (r, false)).collect();
+            update_panel(state, new, content.unwrap_or_default(), &edits);
+            history::record(state, new, original, content);
+        }
+        (old, new) => {
+            // Panels follow renames through the module hook (`FilesModule::on_path_moved`)
+            if let Some(old) = old {
+                history::record(state, old, original, None);
+            }
+            if let Some(new) = new {
+                history::record(state, new, None, content);
+            }
+            cp_base::panels::mark_panels_dirty(state, ContextType::new(ContextType::TREE));
+        }
+    }
+}
+
+pub fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let Some(text) = tool.input.get("patch").and_then(|v| v.as_str()) else {
+        return ToolResult::new(tool.id.clone(), "Missing required parameter: patch".to_string(), true);
+    };
+    let files = match parse_patch(text) {
+        Ok(files) => files,
+        Err(e) => return ToolResult::new(tool.id.clone(), format!("Invalid patch: {}", e), true),
+    };
+
+    // Everything is applied in memory first: a single rejected hunk writes nothing
+    let mut report = String::new();
+    let mut pending: Vec<PendingFile<'_>> = Vec::new();
+    let mut touched: Vec<&str> = Vec::new();
+    let (mut rejected, mut file_errors) = (0, 0);
+    for file in &files {
+        let paths: Vec<&str> = [file.old_path, file.new_path].into_iter().flatten().collect();
+        report.push_str(&format!("{}\n", file_heading(file)));
+        if let Some(path) = paths.iter().find(|p| touched.contains(p)) {
+            report.push_str(&format!("  error: '{}' appears more than once in the patch\n", path));
+            file_errors += 1;
+            continue;
+        }
+        touched.extend(&paths);
+        match plan_file(file, &mut report) {
+            Ok((file, 0)) => pending.push(file),
+            Ok((_, n)) => rejected += n,
+            Err(e) => {
+                report.push_str(&format!("  error: {}\n", e));
+                file_errors += 1;
+            }
+        }
+    }
+
+    let hunks: usize = files.iter().map(|f| f.hunks.len()).sum();
+    if rejected > 0 || file_errors > 0 {
+        let mut problems = Vec::new();
+        if rejected > 0 {
+            problems.push(format!("{} of {} hunk(s) rejected", rejected, hunks));
+        }
+        if file_errors > 0 {
+            problems.push(format!("{} file error(s)", file_errors));
+        }
+        return ToolResult::new(
+            tool.id.clone(),
+            format!("Patch not applied: {}. No file was changed.\n{}", problems.join(", "), report.trim_end()),
+            true,
+        );
+    }
+    if let Err(e) = commit(&pending) {
+        return ToolResult::new(tool.id.clone(), e, true);
+    }
+
+    let mut diff = String::new();
+    for file in &pending {
+        sync_state(state, file);
+        if let Some(content) = &file.content {
+            diff.push_str(&format!("@@ {} @@\n", file.new_path.unwrap_or_default()));
+            diff.push_str(&generate_file_diff(file.original.as_deref().unwrap_or(""), content, DIFF_CONTEXT_LINES));
+        }
+    }
+    let result_msg =
+        format!("Patched {} file(s) ({} hunk(s))\n{}\n```diff\n{}```", pending.len(), hunks, report.trim_end(), diff);
+    ToolResult::new(tool.id.clone(), result_msg, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::in_temp_project;
+
+    fn tool(patch: &str) -> ToolUse {
+        ToolUse { id: "t1".to_string(), name: "Patch".to_string(), input: serde_json::json!({ "patch": patch }) }
+    }
+
+    #[test]
+    fn applies_all_or_nothing() {
+        in_temp_project("patch", || {
+            fs::write("a.txt", "one\ntwo\nthree\n").unwrap();
+            fs::write("gone.txt", "bye\n").unwrap();
+            fs::write("old.txt", "same\n").unwrap();
+            let mut state = State::default();
+
+            let patch = "\
+--- a.txt
++++ a.txt
+@@ -1,3 +1,3 @@
+ one
+-two
++2
+ three
+--- gone.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+--- /dev/null
++++ sub/new.txt
+@@ -0,0 +1 @@
++hello
+diff --git a/old.txt b/moved.txt
+rename from old.txt
+rename to moved.txt
+";
+
+            // One stale hunk: nothing is written
+            let stale = patch.replace("-two\n", "-deux\n");
+            let result = execute(&tool(&stale), &mut state);
+            assert!(result.is_error);
+            assert!(result.content.starts_with("Patch not applied: 1 of 3 hunk(s) rejected"), "{}", result.content);
+            assert!(result.content.contains("rejected: context not found"));
+            assert_eq!(fs::read_to_string("a.txt").unwrap(), "one\ntwo\nthree\n");
+            assert!(Path::new("gone.txt").exists());
+
+            let result = execute(&tool(patch), &mut state);
+            assert!(!result.is_error, "{}", result.content);
+            assert!(result.content.starts_with("Patched 4 file(s) (3 hunk(s))"));
+            assert_eq!(fs::read_to_string("a.txt").unwrap(), "one\n2\nthree\n");
+            assert!(!Path::new("gone.txt").exists());
+            assert_eq!(fs::read_to_string("sub/new.txt").unwrap(), "hello\n");
+            assert_eq!(fs::read_to_string("moved.txt").unwrap(), "same\n");
+            assert!(!Path::new("old.txt").exists());
+        });
+    }
+
+    #[test]
+    fn failed_rename_is_rolled_back() {
+        in_temp_project("patch_rollback", || {
+            // Removing a directory as a file fails after the new path was written
+            fs::create_dir_all("old").unwrap();
+            let file = PendingFile {
+                old_path: Some("old"),
+                new_path: Some("new.txt"),
+                original: Some("x".to_string()),
+                content: Some("x".to_string()),
+                replacements: Vec::new(),
+            };
+            let err = commit(&[file]).unwrap_err();
+            assert!(err.starts_with("Failed to remove 'old'"), "{}", err);
+            assert!(!Path::new("new.txt").exists());
+        });
+    }
+
+    #[test]
+    fn refuses_protected_paths() {
+        in_temp_project("patch_protected", || {
+            fs::create_dir_all(".git").unwrap();
+            fs::write(".git/config", "[core]\n").unwrap();
+            fs::write("a.txt", "a\n").unwrap();
+            let mut state = State::default();
+
+            let patches = [
+                "--- .git/config\n+++ /dev/null\n@@ -1 +0,0 @@\n-[core]\n",
+                "diff --git a/a.txt b/.context-pilot/a.txt\nrename from a.txt\nrename to .context-pilot/a.txt\n",
+                "diff --git a/a.txt b/../a.txt\nrename from a.txt\nrename to ../a.txt\n",
+            ];
+            for patch in patches {
+                let result = execute(&tool(patch), &mut state);
+                assert!(result.content.contains("1 file error(s)"), "{}", result.content);
+            }
+            let absolute = "--- /dev/null\n+++ /tmp/cp_outside.txt\n@@ -0,0 +1 @@\n+x\n";
+            assert!(execute(&tool(absolute), &mut state).content.contains("outside the project"));
+            assert!(Path::new(".git/config").exists());
+            assert!(Path::new("a.txt").exists());
+            assert!(!Path::new("/tmp/cp_outside.txt").exists());
+        });
+    }
+}
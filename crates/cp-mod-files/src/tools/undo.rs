@@ -0,0 +1,16 @@
+use cp_base::state::State;
+use cp_base::tools::{ToolResult, ToolUse};
+
+use crate::history;
+
+/// `file_undo` / `file_redo`
+pub fn execute(tool: &ToolUse, state: &mut State, undo: bool) -> ToolResult {
+    let Some(path) = tool.input.get("file_path").and_then(|v| v.as_str()) else {
+        return ToolResult::new(tool.id.clone(), "Missing required parameter: file_path".to_string(), true);
+    };
+    let result = if undo { history::undo(state, path) } else { history::redo(state, path) };
+    match result {
+        Ok(msg) => ToolResult::new(tool.id.clone(), msg, false),
+        Err(e) => ToolResult::new(tool.id.clone(), e, true),
+    }
+}
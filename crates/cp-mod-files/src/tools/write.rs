@@ -4,6 +4,14 @@ use std::path::Path;
 use cp_base::state::{ContextElement, ContextType, State, estimate_tokens};
 use cp_base::tools::{ToolResult, ToolUse};
 
+use super::diff::generate_file_diff;
+use super::edit_file::preview_message;
+use crate::history;
+use crate::preview::{PreviewKind, PreviewState};
+
+/// Unchanged lines kept around each change in a dry-run diff
+const PREVIEW_CONTEXT_LINES: usize = 3;
+
 pub fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
     let path_str = match tool.input.get("file_path").and_then(|v| v.as_str()) {
         Some(p) => p,
@@ -20,7 +28,59 @@ pub fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
     };
 
     let path = Path::new(path_str);
+
+    // Dry run: diff against the current content and park the change for apply_preview
+    if tool.input.get("dry_run").and_then(|v| v.as_bool()).unwrap_or(false) {
+        let original = if path.exists() {
+            match fs::read_to_string(path) {
+                Ok(c) => Some(c),
+                Err(e) => {
+                    return ToolResult::new(
+                        tool.id.clone(),
+                        format!("Failed to read file '{}': {}", path_str, e),
+                        true,
+                    );
+                }
+            }
+        } else {
+            None
+        };
+        let result_msg = match &original {
+            Some(old) => format!(
+                "Would write '{}' ({} lines, {} tokens)\n```diff\n{}```",
+                path_str,
+                contents.lines().count(),
+                estimate_tokens(contents),
+                generate_file_diff(old, contents, PREVIEW_CONTEXT_LINES)
+            ),
+            None => write_message("Would create", path_str, contents),
+        };
+        let id = PreviewState::get_mut(state).record(
+            path_str,
+            original,
+            contents.to_string(),
+            result_msg.clone(),
+            PreviewKind::Write,
+        );
+        return ToolResult::new(tool.id.clone(), preview_message(&id, &result_msg), false);
+    }
+
     let is_new = !path.exists();
+    match write_file(state, path_str, contents) {
+        Ok(()) => {
+            let action = if is_new { "Created" } else { "Wrote" };
+            ToolResult::new(tool.id.clone(), write_message(action, path_str, contents), false)
+        }
+        Err(e) => ToolResult::new(tool.id.clone(), e, true),
+    }
+}
+
+/// Write `contents` to `path_str` (creating parent directories) and bring its
+/// file panel up to date, opening one if needed
+pub(crate) fn write_file(state: &mut State, path_str: &str, contents: &str) -> Result<(), String> {
+    let path = Path::new(path_str);
+    // Previous content for the undo history; an unreadable existing file is not recorded
+    let before = if path.exists() { fs::read_to_string(path).ok().map(Some) } else { Some(None) };
 
     // Create parent directories if needed
     if let Some(parent) = path.parent()
@@ -28,20 +88,15 @@ pub fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
         && !parent.exists()
         && let Err(e) = fs::create_dir_all(parent)
     {
-        return ToolResult::new(
-            tool.id.clone(),
-            format!("Failed to create directory '{}': {}", parent.display(), e),
-            true,
-        );
+        return Err(format!("Failed to create directory '{}': {}", parent.display(), e));
     }
 
     // Write the file
     if let Err(e) = fs::write(path, contents) {
-        return ToolResult::new(tool.id.clone(), format!("Failed to write file '{}': {}", path_str, e), true);
+        return Err(format!("Failed to write file '{}': {}", path_str, e));
     }
 
     let token_count = estimate_tokens(contents);
-    let line_count = contents.lines().count();
 
     // Check if file is already open in context
     let already_open = state
@@ -89,8 +144,17 @@ pub fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
         cp_base::panels::mark_panels_dirty(state, ContextType::new(ContextType::TREE));
     }
 
-    let action = if is_new { "Created" } else { "Wrote" };
-    let mut result_msg = format!("{} '{}' ({} lines, {} tokens)\n", action, path_str, line_count, token_count);
+    if let Some(before) = before {
+        history::record(state, path_str, before.as_deref(), Some(contents));
+    }
+    Ok(())
+}
+
+/// "Created 'path' (N lines, T tokens)" + a diff-style preview of the content
+fn write_message(action: &str, path_str: &str, contents: &str) -> String {
+    let line_count = contents.lines().count();
+    let mut result_msg =
+        format!("{} '{}' ({} lines, {} tokens)\n", action, path_str, line_count, estimate_tokens(contents));
 
     // Add diff-style preview of written content (truncated for large files)
     result_msg.push_str("```diff\n");
@@ -102,6 +166,5 @@ pub fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
         result_msg.push_str(&format!("+ {}\n", line));
     }
     result_msg.push_str("```");
-
-    ToolResult::new(tool.id.clone(), result_msg, false)
+    result_msg
 }
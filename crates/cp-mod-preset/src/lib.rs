@@ -1,9 +1,12 @@
 pub mod builtin;
+pub mod snapshot;
 pub mod tools;
 pub mod types;
 
 /// Presets subdirectory
 pub const PRESETS_DIR: &str = "presets";
+/// Context snapshots subdirectory
+pub const SNAPSHOTS_DIR: &str = "snapshots";
 
 use std::collections::HashSet;
 
@@ -39,7 +42,7 @@ impl Module for PresetModule {
         "Preset"
     }
     fn description(&self) -> &'static str {
-        "Save and load named worker configuration presets"
+        "Save and load named worker configuration presets and context snapshots"
     }
 
     fn is_core(&self) -> bool {
@@ -89,6 +92,40 @@ impl Module for PresetModule {
                 reverie_allowed: false,
                 category: "System".to_string(),
             },
+            ToolDefinition {
+                id: "context_snapshot".to_string(),
+                name: "Snapshot Context".to_string(),
+                short_desc: "Park the current task".to_string(),
+                description: "Saves the current working set under a name so the task can be parked and resumed \
+                    later: open dynamic panels (with their page and metadata), active todos, scratchpad cells \
+                    and loaded skills. Unlike presets, it does not capture the agent, modules or tool enablement."
+                    .to_string(),
+                params: vec![
+                    ToolParam::new("name", ParamType::String)
+                        .desc("Snapshot name (alphanumeric and hyphens only, e.g., 'auth-refactor')")
+                        .required(),
+                    ToolParam::new("replace", ParamType::Boolean)
+                        .desc("Overwrite an existing snapshot with this name (default false)"),
+                ],
+                enabled: true,
+                reverie_allowed: false,
+                category: "Context".to_string(),
+            },
+            ToolDefinition {
+                id: "context_restore".to_string(),
+                name: "Restore Context".to_string(),
+                short_desc: "Resume a parked task".to_string(),
+                description: "Restores a context snapshot, replacing the open dynamic panels, todos, scratchpad \
+                    cells and loaded skills with the saved ones. Agent, modules, tools and the conversation are \
+                    left untouched. Snapshot the current task first if you want to come back to it."
+                    .to_string(),
+                params: vec![
+                    ToolParam::new("name", ParamType::String).desc("Name of the snapshot to restore").required(),
+                ],
+                enabled: true,
+                reverie_allowed: false,
+                category: "Context".to_string(),
+            },
         ]
     }
 
@@ -102,6 +139,8 @@ impl Module for PresetModule {
                 self.active_tool_defs_fn,
                 self.ensure_defaults_fn,
             )),
+            "context_snapshot" => Some(tools::execute_context_snapshot(tool, state)),
+            "context_restore" => Some(tools::execute_context_restore(tool, state, self.all_modules_fn)),
             _ => None,
         }
     }
@@ -110,6 +149,8 @@ impl Module for PresetModule {
         vec![
             ("preset_snapshot_myself", visualize_preset_output as ToolVisualizer),
             ("preset_load", visualize_preset_output as ToolVisualizer),
+            ("context_snapshot", visualize_preset_output as ToolVisualizer),
+            ("context_restore", visualize_preset_output as ToolVisualizer),
         ]
     }
 
@@ -119,15 +160,27 @@ impl Module for PresetModule {
 
     fn overview_context_section(&self, _state: &State) -> Option<String> {
         let presets = tools::list_presets_with_info();
-        if presets.is_empty() {
+        let snapshots = snapshot::list_snapshots();
+        if presets.is_empty() && snapshots.is_empty() {
             return None;
         }
-        let mut output = String::from("\nPresets:\n\n");
-        output.push_str("| Name | Type | Description |\n");
-        output.push_str("|------|------|-------------|\n");
-        for p in &presets {
-            let ptype = if p.built_in { "built-in" } else { "custom" };
-            output.push_str(&format!("| {} | {} | {} |\n", p.name, ptype, p.description));
+        let mut output = String::new();
+        if !presets.is_empty() {
+            output.push_str("\nPresets:\n\n");
+            output.push_str("| Name | Type | Description |\n");
+            output.push_str("|------|------|-------------|\n");
+            for p in &presets {
+                let ptype = if p.built_in { "built-in" } else { "custom" };
+                output.push_str(&format!("| {} | {} | {} |\n", p.name, ptype, p.description));
+            }
+        }
+        if !snapshots.is_empty() {
+            output.push_str("\nContext snapshots (context_restore to resume):\n\n");
+            output.push_str("| Name | Panels | Todos |\n");
+            output.push_str("|------|--------|-------|\n");
+            for s in &snapshots {
+                output.push_str(&format!("| {} | {} | {} |\n", s.name, s.panels, s.todos));
+            }
         }
         Some(output)
     }
@@ -152,7 +205,11 @@ fn visualize_preset_output(content: &str, width: usize) -> Vec<ratatui::text::Li
 
         let style = if line.starts_with("Error:") {
             Style::default().fg(error_color)
-        } else if line.starts_with("Snapshot saved:") || line.starts_with("Loaded preset") {
+        } else if line.starts_with("Snapshot saved:")
+            || line.starts_with("Loaded preset")
+            || line.starts_with("Snapshot '")
+            || line.starts_with("Restored snapshot")
+        {
             Style::default().fg(success_color)
         } else if line.contains("'") {
             // Preset names in quotes
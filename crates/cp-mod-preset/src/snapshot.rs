@@ -0,0 +1,347 @@
+//! Context snapshots — park a task's working set and bring it back later.
+//!
+//! Lighter than presets: a snapshot holds the open dynamic panels (with their
+//! page and metadata), the active todos, the scratchpad cells and the loaded
+//! skills. Restoring one swaps those in place and leaves the agent, modules and
+//! tool enablement alone.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use cp_base::config::constants::STORE_DIR;
+use cp_base::modules::Module;
+use cp_base::panels::now_ms;
+use cp_base::state::{ContextType, State, make_default_context_element};
+use cp_mod_prompt::PromptState;
+use cp_mod_scratchpad::types::{ScratchpadCell, ScratchpadState};
+use cp_mod_todo::types::{TodoItem, TodoState, TodoStatus};
+
+use crate::SNAPSHOTS_DIR;
+
+/// A named snapshot of the working context
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextSnapshot {
+    pub name: String,
+    pub created_ms: u64,
+    #[serde(default)]
+    pub panels: Vec<SnapshotPanel>,
+    /// Pending and in-progress todos (done ones are not kept)
+    #[serde(default)]
+    pub todos: Vec<TodoItem>,
+    #[serde(default)]
+    pub scratchpad_cells: Vec<ScratchpadCell>,
+    #[serde(default)]
+    pub loaded_skill_ids: Vec<String>,
+}
+
+/// A dynamic panel as it was when the snapshot was taken
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotPanel {
+    pub panel_type: ContextType,
+    pub name: String,
+    #[serde(default)]
+    pub current_page: usize,
+    #[serde(default)]
+    pub metadata: HashMap<String, serde_json::Value>,
+}
+
+/// Summary info for a snapshot, used by the overview panel and the palette
+pub struct SnapshotInfo {
+    pub name: String,
+    pub created_ms: u64,
+    pub panels: usize,
+    pub todos: usize,
+}
+
+fn snapshots_path() -> PathBuf {
+    Path::new(STORE_DIR).join(SNAPSHOTS_DIR)
+}
+
+fn snapshot_file_path(name: &str) -> PathBuf {
+    snapshots_path().join(format!("{}.json", name))
+}
+
+/// Same rule as preset names: alphanumeric and hyphens only
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Snapshot name cannot be empty".to_string());
+    }
+    if !name.chars().all(|c| c.is_alphanumeric() || c == '-') {
+        return Err("Snapshot name must contain only alphanumeric characters and hyphens".to_string());
+    }
+    Ok(())
+}
+
+/// Capture the open dynamic panels. Conversation history chunks belong to the
+/// conversation, not the task, so they are left out.
+pub fn capture_panels(state: &State) -> Vec<SnapshotPanel> {
+    state
+        .context
+        .iter()
+        .filter(|c| c.uid.is_some() && !c.context_type.is_fixed())
+        .filter(|c| c.context_type != ContextType::CONVERSATION_HISTORY)
+        .map(|c| SnapshotPanel {
+            panel_type: c.context_type.clone(),
+            name: c.name.clone(),
+            current_page: c.current_page,
+            metadata: c.metadata.clone(),
+        })
+        .collect()
+}
+
+/// Capture the current working set
+pub fn capture(state: &State, name: &str) -> ContextSnapshot {
+    ContextSnapshot {
+        name: name.to_string(),
+        created_ms: now_ms(),
+        panels: capture_panels(state),
+        todos: TodoState::get(state).todos.iter().filter(|t| t.status != TodoStatus::Done).cloned().collect(),
+        scratchpad_cells: ScratchpadState::get(state).scratchpad_cells.clone(),
+        loaded_skill_ids: PromptState::get(state).loaded_skill_ids.clone(),
+    }
+}
+
+/// Next free number after the highest `<prefix>N` ID
+fn next_id_after(ids: impl Iterator<Item = String>, prefix: char, current: usize) -> usize {
+    ids.filter_map(|id| id.strip_prefix(prefix).and_then(|n| n.parse::<usize>().ok()))
+        .map(|n| n + 1)
+        .fold(current, usize::max)
+}
+
+/// Dynamic panel types provided by the active modules
+pub fn active_panel_types(state: &State, all_modules_fn: fn() -> Vec<Box<dyn Module>>) -> HashSet<ContextType> {
+    all_modules_fn()
+        .iter()
+        .filter(|m| state.active_modules.contains(m.id()))
+        .flat_map(|m| m.dynamic_panel_types())
+        .collect()
+}
+
+/// Replace the open dynamic panels (history chunks stay) with `panels`. Panels whose
+/// type is not in `allowed_types` (their module is inactive) are skipped and their
+/// names returned.
+pub fn apply_panels(state: &mut State, panels: &[SnapshotPanel], allowed_types: &HashSet<ContextType>) -> Vec<String> {
+    state.context.retain(|c| c.context_type.is_fixed() || c.context_type == ContextType::CONVERSATION_HISTORY);
+    let skill_contents: HashMap<String, String> =
+        PromptState::get(state).skills.iter().map(|s| (s.id.clone(), s.content.clone())).collect();
+    let mut skipped = Vec::new();
+    for panel in panels {
+        if !allowed_types.contains(&panel.panel_type) {
+            skipped.push(panel.name.clone());
+            continue;
+        }
+        let context_id = state.next_available_context_id();
+        let mut elem = make_default_context_element(&context_id, panel.panel_type.clone(), &panel.name, true);
+        elem.uid = Some(format!("UID_{}_P", state.global_next_uid));
+        state.global_next_uid += 1;
+        elem.metadata = panel.metadata.clone();
+        elem.current_page = panel.current_page;
+        if panel.panel_type == ContextType::SKILL
+            && let Some(content) = elem.get_meta_str("skill_prompt_id").and_then(|id| skill_contents.get(id))
+        {
+            elem.cached_content = Some(content.clone());
+        }
+        state.context.push(elem);
+    }
+    for ctx in &mut state.context {
+        ctx.cache_deprecated = true;
+    }
+    state.dirty = true;
+    skipped
+}
+
+/// Swap the snapshot's working set into `state`. Returns the names of panels
+/// skipped because their module is inactive.
+pub fn apply(state: &mut State, snapshot: &ContextSnapshot, allowed_types: &HashSet<ContextType>) -> Vec<String> {
+    let skipped = apply_panels(state, &snapshot.panels, allowed_types);
+
+    // Todos: the snapshot's active list replaces the current one
+    let ts = TodoState::get_mut(state);
+    let ids: HashSet<String> = snapshot.todos.iter().map(|t| t.id.clone()).collect();
+    ts.todos = snapshot.todos.clone();
+    for todo in &mut ts.todos {
+        // Parents that were done when the snapshot was taken are gone
+        if todo.parent_id.as_ref().is_some_and(|p| !ids.contains(p)) {
+            todo.parent_id = None;
+        }
+    }
+    ts.next_todo_id = next_id_after(ids.into_iter(), 'X', ts.next_todo_id);
+
+    // Scratchpad
+    let ss = ScratchpadState::get_mut(state);
+    ss.scratchpad_cells = snapshot.scratchpad_cells.clone();
+    ss.next_scratchpad_id =
+        next_id_after(snapshot.scratchpad_cells.iter().map(|c| c.id.clone()), 'C', ss.next_scratchpad_id);
+
+    // Skills that still exist
+    let ps = PromptState::get_mut(state);
+    let existing: HashSet<&String> = ps.skills.iter().map(|s| &s.id).collect();
+    let loaded = snapshot.loaded_skill_ids.iter().filter(|id| existing.contains(id)).cloned().collect();
+    ps.loaded_skill_ids = loaded;
+
+    skipped
+}
+
+/// Save the current working set under `name`. Refuses to overwrite unless `replace`.
+pub fn save(state: &State, name: &str, replace: bool) -> Result<String, String> {
+    validate_name(name)?;
+    let path = snapshot_file_path(name);
+    if path.exists() && !replace {
+        return Err(format!("Snapshot '{}' already exists. Set replace to overwrite it.", name));
+    }
+    let snapshot = capture(state, name);
+    fs::create_dir_all(snapshots_path()).map_err(|e| format!("Failed to create snapshots directory: {}", e))?;
+    let json = serde_json::to_string_pretty(&snapshot).map_err(|e| format!("Failed to serialize snapshot: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write snapshot file: {}", e))?;
+    Ok(format!(
+        "Snapshot '{}' saved ({} panels, {} todos, {} scratchpad cells, {} skills)",
+        name,
+        snapshot.panels.len(),
+        snapshot.todos.len(),
+        snapshot.scratchpad_cells.len(),
+        snapshot.loaded_skill_ids.len()
+    ))
+}
+
+/// Restore the snapshot `name`, replacing the current working set
+pub fn restore(state: &mut State, name: &str, all_modules_fn: fn() -> Vec<Box<dyn Module>>) -> Result<String, String> {
+    let path = snapshot_file_path(name);
+    if !path.exists() {
+        let available: Vec<String> = list_snapshots().into_iter().map(|s| s.name).collect();
+        return Err(if available.is_empty() {
+            format!("Snapshot '{}' not found. No snapshots saved.", name)
+        } else {
+            format!("Snapshot '{}' not found. Available snapshots: {}", name, available.join(", "))
+        });
+    }
+    let json = fs::read_to_string(&path).map_err(|e| format!("Failed to read snapshot '{}': {}", name, e))?;
+    let snapshot: ContextSnapshot =
+        serde_json::from_str(&json).map_err(|e| format!("Failed to parse snapshot '{}': {}", name, e))?;
+
+    let allowed_types = active_panel_types(state, all_modules_fn);
+    let skipped = apply(state, &snapshot, &allowed_types);
+
+    let mut msg = format!(
+        "Restored snapshot '{}': {} panels, {} todos, {} scratchpad cells",
+        name,
+        snapshot.panels.len() - skipped.len(),
+        snapshot.todos.len(),
+        snapshot.scratchpad_cells.len()
+    );
+    if !skipped.is_empty() {
+        msg.push_str(&format!("\nSkipped (module inactive): {}", skipped.join(", ")));
+    }
+    Ok(msg)
+}
+
+/// List saved snapshots, newest first
+pub fn list_snapshots() -> Vec<SnapshotInfo> {
+    let mut snapshots = Vec::new();
+    if let Ok(entries) = fs::read_dir(snapshots_path()) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            if let Ok(contents) = fs::read_to_string(&path)
+                && let Ok(s) = serde_json::from_str::<ContextSnapshot>(&contents)
+            {
+                snapshots.push(SnapshotInfo {
+                    name: s.name,
+                    created_ms: s.created_ms,
+                    panels: s.panels.len(),
+                    todos: s.todos.len(),
+                });
+            }
+        }
+    }
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.created_ms));
+    snapshots
+}
+
+/// First unused "snap-N" name, for snapshots taken from the command palette
+pub fn next_auto_name() -> String {
+    let taken: HashSet<String> = list_snapshots().into_iter().map(|s| s.name).collect();
+    (1..).map(|n| format!("snap-{}", n)).find(|n| !taken.contains(n)).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn todo(id: &str, parent: Option<&str>, status: TodoStatus) -> TodoItem {
+        TodoItem {
+            id: id.to_string(),
+            parent_id: parent.map(|p| p.to_string()),
+            name: format!("task {}", id),
+            description: String::new(),
+            status,
+        }
+    }
+
+    fn test_state() -> State {
+        let mut state = State::default();
+        state.set_ext(TodoState::new());
+        state.set_ext(ScratchpadState::new());
+        state.set_ext(PromptState::new());
+        state
+    }
+
+    fn open_file(state: &mut State, path: &str) {
+        let id = state.next_available_context_id();
+        let mut ctx = make_default_context_element(&id, ContextType::new(ContextType::FILE), path, false);
+        ctx.uid = Some(format!("UID_{}_P", state.global_next_uid));
+        state.global_next_uid += 1;
+        ctx.set_meta("file_path", &path);
+        ctx.current_page = 2;
+        state.context.push(ctx);
+    }
+
+    #[test]
+    fn capture_keeps_active_todos_and_panels() {
+        let mut state = test_state();
+        open_file(&mut state, "src/main.rs");
+        TodoState::get_mut(&mut state).todos = vec![
+            todo("X1", None, TodoStatus::Done),
+            todo("X2", Some("X1"), TodoStatus::InProgress),
+            todo("X3", None, TodoStatus::Pending),
+        ];
+
+        let snap = capture(&state, "task-a");
+        assert_eq!(snap.panels.len(), 1);
+        assert_eq!(snap.panels[0].current_page, 2);
+        assert_eq!(snap.todos.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(), vec!["X2", "X3"]);
+    }
+
+    #[test]
+    fn apply_swaps_the_working_set() {
+        let mut state = test_state();
+        open_file(&mut state, "src/main.rs");
+        TodoState::get_mut(&mut state).todos =
+            vec![todo("X1", None, TodoStatus::Done), todo("X2", Some("X1"), TodoStatus::Pending)];
+        let snap = capture(&state, "task-a");
+
+        // Switch to another task
+        state.context.retain(|c| c.uid.is_none());
+        open_file(&mut state, "README.md");
+        TodoState::get_mut(&mut state).todos = vec![todo("X7", None, TodoStatus::Pending)];
+        TodoState::get_mut(&mut state).next_todo_id = 8;
+
+        let allowed: HashSet<ContextType> = [ContextType::new(ContextType::FILE)].into_iter().collect();
+        assert!(apply(&mut state, &snap, &allowed).is_empty());
+
+        let files: Vec<&str> = state.context.iter().filter_map(|c| c.get_meta_str("file_path")).collect();
+        assert_eq!(files, vec!["src/main.rs"]);
+        assert_eq!(state.context[0].current_page, 2);
+        let ts = TodoState::get(&state);
+        assert_eq!(ts.todos.len(), 1);
+        assert_eq!(ts.todos[0].parent_id, None, "done parent was not captured");
+        assert_eq!(ts.next_todo_id, 8, "IDs never go backwards");
+
+        // Panels of inactive modules are skipped
+        assert_eq!(apply(&mut state, &snap, &HashSet::new()), vec!["src/main.rs".to_string()]);
+    }
+}
@@ -117,6 +117,9 @@ pub(crate) fn execute_snapshot(
             grep_pattern: ctx.get_meta_str("grep_pattern").map(|s| s.to_string()),
             grep_path: ctx.get_meta_str("grep_path").map(|s| s.to_string()),
             grep_file_pattern: ctx.get_meta_str("grep_file_pattern").map(|s| s.to_string()),
+            grep_context_lines: ctx.get_meta_usize("grep_context_lines"),
+            grep_ignore_case: ctx.get_meta::<bool>("grep_ignore_case"),
+            search_max_results: ctx.get_meta_usize("search_max_results"),
             tmux_pane_id: ctx.get_meta_str("tmux_pane_id").map(|s| s.to_string()),
             tmux_lines: ctx.get_meta_usize("tmux_lines"),
             tmux_description: ctx.get_meta_str("tmux_description").map(|s| s.to_string()),
@@ -282,6 +285,15 @@ pub(crate) fn execute_load(
         if let Some(ref v) = panel_cfg.grep_file_pattern {
             elem.set_meta("grep_file_pattern", v);
         }
+        if let Some(v) = panel_cfg.grep_context_lines {
+            elem.set_meta("grep_context_lines", &v);
+        }
+        if let Some(v) = panel_cfg.grep_ignore_case {
+            elem.set_meta("grep_ignore_case", &v);
+        }
+        if let Some(v) = panel_cfg.search_max_results {
+            elem.set_meta("search_max_results", &v);
+        }
         if let Some(ref v) = panel_cfg.tmux_pane_id {
             elem.set_meta("tmux_pane_id", v);
         }
@@ -339,6 +351,31 @@ pub(crate) fn execute_load(
     )
 }
 
+pub(crate) fn execute_context_snapshot(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let Some(name) = tool.input.get("name").and_then(|v| v.as_str()) else {
+        return ToolResult::new(tool.id.clone(), "Missing required 'name' parameter".to_string(), true);
+    };
+    let replace = tool.input.get("replace").and_then(|v| v.as_bool()).unwrap_or(false);
+    match crate::snapshot::save(state, name, replace) {
+        Ok(msg) => ToolResult::new(tool.id.clone(), msg, false),
+        Err(e) => ToolResult::new(tool.id.clone(), e, true),
+    }
+}
+
+pub(crate) fn execute_context_restore(
+    tool: &ToolUse,
+    state: &mut State,
+    all_modules_fn: fn() -> Vec<Box<dyn Module>>,
+) -> ToolResult {
+    let Some(name) = tool.input.get("name").and_then(|v| v.as_str()) else {
+        return ToolResult::new(tool.id.clone(), "Missing required 'name' parameter".to_string(), true);
+    };
+    match crate::snapshot::restore(state, name, all_modules_fn) {
+        Ok(msg) => ToolResult::new(tool.id.clone(), msg, false),
+        Err(e) => ToolResult::new(tool.id.clone(), e, true),
+    }
+}
+
 /// List all available preset names
 fn list_available_presets() -> Vec<String> {
     let dir = presets_path();
@@ -51,6 +51,12 @@ pub struct PresetPanelConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub grep_file_pattern: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grep_context_lines: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grep_ignore_case: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search_max_results: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tmux_pane_id: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tmux_lines: Option<usize>,
@@ -0,0 +1,237 @@
+mod panel;
+pub mod search;
+mod tools;
+pub mod types;
+
+use std::collections::BTreeSet;
+
+use cp_base::panels::{Panel, WatchSpec};
+use cp_base::state::{ContextElement, ContextType, State};
+use cp_base::tools::{ParamType, ToolDefinition, ToolParam};
+use cp_base::tools::{ToolResult, ToolUse};
+
+use self::panel::SearchPanel;
+use self::search::{glob_matcher, relative_to};
+use self::types::{MAX_CONTEXT_LINES, SearchSpec, normalize_path};
+use cp_base::modules::Module;
+
+/// Current tree filter (the tree module may be inactive: fall back to its default)
+pub(crate) fn tree_filter(state: &State) -> String {
+    state
+        .get_ext::<cp_mod_tree::TreeState>()
+        .map(|t| t.tree_filter.clone())
+        .unwrap_or_else(|| cp_mod_tree::DEFAULT_TREE_FILTER.to_string())
+}
+
+fn is_search_panel(ctx: &ContextElement) -> bool {
+    matches!(ctx.context_type.as_str(), ContextType::GLOB | ContextType::GREP)
+}
+
+pub struct SearchModule;
+
+impl Module for SearchModule {
+    fn id(&self) -> &'static str {
+        "search"
+    }
+    fn name(&self) -> &'static str {
+        "Search"
+    }
+    fn description(&self) -> &'static str {
+        "Live glob and grep panels over the project"
+    }
+
+    fn dynamic_panel_types(&self) -> Vec<ContextType> {
+        vec![ContextType::new(ContextType::GLOB), ContextType::new(ContextType::GREP)]
+    }
+
+    fn create_panel(&self, context_type: &ContextType) -> Option<Box<dyn Panel>> {
+        match context_type.as_str() {
+            ContextType::GLOB => Some(Box::new(SearchPanel { context_type: ContextType::GLOB })),
+            ContextType::GREP => Some(Box::new(SearchPanel { context_type: ContextType::GREP })),
+            _ => None,
+        }
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        vec![
+            ToolDefinition {
+                id: "glob".to_string(),
+                name: "Glob".to_string(),
+                short_desc: "Find files by path pattern".to_string(),
+                description: "Lists files whose path matches a glob (e.g. '**/*.rs', 'src/**/mod.rs'), relative to the searched directory. Respects .gitignore and the tree filter. Results appear in a panel that refreshes when matching files are added or removed.".to_string(),
+                params: vec![
+                    ToolParam::new("pattern", ParamType::String)
+                        .desc("Glob pattern (e.g., '**/*.rs', '*.md')")
+                        .required(),
+                    ToolParam::new("path", ParamType::String)
+                        .desc("Directory to search")
+                        .default_val("."),
+                    ToolParam::new("max_results", ParamType::Integer)
+                        .desc("Maximum files listed (default 200, max 2000)"),
+                ],
+                enabled: true,
+                reverie_allowed: true,
+                category: "Search".to_string(),
+            },
+            ToolDefinition {
+                id: "grep".to_string(),
+                name: "Grep".to_string(),
+                short_desc: "Search file contents".to_string(),
+                description: "Searches file contents with a regex. Respects .gitignore and the tree filter. Results are path:line:text entries (context lines use path-line-text) in a panel that refreshes when searched files change. Prefer this over shelling out to grep/rg in the console.".to_string(),
+                params: vec![
+                    ToolParam::new("pattern", ParamType::String)
+                        .desc("Regex pattern (Rust regex syntax)")
+                        .required(),
+                    ToolParam::new("path", ParamType::String)
+                        .desc("Directory to search")
+                        .default_val("."),
+                    ToolParam::new("file_pattern", ParamType::String)
+                        .desc("Only search files whose path matches this glob (e.g., '*.rs', 'src/**/*.ts')"),
+                    ToolParam::new("context_lines", ParamType::Integer)
+                        .desc(&format!("Lines of context around each match (default 0, max {})", MAX_CONTEXT_LINES)),
+                    ToolParam::new("ignore_case", ParamType::Boolean)
+                        .desc("Case-insensitive matching")
+                        .default_val("false"),
+                    ToolParam::new("max_results", ParamType::Integer)
+                        .desc("Maximum matching lines (default 200, max 2000)"),
+                ],
+                enabled: true,
+                reverie_allowed: true,
+                category: "Search".to_string(),
+            },
+        ]
+    }
+
+    fn execute_tool(&self, tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+        match tool.name.as_str() {
+            "glob" => Some(self::tools::execute_glob(tool, state)),
+            "grep" => Some(self::tools::execute_grep(tool, state)),
+            _ => None,
+        }
+    }
+
+    fn context_type_metadata(&self) -> Vec<cp_base::state::ContextTypeMeta> {
+        vec![
+            cp_base::state::ContextTypeMeta {
+                context_type: ContextType::GLOB,
+                icon_id: "glob",
+                is_fixed: false,
+                needs_cache: true,
+                fixed_order: None,
+                display_name: "glob",
+                short_name: "glob",
+                needs_async_wait: false,
+            },
+            cp_base::state::ContextTypeMeta {
+                context_type: ContextType::GREP,
+                icon_id: "grep",
+                is_fixed: false,
+                needs_cache: true,
+                fixed_order: None,
+                display_name: "grep",
+                short_name: "grep",
+                needs_async_wait: false,
+            },
+        ]
+    }
+
+    fn context_detail(&self, ctx: &ContextElement) -> Option<String> {
+        if is_search_panel(ctx) { SearchSpec::from_meta(ctx).map(|s| s.label()) } else { None }
+    }
+
+    fn tool_category_descriptions(&self) -> Vec<(&'static str, &'static str)> {
+        vec![("Search", "Find files by name and content")]
+    }
+
+    /// Searched directories, recursively. A whole-project search watches the
+    /// root (for its own files) plus each top-level directory the tree filter keeps.
+    fn watch_paths(&self, state: &State) -> Vec<WatchSpec> {
+        let roots: BTreeSet<String> =
+            state.context.iter().filter_map(SearchSpec::from_meta).map(|s| s.path().to_string()).collect();
+        if roots.is_empty() {
+            return vec![];
+        }
+        let mut specs = Vec::new();
+        for root in roots {
+            if root != "." {
+                specs.push(WatchSpec::DirRecursive(root));
+                continue;
+            }
+            specs.push(WatchSpec::Dir(".".to_string()));
+            let filter = cp_mod_tree::filter_matcher(&tree_filter(state));
+            let Ok(entries) = std::fs::read_dir(".") else { continue };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let name = entry.file_name().to_string_lossy().to_string();
+                if !path.is_dir() || name.starts_with('.') {
+                    continue;
+                }
+                if filter.as_ref().is_some_and(|f| f.matched(&path, true).is_ignore()) {
+                    continue;
+                }
+                specs.push(WatchSpec::DirRecursive(name));
+            }
+        }
+        specs
+    }
+
+    fn should_invalidate_on_fs_change(&self, ctx: &ContextElement, changed_path: &str, is_dir_event: bool) -> bool {
+        if !is_search_panel(ctx) {
+            return false;
+        }
+        let Some(spec) = SearchSpec::from_meta(ctx) else { return false };
+        let changed = normalize_path(changed_path);
+        // Direct children of a watched directory only report the directory
+        if is_dir_event {
+            return changed == spec.path();
+        }
+        let root = spec.path();
+        if root != "." && !changed.starts_with(&format!("{}/", root)) {
+            return false;
+        }
+        let rel = relative_to(&changed, root);
+        match &spec {
+            SearchSpec::Glob(g) => glob_matcher(&g.pattern).is_ok_and(|m| m.is_match(rel)),
+            SearchSpec::Grep(g) => {
+                g.file_pattern.as_deref().is_none_or(|f| glob_matcher(f).is_ok_and(|m| m.is_match(rel)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{GlobSpec, GrepSpec};
+
+    fn panel(spec: SearchSpec) -> ContextElement {
+        let mut ctx = cp_base::state::make_default_context_element("P10", spec.context_type(), "x", false);
+        spec.to_meta(&mut ctx);
+        ctx
+    }
+
+    #[test]
+    fn only_matching_file_changes_invalidate() {
+        let glob = panel(SearchSpec::Glob(GlobSpec {
+            pattern: "**/*.rs".to_string(),
+            path: "src".to_string(),
+            max_results: 10,
+        }));
+        let grep = panel(SearchSpec::Grep(GrepSpec {
+            pattern: "x".to_string(),
+            path: ".".to_string(),
+            file_pattern: Some("*.md".to_string()),
+            context_lines: 0,
+            ignore_case: false,
+            max_results: 10,
+        }));
+        let m = SearchModule;
+        assert!(m.should_invalidate_on_fs_change(&glob, "src/ui/mod.rs", false));
+        assert!(!m.should_invalidate_on_fs_change(&glob, "src/ui/notes.md", false));
+        assert!(!m.should_invalidate_on_fs_change(&glob, "tests/a.rs", false));
+        assert!(m.should_invalidate_on_fs_change(&grep, "docs/arch.md", false));
+        assert!(!m.should_invalidate_on_fs_change(&grep, "src/main.rs", false));
+        assert!(m.should_invalidate_on_fs_change(&grep, ".", true));
+        assert!(!m.should_invalidate_on_fs_change(&glob, ".", true));
+    }
+}
@@ -0,0 +1,165 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+
+use cp_base::config::constants::{SCROLL_ARROW_AMOUNT, SCROLL_PAGE_AMOUNT};
+use cp_base::config::theme;
+use cp_base::panels::{CacheRequest, CacheUpdate};
+use cp_base::panels::{ContextItem, Panel, paginate_content, update_if_changed};
+use cp_base::state::Action;
+use cp_base::state::{ContextElement, ContextType, State, compute_total_pages, estimate_tokens};
+
+use crate::search::{run_glob, run_grep};
+use crate::tree_filter;
+use crate::types::SearchSpec;
+
+pub struct SearchRequest {
+    pub context_id: String,
+    pub spec: SearchSpec,
+    /// Tree filter at request time (gitignore-style patterns)
+    pub tree_filter: String,
+}
+
+/// Glob or grep result panel
+pub(crate) struct SearchPanel {
+    pub context_type: &'static str,
+}
+
+/// Split a result line into (location, is_match, text):
+/// "src/a.rs:12:text" -> ("src/a.rs:12", true, "text")
+fn split_location(line: &str) -> Option<(&str, bool, &str)> {
+    line.char_indices().find_map(|(i, sep)| {
+        if sep != ':' && sep != '-' {
+            return None;
+        }
+        let rest = &line[i + 1..];
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        (digits > 0 && rest[digits..].starts_with(sep))
+            .then(|| (&line[..i + 1 + digits], sep == ':', &rest[digits + 1..]))
+    })
+}
+
+impl Panel for SearchPanel {
+    fn needs_cache(&self) -> bool {
+        true
+    }
+
+    fn build_cache_request(&self, ctx: &ContextElement, state: &State) -> Option<CacheRequest> {
+        Some(CacheRequest {
+            context_type: ctx.context_type.clone(),
+            data: Box::new(SearchRequest {
+                context_id: ctx.id.clone(),
+                spec: SearchSpec::from_meta(ctx)?,
+                tree_filter: tree_filter(state),
+            }),
+        })
+    }
+
+    fn apply_cache_update(&self, update: CacheUpdate, ctx: &mut ContextElement, _state: &mut State) -> bool {
+        let CacheUpdate::Content { content, token_count, .. } = update else {
+            return false;
+        };
+        ctx.cache_deprecated = false;
+        if !update_if_changed(ctx, &content) && ctx.cached_content.is_some() {
+            return false;
+        }
+        ctx.cached_content = Some(content);
+        ctx.full_token_count = token_count;
+        ctx.token_count = token_count;
+        ctx.total_pages = compute_total_pages(token_count);
+        ctx.current_page = 0;
+        true
+    }
+
+    fn refresh_cache(&self, request: CacheRequest) -> Option<CacheUpdate> {
+        let req = request.data.downcast::<SearchRequest>().ok()?;
+        let SearchRequest { context_id, spec, tree_filter } = *req;
+        let filter = cp_mod_tree::filter_matcher(&tree_filter);
+        let content = match &spec {
+            SearchSpec::Glob(glob) => run_glob(glob, filter.as_ref()),
+            SearchSpec::Grep(grep) => run_grep(grep, filter.as_ref()),
+        };
+        let token_count = estimate_tokens(&content);
+        Some(CacheUpdate::Content { context_id, content, token_count })
+    }
+
+    fn handle_key(&self, key: &KeyEvent, _state: &State) -> Option<Action> {
+        match key.code {
+            KeyCode::Up => Some(Action::ScrollUp(SCROLL_ARROW_AMOUNT)),
+            KeyCode::Down => Some(Action::ScrollDown(SCROLL_ARROW_AMOUNT)),
+            KeyCode::PageUp => Some(Action::ScrollUp(SCROLL_PAGE_AMOUNT)),
+            KeyCode::PageDown => Some(Action::ScrollDown(SCROLL_PAGE_AMOUNT)),
+            _ => None,
+        }
+    }
+
+    fn title(&self, state: &State) -> String {
+        state
+            .context
+            .get(state.selected_context)
+            .filter(|c| c.context_type.as_str() == self.context_type)
+            .and_then(SearchSpec::from_meta)
+            .map(|s| s.label())
+            .unwrap_or_else(|| if self.context_type == ContextType::GLOB { "Glob" } else { "Grep" }.to_string())
+    }
+
+    fn context(&self, state: &State) -> Vec<ContextItem> {
+        let mut items = Vec::new();
+        for ctx in &state.context {
+            if ctx.context_type.as_str() != self.context_type {
+                continue;
+            }
+            let content = ctx.cached_content.as_deref().unwrap_or("[searching...]");
+            let header = SearchSpec::from_meta(ctx).map(|s| s.label()).unwrap_or_else(|| ctx.name.clone());
+            let output = paginate_content(content, ctx.current_page, ctx.total_pages);
+            items.push(ContextItem::new(&ctx.id, &header, output, ctx.last_refresh_ms));
+        }
+        items
+    }
+
+    fn content(&self, state: &State, base_style: Style) -> Vec<Line<'static>> {
+        let ctx = state.context.get(state.selected_context).filter(|c| c.context_type.as_str() == self.context_type);
+        let Some(content) = ctx.and_then(|c| c.cached_content.as_ref()) else {
+            return vec![Line::from(Span::styled(" Searching...", Style::default().fg(theme::text_muted()).italic()))];
+        };
+
+        let mut lines = content.lines();
+        let mut text = vec![Line::from(vec![
+            Span::styled(" ".to_string(), base_style),
+            Span::styled(lines.next().unwrap_or("").to_string(), Style::default().fg(theme::text_muted()).bold()),
+        ])];
+        for line in lines {
+            let spans = if line == "--" || line.starts_with("... ") {
+                vec![Span::styled(line.to_string(), Style::default().fg(theme::text_muted()))]
+            } else if let Some((location, is_match, rest)) = split_location(line) {
+                let (loc_style, text_style) = if is_match {
+                    (Style::default().fg(theme::accent()), Style::default().fg(theme::text()))
+                } else {
+                    (Style::default().fg(theme::text_muted()), Style::default().fg(theme::text_muted()))
+                };
+                vec![
+                    Span::styled(location.to_string(), loc_style),
+                    Span::styled("  ".to_string(), base_style),
+                    Span::styled(rest.to_string(), text_style),
+                ]
+            } else {
+                vec![Span::styled(line.to_string(), Style::default().fg(theme::text()))]
+            };
+            let mut line_spans = vec![Span::styled(" ".to_string(), base_style)];
+            line_spans.extend(spans);
+            text.push(Line::from(line_spans));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn result_lines_split_at_their_location() {
+        assert_eq!(split_location("src/a-b.rs:12:let x = 1;"), Some(("src/a-b.rs:12", true, "let x = 1;")));
+        assert_eq!(split_location("src/a-b.rs-13-}"), Some(("src/a-b.rs-13", false, "}")));
+        assert_eq!(split_location("src/main.rs"), None);
+    }
+}
@@ -0,0 +1,249 @@
+//! Project walks for glob and grep panels.
+//!
+//! Both honour `.gitignore` (through the `ignore` walker) and the tree filter,
+//! skip hidden entries, and stop at the panel's result cap.
+
+use std::fs;
+use std::path::Path;
+
+use globset::{Glob, GlobMatcher};
+use ignore::gitignore::Gitignore;
+use regex::RegexBuilder;
+
+use crate::types::{GlobSpec, GrepSpec};
+
+/// Files larger than this are not grepped
+const MAX_GREP_FILE_BYTES: u64 = 2_000_000;
+/// Matched lines are cut to this many characters
+const MAX_LINE_CHARS: usize = 300;
+
+pub fn glob_matcher(pattern: &str) -> Result<GlobMatcher, String> {
+    Glob::new(pattern).map(|g| g.compile_matcher()).map_err(|e| format!("Invalid glob '{}': {}", pattern, e))
+}
+
+/// Project-relative paths of the files under `root`, sorted
+fn walk(root: &str, filter: Option<&Gitignore>) -> Vec<String> {
+    let filter = filter.cloned();
+    let mut files: Vec<String> = ignore::WalkBuilder::new(root)
+        .filter_entry(move |entry| {
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            filter.as_ref().is_none_or(|f| !f.matched(entry.path(), is_dir).is_ignore())
+        })
+        .build()
+        .flatten()
+        .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+        .map(|e| e.path().to_string_lossy().trim_start_matches("./").to_string())
+        .collect();
+    files.sort();
+    files
+}
+
+/// `path` relative to the searched directory, for matching patterns
+pub fn relative_to<'a>(path: &'a str, root: &str) -> &'a str {
+    if root == "." { path } else { path.strip_prefix(root).map(|p| p.trim_start_matches('/')).unwrap_or(path) }
+}
+
+fn more_note(shown: usize, total: usize) -> String {
+    if total > shown {
+        format!("... {} more (narrow the pattern or raise max_results)\n", total - shown)
+    } else {
+        String::new()
+    }
+}
+
+/// Files matching a glob, one path per line
+pub fn run_glob(spec: &GlobSpec, filter: Option<&Gitignore>) -> String {
+    let matcher = match glob_matcher(&spec.pattern) {
+        Ok(m) => m,
+        Err(e) => return e,
+    };
+    let matches: Vec<String> =
+        walk(&spec.path, filter).into_iter().filter(|p| matcher.is_match(relative_to(p, &spec.path))).collect();
+    if matches.is_empty() {
+        return format!("No files matching '{}'\n", spec.pattern);
+    }
+    let mut out = format!("{} files matching '{}'\n", matches.len(), spec.pattern);
+    for path in matches.iter().take(spec.max_results) {
+        out.push_str(path);
+        out.push('\n');
+    }
+    out.push_str(&more_note(spec.max_results.min(matches.len()), matches.len()));
+    out
+}
+
+fn clip(line: &str) -> &str {
+    match line.char_indices().nth(MAX_LINE_CHARS) {
+        Some((i, _)) => &line[..i],
+        None => line,
+    }
+}
+
+/// Lines matching a regex, ripgrep-style: `path:line:text` for matches,
+/// `path-line-text` for context lines, `--` between separate groups
+pub fn run_grep(spec: &GrepSpec, filter: Option<&Gitignore>) -> String {
+    let regex = match RegexBuilder::new(&spec.pattern).case_insensitive(spec.ignore_case).build() {
+        Ok(r) => r,
+        Err(e) => return format!("Invalid regex '{}': {}\n", spec.pattern, e),
+    };
+    let file_matcher = match spec.file_pattern.as_deref().map(glob_matcher).transpose() {
+        Ok(m) => m,
+        Err(e) => return e,
+    };
+
+    let mut body = String::new();
+    let mut match_count = 0;
+    let mut file_count = 0;
+    let mut capped = false;
+
+    'files: for path in walk(&spec.path, filter) {
+        if file_matcher.as_ref().is_some_and(|m| !m.is_match(relative_to(&path, &spec.path))) {
+            continue;
+        }
+        if fs::metadata(&path).is_ok_and(|m| m.len() > MAX_GREP_FILE_BYTES) {
+            continue;
+        }
+        // Binary and non-UTF-8 files fail here
+        let Ok(content) = fs::read_to_string(Path::new(&path)) else { continue };
+        let lines: Vec<&str> = content.lines().collect();
+        let hits: Vec<usize> = lines.iter().enumerate().filter(|(_, l)| regex.is_match(l)).map(|(i, _)| i).collect();
+        if hits.is_empty() {
+            continue;
+        }
+        file_count += 1;
+
+        // Last line printed for this file, to merge overlapping context windows
+        let mut printed_until: Option<usize> = None;
+        for &hit in &hits {
+            if match_count >= spec.max_results {
+                capped = true;
+                break 'files;
+            }
+            match_count += 1;
+            let from = hit.saturating_sub(spec.context_lines);
+            let to = (hit + spec.context_lines).min(lines.len() - 1);
+            let start = match printed_until {
+                Some(last) if from <= last + 1 => last + 1,
+                _ => {
+                    if spec.context_lines > 0 && !body.is_empty() {
+                        body.push_str("--\n");
+                    }
+                    from
+                }
+            };
+            for (i, line) in lines.iter().enumerate().take(to + 1).skip(start) {
+                let sep = if hits.binary_search(&i).is_ok() { ':' } else { '-' };
+                body.push_str(&format!("{}{}{}{}{}\n", path, sep, i + 1, sep, clip(line)));
+            }
+            printed_until = Some(to.max(printed_until.unwrap_or(0)));
+        }
+    }
+
+    if match_count == 0 {
+        return format!("No matches for /{}/\n", spec.pattern);
+    }
+    let cap_note = if capped { " (stopped at max_results, narrow the pattern or raise it)" } else { "" };
+    format!("{} matches in {} files for /{}/{}\n{}", match_count, file_count, spec.pattern, cap_note, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A small project in a temp dir; walks run relative to the current directory,
+    /// so tests pass the absolute root as `path`
+    fn project(name: &str) -> String {
+        let root = std::env::temp_dir().join(format!("cp_search_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src/ui")).unwrap();
+        fs::create_dir_all(root.join("target")).unwrap();
+        fs::write(root.join("src/main.rs"), "fn main() {\n    run();\n}\n\nfn run() {}\n").unwrap();
+        fs::write(root.join("src/ui/mod.rs"), "pub fn render() {\n    // run later\n}\n").unwrap();
+        fs::write(root.join("target/out.rs"), "fn run() {}\n").unwrap();
+        fs::write(root.join("README.md"), "Run it.\n").unwrap();
+        root.to_string_lossy().to_string()
+    }
+
+    fn filter_for(root: &str) -> Gitignore {
+        let mut builder = ignore::gitignore::GitignoreBuilder::new(root);
+        builder.add_line(None, "target/").unwrap();
+        builder.build().unwrap()
+    }
+
+    fn strip(out: &str, root: &str) -> String {
+        out.replace(&format!("{}/", root), "")
+    }
+
+    #[test]
+    fn glob_respects_filter_and_cap() {
+        let root = project("glob");
+        let filter = filter_for(&root);
+        let spec = GlobSpec { pattern: "**/*.rs".to_string(), path: root.clone(), max_results: 200 };
+        assert_eq!(
+            strip(&run_glob(&spec, Some(&filter)), &root),
+            "2 files matching '**/*.rs'\nsrc/main.rs\nsrc/ui/mod.rs\n"
+        );
+
+        let capped = GlobSpec { max_results: 1, ..spec };
+        assert_eq!(
+            strip(&run_glob(&capped, None), &root),
+            "3 files matching '**/*.rs'\nsrc/main.rs\n... 2 more (narrow the pattern or raise max_results)\n"
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn grep_with_context_and_file_pattern() {
+        let root = project("grep");
+        let filter = filter_for(&root);
+        let spec = GrepSpec {
+            pattern: r"\brun\b".to_string(),
+            path: root.clone(),
+            file_pattern: Some("src/*.rs".to_string()),
+            context_lines: 1,
+            ignore_case: false,
+            max_results: 200,
+        };
+        assert_eq!(
+            strip(&run_grep(&spec, Some(&filter)), &root),
+            "3 matches in 2 files for /\\brun\\b/\n\
+             src/main.rs-1-fn main() {\n\
+             src/main.rs:2:    run();\n\
+             src/main.rs-3-}\n\
+             src/main.rs-4-\n\
+             src/main.rs:5:fn run() {}\n\
+             --\n\
+             src/ui/mod.rs-1-pub fn render() {\n\
+             src/ui/mod.rs:2:    // run later\n\
+             src/ui/mod.rs-3-}\n"
+        );
+
+        let any_case = GrepSpec {
+            pattern: "^run".to_string(),
+            file_pattern: None,
+            context_lines: 0,
+            ignore_case: true,
+            max_results: 1,
+            ..spec
+        };
+        assert_eq!(
+            strip(&run_grep(&any_case, Some(&filter)), &root),
+            "1 matches in 1 files for /^run/\nREADME.md:1:Run it.\n"
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn invalid_patterns_are_reported() {
+        let spec = GrepSpec {
+            pattern: "(".to_string(),
+            path: ".".to_string(),
+            file_pattern: None,
+            context_lines: 0,
+            ignore_case: false,
+            max_results: 10,
+        };
+        assert!(run_grep(&spec, None).starts_with("Invalid regex '('"));
+        assert_eq!(relative_to("src/ui/mod.rs", "src"), "ui/mod.rs");
+        assert_eq!(relative_to("src/main.rs", "."), "src/main.rs");
+    }
+}
@@ -0,0 +1,99 @@
+use std::path::Path;
+
+use cp_base::state::State;
+use cp_base::tools::{ToolResult, ToolUse};
+
+use crate::search::glob_matcher;
+use crate::types::{
+    DEFAULT_MAX_RESULTS, GlobSpec, GrepSpec, MAX_CONTEXT_LINES, MAX_RESULTS_LIMIT, SearchSpec, normalize_path,
+};
+
+fn error(tool: &ToolUse, msg: String) -> ToolResult {
+    ToolResult::new(tool.id.clone(), format!("Error: {}", msg), true)
+}
+
+/// Common parameters: required pattern, searched directory, result cap
+fn common_params(tool: &ToolUse) -> Result<(String, String, usize), String> {
+    let pattern = tool
+        .input
+        .get("pattern")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| "'pattern' parameter is required".to_string())?;
+    let path = normalize_path(tool.input.get("path").and_then(|v| v.as_str()).unwrap_or("."));
+    if !Path::new(&path).is_dir() {
+        return Err(format!("'{}' is not a directory", path));
+    }
+    let max_results = tool
+        .input
+        .get("max_results")
+        .and_then(|v| v.as_u64())
+        .map(|v| (v as usize).clamp(1, MAX_RESULTS_LIMIT))
+        .unwrap_or(DEFAULT_MAX_RESULTS);
+    Ok((pattern.to_string(), path, max_results))
+}
+
+pub fn execute_glob(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let (pattern, path, max_results) = match common_params(tool) {
+        Ok(p) => p,
+        Err(e) => return error(tool, e),
+    };
+    if let Err(e) = glob_matcher(&pattern) {
+        return error(tool, e);
+    }
+    open_search_panel(tool, state, SearchSpec::Glob(GlobSpec { pattern, path, max_results }))
+}
+
+pub fn execute_grep(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let (pattern, path, max_results) = match common_params(tool) {
+        Ok(p) => p,
+        Err(e) => return error(tool, e),
+    };
+    let ignore_case = tool.input.get("ignore_case").and_then(|v| v.as_bool()).unwrap_or(false);
+    if let Err(e) = regex::RegexBuilder::new(&pattern).case_insensitive(ignore_case).build() {
+        return error(tool, format!("invalid regex '{}': {}", pattern, e));
+    }
+    let file_pattern = tool.input.get("file_pattern").and_then(|v| v.as_str()).filter(|s| !s.is_empty());
+    if let Some(Err(e)) = file_pattern.map(glob_matcher) {
+        return error(tool, e);
+    }
+    let context_lines = tool
+        .input
+        .get("context_lines")
+        .and_then(|v| v.as_u64())
+        .map(|v| (v as usize).min(MAX_CONTEXT_LINES))
+        .unwrap_or(0);
+    let spec = SearchSpec::Grep(GrepSpec {
+        pattern,
+        path,
+        file_pattern: file_pattern.map(|s| s.to_string()),
+        context_lines,
+        ignore_case,
+        max_results,
+    });
+    open_search_panel(tool, state, spec)
+}
+
+/// Reuse the panel already showing this search, or create one
+fn open_search_panel(tool: &ToolUse, state: &mut State, spec: SearchSpec) -> ToolResult {
+    let context_type = spec.context_type();
+    let existing = state
+        .context
+        .iter_mut()
+        .find(|c| c.context_type == context_type && SearchSpec::from_meta(c).as_ref() == Some(&spec));
+    if let Some(ctx) = existing {
+        ctx.cache_deprecated = true;
+        return ToolResult::new(tool.id.clone(), format!("Panel updated: {} ({})", ctx.id, spec.label()), false);
+    }
+
+    let panel_id = state.next_available_context_id();
+    let uid = format!("UID_{}_P", state.global_next_uid);
+    state.global_next_uid += 1;
+
+    let mut elem = cp_base::state::make_default_context_element(&panel_id, context_type, &spec.label(), true);
+    elem.uid = Some(uid);
+    spec.to_meta(&mut elem);
+    state.context.push(elem);
+
+    ToolResult::new(tool.id.clone(), format!("Panel created: {} ({})", panel_id, spec.label()), false)
+}
@@ -0,0 +1,161 @@
+use cp_base::state::{ContextElement, ContextType};
+
+/// Default cap on listed files (glob) or matching lines (grep)
+pub const DEFAULT_MAX_RESULTS: usize = 200;
+/// Hard ceiling for `max_results`
+pub const MAX_RESULTS_LIMIT: usize = 2_000;
+/// Hard ceiling for grep `context_lines`
+pub const MAX_CONTEXT_LINES: usize = 10;
+
+// Panel metadata keys. The pattern/path keys are the ones presets have always
+// stored for glob and grep panels.
+pub const GLOB_PATTERN_META: &str = "glob_pattern";
+pub const GLOB_PATH_META: &str = "glob_path";
+pub const GREP_PATTERN_META: &str = "grep_pattern";
+pub const GREP_PATH_META: &str = "grep_path";
+pub const GREP_FILE_PATTERN_META: &str = "grep_file_pattern";
+pub const GREP_CONTEXT_LINES_META: &str = "grep_context_lines";
+pub const GREP_IGNORE_CASE_META: &str = "grep_ignore_case";
+pub const MAX_RESULTS_META: &str = "search_max_results";
+
+/// Files whose path matches a glob
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlobSpec {
+    pub pattern: String,
+    /// Directory searched (project-relative, "." for the whole project)
+    pub path: String,
+    pub max_results: usize,
+}
+
+/// Lines matching a regex
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrepSpec {
+    pub pattern: String,
+    pub path: String,
+    /// Only search files whose path matches this glob
+    pub file_pattern: Option<String>,
+    pub context_lines: usize,
+    pub ignore_case: bool,
+    pub max_results: usize,
+}
+
+/// What a search panel shows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchSpec {
+    Glob(GlobSpec),
+    Grep(GrepSpec),
+}
+
+/// "./src/" -> "src", "" -> "."
+pub fn normalize_path(path: &str) -> String {
+    let trimmed = path.trim().trim_start_matches("./").trim_end_matches('/');
+    if trimmed.is_empty() { ".".to_string() } else { trimmed.to_string() }
+}
+
+impl SearchSpec {
+    pub fn context_type(&self) -> ContextType {
+        match self {
+            SearchSpec::Glob(_) => ContextType::new(ContextType::GLOB),
+            SearchSpec::Grep(_) => ContextType::new(ContextType::GREP),
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        match self {
+            SearchSpec::Glob(g) => &g.path,
+            SearchSpec::Grep(g) => &g.path,
+        }
+    }
+
+    /// Panel name and context header, e.g. "grep: fn main (*.rs)"
+    pub fn label(&self) -> String {
+        let scope = |path: &str| if path == "." { String::new() } else { format!(" in {}", path) };
+        match self {
+            SearchSpec::Glob(g) => format!("glob: {}{}", g.pattern, scope(&g.path)),
+            SearchSpec::Grep(g) => {
+                let files = g.file_pattern.as_ref().map(|f| format!(" ({})", f)).unwrap_or_default();
+                format!("grep: {}{}{}", g.pattern, files, scope(&g.path))
+            }
+        }
+    }
+
+    pub fn from_meta(ctx: &ContextElement) -> Option<Self> {
+        let max_results = ctx.get_meta_usize(MAX_RESULTS_META).unwrap_or(DEFAULT_MAX_RESULTS);
+        match ctx.context_type.as_str() {
+            ContextType::GLOB => Some(SearchSpec::Glob(GlobSpec {
+                pattern: ctx.get_meta_str(GLOB_PATTERN_META)?.to_string(),
+                path: normalize_path(ctx.get_meta_str(GLOB_PATH_META).unwrap_or(".")),
+                max_results,
+            })),
+            ContextType::GREP => Some(SearchSpec::Grep(GrepSpec {
+                pattern: ctx.get_meta_str(GREP_PATTERN_META)?.to_string(),
+                path: normalize_path(ctx.get_meta_str(GREP_PATH_META).unwrap_or(".")),
+                file_pattern: ctx.get_meta_str(GREP_FILE_PATTERN_META).map(|s| s.to_string()),
+                context_lines: ctx.get_meta_usize(GREP_CONTEXT_LINES_META).unwrap_or(0),
+                ignore_case: ctx.get_meta::<bool>(GREP_IGNORE_CASE_META).unwrap_or(false),
+                max_results,
+            })),
+            _ => None,
+        }
+    }
+
+    pub fn to_meta(&self, ctx: &mut ContextElement) {
+        match self {
+            SearchSpec::Glob(g) => {
+                ctx.set_meta(GLOB_PATTERN_META, &g.pattern);
+                ctx.set_meta(GLOB_PATH_META, &g.path);
+                ctx.set_meta(MAX_RESULTS_META, &g.max_results);
+            }
+            SearchSpec::Grep(g) => {
+                ctx.set_meta(GREP_PATTERN_META, &g.pattern);
+                ctx.set_meta(GREP_PATH_META, &g.path);
+                if let Some(f) = &g.file_pattern {
+                    ctx.set_meta(GREP_FILE_PATTERN_META, f);
+                }
+                ctx.set_meta(GREP_CONTEXT_LINES_META, &g.context_lines);
+                ctx.set_meta(GREP_IGNORE_CASE_META, &g.ignore_case);
+                ctx.set_meta(MAX_RESULTS_META, &g.max_results);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn specs_round_trip_through_metadata() {
+        let spec = SearchSpec::Grep(GrepSpec {
+            pattern: "fn \\w+".to_string(),
+            path: "src".to_string(),
+            file_pattern: Some("*.rs".to_string()),
+            context_lines: 2,
+            ignore_case: true,
+            max_results: 50,
+        });
+        let mut ctx = cp_base::state::make_default_context_element("P10", spec.context_type(), "x", true);
+        spec.to_meta(&mut ctx);
+        assert_eq!(SearchSpec::from_meta(&ctx), Some(spec));
+
+        // Panels restored from old presets only carry the pattern
+        let mut legacy =
+            cp_base::state::make_default_context_element("P11", ContextType::new(ContextType::GLOB), "x", true);
+        legacy.set_meta(GLOB_PATTERN_META, &"**/*.md".to_string());
+        assert_eq!(
+            SearchSpec::from_meta(&legacy),
+            Some(SearchSpec::Glob(GlobSpec {
+                pattern: "**/*.md".to_string(),
+                path: ".".to_string(),
+                max_results: DEFAULT_MAX_RESULTS
+            }))
+        );
+    }
+
+    #[test]
+    fn paths_are_normalized() {
+        assert_eq!(normalize_path("./src/"), "src");
+        assert_eq!(normalize_path(""), ".");
+        assert_eq!(normalize_path("."), ".");
+    }
+}
@@ -44,10 +44,20 @@ pub fn check_spine(state: &mut State) -> SpineDecision {
     }
 
     // Backoff after consecutive failed continuations (errors with all retries exhausted).
-    // Delay: 2^errors seconds, capped at 60s. Prevents runaway loops on persistent API failures.
+    // When the provider said how long to wait (retry-after, or a spent budget's reset),
+    // wait exactly that long. Otherwise: 2^errors seconds, capped at 60s. Prevents
+    // runaway loops on persistent API failures.
     {
         let cfg = &SpineState::get(state).config;
+        let provider = state.stream_target().provider;
+        let server_hint = state.rate_limits.get(&provider).and_then(|s| s.retry_at_ms());
         if cfg.consecutive_continuation_errors > 0
+            && let Some(retry_at_ms) = server_hint
+        {
+            if now_ms() < retry_at_ms {
+                return SpineDecision::Idle;
+            }
+        } else if cfg.consecutive_continuation_errors > 0
             && let Some(last_err_ms) = cfg.last_continuation_error_ms
         {
             let backoff_secs = (1u64 << cfg.consecutive_continuation_errors.min(6)).min(60);
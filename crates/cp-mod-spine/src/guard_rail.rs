@@ -101,10 +101,7 @@ impl GuardRailStopLogic for MaxCostGuard {
 
 impl MaxCostGuard {
     fn calculate_cost(state: &State) -> f64 {
-        let hit_cost = State::token_cost(state.cache_hit_tokens, state.cache_hit_price_per_mtok());
-        let miss_cost = State::token_cost(state.cache_miss_tokens, state.cache_miss_price_per_mtok());
-        let output_cost = State::token_cost(state.total_output_tokens, state.output_price_per_mtok());
-        hit_cost + miss_cost + output_cost
+        state.session_cost()
     }
 }
 
@@ -141,10 +138,7 @@ impl GuardRailStopLogic for MaxStreamCostGuard {
 
 impl MaxStreamCostGuard {
     fn calculate_stream_cost(state: &State) -> f64 {
-        let hit_cost = State::token_cost(state.stream_cache_hit_tokens, state.cache_hit_price_per_mtok());
-        let miss_cost = State::token_cost(state.stream_cache_miss_tokens, state.cache_miss_price_per_mtok());
-        let output_cost = State::token_cost(state.stream_output_tokens, state.output_price_per_mtok());
-        hit_cost + miss_cost + output_cost
+        state.stream_cost()
     }
 }
 
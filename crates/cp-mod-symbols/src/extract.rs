@@ -0,0 +1,336 @@
+//! Per-language symbol extractors.
+//!
+//! Extractors are line-based on purpose: they run on every changed file of the
+//! project and must stay fast and dependency-free. Adding a language means
+//! implementing `SymbolExtractor` and listing it in `EXTRACTORS`.
+
+use crate::types::SymbolKind;
+
+/// A definition found in one file (before it is attached to a path)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// 1-based line of the definition
+    pub line: usize,
+    /// Enclosing impl/trait/mod/class, if any
+    pub container: Option<String>,
+    /// The definition line, trimmed and shortened
+    pub signature: String,
+}
+
+pub trait SymbolExtractor: Send + Sync {
+    /// File extensions handled (without the dot)
+    fn extensions(&self) -> &'static [&'static str];
+    /// All definitions in a file's content
+    fn extract(&self, content: &str) -> Vec<RawSymbol>;
+}
+
+/// Registered extractors, checked in order
+const EXTRACTORS: &[&dyn SymbolExtractor] = &[&RustExtractor, &PythonExtractor];
+
+/// The extractor for a path, if its language is indexed
+pub fn extractor_for(path: &str) -> Option<&'static dyn SymbolExtractor> {
+    let ext = std::path::Path::new(path).extension()?.to_str()?;
+    EXTRACTORS.iter().copied().find(|e| e.extensions().contains(&ext))
+}
+
+const MAX_SIGNATURE_CHARS: usize = 100;
+
+fn signature(line: &str) -> String {
+    let trimmed = line.trim().trim_end_matches('{').trim_end();
+    match trimmed.char_indices().nth(MAX_SIGNATURE_CHARS) {
+        Some((i, _)) => format!("{}…", &trimmed[..i]),
+        None => trimmed.to_string(),
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Leading identifier of `s`
+fn ident(s: &str) -> Option<&str> {
+    let end = s.find(|c: char| !is_ident_char(c)).unwrap_or(s.len());
+    (end > 0).then(|| &s[..end])
+}
+
+// === Rust ===
+
+pub struct RustExtractor;
+
+/// Modifiers that may precede a Rust item keyword
+const RUST_MODIFIERS: &[&str] = &["pub", "async", "unsafe", "const", "extern", "default"];
+
+/// Self type of an impl header: `impl<T> Foo<T> for Bar<T> {` -> "Bar"
+fn impl_target(rest: &str) -> Option<String> {
+    // Skip the generic parameter list
+    let mut rest = rest.trim_start();
+    if rest.starts_with('<') {
+        let mut depth = 0;
+        let end = rest.char_indices().find_map(|(i, c)| {
+            match c {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                _ => {}
+            }
+            (depth == 0).then_some(i)
+        })?;
+        rest = rest[end + 1..].trim_start();
+    }
+    let header = rest.split(" where ").next().unwrap_or(rest).trim_end_matches('{').trim();
+    let target = header.rsplit_once(" for ").map(|(_, t)| t).unwrap_or(header);
+    let target = target.trim_start_matches(['&', '!']).trim_start_matches("dyn ").trim_start_matches("mut ");
+    // Last path segment without generics: `crate::a::Foo<T>` -> "Foo"
+    let base = target.split('<').next().unwrap_or(target);
+    ident(base.rsplit("::").next().unwrap_or(base).trim()).map(str::to_string)
+}
+
+/// Kind and name declared by a Rust line, if it starts an item
+fn rust_item(line: &str) -> Option<(SymbolKind, String)> {
+    let mut rest = line.trim_start();
+    loop {
+        let word = ident(rest).unwrap_or("");
+        if !RUST_MODIFIERS.contains(&word) {
+            break;
+        }
+        let after = rest[word.len()..].trim_start();
+        // `const NAME` is an item, `const fn` a modifier
+        if word == "const" && !["fn", "unsafe", "async", "extern"].contains(&ident(after).unwrap_or("")) {
+            break;
+        }
+        rest = after;
+        // pub(crate), extern "C"
+        if rest.starts_with('(') {
+            rest = rest[rest.find(')')? + 1..].trim_start();
+        } else if rest.starts_with('"') {
+            rest = rest[1..][rest[1..].find('"')? + 1..].trim_start();
+        }
+    }
+
+    if let Some(after) = rest.strip_prefix("macro_rules!") {
+        return ident(after.trim_start()).map(|n| (SymbolKind::Macro, n.to_string()));
+    }
+    let keyword = ident(rest)?;
+    let after = &rest[keyword.len()..];
+    if keyword == "impl" && (after.starts_with(' ') || after.starts_with('<')) {
+        return impl_target(after).map(|n| (SymbolKind::Impl, n));
+    }
+    let kind = match keyword {
+        "fn" => SymbolKind::Function,
+        "struct" => SymbolKind::Struct,
+        "enum" => SymbolKind::Enum,
+        "union" => SymbolKind::Struct,
+        "trait" => SymbolKind::Trait,
+        "mod" => SymbolKind::Module,
+        "type" => SymbolKind::Type,
+        "const" | "static" => SymbolKind::Const,
+        _ => return None,
+    };
+    if !after.starts_with(' ') {
+        return None;
+    }
+    let after = after.trim_start();
+    let after = after.strip_prefix("mut ").unwrap_or(after);
+    let name = ident(after)?;
+    // `const _: () = ...` declares nothing worth finding
+    (name != "_").then(|| (kind, name.to_string()))
+}
+
+/// Net brace depth change of a line, ignoring comments, strings and char literals
+fn brace_delta(line: &str) -> isize {
+    let code = line.split("//").next().unwrap_or("");
+    let mut delta = 0;
+    let mut in_string = false;
+    let mut prev = '\0';
+    let mut chars = code.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_string {
+            if c == '"' && prev != '\\' {
+                in_string = false;
+            }
+        } else {
+            match c {
+                '"' => in_string = true,
+                // '{' and '}' char literals
+                '\'' if matches!(chars.peek(), Some('{') | Some('}')) => {
+                    chars.next();
+                }
+                '{' => delta += 1,
+                '}' => delta -= 1,
+                _ => {}
+            }
+        }
+        prev = if prev == '\\' && c == '\\' { '\0' } else { c };
+    }
+    delta
+}
+
+impl SymbolExtractor for RustExtractor {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["rs"]
+    }
+
+    fn extract(&self, content: &str) -> Vec<RawSymbol> {
+        let mut symbols = Vec::new();
+        // Open impl/trait/mod blocks: (name, depth inside the block)
+        let mut containers: Vec<(String, isize)> = Vec::new();
+        let mut depth: isize = 0;
+        let mut in_block_comment = false;
+
+        for (i, line) in content.lines().enumerate() {
+            let trimmed = line.trim_start();
+            if in_block_comment {
+                in_block_comment = !trimmed.contains("*/");
+                continue;
+            }
+            if trimmed.starts_with("/*") {
+                in_block_comment = !trimmed.contains("*/");
+                continue;
+            }
+            if trimmed.starts_with("//") {
+                continue;
+            }
+
+            let item = rust_item(line);
+            if let Some((kind, name)) = &item {
+                symbols.push(RawSymbol {
+                    name: name.clone(),
+                    kind: *kind,
+                    line: i + 1,
+                    container: containers.last().map(|(c, _)| c.clone()),
+                    signature: signature(line),
+                });
+            }
+
+            let delta = brace_delta(line);
+            if let Some((kind, name)) = item
+                && matches!(kind, SymbolKind::Impl | SymbolKind::Trait | SymbolKind::Module)
+                && delta > 0
+            {
+                containers.push((name, depth + 1));
+            }
+            depth += delta;
+            while containers.last().is_some_and(|(_, d)| depth < *d) {
+                containers.pop();
+            }
+        }
+        symbols
+    }
+}
+
+// === Python ===
+
+pub struct PythonExtractor;
+
+impl SymbolExtractor for PythonExtractor {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["py"]
+    }
+
+    fn extract(&self, content: &str) -> Vec<RawSymbol> {
+        let mut symbols = Vec::new();
+        // Enclosing classes: (name, indentation of the class line)
+        let mut classes: Vec<(String, usize)> = Vec::new();
+
+        for (i, line) in content.lines().enumerate() {
+            let trimmed = line.trim_start();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let indent = line.len() - trimmed.len();
+            while classes.last().is_some_and(|(_, d)| indent <= *d) {
+                classes.pop();
+            }
+
+            let rest = trimmed.strip_prefix("async ").unwrap_or(trimmed);
+            let (kind, after) = if let Some(after) = rest.strip_prefix("def ") {
+                (SymbolKind::Function, after)
+            } else if let Some(after) = rest.strip_prefix("class ") {
+                (SymbolKind::Class, after)
+            } else {
+                continue;
+            };
+            let Some(name) = ident(after.trim_start()) else { continue };
+            symbols.push(RawSymbol {
+                name: name.to_string(),
+                kind,
+                line: i + 1,
+                container: classes.last().map(|(c, _)| c.clone()),
+                signature: signature(line.trim_end_matches(':')),
+            });
+            if kind == SymbolKind::Class {
+                classes.push((name.to_string(), indent));
+            }
+        }
+        symbols
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(symbols: &[RawSymbol]) -> Vec<(SymbolKind, &str, usize, Option<&str>)> {
+        symbols.iter().map(|s| (s.kind, s.name.as_str(), s.line, s.container.as_deref())).collect()
+    }
+
+    #[test]
+    fn rust_items_and_containers() {
+        let src = "pub(crate) mod panel;\n\
+            /// Doc\n\
+            pub struct State {\n\
+            \x20   pub a: u32,\n\
+            }\n\
+            impl<T: Clone> Display for Wrapper<T> {\n\
+            \x20   fn fmt(&self) -> String { format!(\"{{\") }\n\
+            \x20   pub async fn helper() {}\n\
+            }\n\
+            // fn commented_out() {}\n\
+            const _: () = ();\n\
+            pub const MAX: usize = 3;\n\
+            macro_rules! log { () => {} }\n\
+            pub trait Panel: Send {\n\
+            \x20   fn title(&self) -> String;\n\
+            }\n\
+            fn free() {}\n";
+        let symbols = RustExtractor.extract(src);
+        assert_eq!(
+            names(&symbols),
+            vec![
+                (SymbolKind::Module, "panel", 1, None),
+                (SymbolKind::Struct, "State", 3, None),
+                (SymbolKind::Impl, "Wrapper", 6, None),
+                (SymbolKind::Function, "fmt", 7, Some("Wrapper")),
+                (SymbolKind::Function, "helper", 8, Some("Wrapper")),
+                (SymbolKind::Const, "MAX", 12, None),
+                (SymbolKind::Macro, "log", 13, None),
+                (SymbolKind::Trait, "Panel", 14, None),
+                (SymbolKind::Function, "title", 15, Some("Panel")),
+                (SymbolKind::Function, "free", 17, None),
+            ]
+        );
+        assert_eq!(symbols[2].signature, "impl<T: Clone> Display for Wrapper<T>");
+    }
+
+    #[test]
+    fn python_defs_and_classes() {
+        let src = "class Loader:\n    def load(self):\n        pass\n\nasync def main():\n    pass\n";
+        assert_eq!(
+            names(&PythonExtractor.extract(src)),
+            vec![
+                (SymbolKind::Class, "Loader", 1, None),
+                (SymbolKind::Function, "load", 2, Some("Loader")),
+                (SymbolKind::Function, "main", 5, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn extractors_are_chosen_by_extension() {
+        assert!(extractor_for("src/main.rs").is_some());
+        assert!(extractor_for("tools/gen.py").is_some());
+        assert!(extractor_for("README.md").is_none());
+        assert!(extractor_for("Makefile").is_none());
+    }
+}
@@ -0,0 +1,243 @@
+//! Incremental project symbol index.
+//!
+//! Each refresh walks the project (honouring .gitignore), re-extracts only the
+//! files whose size or mtime changed and drops files that disappeared, so the
+//! watcher can trigger it on every save.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::extract::extractor_for;
+use crate::types::{Symbol, SymbolKind};
+
+/// Files larger than this are not indexed (generated code, bundles)
+const MAX_INDEXED_FILE_BYTES: u64 = 1_000_000;
+/// Upper bound on indexed files, to keep huge monorepos responsive
+const MAX_INDEXED_FILES: usize = 20_000;
+/// Upper bound on returned matches
+pub const MAX_RESULTS: usize = 300;
+
+struct IndexedFile {
+    modified: Option<SystemTime>,
+    len: u64,
+    symbols: Vec<Symbol>,
+}
+
+/// A whole-word use of a name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub path: String,
+    pub line: usize,
+    pub text: String,
+    /// The line is one of the name's definitions
+    pub is_definition: bool,
+}
+
+#[derive(Default)]
+pub struct SymbolIndex {
+    files: HashMap<String, IndexedFile>,
+}
+
+impl SymbolIndex {
+    /// Bring the index up to date with the files under `root`.
+    /// Returns the number of files (re-)extracted.
+    pub fn refresh(&mut self, root: &Path) -> usize {
+        let mut seen = BTreeSet::new();
+        let mut extracted = 0;
+        let walker = ignore::WalkBuilder::new(root).build();
+        for entry in walker.flatten() {
+            if seen.len() >= MAX_INDEXED_FILES {
+                break;
+            }
+            if !entry.file_type().is_some_and(|t| t.is_file()) {
+                continue;
+            }
+            let Ok(rel) = entry.path().strip_prefix(root) else { continue };
+            let rel = rel.to_string_lossy().to_string();
+            let Some(extractor) = extractor_for(&rel) else { continue };
+            let Ok(meta) = entry.metadata() else { continue };
+            if meta.len() > MAX_INDEXED_FILE_BYTES {
+                continue;
+            }
+            seen.insert(rel.clone());
+
+            let modified = meta.modified().ok();
+            if self.files.get(&rel).is_some_and(|f| f.modified == modified && f.len == meta.len()) {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(entry.path()) else { continue };
+            let symbols = to_symbols(&rel, extractor.extract(&content));
+            self.files.insert(rel, IndexedFile { modified, len: meta.len(), symbols });
+            extracted += 1;
+        }
+        self.files.retain(|path, _| seen.contains(path));
+        extracted
+    }
+
+    /// Index one file's content directly (no mtime tracking)
+    pub fn insert(&mut self, path: &str, content: &str) {
+        if let Some(extractor) = extractor_for(path) {
+            let symbols = to_symbols(path, extractor.extract(content));
+            self.files.insert(path.to_string(), IndexedFile { modified: None, len: 0, symbols });
+        }
+    }
+
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn symbol_count(&self) -> usize {
+        self.files.values().map(|f| f.symbols.len()).sum()
+    }
+
+    /// Top-level directories holding indexed files (what the watcher should cover)
+    pub fn roots(&self) -> Vec<String> {
+        let roots: BTreeSet<&str> = self.files.keys().filter_map(|p| p.split_once('/').map(|(dir, _)| dir)).collect();
+        roots.into_iter().map(str::to_string).collect()
+    }
+
+    fn symbols(&self) -> impl Iterator<Item = &Symbol> {
+        self.files.values().flat_map(|f| f.symbols.iter())
+    }
+
+    /// Definitions whose name contains `query` (case-insensitive):
+    /// exact matches first, then prefixes, then other substrings
+    pub fn search(&self, query: &str, kind: Option<SymbolKind>) -> Vec<&Symbol> {
+        let needle = query.to_lowercase();
+        let mut hits: Vec<(u8, &Symbol)> = self
+            .symbols()
+            .filter(|s| kind.is_none_or(|k| s.kind == k))
+            .filter_map(|s| {
+                let name = s.name.to_lowercase();
+                let rank = if name == needle {
+                    0
+                } else if name.starts_with(&needle) {
+                    1
+                } else if name.contains(&needle) {
+                    2
+                } else {
+                    return None;
+                };
+                Some((rank, s))
+            })
+            .collect();
+        hits.sort_by(|(ra, a), (rb, b)| ra.cmp(rb).then_with(|| a.path.cmp(&b.path)).then(a.line.cmp(&b.line)));
+        hits.into_iter().take(MAX_RESULTS).map(|(_, s)| s).collect()
+    }
+
+    /// Definitions named exactly `name`; `Container::name` narrows to one impl/trait/mod/class
+    pub fn definitions(&self, name: &str) -> Vec<&Symbol> {
+        let (container, name) = match name.rsplit_once("::") {
+            Some((container, name)) => (Some(container.rsplit("::").next().unwrap_or(container)), name),
+            None => (None, name),
+        };
+        let mut defs: Vec<&Symbol> = self
+            .symbols()
+            .filter(|s| s.name == name)
+            .filter(|s| container.is_none_or(|c| s.container.as_deref() == Some(c)))
+            .collect();
+        // Types before their impls, then by location
+        defs.sort_by(|a, b| {
+            (a.kind == SymbolKind::Impl)
+                .cmp(&(b.kind == SymbolKind::Impl))
+                .then_with(|| a.path.cmp(&b.path))
+                .then(a.line.cmp(&b.line))
+        });
+        defs.truncate(MAX_RESULTS);
+        defs
+    }
+
+    /// Whole-word occurrences of `name` in the indexed files under `root`
+    pub fn references(&self, root: &Path, name: &str) -> Vec<Reference> {
+        let name = name.rsplit("::").next().unwrap_or(name);
+        let mut paths: Vec<&String> = self.files.keys().collect();
+        paths.sort();
+        let mut refs = Vec::new();
+        for path in paths {
+            let Ok(content) = fs::read_to_string(root.join(path)) else { continue };
+            let defs: Vec<usize> = self.files[path].symbols.iter().filter(|s| s.name == name).map(|s| s.line).collect();
+            for (i, line) in content.lines().enumerate() {
+                if !contains_word(line, name) {
+                    continue;
+                }
+                refs.push(Reference {
+                    path: path.clone(),
+                    line: i + 1,
+                    text: line.trim().to_string(),
+                    is_definition: defs.contains(&(i + 1)),
+                });
+                if refs.len() >= MAX_RESULTS {
+                    return refs;
+                }
+            }
+        }
+        refs
+    }
+}
+
+fn to_symbols(path: &str, raw: Vec<crate::extract::RawSymbol>) -> Vec<Symbol> {
+    raw.into_iter()
+        .map(|r| Symbol {
+            name: r.name,
+            kind: r.kind,
+            path: path.to_string(),
+            line: r.line,
+            container: r.container,
+            signature: r.signature,
+        })
+        .collect()
+}
+
+fn contains_word(line: &str, word: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    line.match_indices(word).any(|(i, _)| {
+        !line[..i].chars().next_back().is_some_and(is_ident)
+            && !line[i + word.len()..].chars().next().is_some_and(is_ident)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> SymbolIndex {
+        let mut index = SymbolIndex::default();
+        index.insert("src/state.rs", "pub struct State {}\nimpl State {\n    pub fn new() -> Self { State {} }\n}\n");
+        index.insert("src/app/mod.rs", "pub struct App {}\nimpl App {\n    pub fn new() -> Self { App {} }\n}\n");
+        index.insert("crates/base/src/lib.rs", "pub fn state_path() {}\n");
+        index.insert("README.md", "# State\n");
+        index
+    }
+
+    #[test]
+    fn search_ranks_exact_then_prefix_then_substring() {
+        let index = index();
+        let hits: Vec<(&str, usize)> = index.search("state", None).iter().map(|s| (s.name.as_str(), s.line)).collect();
+        assert_eq!(hits, vec![("State", 1), ("State", 2), ("state_path", 1)]);
+        let structs = index.search("state", Some(SymbolKind::Struct));
+        assert_eq!(structs.len(), 1);
+        assert_eq!(index.file_count(), 3);
+        assert_eq!(index.roots(), vec!["crates".to_string(), "src".to_string()]);
+    }
+
+    #[test]
+    fn definitions_put_types_before_impls_and_honour_containers() {
+        let index = index();
+        let defs: Vec<(SymbolKind, &str)> =
+            index.definitions("State").iter().map(|s| (s.kind, s.path.as_str())).collect();
+        assert_eq!(defs, vec![(SymbolKind::Struct, "src/state.rs"), (SymbolKind::Impl, "src/state.rs")]);
+        assert_eq!(index.definitions("new").len(), 2);
+        let scoped = index.definitions("App::new");
+        assert_eq!(scoped.len(), 1);
+        assert_eq!(scoped[0].path, "src/app/mod.rs");
+    }
+
+    #[test]
+    fn words_match_on_identifier_boundaries() {
+        assert!(contains_word("let s = State::new();", "State"));
+        assert!(!contains_word("let s = StateStore::new();", "State"));
+        assert!(!contains_word("fn state_path()", "state"));
+    }
+}
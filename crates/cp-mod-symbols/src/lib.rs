@@ -0,0 +1,159 @@
+pub mod extract;
+pub mod index;
+mod panel;
+mod tools;
+pub mod types;
+
+use cp_base::panels::Panel;
+use cp_base::state::{ContextType, State};
+use cp_base::tools::{ParamType, ToolDefinition, ToolParam};
+use cp_base::tools::{ToolResult, ToolUse};
+
+use self::panel::SymbolsPanel;
+use cp_base::modules::Module;
+
+pub use types::{SymbolKind, SymbolQuery, SymbolState};
+
+/// Context type of symbol lookup result panels
+pub const SYMBOLS_CONTEXT_TYPE: &str = "symbols";
+
+pub struct SymbolsModule;
+
+impl Module for SymbolsModule {
+    fn id(&self) -> &'static str {
+        "symbols"
+    }
+    fn name(&self) -> &'static str {
+        "Symbols"
+    }
+    fn description(&self) -> &'static str {
+        "Project symbol index with definition and reference lookup"
+    }
+
+    fn init_state(&self, state: &mut State) {
+        state.set_ext(SymbolState::new());
+    }
+
+    fn reset_state(&self, state: &mut State) {
+        state.set_ext(SymbolState::new());
+    }
+
+    fn dynamic_panel_types(&self) -> Vec<ContextType> {
+        vec![ContextType::new(SYMBOLS_CONTEXT_TYPE)]
+    }
+
+    fn create_panel(&self, context_type: &ContextType) -> Option<Box<dyn Panel>> {
+        match context_type.as_str() {
+            SYMBOLS_CONTEXT_TYPE => Some(Box::new(SymbolsPanel)),
+            _ => None,
+        }
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        let kinds: Vec<&str> = SymbolKind::ALL.iter().map(|k| k.as_str()).collect();
+        vec![
+            ToolDefinition {
+                id: "symbol_search".to_string(),
+                name: "Symbol Search".to_string(),
+                short_desc: "Find definitions by partial name".to_string(),
+                description: "Searches the project symbol index (functions, structs, enums, traits, impls, modules, consts, macros; Rust and Python) for definitions whose name contains the query, case-insensitive. Results appear in an auto-refreshing panel as path:line entries — open one with Open(path, symbol=...) or Open(path, start_line=...) instead of reading whole files.".to_string(),
+                params: vec![
+                    ToolParam::new("query", ParamType::String)
+                        .desc("Part of the symbol name (e.g., 'prompt', 'CacheUpdate')")
+                        .required(),
+                    ToolParam::new("kind", ParamType::String)
+                        .desc("Only return this kind of definition")
+                        .enum_vals(&kinds),
+                ],
+                enabled: true,
+                reverie_allowed: true,
+                category: "Symbols".to_string(),
+            },
+            ToolDefinition {
+                id: "find_definition".to_string(),
+                name: "Find Definition".to_string(),
+                short_desc: "Locate where a symbol is defined".to_string(),
+                description: "Finds the definitions (and impl blocks) of an exact symbol name. Use 'Type::method' to pick a method of one type. Results appear in an auto-refreshing panel as path:line entries.".to_string(),
+                params: vec![
+                    ToolParam::new("name", ParamType::String)
+                        .desc("Exact symbol name (e.g., 'assemble_prompt', 'State', 'State::new')")
+                        .required(),
+                ],
+                enabled: true,
+                reverie_allowed: true,
+                category: "Symbols".to_string(),
+            },
+            ToolDefinition {
+                id: "find_references".to_string(),
+                name: "Find References".to_string(),
+                short_desc: "List uses of a symbol".to_string(),
+                description: "Lists every whole-word occurrence of a name across the indexed source files, definitions marked [def]. Results appear in an auto-refreshing panel as path:line entries with the matching line.".to_string(),
+                params: vec![
+                    ToolParam::new("name", ParamType::String)
+                        .desc("Symbol name (e.g., 'apply_token_budget')")
+                        .required(),
+                ],
+                enabled: true,
+                reverie_allowed: true,
+                category: "Symbols".to_string(),
+            },
+        ]
+    }
+
+    fn execute_tool(&self, tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+        match tool.name.as_str() {
+            "symbol_search" => Some(self::tools::execute_symbol_search(tool, state)),
+            "find_definition" => Some(self::tools::execute_find_definition(tool, state)),
+            "find_references" => Some(self::tools::execute_find_references(tool, state)),
+            _ => None,
+        }
+    }
+
+    fn context_type_metadata(&self) -> Vec<cp_base::state::ContextTypeMeta> {
+        vec![cp_base::state::ContextTypeMeta {
+            context_type: SYMBOLS_CONTEXT_TYPE,
+            icon_id: "grep", // Reuse grep icon for now
+            is_fixed: false,
+            needs_cache: true,
+            fixed_order: None,
+            display_name: "symbols",
+            short_name: "symbols",
+            needs_async_wait: false,
+        }]
+    }
+
+    fn context_detail(&self, ctx: &cp_base::state::ContextElement) -> Option<String> {
+        if ctx.context_type.as_str() == SYMBOLS_CONTEXT_TYPE {
+            SymbolQuery::from_meta(ctx).map(|q| q.label())
+        } else {
+            None
+        }
+    }
+
+    fn tool_category_descriptions(&self) -> Vec<(&'static str, &'static str)> {
+        vec![("Symbols", "Locate definitions and references without opening files")]
+    }
+
+    /// Source trees holding indexed files, watched only while a symbols panel is open
+    fn watch_paths(&self, state: &State) -> Vec<cp_base::panels::WatchSpec> {
+        if !state.context.iter().any(|c| c.context_type.as_str() == SYMBOLS_CONTEXT_TYPE) {
+            return vec![];
+        }
+        // A background refresh may hold the index; the next sync picks the roots up
+        let Ok(index) = SymbolState::get(state).index.try_lock() else {
+            return vec![];
+        };
+        index.roots().into_iter().map(cp_base::panels::WatchSpec::DirRecursive).collect()
+    }
+
+    fn should_invalidate_on_fs_change(
+        &self,
+        ctx: &cp_base::state::ContextElement,
+        changed_path: &str,
+        is_dir_event: bool,
+    ) -> bool {
+        !is_dir_event
+            && ctx.context_type.as_str() == SYMBOLS_CONTEXT_TYPE
+            && extract::extractor_for(changed_path).is_some()
+    }
+}
@@ -0,0 +1,204 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+
+use cp_base::config::constants::{SCROLL_ARROW_AMOUNT, SCROLL_PAGE_AMOUNT};
+use cp_base::config::theme;
+use cp_base::panels::{CacheRequest, CacheUpdate};
+use cp_base::panels::{ContextItem, Panel, paginate_content, update_if_changed};
+use cp_base::state::Action;
+use cp_base::state::{ContextElement, ContextType, State, compute_total_pages, estimate_tokens};
+
+use crate::SYMBOLS_CONTEXT_TYPE;
+use crate::index::{MAX_RESULTS, SymbolIndex};
+use crate::types::{Symbol, SymbolQuery, SymbolState};
+
+pub struct SymbolsRequest {
+    pub context_id: String,
+    pub query: SymbolQuery,
+    pub index: Arc<Mutex<SymbolIndex>>,
+}
+
+pub(crate) struct SymbolsPanel;
+
+fn symbol_line(s: &Symbol) -> String {
+    match &s.container {
+        Some(container) => format!("{}:{}  {}  (in {})\n", s.path, s.line, s.signature, container),
+        None => format!("{}:{}  {}\n", s.path, s.line, s.signature),
+    }
+}
+
+fn truncation_note(count: usize) -> &'static str {
+    if count >= MAX_RESULTS { " (truncated, refine the query)" } else { "" }
+}
+
+/// Run a lookup against an up-to-date index and format it as path:line entries
+pub(crate) fn render_query(index: &SymbolIndex, root: &Path, query: &SymbolQuery) -> String {
+    let scope = format!("{} files indexed", index.file_count());
+    match query {
+        SymbolQuery::Search { query: text, kind } => {
+            let hits = index.search(text, *kind);
+            if hits.is_empty() {
+                return format!("No symbol matching '{}' ({})\n", text, scope);
+            }
+            let mut out = format!("{} symbols matching '{}'{}\n", hits.len(), text, truncation_note(hits.len()));
+            hits.iter().for_each(|s| out.push_str(&symbol_line(s)));
+            out
+        }
+        SymbolQuery::Definition(name) => {
+            let defs = index.definitions(name);
+            if defs.is_empty() {
+                return format!("No definition of '{}' ({}). Try symbol_search for partial names.\n", name, scope);
+            }
+            let mut out = format!("{} definitions of '{}'\n", defs.len(), name);
+            defs.iter().for_each(|s| out.push_str(&symbol_line(s)));
+            out
+        }
+        SymbolQuery::References(name) => {
+            let refs = index.references(root, name);
+            if refs.is_empty() {
+                return format!("No references to '{}' ({})\n", name, scope);
+            }
+            let files = refs.iter().map(|r| r.path.as_str()).collect::<std::collections::BTreeSet<_>>().len();
+            let mut out =
+                format!("{} references to '{}' in {} files{}\n", refs.len(), name, files, truncation_note(refs.len()));
+            for r in &refs {
+                let marker = if r.is_definition { "[def] " } else { "" };
+                out.push_str(&format!("{}:{}  {}{}\n", r.path, r.line, marker, r.text));
+            }
+            out
+        }
+    }
+}
+
+impl Panel for SymbolsPanel {
+    fn needs_cache(&self) -> bool {
+        true
+    }
+
+    fn build_cache_request(&self, ctx: &ContextElement, state: &State) -> Option<CacheRequest> {
+        Some(CacheRequest {
+            context_type: ContextType::new(SYMBOLS_CONTEXT_TYPE),
+            data: Box::new(SymbolsRequest {
+                context_id: ctx.id.clone(),
+                query: SymbolQuery::from_meta(ctx)?,
+                index: SymbolState::get(state).index.clone(),
+            }),
+        })
+    }
+
+    fn apply_cache_update(&self, update: CacheUpdate, ctx: &mut ContextElement, _state: &mut State) -> bool {
+        let CacheUpdate::Content { content, token_count, .. } = update else {
+            return false;
+        };
+        ctx.cache_deprecated = false;
+        if !update_if_changed(ctx, &content) && ctx.cached_content.is_some() {
+            return false;
+        }
+        ctx.cached_content = Some(content);
+        ctx.full_token_count = token_count;
+        ctx.token_count = token_count;
+        ctx.total_pages = compute_total_pages(token_count);
+        ctx.current_page = 0;
+        true
+    }
+
+    fn refresh_cache(&self, request: CacheRequest) -> Option<CacheUpdate> {
+        let req = request.data.downcast::<SymbolsRequest>().ok()?;
+        let SymbolsRequest { context_id, query, index } = *req;
+        let root = Path::new(".");
+        let mut index = index.lock().ok()?;
+        index.refresh(root);
+        let content = render_query(&index, root, &query);
+        let token_count = estimate_tokens(&content);
+        Some(CacheUpdate::Content { context_id, content, token_count })
+    }
+
+    fn handle_key(&self, key: &KeyEvent, _state: &State) -> Option<Action> {
+        match key.code {
+            KeyCode::Up => Some(Action::ScrollUp(SCROLL_ARROW_AMOUNT)),
+            KeyCode::Down => Some(Action::ScrollDown(SCROLL_ARROW_AMOUNT)),
+            KeyCode::PageUp => Some(Action::ScrollUp(SCROLL_PAGE_AMOUNT)),
+            KeyCode::PageDown => Some(Action::ScrollDown(SCROLL_PAGE_AMOUNT)),
+            _ => None,
+        }
+    }
+
+    fn title(&self, state: &State) -> String {
+        state
+            .context
+            .get(state.selected_context)
+            .filter(|c| c.context_type.as_str() == SYMBOLS_CONTEXT_TYPE)
+            .and_then(SymbolQuery::from_meta)
+            .map(|q| q.label())
+            .unwrap_or_else(|| "Symbols".to_string())
+    }
+
+    fn context(&self, state: &State) -> Vec<ContextItem> {
+        let mut items = Vec::new();
+        for ctx in &state.context {
+            if ctx.context_type.as_str() != SYMBOLS_CONTEXT_TYPE {
+                continue;
+            }
+            let content = ctx.cached_content.as_deref().unwrap_or("[indexing...]");
+            let header = SymbolQuery::from_meta(ctx).map(|q| q.label()).unwrap_or_else(|| "Symbols".to_string());
+            let output = paginate_content(content, ctx.current_page, ctx.total_pages);
+            items.push(ContextItem::new(&ctx.id, &header, output, ctx.last_refresh_ms));
+        }
+        items
+    }
+
+    fn content(&self, state: &State, base_style: Style) -> Vec<Line<'static>> {
+        let ctx = state.context.get(state.selected_context).filter(|c| c.context_type.as_str() == SYMBOLS_CONTEXT_TYPE);
+        let Some(content) = ctx.and_then(|c| c.cached_content.as_ref()) else {
+            return vec![Line::from(Span::styled(" Indexing...", Style::default().fg(theme::text_muted()).italic()))];
+        };
+
+        let mut lines = content.lines();
+        let mut text = vec![Line::from(vec![
+            Span::styled(" ".to_string(), base_style),
+            Span::styled(lines.next().unwrap_or("").to_string(), Style::default().fg(theme::text_muted()).bold()),
+        ])];
+        for line in lines {
+            // "path:line  rest"
+            let (location, rest) = line.split_once("  ").unwrap_or((line, ""));
+            let (rest_style, rest) = match rest.strip_prefix("[def] ") {
+                Some(def) => (Style::default().fg(theme::accent()).bold(), def),
+                None => (Style::default().fg(theme::text()), rest),
+            };
+            text.push(Line::from(vec![
+                Span::styled(" ".to_string(), base_style),
+                Span::styled(location.to_string(), Style::default().fg(theme::accent())),
+                Span::styled("  ".to_string(), base_style),
+                Span::styled(rest.to_string(), rest_style),
+            ]));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn results_are_path_line_entries() {
+        let mut index = SymbolIndex::default();
+        index.insert("src/state.rs", "pub struct State {}\nimpl State {\n    pub fn new() -> Self { State {} }\n}\n");
+        let root = Path::new(".");
+
+        let defs = render_query(&index, root, &SymbolQuery::Definition("new".to_string()));
+        assert_eq!(defs, "1 definitions of 'new'\nsrc/state.rs:3  pub fn new() -> Self { State {} }  (in State)\n");
+
+        let search = render_query(&index, root, &SymbolQuery::Search { query: "stat".to_string(), kind: None });
+        assert_eq!(
+            search,
+            "2 symbols matching 'stat'\nsrc/state.rs:1  pub struct State {}\nsrc/state.rs:2  impl State\n"
+        );
+
+        let missing = render_query(&index, root, &SymbolQuery::Definition("Missing".to_string()));
+        assert!(missing.starts_with("No definition of 'Missing' (1 files indexed)"));
+    }
+}
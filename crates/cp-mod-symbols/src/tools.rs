@@ -0,0 +1,70 @@
+use cp_base::state::{ContextType, State};
+use cp_base::tools::{ToolResult, ToolUse};
+
+use crate::SYMBOLS_CONTEXT_TYPE;
+use crate::types::{SymbolKind, SymbolQuery};
+
+fn required_str<'a>(tool: &'a ToolUse, key: &str) -> Result<&'a str, ToolResult> {
+    tool.input
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| ToolResult::new(tool.id.clone(), format!("Error: '{}' parameter is required", key), true))
+}
+
+pub fn execute_symbol_search(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let query = match required_str(tool, "query") {
+        Ok(q) => q.to_string(),
+        Err(e) => return e,
+    };
+    let kind = match tool.input.get("kind").and_then(|v| v.as_str()) {
+        None => None,
+        Some(k) => match SymbolKind::parse(k) {
+            Some(kind) => Some(kind),
+            None => return ToolResult::new(tool.id.clone(), format!("Error: unknown symbol kind '{}'", k), true),
+        },
+    };
+    open_query_panel(tool, state, SymbolQuery::Search { query, kind })
+}
+
+pub fn execute_find_definition(tool: &ToolUse, state: &mut State) -> ToolResult {
+    match required_str(tool, "name") {
+        Ok(name) => open_query_panel(tool, state, SymbolQuery::Definition(name.to_string())),
+        Err(e) => e,
+    }
+}
+
+pub fn execute_find_references(tool: &ToolUse, state: &mut State) -> ToolResult {
+    match required_str(tool, "name") {
+        Ok(name) => open_query_panel(tool, state, SymbolQuery::References(name.to_string())),
+        Err(e) => e,
+    }
+}
+
+/// Reuse the panel already showing this lookup, or create one
+fn open_query_panel(tool: &ToolUse, state: &mut State, query: SymbolQuery) -> ToolResult {
+    let existing = state.context.iter_mut().find(|c| {
+        c.context_type.as_str() == SYMBOLS_CONTEXT_TYPE && SymbolQuery::from_meta(c).as_ref() == Some(&query)
+    });
+    if let Some(ctx) = existing {
+        ctx.cache_deprecated = true;
+        return ToolResult::new(tool.id.clone(), format!("Panel updated: {} ({})", ctx.id, query.label()), false);
+    }
+
+    let panel_id = state.next_available_context_id();
+    let uid = format!("UID_{}_P", state.global_next_uid);
+    state.global_next_uid += 1;
+
+    let mut elem = cp_base::state::make_default_context_element(
+        &panel_id,
+        ContextType::new(SYMBOLS_CONTEXT_TYPE),
+        &query.label(),
+        true,
+    );
+    elem.uid = Some(uid);
+    query.to_meta(&mut elem);
+    state.context.push(elem);
+
+    ToolResult::new(tool.id.clone(), format!("Panel created: {} ({})", panel_id, query.label()), false)
+}
@@ -0,0 +1,158 @@
+use std::sync::{Arc, Mutex};
+
+use cp_base::state::{ContextElement, State};
+
+use crate::index::SymbolIndex;
+
+/// What a definition declares
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    Function,
+    Struct,
+    Enum,
+    Trait,
+    Impl,
+    Module,
+    Type,
+    Const,
+    Macro,
+    Class,
+}
+
+impl SymbolKind {
+    pub const ALL: &'static [SymbolKind] = &[
+        SymbolKind::Function,
+        SymbolKind::Struct,
+        SymbolKind::Enum,
+        SymbolKind::Trait,
+        SymbolKind::Impl,
+        SymbolKind::Module,
+        SymbolKind::Type,
+        SymbolKind::Const,
+        SymbolKind::Macro,
+        SymbolKind::Class,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SymbolKind::Function => "fn",
+            SymbolKind::Struct => "struct",
+            SymbolKind::Enum => "enum",
+            SymbolKind::Trait => "trait",
+            SymbolKind::Impl => "impl",
+            SymbolKind::Module => "mod",
+            SymbolKind::Type => "type",
+            SymbolKind::Const => "const",
+            SymbolKind::Macro => "macro",
+            SymbolKind::Class => "class",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|k| k.as_str() == s)
+    }
+}
+
+/// An indexed definition
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Project-relative path
+    pub path: String,
+    /// 1-based line
+    pub line: usize,
+    pub container: Option<String>,
+    pub signature: String,
+}
+
+/// Panel metadata: which lookup a symbols panel shows
+pub const QUERY_MODE_META: &str = "symbol_mode";
+/// Panel metadata: the searched name or substring
+pub const QUERY_META: &str = "symbol_query";
+/// Panel metadata: optional kind filter of a search
+pub const QUERY_KIND_META: &str = "symbol_kind";
+
+/// Lookup shown by a symbols panel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolQuery {
+    /// Definitions whose name contains the query (case-insensitive)
+    Search { query: String, kind: Option<SymbolKind> },
+    /// Definitions named exactly this (`Type::method` narrows by container)
+    Definition(String),
+    /// Whole-word uses of a name across indexed files
+    References(String),
+}
+
+impl SymbolQuery {
+    pub fn mode(&self) -> &'static str {
+        match self {
+            SymbolQuery::Search { .. } => "search",
+            SymbolQuery::Definition(_) => "definition",
+            SymbolQuery::References(_) => "references",
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        match self {
+            SymbolQuery::Search { query, .. } => query,
+            SymbolQuery::Definition(name) | SymbolQuery::References(name) => name,
+        }
+    }
+
+    /// Panel name and header, e.g. "refs: assemble_prompt"
+    pub fn label(&self) -> String {
+        match self {
+            SymbolQuery::Search { query, kind: Some(kind) } => format!("symbols: {} ({})", query, kind.as_str()),
+            SymbolQuery::Search { query, kind: None } => format!("symbols: {}", query),
+            SymbolQuery::Definition(name) => format!("def: {}", name),
+            SymbolQuery::References(name) => format!("refs: {}", name),
+        }
+    }
+
+    pub fn from_meta(ctx: &ContextElement) -> Option<Self> {
+        let text = ctx.get_meta_str(QUERY_META)?.to_string();
+        match ctx.get_meta_str(QUERY_MODE_META)? {
+            "search" => Some(SymbolQuery::Search {
+                query: text,
+                kind: ctx.get_meta_str(QUERY_KIND_META).and_then(SymbolKind::parse),
+            }),
+            "definition" => Some(SymbolQuery::Definition(text)),
+            "references" => Some(SymbolQuery::References(text)),
+            _ => None,
+        }
+    }
+
+    pub fn to_meta(&self, ctx: &mut ContextElement) {
+        ctx.set_meta(QUERY_MODE_META, &self.mode().to_string());
+        ctx.set_meta(QUERY_META, &self.text().to_string());
+        if let SymbolQuery::Search { kind: Some(kind), .. } = self {
+            ctx.set_meta(QUERY_KIND_META, &kind.as_str().to_string());
+        }
+    }
+}
+
+/// Module-owned state: the project index, shared with background refreshes
+pub struct SymbolState {
+    pub index: Arc<Mutex<SymbolIndex>>,
+}
+
+impl Default for SymbolState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SymbolState {
+    pub fn new() -> Self {
+        Self { index: Arc::new(Mutex::new(SymbolIndex::default())) }
+    }
+
+    pub fn get(state: &State) -> &Self {
+        state.get_ext::<Self>().expect("SymbolState not initialized")
+    }
+
+    pub fn get_mut(state: &mut State) -> &mut Self {
+        state.get_ext_mut::<Self>().expect("SymbolState not initialized")
+    }
+}
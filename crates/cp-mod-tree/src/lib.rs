@@ -15,8 +15,8 @@ use cp_base::modules::Module;
 
 pub use types::{DEFAULT_TREE_FILTER, TreeFileDescription, TreeState};
 
-// Re-export directory listing for autocomplete
-pub use self::tools::list_dir_entries;
+// Re-export directory listing for autocomplete, filter matching for project walks
+pub use self::tools::{filter_matcher, list_dir_entries};
 
 pub struct TreeModule;
 
@@ -200,6 +200,29 @@ impl Module for TreeModule {
         vec![("Tree", "Navigate and annotate the directory structure")]
     }
 
+    /// Descriptions and open folders follow moved paths; deleted ones are dropped
+    fn on_path_moved(&self, from: &str, to: Option<&str>, state: &mut State) {
+        use cp_base::modules::follow_move;
+        let ts = TreeState::get_mut(state);
+        ts.tree_descriptions.retain_mut(|d| match follow_move(&d.path, from, to) {
+            None => true,
+            Some(Some(new_path)) => {
+                d.path = new_path;
+                true
+            }
+            Some(None) => false,
+        });
+        ts.tree_open_folders.retain_mut(|f| match follow_move(f, from, to) {
+            None => true,
+            Some(Some(new_path)) => {
+                *f = new_path;
+                true
+            }
+            Some(None) => false,
+        });
+        cp_base::panels::mark_panels_dirty(state, ContextType::new(ContextType::TREE));
+    }
+
     fn watch_paths(&self, state: &State) -> Vec<cp_base::panels::WatchSpec> {
         TreeState::get(state).tree_open_folders.iter().map(|f| cp_base::panels::WatchSpec::Dir(f.clone())).collect()
     }
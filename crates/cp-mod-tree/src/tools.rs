@@ -2,7 +2,7 @@ use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use ignore::gitignore::GitignoreBuilder;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use sha2::{Digest, Sha256};
 
 use cp_base::state::{ContextType, State};
@@ -15,6 +15,19 @@ fn invalidate_tree_cache(state: &mut State) {
     cp_base::panels::mark_panels_dirty(state, ContextType::new(ContextType::TREE));
 }
 
+/// Gitignore-style matcher for the tree filter, rooted at the project directory.
+/// Also used by other modules that walk the project (search panels).
+pub fn filter_matcher(tree_filter: &str) -> Option<Gitignore> {
+    let mut builder = GitignoreBuilder::new(".");
+    for line in tree_filter.lines() {
+        let line = line.trim();
+        if !line.is_empty() && !line.starts_with('#') {
+            let _ = builder.add_line(None, line);
+        }
+    }
+    builder.build().ok()
+}
+
 /// Generate tree string without mutating state (for read-only rendering)
 pub fn generate_tree_string(
     tree_filter: &str,
@@ -23,15 +36,7 @@ pub fn generate_tree_string(
 ) -> String {
     let root = PathBuf::from(".");
 
-    // Build gitignore matcher from filter
-    let mut builder = GitignoreBuilder::new(&root);
-    for line in tree_filter.lines() {
-        let line = line.trim();
-        if !line.is_empty() && !line.starts_with('#') {
-            let _ = builder.add_line(None, line);
-        }
-    }
-    let gitignore = builder.build().ok();
+    let gitignore = filter_matcher(tree_filter);
 
     // Build set of open folders for quick lookup
     let open_set: HashSet<_> = tree_open_folders.iter().cloned().collect();
@@ -278,17 +283,7 @@ pub fn list_dir_entries(
     dir_prefix: &str,
     name_prefix: &str,
 ) -> Vec<cp_base::autocomplete::AutocompleteEntry> {
-    let root = PathBuf::from(".");
-
-    // Build gitignore matcher from filter
-    let mut builder = GitignoreBuilder::new(&root);
-    for line in tree_filter.lines() {
-        let line = line.trim();
-        if !line.is_empty() && !line.starts_with('#') {
-            let _ = builder.add_line(None, line);
-        }
-    }
-    let gitignore = builder.build().ok();
+    let gitignore = filter_matcher(tree_filter);
 
     let dir_path = if dir_prefix.is_empty() { PathBuf::from(".") } else { PathBuf::from(dir_prefix) };
 
@@ -124,6 +124,14 @@ impl Module for TypstModule {
     fn tool_category_descriptions(&self) -> Vec<(&'static str, &'static str)> {
         vec![("PDF", "Create and manage Typst PDF documents")]
     }
+
+    /// Watched documents, outputs and dependencies follow moved files
+    fn on_path_moved(&self, from: &str, to: Option<&str>, _state: &mut State) {
+        let mut watchlist = watchlist::Watchlist::load();
+        if watchlist.follow_move(from, to) {
+            watchlist.save();
+        }
+    }
 }
 
 /// Ensure the typst watchlist callback exists in CallbackState.
@@ -74,6 +74,46 @@ impl Watchlist {
             .collect()
     }
 
+    /// Update paths after `from` moved to `to` (`None`: deleted). A deleted
+    /// source drops its entry; a deleted dependency leaves its list.
+    /// Returns whether anything changed (the caller saves).
+    pub fn follow_move(&mut self, from: &str, to: Option<&str>) -> bool {
+        use cp_base::modules::follow_move;
+        let mut changed = false;
+        let sources: Vec<String> = self.entries.keys().cloned().collect();
+        for source in sources {
+            let Some(mut entry) = self.entries.remove(&source) else { continue };
+            if let Some(Some(output)) = follow_move(&entry.output, from, to) {
+                entry.output = output;
+                changed = true;
+            }
+            let deps = entry.deps.len();
+            entry.deps = entry
+                .deps
+                .into_iter()
+                .filter_map(|d| match follow_move(&d, from, to) {
+                    None => Some(d),
+                    Some(moved) => {
+                        changed = true;
+                        moved
+                    }
+                })
+                .collect();
+            changed |= entry.deps.len() != deps;
+            match follow_move(&source, from, to) {
+                None => {
+                    self.entries.insert(source, entry);
+                }
+                Some(Some(new_source)) => {
+                    self.entries.insert(new_source, entry);
+                    changed = true;
+                }
+                Some(None) => changed = true,
+            }
+        }
+        changed
+    }
+
     /// List all watched documents.
     pub fn list(&self) -> Vec<(&str, &WatchEntry)> {
         let mut items: Vec<_> = self.entries.iter().map(|(k, v)| (k.as_str(), v)).collect();
@@ -123,3 +163,28 @@ pub fn compile_and_update_deps(source: &str, output: &str) -> Result<String, Str
     }
     Ok(msg)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_follow_moves() {
+        let mut watchlist = Watchlist::default();
+        let deps = vec!["docs/report.typ".to_string(), "docs/img/logo.png".to_string(), "data.toml".to_string()];
+        watchlist
+            .entries
+            .insert("docs/report.typ".to_string(), WatchEntry { output: "docs/report.pdf".to_string(), deps });
+
+        assert!(!watchlist.follow_move("other", Some("elsewhere")));
+        assert!(watchlist.follow_move("docs", Some("papers")));
+        let entry = &watchlist.entries["papers/report.typ"];
+        assert_eq!(entry.output, "papers/report.pdf");
+        assert_eq!(entry.deps, vec!["papers/report.typ", "papers/img/logo.png", "data.toml"]);
+
+        assert!(watchlist.follow_move("data.toml", None));
+        assert_eq!(watchlist.entries["papers/report.typ"].deps.len(), 2);
+        assert!(watchlist.follow_move("papers/report.typ", None));
+        assert!(watchlist.entries.is_empty());
+    }
+}
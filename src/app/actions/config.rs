@@ -1,3 +1,6 @@
+use crate::infra::config::constants::{
+    BUDGETED_PANEL_TYPES, PANEL_BUDGET_STEP, THINKING_BUDGET_MAX, THINKING_BUDGET_MIN, THINKING_BUDGET_STEP,
+};
 use crate::state::State;
 
 use super::ActionResult;
@@ -9,16 +12,9 @@ pub fn handle_secondary_provider(state: &mut State, provider: crate::llms::LlmPr
     ActionResult::Save
 }
 
-/// Handle secondary model selection for all providers
-pub fn handle_secondary_model(state: &mut State, action: &super::Action) -> ActionResult {
-    use super::Action;
-    match action {
-        Action::ConfigSelectSecondaryAnthropicModel(model) => state.secondary_anthropic_model = *model,
-        Action::ConfigSelectSecondaryGrokModel(model) => state.secondary_grok_model = *model,
-        Action::ConfigSelectSecondaryGroqModel(model) => state.secondary_groq_model = *model,
-        Action::ConfigSelectSecondaryDeepSeekModel(model) => state.secondary_deepseek_model = *model,
-        _ => return ActionResult::Nothing,
-    }
+/// Handle secondary model selection (applies to the current secondary provider)
+pub fn handle_secondary_model(state: &mut State, model: String) -> ActionResult {
+    state.set_model_id(state.secondary_provider, true, model);
     state.dirty = true;
     ActionResult::Save
 }
@@ -47,6 +43,21 @@ pub fn handle_config_increase_bar(state: &mut State) -> ActionResult {
             let current = spine.config.max_cost.unwrap_or(0.0);
             spine.config.max_cost = Some(current + 0.50);
         }
+        4 => {
+            // Thinking budget: off → minimum → step multiples
+            let next = if state.thinking_budget < THINKING_BUDGET_MIN {
+                THINKING_BUDGET_MIN
+            } else {
+                (state.thinking_budget / THINKING_BUDGET_STEP + 1) * THINKING_BUDGET_STEP
+            };
+            state.thinking_budget = next.min(THINKING_BUDGET_MAX);
+        }
+        5 => {
+            // Panel budget for the selected type: unlimited → one step → step multiples
+            let panel_type = BUDGETED_PANEL_TYPES[state.config_budget_type % BUDGETED_PANEL_TYPES.len()];
+            let current = state.panel_budgets.get(panel_type).copied().unwrap_or(0);
+            set_type_budget(state, panel_type, current + PANEL_BUDGET_STEP);
+        }
         _ => {}
     }
     state.dirty = true;
@@ -79,6 +90,20 @@ pub fn handle_config_decrease_bar(state: &mut State) -> ActionResult {
             let new_val = current - 0.50;
             spine.config.max_cost = if new_val <= 0.0 { None } else { Some(new_val) };
         }
+        4 => {
+            // Thinking budget: step multiples → minimum → off
+            state.thinking_budget = if state.thinking_budget <= THINKING_BUDGET_MIN {
+                0
+            } else {
+                ((state.thinking_budget - 1) / THINKING_BUDGET_STEP * THINKING_BUDGET_STEP).max(THINKING_BUDGET_MIN)
+            };
+        }
+        5 => {
+            // Panel budget for the selected type: down to unlimited (0)
+            let panel_type = BUDGETED_PANEL_TYPES[state.config_budget_type % BUDGETED_PANEL_TYPES.len()];
+            let current = state.panel_budgets.get(panel_type).copied().unwrap_or(0);
+            set_type_budget(state, panel_type, current.saturating_sub(PANEL_BUDGET_STEP));
+        }
         _ => {}
     }
     state.dirty = true;
@@ -106,3 +131,23 @@ pub fn handle_config_prev_theme(state: &mut State) -> ActionResult {
     state.dirty = true;
     ActionResult::Save
 }
+
+/// Handle ConfigNextBudgetType action
+pub fn handle_config_next_budget_type(state: &mut State) -> ActionResult {
+    state.config_budget_type = (state.config_budget_type + 1) % BUDGETED_PANEL_TYPES.len();
+    state.config_selected_bar = 5;
+    state.dirty = true;
+    ActionResult::Nothing
+}
+
+/// Set a panel type's token budget (0 = unlimited) and re-fetch its panels so it applies
+fn set_type_budget(state: &mut State, panel_type: &str, budget: usize) {
+    if budget == 0 {
+        state.panel_budgets.remove(panel_type);
+    } else {
+        state.panel_budgets.insert(panel_type.to_string(), budget);
+    }
+    for ctx in state.context.iter_mut().filter(|c| c.context_type.as_str() == panel_type) {
+        ctx.cache_deprecated = true;
+    }
+}
@@ -25,6 +25,23 @@ pub fn handle_input_submit(state: &mut State) -> ActionResult {
         return ActionResult::Nothing;
     }
 
+    // Forking is a local operation: "/fork A12 [name]" never reaches the LLM
+    if let Some((message_id, name)) = crate::app::branches::parse_fork_command(&state.input) {
+        let result = crate::app::branches::fork(state, &message_id, name.as_deref());
+        let forked = result.is_ok();
+        super::notify_outcome(state, "Branch", result);
+        if forked {
+            state.input.clear();
+            state.input_cursor = 0;
+        }
+        return ActionResult::Save;
+    }
+
+    // A new user turn starts here: checkpoint first so it can be undone in one step
+    if !state.is_streaming {
+        crate::app::checkpoints::create_turn_checkpoint(state);
+    }
+
     let content = replace_commands(&state.input, &PromptState::get(state).commands);
     // Expand paste sentinels: replace \x00{idx}\x00 with actual paste buffer content
     let content = expand_paste_sentinels(&content, &state.paste_buffers);
@@ -83,6 +100,9 @@ pub fn handle_input_submit(state: &mut State) -> ActionResult {
     state.tick_cache_hit_tokens = 0;
     state.tick_cache_miss_tokens = 0;
     state.tick_output_tokens = 0;
+    state.stream_fallback_usage = Default::default();
+    // Give the main model another chance after a failover
+    state.active_fallback = None;
 
     // Return Save — the spine check in handle_action will detect the unprocessed
     // notification and start streaming synchronously for responsive feel.
@@ -91,10 +111,14 @@ pub fn handle_input_submit(state: &mut State) -> ActionResult {
 
 /// Handle ClearConversation action
 pub fn handle_clear_conversation(state: &mut State) -> ActionResult {
+    // Messages shared with other conversation branches must stay on disk
+    let shared = crate::state::persistence::branch::load_branches().uids_outside_active();
     for msg in &state.messages {
         // Delete by UID if available, otherwise by id
         let file_id = msg.uid.as_ref().unwrap_or(&msg.id);
-        delete_message(file_id);
+        if !shared.contains(file_id) {
+            delete_message(file_id);
+        }
     }
     state.messages.clear();
     state.input.clear();
@@ -127,6 +127,15 @@ pub fn apply_action(state: &mut State, action: Action) -> ActionResult {
             ActionResult::Nothing
         }
         Action::PasteText(text) => {
+            // A pasted (or drag-and-dropped) image path opens an image panel instead
+            if let Some(path) = cp_mod_files::image::image_path_from_paste(&text)
+                && let Ok(panel_id) = cp_mod_files::open_image(state, &path)
+            {
+                let reference = format!("[image {}] ", panel_id);
+                state.input.insert_str(state.input_cursor, &reference);
+                state.input_cursor += reference.len();
+                return ActionResult::Save;
+            }
             // Store in paste buffers and insert sentinel marker at cursor
             let idx = state.paste_buffers.len();
             state.paste_buffers.push(text);
@@ -331,6 +340,7 @@ pub fn apply_action(state: &mut State, action: Action) -> ActionResult {
 
         // === Streaming (delegated) ===
         Action::AppendChars(text) => streaming::handle_append_chars(state, &text),
+        Action::AppendThinking(block) => streaming::handle_append_thinking(state, block),
         Action::StreamDone { _input_tokens, output_tokens, cache_hit_tokens, cache_miss_tokens, ref stop_reason } => {
             streaming::handle_stream_done(
                 state,
@@ -387,9 +397,80 @@ pub fn apply_action(state: &mut State, action: Action) -> ActionResult {
             state.cache_hit_tokens = 0;
             state.cache_miss_tokens = 0;
             state.total_output_tokens = 0;
+            state.fallback_usage = Default::default();
             state.guard_rail_blocked = None;
             ActionResult::Save
         }
+        Action::TogglePinSelected => {
+            let Some(ctx) = state.context.get_mut(state.selected_context) else {
+                return ActionResult::Nothing;
+            };
+            // Fixed panels are never evicted, so there is nothing to pin
+            if ctx.uid.is_none() || ctx.context_type.is_fixed() {
+                return ActionResult::Nothing;
+            }
+            let pinned = !crate::app::eviction::is_pinned(ctx);
+            ctx.set_meta(crate::app::eviction::PINNED_META, &pinned);
+            state.dirty = true;
+            ActionResult::Save
+        }
+        Action::SnapshotContext => {
+            let name = cp_mod_preset::snapshot::next_auto_name();
+            let result = cp_mod_preset::snapshot::save(state, &name, false);
+            notify_outcome(state, "Snapshot", result);
+            ActionResult::Save
+        }
+        Action::RestoreSnapshot(name) => {
+            let result = cp_mod_preset::snapshot::restore(state, &name, crate::modules::all_modules);
+            notify_outcome(state, "Snapshot", result);
+            ActionResult::Save
+        }
+        Action::ForkConversation(message_id) => {
+            let result = crate::app::branches::fork(state, &message_id, None);
+            notify_outcome(state, "Branch", result);
+            ActionResult::Save
+        }
+        Action::SwitchBranch(name, restore_panels) => {
+            let result = crate::app::branches::switch(state, &name, restore_panels);
+            notify_outcome(state, "Branch", result);
+            ActionResult::Save
+        }
+        Action::CreateCheckpoint => {
+            let result = crate::app::checkpoints::create(state, Some("manual"), false);
+            notify_outcome(state, "Checkpoint", result);
+            ActionResult::Save
+        }
+        Action::PreviewCheckpoint(id) => {
+            let result = crate::app::checkpoints::preview(state, &id, true);
+            notify_outcome(state, "Checkpoint", result);
+            ActionResult::Save
+        }
+        Action::RestoreCheckpoint(id) => {
+            let result = crate::app::checkpoints::restore(state, &id, true);
+            notify_outcome(state, "Checkpoint", result);
+            ActionResult::Save
+        }
+        Action::OpenPromptInspector(mode) => {
+            if let Some(mode) = crate::app::prompt_inspector::InspectMode::parse(&mode) {
+                crate::app::prompt_inspector::open_panel(state, mode);
+            }
+            ActionResult::Save
+        }
+        Action::UndoFileEdit(path) => {
+            let result = cp_mod_files::history::undo(state, &path);
+            notify_outcome(state, "Undo", result.map(|msg| msg.lines().next().unwrap_or_default().to_string()));
+            ActionResult::Save
+        }
+        Action::RedoFileEdit(path) => {
+            let result = cp_mod_files::history::redo(state, &path);
+            notify_outcome(state, "Redo", result.map(|msg| msg.lines().next().unwrap_or_default().to_string()));
+            ActionResult::Save
+        }
+        Action::UndoLastTurn => {
+            let result = crate::app::checkpoints::undo_last_turn(state);
+            notify_outcome(state, "Checkpoint", result);
+            ActionResult::Save
+        }
         Action::TogglePerfMonitor => {
             state.perf_enabled = crate::ui::perf::PERF.toggle();
             state.dirty = true;
@@ -402,46 +483,29 @@ pub fn apply_action(state: &mut State, action: Action) -> ActionResult {
         }
         Action::ConfigSelectProvider(provider) => {
             state.llm_provider = provider;
+            state.active_fallback = None;
+            cp_base::tokenizer::select_for_model(provider, &state.current_model());
             state.api_check_in_progress = true;
             state.api_check_result = None;
             state.dirty = true;
             ActionResult::StartApiCheck
         }
-        Action::ConfigSelectAnthropicModel(model) => {
-            state.anthropic_model = model;
-            state.api_check_in_progress = true;
-            state.api_check_result = None;
-            state.dirty = true;
-            ActionResult::StartApiCheck
-        }
-        Action::ConfigSelectGrokModel(model) => {
-            state.grok_model = model;
-            state.api_check_in_progress = true;
-            state.api_check_result = None;
-            state.dirty = true;
-            ActionResult::StartApiCheck
-        }
-        Action::ConfigSelectGroqModel(model) => {
-            state.groq_model = model;
-            state.api_check_in_progress = true;
-            state.api_check_result = None;
-            state.dirty = true;
-            ActionResult::StartApiCheck
-        }
-        Action::ConfigSelectDeepSeekModel(model) => {
-            state.deepseek_model = model;
+        Action::ConfigSelectModel(model) => {
+            state.set_model_id(state.llm_provider, false, model);
+            state.active_fallback = None;
+            cp_base::tokenizer::select_for_model(state.llm_provider, &state.current_model());
             state.api_check_in_progress = true;
             state.api_check_result = None;
             state.dirty = true;
             ActionResult::StartApiCheck
         }
         Action::ConfigSelectNextBar => {
-            state.config_selected_bar = (state.config_selected_bar + 1) % 4;
+            state.config_selected_bar = (state.config_selected_bar + 1) % 6;
             state.dirty = true;
             ActionResult::Nothing
         }
         Action::ConfigSelectPrevBar => {
-            state.config_selected_bar = if state.config_selected_bar == 0 { 3 } else { state.config_selected_bar - 1 };
+            state.config_selected_bar = if state.config_selected_bar == 0 { 5 } else { state.config_selected_bar - 1 };
             state.dirty = true;
             ActionResult::Nothing
         }
@@ -451,6 +515,7 @@ pub fn apply_action(state: &mut State, action: Action) -> ActionResult {
         Action::ConfigDecreaseSelectedBar => config::handle_config_decrease_bar(state),
         Action::ConfigNextTheme => config::handle_config_next_theme(state),
         Action::ConfigPrevTheme => config::handle_config_prev_theme(state),
+        Action::ConfigNextBudgetType => config::handle_config_next_budget_type(state),
 
         Action::OpenCommandPalette => {
             // Handled in app.rs directly
@@ -472,15 +537,22 @@ pub fn apply_action(state: &mut State, action: Action) -> ActionResult {
             ActionResult::Save
         }
         Action::ConfigSelectSecondaryProvider(provider) => config::handle_secondary_provider(state, provider),
-        Action::ConfigSelectSecondaryAnthropicModel(_)
-        | Action::ConfigSelectSecondaryGrokModel(_)
-        | Action::ConfigSelectSecondaryGroqModel(_)
-        | Action::ConfigSelectSecondaryDeepSeekModel(_) => config::handle_secondary_model(state, &action),
+        Action::ConfigSelectSecondaryModel(model) => config::handle_secondary_model(state, model),
         Action::ConfigToggleReverie => {
             state.reverie_enabled = !state.reverie_enabled;
             state.dirty = true;
             ActionResult::Save
         }
+        Action::ConfigToggleHistorySummaries => {
+            state.summarize_history = !state.summarize_history;
+            state.dirty = true;
+            ActionResult::Save
+        }
+        Action::ConfigToggleAutoCheckpoints => {
+            state.auto_checkpoints = !state.auto_checkpoints;
+            state.dirty = true;
+            ActionResult::Save
+        }
         Action::ConfigToggleSecondaryMode => {
             state.config_secondary_mode = !state.config_secondary_mode;
             state.dirty = true;
@@ -489,3 +561,17 @@ pub fn apply_action(state: &mut State, action: Action) -> ActionResult {
         Action::None => ActionResult::Nothing,
     }
 }
+
+/// Report the outcome of a user-triggered snapshot or branch operation as an
+/// already-processed spine notification
+pub(crate) fn notify_outcome(state: &mut State, source: &str, result: Result<String, String>) {
+    let message = result.unwrap_or_else(|e| format!("Error: {}", e));
+    let nid = cp_mod_spine::SpineState::create_notification(
+        state,
+        cp_mod_spine::NotificationType::Custom,
+        source.to_string(),
+        message,
+    );
+    cp_mod_spine::SpineState::mark_notification_processed(state, &nid);
+    state.dirty = true;
+}
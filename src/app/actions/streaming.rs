@@ -1,5 +1,5 @@
 use crate::state::persistence::log_error;
-use crate::state::{ContextType, State, estimate_tokens};
+use crate::state::{ContextType, State, ThinkingBlock, estimate_tokens};
 
 use super::ActionResult;
 use super::helpers::clean_llm_id_prefix;
@@ -25,6 +25,19 @@ pub fn handle_append_chars(state: &mut State, text: &str) -> ActionResult {
     ActionResult::Nothing
 }
 
+/// Handle AppendThinking action — keep the signed block on the assistant message for replay
+pub fn handle_append_thinking(state: &mut State, block: ThinkingBlock) -> ActionResult {
+    if let Some(msg) = state.messages.last_mut()
+        && msg.role == "assistant"
+    {
+        if let ThinkingBlock::Thinking { thinking, .. } = &block {
+            msg.thinking_token_count += estimate_tokens(thinking);
+        }
+        msg.thinking_blocks.push(block);
+    }
+    ActionResult::Nothing
+}
+
 /// Handle StreamDone action — finalize streaming, correct token counts
 pub fn handle_stream_done(
     state: &mut State,
@@ -37,20 +50,8 @@ pub fn handle_stream_done(
     state.is_streaming = false;
     state.last_stop_reason = stop_reason.clone();
 
-    // Set tick stats (this tick only)
-    state.tick_cache_hit_tokens = cache_hit_tokens;
-    state.tick_cache_miss_tokens = cache_miss_tokens;
-    state.tick_output_tokens = output_tokens;
-
-    // Accumulate per-stream stats (reset at InputSubmit)
-    state.stream_cache_hit_tokens += cache_hit_tokens;
-    state.stream_cache_miss_tokens += cache_miss_tokens;
-    state.stream_output_tokens += output_tokens;
-
-    // Accumulate total stats
-    state.cache_hit_tokens += cache_hit_tokens;
-    state.cache_miss_tokens += cache_miss_tokens;
-    state.total_output_tokens += output_tokens;
+    // Tick, per-stream (reset at InputSubmit) and total stats
+    state.record_usage(cache_hit_tokens, cache_miss_tokens, output_tokens);
 
     // Correct the estimated tokens with actual output tokens on Conversation context and update timestamp
     if let Some(ctx) = state.context.iter_mut().find(|c| c.context_type == ContextType::CONVERSATION) {
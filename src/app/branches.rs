@@ -0,0 +1,213 @@
+//! Conversation branches — fork the conversation at a message and switch between forks.
+//!
+//! Forking records the live branch (its message UIDs and open panels) in
+//! branches.json, then truncates the conversation after the fork message on a
+//! new branch. Later messages are not deleted: they stay listed by the branch
+//! that was left. Switching swaps `state.messages` for the target branch's
+//! messages and can restore the panel set recorded for it.
+//!
+//! History chunks are not branched — they stay open across switches.
+
+use crate::app::panels::now_ms;
+use crate::infra::tools::refresh_conversation_context;
+use crate::modules::all_modules;
+use crate::state::persistence::branch::{Branch, BranchStore, load_branches, save_branches};
+use crate::state::persistence::{load_message, save_message};
+use crate::state::{ContextType, Message, MessageType, State};
+use cp_mod_preset::snapshot;
+
+pub(crate) fn message_uid(msg: &Message) -> String {
+    msg.uid.clone().unwrap_or_else(|| msg.id.clone())
+}
+
+/// Index just past the fork point: the message itself plus the tool results
+/// answering it (a tool call without its results would be rejected by the API)
+pub fn fork_end(messages: &[Message], message_id: &str) -> Option<usize> {
+    let idx = messages.iter().position(|m| m.id == message_id)?;
+    let mut end = idx + 1;
+    while messages.get(end).is_some_and(|m| m.message_type == MessageType::ToolResult) {
+        end += 1;
+    }
+    Some(end)
+}
+
+/// Same rule as preset and snapshot names: alphanumeric and hyphens only
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '-') {
+        return Err(format!("Invalid branch name '{}' (alphanumeric characters and hyphens only)", name));
+    }
+    Ok(())
+}
+
+/// First unused "branch-N" name
+fn next_auto_name(store: &BranchStore) -> String {
+    (1..).map(|n| format!("branch-{}", n)).find(|n| *n != store.active && store.get(n).is_none()).unwrap_or_default()
+}
+
+/// Record the live branch's messages (making sure their files exist) and panels
+fn leave_active(store: &mut BranchStore, state: &State) {
+    for msg in &state.messages {
+        save_message(msg);
+    }
+    let message_uids = state.messages.iter().map(message_uid).collect();
+    let panels = snapshot::capture_panels(state);
+    let active = store.active.clone();
+    match store.get_mut(&active) {
+        Some(branch) => {
+            branch.message_uids = message_uids;
+            branch.panels = panels;
+        }
+        None => store.branches.push(Branch {
+            name: active,
+            parent: None,
+            fork_message_id: None,
+            created_ms: now_ms(),
+            message_uids,
+            panels,
+        }),
+    }
+}
+
+/// Bring counters and the Conversation panel in line with a swapped message list
+pub(crate) fn refresh_live(state: &mut State) {
+    let next_after = |prefix: char| {
+        state
+            .messages
+            .iter()
+            .filter_map(|m| m.id.strip_prefix(prefix).and_then(|n| n.parse::<usize>().ok()))
+            .map(|n| n + 1)
+            .max()
+            .unwrap_or(1)
+    };
+    // Display IDs never go backwards, so an ID stays unique within any one branch
+    state.next_user_id = state.next_user_id.max(next_after('U'));
+    state.next_assistant_id = state.next_assistant_id.max(next_after('A'));
+    refresh_conversation_context(state);
+    if let Some(ctx) = state.context.iter_mut().find(|c| c.context_type == ContextType::CONVERSATION) {
+        ctx.last_refresh_ms = now_ms();
+    }
+    state.scroll_offset = 0.0;
+    state.user_scrolled = false;
+    state.dirty = true;
+}
+
+/// Fork the live conversation after `message_id` into a new branch and switch to it
+pub fn fork(state: &mut State, message_id: &str, name: Option<&str>) -> Result<String, String> {
+    if state.is_streaming {
+        return Err("Cannot fork while a response is streaming".to_string());
+    }
+    let end = fork_end(&state.messages, message_id)
+        .ok_or_else(|| format!("Message {} is not in the live conversation", message_id))?;
+
+    let mut store = load_branches();
+    let name = match name {
+        Some(name) => {
+            validate_name(name)?;
+            name.to_string()
+        }
+        None => next_auto_name(&store),
+    };
+    if name == store.active || store.get(&name).is_some() {
+        return Err(format!("Branch '{}' already exists", name));
+    }
+
+    leave_active(&mut store, state);
+    let parent = store.active.clone();
+    store.branches.push(Branch {
+        name: name.clone(),
+        parent: Some(parent.clone()),
+        fork_message_id: Some(message_id.to_string()),
+        created_ms: now_ms(),
+        message_uids: state.messages[..end].iter().map(message_uid).collect(),
+        panels: snapshot::capture_panels(state),
+    });
+    store.active = name.clone();
+    save_branches(&store)?;
+
+    let left_behind = state.messages.len() - end;
+    state.messages.truncate(end);
+    refresh_live(state);
+    Ok(format!("Forked at {} into branch '{}' ({} later messages kept on '{}')", message_id, name, left_behind, parent))
+}
+
+/// Switch to branch `name`, optionally restoring the panels recorded for it
+pub fn switch(state: &mut State, name: &str, restore_panels: bool) -> Result<String, String> {
+    if state.is_streaming {
+        return Err("Cannot switch branches while a response is streaming".to_string());
+    }
+    let mut store = load_branches();
+    if store.active == name {
+        return Err(format!("Already on branch '{}'", name));
+    }
+    let target = store.get(name).cloned().ok_or_else(|| format!("Branch '{}' not found", name))?;
+
+    leave_active(&mut store, state);
+    store.active = name.to_string();
+    save_branches(&store)?;
+
+    let messages: Vec<Message> = target.message_uids.iter().filter_map(|uid| load_message(uid)).collect();
+    let missing = target.message_uids.len() - messages.len();
+    state.messages = messages;
+    refresh_live(state);
+
+    let mut result = format!("Switched to branch '{}' ({} messages)", name, state.messages.len());
+    if missing > 0 {
+        result.push_str(&format!(", {} message files missing", missing));
+    }
+    if restore_panels {
+        let allowed = snapshot::active_panel_types(state, all_modules);
+        let skipped = snapshot::apply_panels(state, &target.panels, &allowed);
+        result.push_str(&format!(", {} panels restored", target.panels.len() - skipped.len()));
+        if !skipped.is_empty() {
+            result.push_str(&format!(" (skipped, module inactive: {})", skipped.join(", ")));
+        }
+    }
+    Ok(result)
+}
+
+/// Parse `/fork <message-id> [name]` typed in the input box
+pub fn parse_fork_command(input: &str) -> Option<(String, Option<String>)> {
+    let mut parts = input.trim().strip_prefix("/fork ")?.split_whitespace();
+    let message_id = parts.next()?.to_string();
+    let name = parts.next().map(|s| s.to_string());
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((message_id, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::message::test_helpers::MessageBuilder;
+    use serde_json::json;
+
+    #[test]
+    fn fork_point_keeps_tool_results_with_their_call() {
+        let mut user = MessageBuilder::user("open the config").build();
+        user.id = "U1".to_string();
+        let mut call = MessageBuilder::tool_call("Open", json!({"path": "a.toml"})).build();
+        call.id = "T1".to_string();
+        let result = MessageBuilder::tool_result(&call.tool_uses[0].id, "opened").build();
+        let mut reply = MessageBuilder::assistant("done").build();
+        reply.id = "A1".to_string();
+        let messages = vec![user, call, result, reply];
+
+        assert_eq!(fork_end(&messages, "U1"), Some(1));
+        assert_eq!(fork_end(&messages, "T1"), Some(3));
+        assert_eq!(fork_end(&messages, "A1"), Some(4));
+        assert_eq!(fork_end(&messages, "A9"), None);
+    }
+
+    #[test]
+    fn fork_command_parsing() {
+        assert_eq!(parse_fork_command("/fork A12"), Some(("A12".to_string(), None)));
+        assert_eq!(
+            parse_fork_command(" /fork A12 try-redis "),
+            Some(("A12".to_string(), Some("try-redis".to_string())))
+        );
+        assert_eq!(parse_fork_command("/fork"), None);
+        assert_eq!(parse_fork_command("/fork A12 two words"), None);
+        assert_eq!(parse_fork_command("please /fork A12"), None);
+    }
+}
@@ -0,0 +1,366 @@
+//! Checkpoints — roll the working tree, conversation and panels back together.
+//!
+//! The working tree is captured as a git tree built through a private index
+//! (`.context-pilot/checkpoint.index`), so the user's index, HEAD and stash are
+//! never touched. Untracked files are included, ignored files are not. Each
+//! tree is wrapped in a commit under `refs/context-pilot/checkpoints/` to keep
+//! it alive across `git gc`.
+//!
+//! A checkpoint is taken automatically at the start of every user turn (unless
+//! `auto_checkpoints` is off), which makes "undo the last turn" a single restore
+//! of the latest automatic one. Its git snapshot is built on a background thread
+//! so submitting a message never waits on git; the main loop records it once done.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{LazyLock, Mutex};
+
+use crate::app::branches::{message_uid, refresh_live};
+use crate::app::panels::now_ms;
+use crate::infra::constants::{CHECKPOINT_INDEX_FILE, CHECKPOINT_REF_PREFIX, STORE_DIR};
+use crate::modules::all_modules;
+use crate::state::persistence::branch::load_branches;
+use crate::state::persistence::checkpoint::{Checkpoint, CheckpointStore, load_checkpoints, save_checkpoints};
+use crate::state::persistence::delete_message;
+use crate::state::{MessageType, State};
+use cp_base::modules::run_with_timeout;
+use cp_mod_preset::snapshot;
+
+/// Timeout for each git invocation
+const GIT_TIMEOUT_SECS: u64 = 60;
+
+/// Pathspec keeping our own store out of the captured tree
+const EXCLUDE_STORE: &str = ":(exclude,glob)**/.context-pilot/**";
+
+/// Top-level directory of the git repository the TUI runs in
+fn repo_root() -> Result<PathBuf, String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err("Checkpoints need a git repository".to_string());
+    }
+    Ok(PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+}
+
+fn index_path() -> Result<PathBuf, String> {
+    std::fs::create_dir_all(STORE_DIR).map_err(|e| format!("Failed to create store directory: {}", e))?;
+    let cwd = std::env::current_dir().map_err(|e| format!("Failed to read working directory: {}", e))?;
+    Ok(cwd.join(STORE_DIR).join(CHECKPOINT_INDEX_FILE))
+}
+
+/// Run git at the repository root against the private checkpoint index
+fn git(root: &Path, args: &[&str]) -> Result<String, String> {
+    let mut cmd = Command::new("git");
+    cmd.current_dir(root)
+        .args(args)
+        .env("GIT_INDEX_FILE", index_path()?)
+        .env("GIT_AUTHOR_NAME", "context-pilot")
+        .env("GIT_AUTHOR_EMAIL", "checkpoint@context-pilot")
+        .env("GIT_COMMITTER_NAME", "context-pilot")
+        .env("GIT_COMMITTER_EMAIL", "checkpoint@context-pilot");
+    let output = run_with_timeout(cmd, GIT_TIMEOUT_SECS).map_err(|e| format!("git {}: {}", args[0], e))?;
+    if !output.status.success() {
+        return Err(format!("git {} failed: {}", args[0], String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+}
+
+/// Hash of a tree holding the current working tree (tracked + untracked, minus ignored)
+fn snapshot_tree(root: &Path) -> Result<String, String> {
+    git(root, &["add", "--all", "--", ".", EXCLUDE_STORE])?;
+    git(root, &["write-tree"])
+}
+
+/// A file that differs between the working tree and a checkpoint
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileChange {
+    /// What restoring does to the file: 'M' rewrite, 'A' recreate, 'D' delete
+    pub status: char,
+    pub path: String,
+}
+
+/// Parse `git diff --name-status -z` output
+fn parse_name_status(output: &str) -> Vec<FileChange> {
+    let mut parts = output.split('\0').filter(|s| !s.is_empty());
+    let mut changes = Vec::new();
+    while let (Some(status), Some(path)) = (parts.next(), parts.next()) {
+        if let Some(status) = status.chars().next() {
+            changes.push(FileChange { status, path: path.to_string() });
+        }
+    }
+    changes
+}
+
+/// Files restoring `commit` would change, plus git's diffstat
+fn file_changes(root: &Path, commit: &str) -> Result<(Vec<FileChange>, String), String> {
+    let current = snapshot_tree(root)?;
+    let target = format!("{}^{{tree}}", commit);
+    let changes = parse_name_status(&git(root, &["diff", "--name-status", "--no-renames", "-z", &current, &target])?);
+    let stat = git(root, &["diff", "--stat", &current, &target])?;
+    Ok((changes, stat))
+}
+
+/// Index just past the checkpoint's last message, if it is still in the live conversation
+fn message_end(state: &State, checkpoint: &Checkpoint) -> Option<usize> {
+    match &checkpoint.last_message_uid {
+        None => Some(0),
+        Some(uid) => state.messages.iter().position(|m| message_uid(m) == *uid).map(|i| i + 1),
+    }
+}
+
+/// Checkpoint manifest for the current conversation position and panels; `commit` is filled in later
+fn manifest(state: &State, label: String, auto: bool) -> Checkpoint {
+    Checkpoint {
+        id: String::new(),
+        label,
+        created_ms: now_ms(),
+        auto,
+        commit: String::new(),
+        message_count: state.messages.len(),
+        last_message_uid: state.messages.last().map(message_uid),
+        panels: snapshot::capture_panels(state),
+    }
+}
+
+/// Commit holding the current working tree
+fn commit_tree(root: &Path, label: &str) -> Result<String, String> {
+    let tree = snapshot_tree(root)?;
+    git(root, &["commit-tree", &tree, "-m", &format!("context-pilot checkpoint: {}", label)])
+}
+
+/// Store the manifest and pin its commit with a ref; returns the checkpoint ID
+fn record(root: &Path, checkpoint: Checkpoint) -> Result<String, String> {
+    let commit = checkpoint.commit.clone();
+    let mut store = load_checkpoints();
+    let id = store.push(checkpoint);
+    git(root, &["update-ref", &format!("{}{}", CHECKPOINT_REF_PREFIX, id), &commit])?;
+    for pruned in store.prune_auto() {
+        let _ = git(root, &["update-ref", "-d", &format!("{}{}", CHECKPOINT_REF_PREFIX, pruned.id)]);
+    }
+    save_checkpoints(&store)?;
+    Ok(id)
+}
+
+/// Capture files, conversation position and panels
+pub fn create(state: &State, label: Option<&str>, auto: bool) -> Result<String, String> {
+    finish_turn_checkpoint(true);
+    let root = repo_root()?;
+    let label = label.map(|l| l.to_string()).unwrap_or_else(|| format!("before U{}", state.next_user_id));
+    let mut checkpoint = manifest(state, label.clone(), auto);
+    checkpoint.commit = commit_tree(&root, &label)?;
+    let panel_count = checkpoint.panels.len();
+    let id = record(&root, checkpoint)?;
+    Ok(format!("Checkpoint {} '{}' created ({} messages, {} panels)", id, label, state.messages.len(), panel_count))
+}
+
+/// Turn checkpoint whose commit is being built on a background thread
+struct PendingTurn {
+    checkpoint: Checkpoint,
+    rx: Receiver<Result<(PathBuf, String), String>>,
+}
+
+static PENDING_TURN: LazyLock<Mutex<Option<PendingTurn>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Automatic checkpoint at the start of a user turn. The manifest is captured
+/// now, the git snapshot in the background. Silent when there is no git
+/// repository; a failure must never block sending the message.
+pub fn create_turn_checkpoint(state: &State) {
+    if !state.auto_checkpoints {
+        return;
+    }
+    // A previous snapshot still running on a huge tree: let it land first
+    finish_turn_checkpoint(true);
+    let label = format!("before U{}", state.next_user_id);
+    let checkpoint = manifest(state, label.clone(), true);
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let result = repo_root().and_then(|root| commit_tree(&root, &label).map(|commit| (root, commit)));
+        let _ = tx.send(result);
+    });
+    *PENDING_TURN.lock().unwrap_or_else(|e| e.into_inner()) = Some(PendingTurn { checkpoint, rx });
+}
+
+/// Record the background turn checkpoint once its commit is ready. With `wait`,
+/// block until it is: done before anything that changes files or the store, so
+/// the snapshot never catches the turn's own edits.
+pub fn finish_turn_checkpoint(wait: bool) {
+    let mut pending = PENDING_TURN.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(turn) = pending.as_ref() else {
+        return;
+    };
+    let result = match turn.rx.try_recv() {
+        Ok(result) => result,
+        Err(mpsc::TryRecvError::Empty) if !wait => return,
+        Err(mpsc::TryRecvError::Empty) => {
+            turn.rx.recv().unwrap_or_else(|_| Err("checkpoint thread stopped".to_string()))
+        }
+        Err(mpsc::TryRecvError::Disconnected) => Err("checkpoint thread stopped".to_string()),
+    };
+    let Some(PendingTurn { mut checkpoint, .. }) = pending.take() else {
+        return;
+    };
+    if let Ok((root, commit)) = result {
+        checkpoint.commit = commit;
+        let _ = record(&root, checkpoint);
+    }
+}
+
+fn find(store: &CheckpointStore, id: &str) -> Result<Checkpoint, String> {
+    store.get(id).cloned().ok_or_else(|| format!("Checkpoint '{}' not found", id))
+}
+
+/// Describe what restoring `id` would change, without changing anything
+pub fn preview(state: &State, id: &str, include_messages: bool) -> Result<String, String> {
+    let checkpoint = find(&load_checkpoints(), id)?;
+    let root = repo_root()?;
+    let (changes, stat) = file_changes(&root, &checkpoint.commit)?;
+
+    let mut out = format!(
+        "Restoring {} '{}' ({}) would change:\n",
+        checkpoint.id,
+        checkpoint.label,
+        crate::ui::helpers::format_time_ago(now_ms().saturating_sub(checkpoint.created_ms))
+    );
+    if changes.is_empty() {
+        out.push_str("Files: none (working tree matches)\n");
+    } else {
+        out.push_str(&format!("Files ({}):\n", changes.len()));
+        for change in &changes {
+            out.push_str(&format!("  {} {}\n", change.status, change.path));
+        }
+        out.push_str(&format!("{}\n", stat));
+    }
+
+    if include_messages {
+        match message_end(state, &checkpoint) {
+            Some(end) if end < state.messages.len() => {
+                out.push_str(&format!("Conversation: {} later messages dropped\n", state.messages.len() - end))
+            }
+            Some(_) => out.push_str("Conversation: unchanged\n"),
+            None => out.push_str("Conversation: checkpoint position no longer in the live conversation, kept as is\n"),
+        }
+    }
+
+    let current: Vec<String> = snapshot::capture_panels(state).into_iter().map(|p| p.name).collect();
+    let target: Vec<&str> = checkpoint.panels.iter().map(|p| p.name.as_str()).collect();
+    let closing: Vec<&str> = current.iter().map(|n| n.as_str()).filter(|n| !target.contains(n)).collect();
+    let opening: Vec<&str> = target.iter().copied().filter(|n| !current.iter().any(|c| c == n)).collect();
+    if closing.is_empty() && opening.is_empty() {
+        out.push_str("Panels: unchanged");
+    } else {
+        out.push_str(&format!("Panels: closing [{}], reopening [{}]", closing.join(", "), opening.join(", ")));
+    }
+    Ok(out)
+}
+
+/// Write the checkpoint's files back and delete files it did not have
+fn restore_files(root: &Path, commit: &str, changes: &[FileChange]) -> Result<(), String> {
+    let rewrite: Vec<&str> = changes.iter().filter(|c| c.status != 'D').map(|c| c.path.as_str()).collect();
+    if !rewrite.is_empty() {
+        git(root, &["read-tree", commit])?;
+        let mut args = vec!["checkout-index", "--force", "--"];
+        args.extend(rewrite);
+        git(root, &args)?;
+    }
+    for change in changes.iter().filter(|c| c.status == 'D') {
+        let path = root.join(&change.path);
+        std::fs::remove_file(&path).map_err(|e| format!("Failed to delete {}: {}", change.path, e))?;
+        // Drop directories the deletion left empty (remove_dir fails on non-empty ones)
+        let mut dir = path.parent();
+        while let Some(d) = dir.filter(|d| *d != root) {
+            if std::fs::remove_dir(d).is_err() {
+                break;
+            }
+            dir = d.parent();
+        }
+    }
+    Ok(())
+}
+
+/// Roll back to checkpoint `id`: files and panels always, the conversation only
+/// when `include_messages` (never while a response is streaming).
+pub fn restore(state: &mut State, id: &str, include_messages: bool) -> Result<String, String> {
+    if include_messages && state.is_streaming {
+        return Err("Cannot roll back the conversation while a response is streaming".to_string());
+    }
+    finish_turn_checkpoint(true);
+    let checkpoint = find(&load_checkpoints(), id)?;
+    let root = repo_root()?;
+    let (changes, _) = file_changes(&root, &checkpoint.commit)?;
+    restore_files(&root, &checkpoint.commit, &changes)?;
+
+    let mut result = format!("Restored {} '{}': {} files changed", checkpoint.id, checkpoint.label, changes.len());
+
+    if include_messages {
+        match message_end(state, &checkpoint) {
+            Some(end) => {
+                let dropped: Vec<_> = state.messages.drain(end..).collect();
+                let shared = load_branches().uids_outside_active();
+                for msg in &dropped {
+                    let uid = message_uid(msg);
+                    if !shared.contains(&uid) {
+                        delete_message(&uid);
+                    }
+                }
+                // Hand the undone prompt back so it can be edited and resent
+                if let Some(first) = dropped.first()
+                    && first.message_type == MessageType::TextMessage
+                    && first.role == "user"
+                    && state.input.is_empty()
+                {
+                    state.input = first.content.clone();
+                    state.input_cursor = state.input.len();
+                }
+                refresh_live(state);
+                result.push_str(&format!(", {} messages dropped", dropped.len()));
+            }
+            None => result.push_str(", conversation kept (checkpoint position no longer live)"),
+        }
+    }
+
+    let allowed = snapshot::active_panel_types(state, all_modules);
+    let skipped = snapshot::apply_panels(state, &checkpoint.panels, &allowed);
+    result.push_str(&format!(", {} panels restored", checkpoint.panels.len() - skipped.len()));
+    if !skipped.is_empty() {
+        result.push_str(&format!(" (skipped, module inactive: {})", skipped.join(", ")));
+    }
+    state.dirty = true;
+    Ok(result)
+}
+
+/// Restore the latest automatic checkpoint (files, conversation and panels) and
+/// consume it, so repeating the command steps back one more turn.
+pub fn undo_last_turn(state: &mut State) -> Result<String, String> {
+    finish_turn_checkpoint(true);
+    let mut store = load_checkpoints();
+    let id = store.last_auto().map(|c| c.id.clone()).ok_or_else(|| "No turn checkpoint to undo".to_string())?;
+    let result = restore(state, &id, true)?;
+    store.remove(&id);
+    save_checkpoints(&store)?;
+    if let Ok(root) = repo_root() {
+        let _ = git(&root, &["update-ref", "-d", &format!("{}{}", CHECKPOINT_REF_PREFIX, id)]);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name_status_parsing_handles_odd_paths() {
+        let output = "M\0src/main.rs\0D\0docs/with space.md\0A\0new\tfile.rs\0";
+        assert_eq!(
+            parse_name_status(output),
+            vec![
+                FileChange { status: 'M', path: "src/main.rs".to_string() },
+                FileChange { status: 'D', path: "docs/with space.md".to_string() },
+                FileChange { status: 'A', path: "new\tfile.rs".to_string() },
+            ]
+        );
+        assert!(parse_name_status("").is_empty());
+    }
+}
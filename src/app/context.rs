@@ -55,6 +55,11 @@ pub fn prepare_stream_context(
     // Refresh all panel token counts
     refresh_all_panels(state);
 
+    // Remember what we think the main prompt weighs, to calibrate against the API's count
+    if reverie.is_none() {
+        state.prompt_token_estimate = crate::modules::overview::context::estimate_prompt_tokens(state);
+    }
+
     // Collect all context items from panels
     let mut context_items = collect_all_context(state);
 
@@ -133,6 +138,7 @@ pub fn prepare_stream_context(
             header: "Main Agent Conversation (read-only)".to_string(),
             content: main_conv_content,
             last_refresh_ms: crate::app::panels::now_ms(),
+            image: None,
         });
 
         // Add P-reverie: agent prompt + context + tool restrictions + reverie conversation
@@ -165,6 +171,7 @@ pub fn prepare_stream_context(
             header: "Reverie Context (tool restrictions + conversation)".to_string(),
             content: reverie_panel_content,
             last_refresh_ms: crate::app::panels::now_ms(),
+            image: None,
         });
 
         // The reverie's messages ARE the conversation (may be empty on first run).
@@ -179,14 +186,24 @@ pub fn prepare_stream_context(
             state
                 .messages
                 .iter()
-                .filter(|m| !m.content.is_empty() || !m.tool_uses.is_empty() || !m.tool_results.is_empty())
+                .filter(|m| {
+                    !m.content.is_empty()
+                        || !m.tool_uses.is_empty()
+                        || !m.tool_results.is_empty()
+                        || !m.thinking_blocks.is_empty()
+                })
                 .cloned()
                 .collect()
         } else {
             state
                 .messages
                 .iter()
-                .filter(|m| !m.content.is_empty() || !m.tool_uses.is_empty() || !m.tool_results.is_empty())
+                .filter(|m| {
+                    !m.content.is_empty()
+                        || !m.tool_uses.is_empty()
+                        || !m.tool_results.is_empty()
+                        || !m.thinking_blocks.is_empty()
+                })
                 .take(state.messages.len().saturating_sub(1))
                 .cloned()
                 .collect()
@@ -385,8 +402,11 @@ pub fn detach_conversation_chunks(state: &mut State) {
 
         // 8. Remove detached messages from state and disk
         let removed: Vec<Message> = state.messages.drain(..boundary).collect();
+        let shared = crate::state::persistence::branch::load_branches().uids_outside_active();
         for msg in &removed {
-            if let Some(uid) = &msg.uid {
+            if let Some(uid) = &msg.uid
+                && !shared.contains(uid)
+            {
                 crate::state::persistence::delete_message(uid);
             }
         }
@@ -3,7 +3,7 @@ use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 use crate::app::actions::{Action, find_context_by_id, parse_context_pattern};
 use crate::app::panels::get_panel;
 use crate::infra::constants::{SCROLL_ARROW_AMOUNT, SCROLL_PAGE_AMOUNT};
-use crate::llms::{AnthropicModel, DeepSeekModel, GrokModel, GroqModel, LlmProvider};
+use crate::llms::LlmProvider;
 use crate::state::State;
 
 pub fn handle_event(event: &Event, state: &State) -> Option<Action> {
@@ -19,6 +19,7 @@ pub fn handle_event(event: &Event, state: &State) -> Option<Action> {
                     KeyCode::Char('n') => return Some(Action::NewContext),
                     KeyCode::Char('h') => return Some(Action::ToggleConfigView),
                     KeyCode::Char('o') => return Some(Action::ResetSessionCosts),
+                    KeyCode::Char('k') => return Some(Action::TogglePinSelected),
                     KeyCode::Char('p') => return Some(Action::OpenCommandPalette),
                     _ => {}
                 }
@@ -132,35 +133,34 @@ fn handle_config_event(key: &KeyEvent, _state: &State) -> Option<Action> {
                 Some(Action::ConfigSelectProvider(LlmProvider::ClaudeCodeApiKey))
             }
         }
-        // Letter keys select model based on current provider and Tab mode
-        KeyCode::Char('a') => {
-            if secondary {
-                dispatch_secondary_model(_state, 0)
-            } else {
-                dispatch_primary_model(_state, 0)
-            }
-        }
-        KeyCode::Char('b') => {
+        KeyCode::Char('7') => {
             if secondary {
-                dispatch_secondary_model(_state, 1)
+                Some(Action::ConfigSelectSecondaryProvider(LlmProvider::OpenAi))
             } else {
-                dispatch_primary_model(_state, 1)
+                Some(Action::ConfigSelectProvider(LlmProvider::OpenAi))
             }
         }
-        KeyCode::Char('c') => {
+        KeyCode::Char('8') => {
             if secondary {
-                dispatch_secondary_model(_state, 2)
+                Some(Action::ConfigSelectSecondaryProvider(LlmProvider::Local))
             } else {
-                dispatch_primary_model(_state, 2)
+                Some(Action::ConfigSelectProvider(LlmProvider::Local))
             }
         }
-        KeyCode::Char('d') => {
+        KeyCode::Char('9') => {
             if secondary {
-                dispatch_secondary_model(_state, 3)
+                Some(Action::ConfigSelectSecondaryProvider(LlmProvider::Replay))
             } else {
-                dispatch_primary_model(_state, 3)
+                Some(Action::ConfigSelectProvider(LlmProvider::Replay))
             }
         }
+        // Letter keys select model based on current provider and Tab mode
+        KeyCode::Char('a') => dispatch_model(_state, 0, secondary),
+        KeyCode::Char('b') => dispatch_model(_state, 1, secondary),
+        KeyCode::Char('c') => dispatch_model(_state, 2, secondary),
+        KeyCode::Char('d') => dispatch_model(_state, 3, secondary),
+        // Cycle through all models of the provider (beyond the first four)
+        KeyCode::Char('m') => cycle_model(_state, secondary),
         // Theme selection - t/T to cycle through themes
         KeyCode::Char('t') => Some(Action::ConfigNextTheme),
         KeyCode::Char('T') => Some(Action::ConfigPrevTheme),
@@ -168,6 +168,12 @@ fn handle_config_event(key: &KeyEvent, _state: &State) -> Option<Action> {
         KeyCode::Char('s') => Some(Action::ConfigToggleAutoContinue),
         // Toggle reverie (context optimizer)
         KeyCode::Char('r') => Some(Action::ConfigToggleReverie),
+        // Toggle LLM summaries of detached history chunks
+        KeyCode::Char('h') => Some(Action::ConfigToggleHistorySummaries),
+        // Toggle the automatic checkpoint at the start of every user turn
+        KeyCode::Char('k') => Some(Action::ConfigToggleAutoCheckpoints),
+        // Cycle the panel type edited by the budget bar
+        KeyCode::Char('p') => Some(Action::ConfigNextBudgetType),
         // Tab toggles between main/secondary model selection
         KeyCode::Tab => Some(Action::ConfigToggleSecondaryMode),
         KeyCode::Down => Some(Action::ConfigSelectNextBar),
@@ -179,60 +185,30 @@ fn handle_config_event(key: &KeyEvent, _state: &State) -> Option<Action> {
     }
 }
 
-/// Dispatch primary model selection based on provider and index (0=a, 1=b, 2=c, 3=d)
-fn dispatch_primary_model(state: &State, idx: usize) -> Option<Action> {
-    match state.llm_provider {
-        LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey => match idx {
-            0 => Some(Action::ConfigSelectAnthropicModel(AnthropicModel::ClaudeOpus45)),
-            1 => Some(Action::ConfigSelectAnthropicModel(AnthropicModel::ClaudeSonnet45)),
-            2 => Some(Action::ConfigSelectAnthropicModel(AnthropicModel::ClaudeHaiku45)),
-            _ => Some(Action::None),
-        },
-        LlmProvider::Grok => match idx {
-            0 => Some(Action::ConfigSelectGrokModel(GrokModel::Grok41Fast)),
-            1 => Some(Action::ConfigSelectGrokModel(GrokModel::Grok4Fast)),
-            _ => Some(Action::None),
-        },
-        LlmProvider::Groq => match idx {
-            0 => Some(Action::ConfigSelectGroqModel(GroqModel::GptOss120b)),
-            1 => Some(Action::ConfigSelectGroqModel(GroqModel::GptOss20b)),
-            2 => Some(Action::ConfigSelectGroqModel(GroqModel::Llama33_70b)),
-            3 => Some(Action::ConfigSelectGroqModel(GroqModel::Llama31_8b)),
-            _ => Some(Action::None),
-        },
-        LlmProvider::DeepSeek => match idx {
-            0 => Some(Action::ConfigSelectDeepSeekModel(DeepSeekModel::DeepseekChat)),
-            1 => Some(Action::ConfigSelectDeepSeekModel(DeepSeekModel::DeepseekReasoner)),
-            _ => Some(Action::None),
-        },
+/// Dispatch model selection based on provider and index (0=a, 1=b, 2=c, 3=d)
+fn dispatch_model(state: &State, idx: usize, secondary: bool) -> Option<Action> {
+    let provider = if secondary { state.secondary_provider } else { state.llm_provider };
+    match state.model_choices(provider).into_iter().nth(idx) {
+        Some(model) => Some(select_model_action(model, secondary)),
+        None => Some(Action::None),
     }
 }
 
-/// Dispatch secondary model selection based on secondary provider and index
-fn dispatch_secondary_model(state: &State, idx: usize) -> Option<Action> {
-    match state.secondary_provider {
-        LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey => match idx {
-            0 => Some(Action::ConfigSelectSecondaryAnthropicModel(AnthropicModel::ClaudeOpus45)),
-            1 => Some(Action::ConfigSelectSecondaryAnthropicModel(AnthropicModel::ClaudeSonnet45)),
-            2 => Some(Action::ConfigSelectSecondaryAnthropicModel(AnthropicModel::ClaudeHaiku45)),
-            _ => Some(Action::None),
-        },
-        LlmProvider::Grok => match idx {
-            0 => Some(Action::ConfigSelectSecondaryGrokModel(GrokModel::Grok41Fast)),
-            1 => Some(Action::ConfigSelectSecondaryGrokModel(GrokModel::Grok4Fast)),
-            _ => Some(Action::None),
-        },
-        LlmProvider::Groq => match idx {
-            0 => Some(Action::ConfigSelectSecondaryGroqModel(GroqModel::GptOss120b)),
-            1 => Some(Action::ConfigSelectSecondaryGroqModel(GroqModel::GptOss20b)),
-            2 => Some(Action::ConfigSelectSecondaryGroqModel(GroqModel::Llama33_70b)),
-            3 => Some(Action::ConfigSelectSecondaryGroqModel(GroqModel::Llama31_8b)),
-            _ => Some(Action::None),
-        },
-        LlmProvider::DeepSeek => match idx {
-            0 => Some(Action::ConfigSelectSecondaryDeepSeekModel(DeepSeekModel::DeepseekChat)),
-            1 => Some(Action::ConfigSelectSecondaryDeepSeekModel(DeepSeekModel::DeepseekReasoner)),
-            _ => Some(Action::None),
-        },
+/// Select the model after the current one for the active provider (wraps around)
+fn cycle_model(state: &State, secondary: bool) -> Option<Action> {
+    let (provider, current) = if secondary {
+        (state.secondary_provider, state.secondary_model_id())
+    } else {
+        (state.llm_provider, state.current_model_id())
+    };
+    let choices = state.model_choices(provider);
+    if choices.is_empty() {
+        return Some(Action::None);
     }
+    let next = choices.iter().position(|m| m == current).map_or(0, |i| (i + 1) % choices.len());
+    Some(select_model_action(choices[next].clone(), secondary))
+}
+
+fn select_model_action(model: String, secondary: bool) -> Action {
+    if secondary { Action::ConfigSelectSecondaryModel(model) } else { Action::ConfigSelectModel(model) }
 }
@@ -0,0 +1,278 @@
+//! Deterministic panel eviction — runs before the reverie optimizer.
+//!
+//! Dynamic panels carry three pieces of metadata: `pinned` (never evicted),
+//! `priority` (low / normal / high) and `last_referenced_ms` (bumped whenever a
+//! tool call or assistant message mentions the panel ID or its file path).
+//!
+//! When the context breaches the cleaning threshold, `evict_to_target()` first
+//! collapses expanded history chunks back to their summaries, then goes through
+//! unpinned low- and normal-priority panels, lowest priority and least recently
+//! referenced first, until the context is back under the cleaning target. Large
+//! panels that support pages are paginated down to their first page; the rest
+//! are closed. High-priority panels are left to the LLM optimizer.
+
+use crate::app::panels::{now_ms, paginate_content};
+use crate::modules::all_modules;
+use crate::modules::conversation_history::summary;
+use crate::modules::overview::tools::close_context;
+use crate::state::{ContextElement, ContextType, State, estimate_tokens};
+
+/// Metadata flag: panel is never evicted or closed by Close_panel
+pub const PINNED_META: &str = "pinned";
+/// Metadata: eviction priority ("low", "normal", "high")
+pub const PRIORITY_META: &str = "priority";
+/// Metadata: last time (ms) a tool call or assistant message mentioned the panel
+pub const LAST_REFERENCED_META: &str = "last_referenced_ms";
+
+/// Panel types whose LLM context shows one page of `cached_content` at a time.
+/// The console is left out: its first page is the oldest output.
+const PAGINATED_PANEL_TYPES: &[&str] =
+    &[ContextType::FILE, ContextType::GIT_RESULT, ContextType::GITHUB_RESULT, "brave_result", "firecrawl_result"];
+
+/// Smallest page eviction cuts a panel into; panels under two pages are closed instead
+const MIN_PAGE_TOKENS: usize = 2_000;
+
+/// How reluctant the eviction policy is to close a panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Low,
+    Normal,
+    High,
+}
+
+impl Priority {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "low" => Some(Self::Low),
+            "normal" => Some(Self::Normal),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Normal => "normal",
+            Self::High => "high",
+        }
+    }
+}
+
+pub fn is_pinned(ctx: &ContextElement) -> bool {
+    ctx.get_meta::<bool>(PINNED_META) == Some(true)
+}
+
+pub fn priority(ctx: &ContextElement) -> Priority {
+    ctx.get_meta_str(PRIORITY_META).and_then(Priority::parse).unwrap_or(Priority::Normal)
+}
+
+/// Last reference time, falling back to the last content change for panels
+/// nobody has mentioned yet
+pub fn last_referenced_ms(ctx: &ContextElement) -> u64 {
+    ctx.get_meta::<u64>(LAST_REFERENCED_META).unwrap_or(ctx.last_refresh_ms)
+}
+
+/// Whether `text` mentions `id` as a whole word ("P1" must not match "P12")
+fn mentions_id(text: &str, id: &str) -> bool {
+    text.match_indices(id).any(|(pos, _)| {
+        let before = text[..pos].chars().next_back();
+        let after = text[pos + id.len()..].chars().next();
+        !before.is_some_and(|c| c.is_alphanumeric()) && !after.is_some_and(|c| c.is_alphanumeric())
+    })
+}
+
+/// Bump `last_referenced_ms` on every dynamic panel whose ID or file path appears in `text`
+pub fn note_references(state: &mut State, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    let now = now_ms();
+    for ctx in state.context.iter_mut().filter(|c| c.uid.is_some()) {
+        let by_path = ctx.get_meta_str("file_path").is_some_and(|p| text.contains(p));
+        if by_path || mentions_id(text, &ctx.id) {
+            ctx.set_meta(LAST_REFERENCED_META, &now);
+        }
+    }
+}
+
+/// Panel IDs the policy may close, in eviction order
+fn eviction_order(state: &State) -> Vec<String> {
+    let mut candidates: Vec<&ContextElement> = state
+        .context
+        .iter()
+        .filter(|c| c.uid.is_some() && !c.context_type.is_fixed())
+        .filter(|c| c.context_type != ContextType::CONVERSATION_HISTORY)
+        .filter(|c| !is_pinned(c) && priority(c) < Priority::High)
+        .collect();
+    candidates.sort_by_key(|c| (priority(c), last_referenced_ms(c)));
+    candidates.into_iter().map(|c| c.id.clone()).collect()
+}
+
+/// Split a panel into pages small enough to free `over` tokens, keeping the
+/// first one in context. Returns the tokens freed, or None when the panel
+/// can't be paginated (wrong type, already paged, or too small).
+fn paginate(ctx: &mut ContextElement, over: usize) -> Option<usize> {
+    if !PAGINATED_PANEL_TYPES.contains(&ctx.context_type.as_str()) || ctx.total_pages > 1 {
+        return None;
+    }
+    let content = ctx.cached_content.as_deref()?;
+    let page_tokens = ctx.token_count.saturating_sub(over).max(MIN_PAGE_TOKENS);
+    let total_pages = ctx.token_count.div_ceil(page_tokens);
+    if total_pages < 2 {
+        return None;
+    }
+    let before = ctx.token_count;
+    ctx.total_pages = total_pages;
+    ctx.current_page = 0;
+    ctx.token_count = estimate_tokens(&paginate_content(content, 0, total_pages));
+    Some(before.saturating_sub(ctx.token_count))
+}
+
+fn total_tokens(state: &State) -> usize {
+    state.context.iter().map(|c| c.token_count).sum()
+}
+
+/// Bring the context under the cleaning target without an LLM. Returns a line
+/// per collapsed, paginated or closed panel (empty when nothing was done).
+pub fn evict_to_target(state: &mut State) -> Vec<String> {
+    let target = state.cleaning_target_tokens();
+    let mut actions = Vec::new();
+
+    // 1. Collapse expanded history chunks back to their summaries (nothing is lost)
+    let mut expanded: Vec<usize> = (0..state.context.len())
+        .filter(|&i| {
+            let c = &state.context[i];
+            c.context_type == ContextType::CONVERSATION_HISTORY
+                && !is_pinned(c)
+                && c.metadata.contains_key(summary::SUMMARY_META)
+                && c.get_meta::<bool>(summary::EXPANDED_META) == Some(true)
+        })
+        .collect();
+    expanded.sort_by_key(|&i| last_referenced_ms(&state.context[i]));
+    for idx in expanded {
+        if total_tokens(state) <= target {
+            return actions;
+        }
+        let ctx = &mut state.context[idx];
+        ctx.set_meta(summary::EXPANDED_META, &false);
+        summary::refresh_content(ctx);
+        ctx.last_refresh_ms = now_ms();
+        actions.push(format!("{} collapsed to its summary", ctx.id));
+    }
+
+    // 2. Paginate or close panels, lowest priority and least recently referenced first
+    let modules = all_modules();
+    for id in eviction_order(state) {
+        let total = total_tokens(state);
+        if total <= target {
+            break;
+        }
+        let Some(idx) = state.context.iter().position(|c| c.id == id) else {
+            continue;
+        };
+        let ctx = &mut state.context[idx];
+        if let Some(freed) = paginate(ctx, total - target) {
+            actions.push(format!("{} paginated into {} pages ({} tokens freed)", id, ctx.total_pages, freed));
+            continue;
+        }
+        let tokens = state.context[idx].token_count;
+        if let Ok(desc) = close_context::close_at(state, idx, &modules) {
+            actions.push(format!("{} closed ({}, {} tokens)", id, desc, tokens));
+        }
+    }
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn panel(state: &mut State, id: &str, tokens: usize, refreshed: u64) {
+        let mut ctx = crate::modules::make_default_context_element(id, ContextType::new("scratch"), id, false);
+        ctx.uid = Some(format!("UID_{}", id));
+        ctx.token_count = tokens;
+        ctx.last_refresh_ms = refreshed;
+        state.context.push(ctx);
+    }
+
+    fn get<'a>(state: &'a mut State, id: &str) -> &'a mut ContextElement {
+        state.context.iter_mut().find(|c| c.id == id).unwrap()
+    }
+
+    #[test]
+    fn references_match_whole_ids_and_file_paths() {
+        let mut state = State::default();
+        panel(&mut state, "P1", 10, 0);
+        panel(&mut state, "P12", 10, 0);
+        get(&mut state, "P12").set_meta("file_path", &"src/app/eviction.rs");
+
+        note_references(&mut state, "see P1, line 4");
+        assert!(get(&mut state, "P1").get_meta::<u64>(LAST_REFERENCED_META).is_some());
+        assert!(get(&mut state, "P12").get_meta::<u64>(LAST_REFERENCED_META).is_none());
+
+        note_references(&mut state, r#"{"file_path":"src/app/eviction.rs"}"#);
+        assert!(get(&mut state, "P12").get_meta::<u64>(LAST_REFERENCED_META).is_some());
+    }
+
+    #[test]
+    fn order_skips_pinned_and_high_and_prefers_low_then_stale() {
+        let mut state = State::default();
+        panel(&mut state, "P8", 10, 300);
+        panel(&mut state, "P9", 10, 100);
+        panel(&mut state, "P10", 10, 200);
+        panel(&mut state, "P11", 10, 50);
+        panel(&mut state, "P12", 10, 10);
+        get(&mut state, "P10").set_meta(PRIORITY_META, &"low");
+        get(&mut state, "P11").set_meta(PRIORITY_META, &"high");
+        get(&mut state, "P12").set_meta(PINNED_META, &true);
+        get(&mut state, "P8").set_meta(LAST_REFERENCED_META, &20u64);
+
+        assert_eq!(eviction_order(&state), vec!["P10", "P8", "P9"]);
+    }
+
+    #[test]
+    fn eviction_stops_at_the_cleaning_target() {
+        let mut state = State {
+            context_budget: Some(1_000),
+            cleaning_threshold: 0.7,
+            cleaning_target_proportion: 0.5,
+            ..State::default()
+        };
+        let target = state.cleaning_target_tokens();
+        panel(&mut state, "P8", 300, 10);
+        panel(&mut state, "P9", 300, 20);
+        panel(&mut state, "P10", 300, 30);
+        assert!(total_tokens(&state) > target);
+
+        let actions = evict_to_target(&mut state);
+        assert!(total_tokens(&state) <= target, "{:?}", actions);
+        assert!(state.context.iter().all(|c| c.id != "P8"), "stalest panel goes first");
+        assert!(state.context.iter().any(|c| c.id == "P10"), "stops once under target");
+    }
+
+    #[test]
+    fn large_file_panel_is_paginated_instead_of_closed() {
+        let content: String = (0..4_000).map(|i| format!("fn line_{}() {{}}\n", i)).collect();
+        let full = estimate_tokens(&content);
+        let mut state = State {
+            context_budget: Some(full * 2),
+            cleaning_threshold: 0.4,
+            cleaning_target_proportion: 0.4,
+            ..State::default()
+        };
+        panel(&mut state, "P8", full, 10);
+        let file = get(&mut state, "P8");
+        file.context_type = ContextType::new(ContextType::FILE);
+        file.cached_content = Some(content);
+        panel(&mut state, "P9", 500, 20);
+        let target = state.cleaning_target_tokens();
+        assert!(total_tokens(&state) > target && full > target);
+
+        let actions = evict_to_target(&mut state);
+        assert!(total_tokens(&state) <= target, "{:?}", actions);
+        let file = get(&mut state, "P8");
+        assert!(file.total_pages > 1 && file.current_page == 0);
+        assert!(state.context.iter().any(|c| c.id == "P9"), "paging alone reached the target: {:?}", actions);
+    }
+}
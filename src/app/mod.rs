@@ -1,8 +1,12 @@
 pub mod actions;
+pub mod branches;
+pub mod checkpoints;
 mod context;
 pub mod events;
+pub mod eviction;
 pub mod panels;
 pub mod prompt_builder;
+pub mod prompt_inspector;
 pub mod reverie;
 mod run;
 
@@ -28,6 +32,15 @@ struct ReverieStream {
     report_called: bool,
 }
 
+/// Background summarization of one detached history chunk.
+struct HistorySummaryStream {
+    rx: Receiver<crate::infra::api::StreamEvent>,
+    /// UID of the ConversationHistory panel being summarized
+    panel_uid: String,
+    /// Summary text received so far
+    text: String,
+}
+
 pub struct App {
     pub state: State,
     typewriter: TypewriterBuffer,
@@ -40,6 +53,8 @@ pub struct App {
     watched_file_paths: std::collections::HashSet<String>,
     /// Tracks which directory paths are being watched
     watched_dir_paths: std::collections::HashSet<String>,
+    /// Tracks which directory paths are being watched recursively
+    watched_recursive_paths: std::collections::HashSet<String>,
     /// Last time we checked timer-based caches
     last_timer_check_ms: u64,
     /// Last time we checked ownership
@@ -54,6 +69,8 @@ pub struct App {
     last_gh_sync_ms: u64,
     /// Channel for API check results
     api_check_rx: Option<Receiver<crate::llms::ApiCheckResult>>,
+    /// Channel for local model discovery results
+    local_models_rx: Option<Receiver<Result<Vec<String>, String>>>,
     /// Whether to auto-start streaming on first loop iteration
     resume_stream: bool,
     /// Command palette state
@@ -78,6 +95,8 @@ pub struct App {
     accumulated_blocking_results: Vec<cp_base::watchers::WatcherResult>,
     /// Active reverie stream (context optimizer sub-agent)
     reverie_stream: Option<ReverieStream>,
+    /// Running history-chunk summarization, if any
+    history_summary: Option<HistorySummaryStream>,
 }
 
 impl App {
@@ -95,6 +114,7 @@ impl App {
             gh_watcher,
             watched_file_paths: std::collections::HashSet::new(),
             watched_dir_paths: std::collections::HashSet::new(),
+            watched_recursive_paths: std::collections::HashSet::new(),
             last_timer_check_ms: now_ms(),
             last_ownership_check_ms: now_ms(),
             pending_retry_error: None,
@@ -102,6 +122,7 @@ impl App {
             last_spinner_ms: 0,
             last_gh_sync_ms: 0,
             api_check_rx: None,
+            local_models_rx: None,
             resume_stream,
             command_palette: CommandPalette::new(),
             wait_started_ms: 0,
@@ -113,6 +134,7 @@ impl App {
             pending_console_wait_tool_results: None,
             accumulated_blocking_results: Vec::new(),
             reverie_stream: None,
+            history_summary: None,
         }
     }
 
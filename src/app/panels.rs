@@ -15,7 +15,7 @@ use crate::ui::{helpers::count_wrapped_lines, theme};
 // Re-export the Panel trait, ContextItem, and utility functions from cp-base
 #[cfg(test)]
 pub use cp_base::panels::mark_panels_dirty;
-pub use cp_base::panels::{ContextItem, Panel, now_ms, paginate_content, update_if_changed};
+pub use cp_base::panels::{ContextItem, Panel, apply_token_budget, now_ms, paginate_content, update_if_changed};
 
 /// Render a panel with the binary's full chrome (borders, theme, scroll, profiling).
 /// This is NOT part of the Panel trait — it uses binary-specific deps (theme, profile!, UI helpers).
@@ -190,8 +190,7 @@ mod tests {
 
     #[test]
     fn mark_panels_dirty_sets_state_dirty() {
-        let mut state = State::default();
-        state.dirty = false;
+        let mut state = State { dirty: false, ..State::default() };
         mark_panels_dirty(&mut state, ContextType::new(ContextType::FILE));
         assert!(state.dirty);
     }
@@ -14,7 +14,7 @@ use crate::app::panels::ContextItem;
 use crate::llms::{
     ApiMessage, ContentBlock, panel_footer_text, panel_header_text, panel_timestamp_text, prepare_panel_messages,
 };
-use crate::state::{Message, MessageStatus, MessageType};
+use crate::state::{Message, MessageStatus, MessageType, ThinkingBlock};
 
 /// Assemble the full prompt as `Vec<ApiMessage>`.
 ///
@@ -50,7 +50,11 @@ pub fn assemble_prompt(
             continue;
         }
 
-        if msg.content.is_empty() && msg.tool_uses.is_empty() && msg.tool_results.is_empty() {
+        if msg.content.is_empty()
+            && msg.tool_uses.is_empty()
+            && msg.tool_results.is_empty()
+            && msg.thinking_blocks.is_empty()
+        {
             continue;
         }
 
@@ -82,6 +86,11 @@ pub fn assemble_prompt(
                 continue;
             }
         } else {
+            // Signed thinking blocks must lead the assistant turn, unmodified
+            if msg.role == "assistant" {
+                content_blocks.extend(msg.thinking_blocks.iter().map(thinking_block));
+            }
+
             let message_content = msg.content.clone();
 
             if !message_content.is_empty() {
@@ -129,13 +138,15 @@ fn inject_panel_messages(
                 },
             ],
         });
-        api_messages.push(ApiMessage {
-            role: "user".to_string(),
-            content: vec![ContentBlock::ToolResult {
-                tool_use_id: format!("panel_{}", panel.panel_id),
-                content: panel.content.clone(),
-            }],
-        });
+        let mut result_blocks = vec![ContentBlock::ToolResult {
+            tool_use_id: format!("panel_{}", panel.panel_id),
+            content: panel.content.clone(),
+        }];
+        // Image panels: the picture follows its tool_result (which carries the text description)
+        if let Some(image) = panel.image.as_ref().and_then(ContentBlock::image_from_attachment) {
+            result_blocks.push(image);
+        }
+        api_messages.push(ApiMessage { role: "user".to_string(), content: result_blocks });
     }
 
     // Footer after all panels
@@ -193,6 +204,16 @@ fn build_tool_call_blocks(msg: &Message, messages: &[Message], idx: usize) -> Op
     Some(msg.tool_uses.iter().map(tool_use_block).collect())
 }
 
+/// Convert a stored ThinkingBlock into its ContentBlock.
+fn thinking_block(block: &ThinkingBlock) -> ContentBlock {
+    match block {
+        ThinkingBlock::Thinking { thinking, signature } => {
+            ContentBlock::Thinking { thinking: thinking.clone(), signature: signature.clone() }
+        }
+        ThinkingBlock::RedactedThinking { data } => ContentBlock::RedactedThinking { data: data.clone() },
+    }
+}
+
 /// Convert a ToolUseRecord into a ContentBlock, ensuring input is never null.
 fn tool_use_block(tool_use: &crate::state::ToolUseRecord) -> ContentBlock {
     let input = if tool_use.input.is_null() {
@@ -202,3 +223,67 @@ fn tool_use_block(tool_use: &crate::state::ToolUseRecord) -> ContentBlock {
     };
     ContentBlock::ToolUse { id: tool_use.id.clone(), name: tool_use.name.clone(), input }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::message::test_helpers::MessageBuilder;
+
+    #[test]
+    fn thinking_blocks_lead_assistant_turn() {
+        let msgs = vec![
+            MessageBuilder::user("question").build(),
+            MessageBuilder::assistant("answer")
+                .thinking(ThinkingBlock::Thinking { thinking: "hmm".into(), signature: "sig".into() })
+                .build(),
+        ];
+        let api = assemble_prompt(&msgs, &[], false, None);
+        assert_eq!(api.len(), 2);
+        assert!(matches!(&api[1].content[0], ContentBlock::Thinking { signature, .. } if signature == "sig"));
+        assert!(matches!(&api[1].content[1], ContentBlock::Text { text } if text == "answer"));
+    }
+
+    #[test]
+    fn thinking_only_message_keeps_following_tool_use() {
+        // Thinking then straight to a tool call: the empty-text assistant message must
+        // still be emitted so the tool_use merges after its thinking block.
+        let call = MessageBuilder::tool_call("read_file", serde_json::json!({"path": "a.rs"})).build();
+        let result = MessageBuilder::tool_result(&call.tool_uses[0].id, "contents").build();
+        let msgs = vec![
+            MessageBuilder::user("question").build(),
+            MessageBuilder::assistant("").thinking(ThinkingBlock::RedactedThinking { data: "opaque".into() }).build(),
+            call,
+            result,
+        ];
+
+        let api = assemble_prompt(&msgs, &[], false, None);
+        assert_eq!(api.len(), 3);
+        assert_eq!(api[1].role, "assistant");
+        assert!(matches!(&api[1].content[0], ContentBlock::RedactedThinking { data } if data == "opaque"));
+        assert!(matches!(&api[1].content[1], ContentBlock::ToolUse { name, .. } if name == "read_file"));
+    }
+
+    #[test]
+    fn image_panel_follows_its_tool_result() {
+        let file = std::env::temp_dir().join("cp_prompt_builder_image.png");
+        std::fs::write(&file, [0x89, b'P', b'N', b'G']).unwrap();
+        let item = ContextItem::new("P9", "Image: shot.png", "Image: shot.png", 1).with_image(
+            cp_base::panels::ImageAttachment {
+                path: file.to_string_lossy().to_string(),
+                media_type: "image/png".to_string(),
+            },
+        );
+
+        let api = assemble_prompt(&[MessageBuilder::user("what is this?").build()], &[item], false, None);
+        let _ = std::fs::remove_file(&file);
+
+        let result_msg = &api[1];
+        assert!(
+            matches!(&result_msg.content[0], ContentBlock::ToolResult { tool_use_id, .. } if tool_use_id == "panel_P9")
+        );
+        assert!(matches!(
+            &result_msg.content[1],
+            ContentBlock::Image { source } if source.media_type == "image/png" && source.data == "iVBORw=="
+        ));
+    }
+}
@@ -0,0 +1,365 @@
+//! Prompt inspector — the assembled request broken into blocks, for debugging
+//! what the model actually saw.
+//!
+//! Every main-conversation request assembled in `start_streaming()` is described (not stored
+//! verbatim) and the last two are kept, so the inspector can show the last
+//! request and diff it against the one before. The next request is previewed
+//! on demand from the current state, without the side effects of
+//! `prepare_stream_context()` (no detachment, no notification processing).
+
+use std::collections::VecDeque;
+use std::sync::{LazyLock, Mutex};
+
+use crate::app::panels::{collect_all_context, now_ms};
+use crate::infra::tools::{ToolDefinition, build_api_tools};
+use crate::llms::{ApiMessage, ContentBlock, panel_cache_breakpoints};
+use crate::state::{State, estimate_tokens};
+use cp_base::panels::hash_content;
+
+/// Context type of the inspector panel (never sent to the model)
+pub const PROMPT_INSPECTOR_TYPE: &str = "prompt_inspector";
+
+/// Panel metadata key holding the `InspectMode`
+pub const INSPECT_MODE_META: &str = "inspect_mode";
+
+/// Which request the inspector shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InspectMode {
+    /// Preview of the request the current state would produce
+    Next,
+    /// Last request sent
+    Last,
+    /// Last request compared with the one before
+    Diff,
+}
+
+impl InspectMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InspectMode::Next => "next",
+            InspectMode::Last => "last",
+            InspectMode::Diff => "diff",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "next" => Some(InspectMode::Next),
+            "last" => Some(InspectMode::Last),
+            "diff" => Some(InspectMode::Diff),
+            _ => None,
+        }
+    }
+}
+
+/// Part of the request a block comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockKind {
+    System,
+    Tools,
+    Panel,
+    PanelFooter,
+    Seed,
+    Conversation,
+}
+
+/// One block of an assembled request
+#[derive(Debug, Clone)]
+pub struct PromptBlock {
+    pub kind: BlockKind,
+    /// Stable identity across requests ("system", "tools", a panel ID, ...)
+    pub key: String,
+    pub label: String,
+    pub tokens: usize,
+    /// API messages the block spans
+    pub messages: usize,
+    pub hash: String,
+    /// A prompt-cache breakpoint (`cache_control`) is set on this block
+    pub cache_breakpoint: bool,
+}
+
+/// A described request
+#[derive(Debug, Clone)]
+pub struct PromptCapture {
+    pub captured_ms: u64,
+    pub blocks: Vec<PromptBlock>,
+}
+
+impl PromptCapture {
+    pub fn total_tokens(&self) -> usize {
+        self.blocks.iter().map(|b| b.tokens).sum()
+    }
+}
+
+/// Requests described so far: the last two sent, and the latest preview
+#[derive(Default)]
+struct Captures {
+    sent: VecDeque<PromptCapture>,
+    next: Option<PromptCapture>,
+}
+
+static CAPTURES: LazyLock<Mutex<Captures>> = LazyLock::new(|| Mutex::new(Captures::default()));
+
+fn block_text(block: &ContentBlock) -> String {
+    match block {
+        ContentBlock::Text { text } => text.clone(),
+        ContentBlock::ToolUse { name, input, .. } => format!("{}{}", name, input),
+        ContentBlock::ToolResult { content, .. } => content.clone(),
+        ContentBlock::Thinking { thinking, .. } => thinking.clone(),
+        ContentBlock::RedactedThinking { .. } | ContentBlock::Image { .. } => String::new(),
+    }
+}
+
+fn message_text(msg: &ApiMessage) -> String {
+    msg.content.iter().map(block_text).collect::<Vec<_>>().join("\n")
+}
+
+/// ID of the panel whose tool_result this message carries ("footer" for the footer)
+fn panel_result_id(msg: &ApiMessage) -> Option<&str> {
+    msg.content.iter().find_map(|b| match b {
+        ContentBlock::ToolResult { tool_use_id, .. } => tool_use_id.strip_prefix("panel_"),
+        _ => None,
+    })
+}
+
+/// Panel header from the "======= [P5] header =======" first line of its content
+fn panel_label(text: &str) -> String {
+    text.lines()
+        .find(|l| l.starts_with("======= ["))
+        .map(|l| l.trim_matches(|c| c == '=' || c == ' ').to_string())
+        .unwrap_or_default()
+}
+
+fn block(kind: BlockKind, key: &str, label: String, text: &str, messages: usize) -> PromptBlock {
+    PromptBlock {
+        kind,
+        key: key.to_string(),
+        label,
+        tokens: estimate_tokens(text),
+        messages,
+        hash: hash_content(text),
+        cache_breakpoint: false,
+    }
+}
+
+/// Break an assembled request into blocks, in the order the model reads them
+pub fn describe(api_messages: &[ApiMessage], system_prompt: &str, tools: &[ToolDefinition]) -> PromptCapture {
+    let mut blocks = vec![block(BlockKind::System, "system", "System prompt".to_string(), system_prompt, 0)];
+    let enabled_tools = tools.iter().filter(|t| t.enabled).count();
+    blocks.push(block(
+        BlockKind::Tools,
+        "tools",
+        format!("Tool definitions ({} enabled)", enabled_tools),
+        &build_api_tools(tools).to_string(),
+        0,
+    ));
+
+    // Panel injection: assistant (timestamp + tool_use) / user (tool_result) pairs, then the footer pair
+    let mut idx = 0;
+    let mut panel_count = 0;
+    while idx + 1 < api_messages.len() {
+        let Some(id) = panel_result_id(&api_messages[idx + 1]) else { break };
+        let text = format!("{}\n{}", message_text(&api_messages[idx]), message_text(&api_messages[idx + 1]));
+        if id == "footer" {
+            blocks.push(block(BlockKind::PanelFooter, "footer", "Panel footer".to_string(), &text, 2));
+            idx += 2;
+            break;
+        }
+        let label = panel_label(&text);
+        blocks.push(block(BlockKind::Panel, id, label, &text, 2));
+        panel_count += 1;
+        idx += 2;
+    }
+
+    // Seed re-injection: the system prompt repeated after the panels, and its acknowledgment
+    if idx < api_messages.len() && message_text(&api_messages[idx]).starts_with("System instructions (repeated") {
+        let end = (idx + 2).min(api_messages.len());
+        let text = api_messages[idx..end].iter().map(message_text).collect::<Vec<_>>().join("\n");
+        blocks.push(block(BlockKind::Seed, "seed", "Seed re-injection".to_string(), &text, end - idx));
+        idx = end;
+    }
+
+    let conversation = &api_messages[idx..];
+    let text = conversation.iter().map(message_text).collect::<Vec<_>>().join("\n");
+    blocks.push(block(
+        BlockKind::Conversation,
+        "conversation",
+        format!("Conversation ({} messages)", conversation.len()),
+        &text,
+        conversation.len(),
+    ));
+
+    let breakpoints = panel_cache_breakpoints(panel_count);
+    for (pos, panel) in blocks.iter_mut().filter(|b| b.kind == BlockKind::Panel).enumerate() {
+        panel.cache_breakpoint = breakpoints.contains(&pos);
+    }
+
+    PromptCapture { captured_ms: now_ms(), blocks }
+}
+
+/// Remember a request that is being sent (keeps the last two)
+pub fn record_sent(api_messages: &[ApiMessage], system_prompt: &str, tools: &[ToolDefinition]) {
+    let capture = describe(api_messages, system_prompt, tools);
+    if let Ok(mut captures) = CAPTURES.lock() {
+        captures.sent.push_back(capture);
+        while captures.sent.len() > 2 {
+            captures.sent.pop_front();
+        }
+    }
+}
+
+/// Describe the request the current state would send next and keep it for the panel
+pub fn capture_next(state: &State) {
+    let mut context_items = collect_all_context(state);
+    context_items.sort_by_key(|item| item.last_refresh_ms);
+    let messages: Vec<_> = state
+        .messages
+        .iter()
+        .filter(|m| {
+            !m.content.is_empty()
+                || !m.tool_uses.is_empty()
+                || !m.tool_results.is_empty()
+                || !m.thinking_blocks.is_empty()
+        })
+        .cloned()
+        .collect();
+    let system_prompt = cp_mod_prompt::seed::get_active_agent_content(state);
+    let api_messages =
+        crate::app::prompt_builder::assemble_prompt(&messages, &context_items, false, Some(&system_prompt));
+    let capture = describe(&api_messages, &system_prompt, &state.tools);
+    if let Ok(mut captures) = CAPTURES.lock() {
+        captures.next = Some(capture);
+    }
+}
+
+/// The capture shown in `mode`, and for diffs the previous request
+pub fn captures_for(mode: InspectMode) -> (Option<PromptCapture>, Option<PromptCapture>) {
+    let Ok(captures) = CAPTURES.lock() else {
+        return (None, None);
+    };
+    let last = captures.sent.back().cloned();
+    match mode {
+        InspectMode::Next => (captures.next.clone(), None),
+        InspectMode::Last => (last, None),
+        InspectMode::Diff => {
+            let previous = if captures.sent.len() == 2 { captures.sent.front().cloned() } else { None };
+            (last, previous)
+        }
+    }
+}
+
+/// Open (or reuse) the inspector panel in `mode` and select it. Previews of the
+/// next request are taken now, so the panel shows a stable picture.
+pub fn open_panel(state: &mut State, mode: InspectMode) {
+    if mode == InspectMode::Next {
+        capture_next(state);
+    }
+    let index = match state.context.iter().position(|c| c.context_type.as_str() == PROMPT_INSPECTOR_TYPE) {
+        Some(index) => index,
+        None => {
+            let context_id = state.next_available_context_id();
+            let mut elem = crate::modules::make_default_context_element(
+                &context_id,
+                crate::state::ContextType::new(PROMPT_INSPECTOR_TYPE),
+                "Prompt Inspector",
+                false,
+            );
+            elem.uid = Some(format!("UID_{}_P", state.global_next_uid));
+            state.global_next_uid += 1;
+            state.context.push(elem);
+            state.context.len() - 1
+        }
+    };
+    state.context[index].set_meta(INSPECT_MODE_META, &mode.as_str());
+    state.context[index].last_refresh_ms = now_ms();
+    state.selected_context = index;
+    state.scroll_offset = 0.0;
+    state.user_scrolled = false;
+    state.dirty = true;
+}
+
+/// How a block changed between two requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockChange {
+    Unchanged,
+    Changed,
+    Added,
+    Removed,
+}
+
+/// Per-block changes from `previous` to `current` (current order, then removed
+/// blocks), with the token delta, plus how many leading blocks are identical —
+/// the part of the prompt a prefix cache can still serve.
+pub fn diff(previous: &PromptCapture, current: &PromptCapture) -> (Vec<(BlockChange, PromptBlock, isize)>, usize) {
+    let mut changes = Vec::new();
+    for block in &current.blocks {
+        let change = match previous.blocks.iter().find(|b| b.key == block.key) {
+            None => (BlockChange::Added, block.tokens as isize),
+            Some(prev) if prev.hash == block.hash => (BlockChange::Unchanged, 0),
+            Some(prev) => (BlockChange::Changed, block.tokens as isize - prev.tokens as isize),
+        };
+        changes.push((change.0, block.clone(), change.1));
+    }
+    for prev in previous.blocks.iter().filter(|p| !current.blocks.iter().any(|b| b.key == p.key)) {
+        changes.push((BlockChange::Removed, prev.clone(), -(prev.tokens as isize)));
+    }
+    let common_prefix = current
+        .blocks
+        .iter()
+        .zip(previous.blocks.iter())
+        .take_while(|(a, b)| a.key == b.key && a.hash == b.hash)
+        .count();
+    (changes, common_prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::panels::ContextItem;
+    use crate::state::message::test_helpers::MessageBuilder;
+
+    fn capture(panels: &[(&str, &str, u64)], messages: &[&str]) -> PromptCapture {
+        let items: Vec<ContextItem> =
+            panels.iter().map(|(id, content, ts)| ContextItem::new(*id, "File: a.rs", *content, *ts)).collect();
+        let msgs: Vec<_> = messages.iter().map(|m| MessageBuilder::user(m).build()).collect();
+        let api = crate::app::prompt_builder::assemble_prompt(&msgs, &items, false, Some("be brief"));
+        describe(&api, "be brief", &[])
+    }
+
+    #[test]
+    fn blocks_follow_the_assembled_request() {
+        let capture = capture(&[("P7", "fn a() {}", 1), ("P8", "fn b() {}", 2)], &["hello"]);
+        let kinds: Vec<BlockKind> = capture.blocks.iter().map(|b| b.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                BlockKind::System,
+                BlockKind::Tools,
+                BlockKind::Panel,
+                BlockKind::Panel,
+                BlockKind::PanelFooter,
+                BlockKind::Seed,
+                BlockKind::Conversation,
+            ]
+        );
+        assert_eq!(capture.blocks[2].key, "P7");
+        assert_eq!(capture.blocks[2].label, "[P7] File: a.rs");
+        assert_eq!(capture.blocks[6].messages, 1);
+        // Two panels: breakpoints at 25/50% land on the first, 75/100% on the second
+        assert!(capture.blocks[2].cache_breakpoint && capture.blocks[3].cache_breakpoint);
+    }
+
+    #[test]
+    fn diff_reports_changes_and_the_cached_prefix() {
+        let before = capture(&[("P7", "fn a() {}", 1), ("P8", "fn b() {}", 2)], &["hello"]);
+        let after = capture(&[("P7", "fn a() {}", 1), ("P9", "fn c() {}", 3)], &["hello", "more"]);
+        let (changes, common_prefix) = diff(&before, &after);
+        let find = |key: &str| changes.iter().find(|(_, b, _)| b.key == key).map(|(c, _, _)| *c);
+        assert_eq!(find("P7"), Some(BlockChange::Unchanged));
+        assert_eq!(find("P9"), Some(BlockChange::Added));
+        assert_eq!(find("P8"), Some(BlockChange::Removed));
+        assert_eq!(find("conversation"), Some(BlockChange::Changed));
+        // system, tools and P7 are identical and in place
+        assert_eq!(common_prefix, 3);
+    }
+}
@@ -14,19 +14,6 @@ use cp_base::llm_types::StreamEvent;
 
 use super::tools;
 
-/// Resolve the secondary model string from provider + model enum.
-fn secondary_model_string(state: &State) -> String {
-    use cp_base::llm_types::{LlmProvider, ModelInfo};
-    match state.secondary_provider {
-        LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey => {
-            state.secondary_anthropic_model.api_name().to_string()
-        }
-        LlmProvider::Grok => state.secondary_grok_model.api_name().to_string(),
-        LlmProvider::Groq => state.secondary_groq_model.api_name().to_string(),
-        LlmProvider::DeepSeek => state.secondary_deepseek_model.api_name().to_string(),
-    }
-}
-
 /// The reverie system prompt — kept minimal since the real agent instructions
 /// are injected into the P-reverie panel (for cache-friendly placement).
 const REVERIE_SYSTEM_PROMPT: &str = "You are a background sub-agent. Follow the instructions in the P-reverie panel.";
@@ -63,14 +50,17 @@ pub fn start_reverie_stream(state: &mut State, tx: Sender<StreamEvent>) {
     start_streaming(
         StreamParams {
             provider: state.secondary_provider,
-            model: secondary_model_string(state),
+            model: state.secondary_model(),
             max_output_tokens: state.secondary_max_output_tokens(),
+            thinking_budget: None,
             messages: ctx.messages,
             context_items: ctx.context_items,
             tools: ctx.tools,
             system_prompt: REVERIE_SYSTEM_PROMPT.to_string(),
             seed_content: Some(REVERIE_SYSTEM_PROMPT.to_string()),
             worker_id: DEFAULT_WORKER_ID.to_string(),
+            fallbacks: Vec::new(),
+            inspect: false,
         },
         tx,
     );
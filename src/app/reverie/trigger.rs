@@ -1,7 +1,8 @@
 //! Reverie trigger system — threshold detection and optimize_context tool.
 //!
 //! Two trigger paths:
-//! 1. **Automatic**: context tokens exceed cleaning threshold → fires reverie
+//! 1. **Automatic**: context tokens exceed cleaning threshold → the deterministic
+//!    eviction policy runs first, and the reverie fires only if that wasn't enough
 //! 2. **Manual**: main AI calls `optimize_context` tool → fires reverie with directive
 
 use crate::state::State;
@@ -34,6 +35,23 @@ pub fn check_threshold_trigger(state: &mut State) -> bool {
         return false;
     }
 
+    // Evict by pin/priority/recency first — cheap, predictable, no LLM call
+    let evicted = crate::app::eviction::evict_to_target(state);
+    if !evicted.is_empty() {
+        let nid = cp_mod_spine::SpineState::create_notification(
+            state,
+            cp_mod_spine::NotificationType::Custom,
+            "Eviction".to_string(),
+            format!("Context over threshold, freed panels:\n{}", evicted.join("\n")),
+        );
+        cp_mod_spine::SpineState::mark_notification_processed(state, &nid);
+        state.dirty = true;
+    }
+    let total_tokens: usize = state.context.iter().map(|c| c.token_count).sum();
+    if total_tokens <= threshold {
+        return false;
+    }
+
     // Threshold breached — fire the reverie
     let pct = (total_tokens as f64 / state.effective_context_budget() as f64 * 100.0) as usize;
 
@@ -2,6 +2,10 @@ use crossterm::event;
 
 use crate::app::actions::Action;
 use crate::infra::tools::perform_reload;
+use crate::ui::help::{
+    FORK_AT_PREFIX, INSPECT_PROMPT_PREFIX, PREVIEW_CHECKPOINT_PREFIX, RESTORE_CHECKPOINT_PREFIX,
+    RESTORE_SNAPSHOT_PREFIX, SWITCH_BRANCH_PANELS_PREFIX, SWITCH_BRANCH_PREFIX,
+};
 
 use crate::app::App;
 
@@ -187,11 +191,9 @@ impl App {
             KeyCode::Backspace if typing_other => {
                 form.backspace();
             }
-            KeyCode::Char(c) if typing_other => {
-                // Don't capture ctrl+key combos
-                if !key.modifiers.contains(KeyModifiers::CONTROL) {
-                    form.type_char(c);
-                }
+            // Don't capture ctrl+key combos
+            KeyCode::Char(c) if typing_other && !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                form.type_char(c);
             }
             // Non-typing-other: any char that's not space does nothing
             _ => {}
@@ -227,6 +229,35 @@ impl App {
                             return None; // Won't reach here, but needed for type system
                         }
                         "config" => return Some(Action::ToggleConfigView),
+                        "snapshot" => return Some(Action::SnapshotContext),
+                        _ if id.starts_with(RESTORE_SNAPSHOT_PREFIX) => {
+                            let name = id[RESTORE_SNAPSHOT_PREFIX.len()..].to_string();
+                            return Some(Action::RestoreSnapshot(name));
+                        }
+                        _ if id.starts_with(FORK_AT_PREFIX) => {
+                            return Some(Action::ForkConversation(id[FORK_AT_PREFIX.len()..].to_string()));
+                        }
+                        _ if id.starts_with(SWITCH_BRANCH_PANELS_PREFIX) => {
+                            let name = id[SWITCH_BRANCH_PANELS_PREFIX.len()..].to_string();
+                            return Some(Action::SwitchBranch(name, true));
+                        }
+                        _ if id.starts_with(SWITCH_BRANCH_PREFIX) => {
+                            let name = id[SWITCH_BRANCH_PREFIX.len()..].to_string();
+                            return Some(Action::SwitchBranch(name, false));
+                        }
+                        _ if id.starts_with(INSPECT_PROMPT_PREFIX) => {
+                            return Some(Action::OpenPromptInspector(id[INSPECT_PROMPT_PREFIX.len()..].to_string()));
+                        }
+                        "checkpoint" => return Some(Action::CreateCheckpoint),
+                        "undo-turn" => return Some(Action::UndoLastTurn),
+                        _ if id.starts_with(PREVIEW_CHECKPOINT_PREFIX) => {
+                            let cp_id = id[PREVIEW_CHECKPOINT_PREFIX.len()..].to_string();
+                            return Some(Action::PreviewCheckpoint(cp_id));
+                        }
+                        _ if id.starts_with(RESTORE_CHECKPOINT_PREFIX) => {
+                            let cp_id = id[RESTORE_CHECKPOINT_PREFIX.len()..].to_string();
+                            return Some(Action::RestoreCheckpoint(cp_id));
+                        }
                         _ => {
                             // Navigate to any context panel (P-prefixed or special IDs like "chat")
                             if self.state.context.iter().any(|c| c.id == id) {
@@ -266,11 +297,11 @@ impl App {
             }
             // Backspace/Delete
             KeyCode::Backspace => {
-                self.command_palette.backspace(&self.state);
+                self.command_palette.backspace();
                 None
             }
             KeyCode::Delete => {
-                self.command_palette.delete(&self.state);
+                self.command_palette.delete();
                 None
             }
             // Character input
@@ -279,7 +310,7 @@ impl App {
                 if key.modifiers.contains(KeyModifiers::CONTROL) {
                     return None;
                 }
-                self.command_palette.insert_char(c, &self.state);
+                self.command_palette.insert_char(c);
                 None
             }
             // Tab could cycle through results
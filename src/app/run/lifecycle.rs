@@ -8,8 +8,9 @@ use ratatui::prelude::*;
 use crate::app::actions::{Action, ActionResult, apply_action};
 use crate::app::events::handle_event;
 use crate::app::panels::now_ms;
-use crate::infra::api::{StreamEvent, StreamParams, start_streaming};
-use crate::infra::constants::{DEFAULT_WORKER_ID, EVENT_POLL_MS, RENDER_THROTTLE_MS};
+use crate::infra::api::{StreamEvent, start_streaming};
+use crate::infra::constants::{EVENT_POLL_MS, RENDER_THROTTLE_MS};
+use crate::llms::LlmProvider;
 use crate::state::ContextType;
 use crate::state::cache::CacheUpdate;
 use crate::state::persistence::{check_ownership, save_state};
@@ -158,10 +159,13 @@ impl App {
                 self.sync_gh_watches();
             }
             self.check_timer_based_deprecation();
+            // Record the turn checkpoint once its background git snapshot is done
+            crate::app::checkpoints::finish_turn_checkpoint(false);
             self.handle_tool_execution(&tx);
             self.finalize_stream();
             self.check_spine(&tx);
             self.process_api_check_results();
+            self.process_local_model_discovery();
 
             // === REVERIE (CONTEXT OPTIMIZER SUB-AGENT) ===
             // Check if a reverie needs to start streaming (state.reverie exists but no stream yet)
@@ -173,6 +177,11 @@ impl App {
             // Check if reverie ended without calling Report (auto-relaunch guard rail)
             self.check_reverie_end_turn();
 
+            // === HISTORY SUMMARIES ===
+            // Condense detached history chunks with the secondary model, one at a time
+            self.maybe_start_history_summary();
+            self.process_history_summary_events();
+
             // Check ownership periodically (every 1 second)
             if current_ms.saturating_sub(self.last_ownership_check_ms) >= 1000 {
                 self.last_ownership_check_ms = current_ms;
@@ -210,7 +219,17 @@ impl App {
     fn handle_action(&mut self, action: Action, tx: &Sender<StreamEvent>) {
         // Any action triggers a re-render
         self.state.dirty = true;
-        match apply_action(&mut self.state, action) {
+        let refresh_local_models = matches!(
+            action,
+            Action::ToggleConfigView
+                | Action::ConfigSelectProvider(LlmProvider::Local)
+                | Action::ConfigSelectSecondaryProvider(LlmProvider::Local)
+        );
+        let result = apply_action(&mut self.state, action);
+        if refresh_local_models {
+            self.maybe_discover_local_models();
+        }
+        match result {
             ActionResult::StopStream => {
                 self.typewriter.reset();
                 self.pending_done = None;
@@ -290,21 +309,7 @@ impl App {
                     self.typewriter.reset();
                     self.pending_tools.clear();
                     let ctx = prepare_stream_context(&mut self.state, false, None);
-                    let system_prompt = get_active_agent_content(&self.state);
-                    start_streaming(
-                        StreamParams {
-                            provider: self.state.llm_provider,
-                            model: self.state.current_model(),
-                            max_output_tokens: self.state.current_max_output_tokens(),
-                            messages: ctx.messages,
-                            context_items: ctx.context_items,
-                            tools: ctx.tools,
-                            system_prompt: system_prompt.clone(),
-                            seed_content: Some(system_prompt),
-                            worker_id: DEFAULT_WORKER_ID.to_string(),
-                        },
-                        tx.clone(),
-                    );
+                    start_streaming(self.main_stream_params(ctx, get_active_agent_content(&self.state)), tx.clone());
                     self.save_state_async();
                     self.state.dirty = true;
                 }
@@ -349,3 +354,96 @@ impl App {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::mpsc;
+    use std::time::Instant;
+
+    use super::*;
+    use crate::app::{ensure_default_agent, ensure_default_contexts};
+    use crate::state::MessageType;
+    use crate::state::persistence::load_state;
+
+    /// Runs the app from an empty project directory (it persists under
+    /// `./.context-pilot`) and goes back to the original one on drop
+    struct TempProject {
+        previous: PathBuf,
+        dir: PathBuf,
+    }
+
+    impl TempProject {
+        fn enter(name: &str) -> Self {
+            let previous = std::env::current_dir().unwrap();
+            let dir = std::env::temp_dir().join(format!("cp_{}_{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(dir.join(".context-pilot")).unwrap();
+            std::env::set_current_dir(&dir).unwrap();
+            Self { previous, dir }
+        }
+    }
+
+    impl Drop for TempProject {
+        fn drop(&mut self) {
+            let _ = std::env::set_current_dir(&self.previous);
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    /// The background part of one `run()` loop iteration that drives a turn
+    fn tick(app: &mut App, tx: &Sender<StreamEvent>, rx: &Receiver<StreamEvent>, cache_rx: &Receiver<CacheUpdate>) {
+        app.process_stream_events(rx);
+        app.handle_retry(tx);
+        app.process_typewriter();
+        app.process_cache_updates(cache_rx);
+        app.check_waiting_for_panels(tx);
+        app.handle_tool_execution(tx);
+        app.finalize_stream();
+    }
+
+    #[test]
+    fn replayed_tool_turn_runs_through_the_pipeline() {
+        let _project = TempProject::enter("replay_pipeline");
+        std::fs::write("notes.txt", "remember the milk\n").unwrap();
+        std::fs::write(
+            ".context-pilot/cassette.jsonl",
+            concat!(
+                r#"{"events":[{"type":"text","text":"Opening it."},{"type":"tool_use","id":"t1","name":"Open","input":{"path":"notes.txt"}},{"type":"done","input_tokens":10,"output_tokens":5,"stop_reason":"tool_use"}]}"#,
+                "\n",
+                r#"{"events":[{"type":"text","text":"It says to buy milk."},{"type":"done","input_tokens":20,"output_tokens":6,"stop_reason":"end_turn"}]}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+
+        let mut state = load_state();
+        crate::modules::init_registry();
+        ensure_default_contexts(&mut state);
+        ensure_default_agent(&mut state);
+        state.llm_provider = LlmProvider::Replay;
+        let (tx, rx) = mpsc::channel::<StreamEvent>();
+        let (cache_tx, cache_rx) = mpsc::channel::<CacheUpdate>();
+        let mut app = App::new(state, cache_tx, false);
+
+        app.state.input = "What does notes.txt say?".to_string();
+        app.handle_action(Action::InputSubmit, &tx);
+        assert!(app.state.is_streaming);
+        let started = Instant::now();
+        while app.state.is_streaming && started.elapsed() < Duration::from_secs(10) {
+            tick(&mut app, &tx, &rx, &cache_rx);
+            std::thread::sleep(Duration::from_millis(2));
+        }
+        assert!(!app.state.is_streaming, "turn did not finish");
+
+        let turn: Vec<_> = app.state.messages.iter().skip_while(|m| m.role != "user").collect();
+        assert_eq!(turn.len(), 5, "{:#?}", turn);
+        assert_eq!(turn[1].content, "Opening it.");
+        assert_eq!(turn[2].message_type, MessageType::ToolCall);
+        assert_eq!(turn[2].tool_uses[0].name, "Open");
+        assert_eq!(turn[3].message_type, MessageType::ToolResult);
+        assert!(!turn[3].tool_results[0].is_error, "{}", turn[3].tool_results[0].content);
+        assert_eq!(turn[4].content, "It says to buy milk.");
+        assert!(app.state.context.iter().any(|c| c.get_meta_str("file_path") == Some("notes.txt")));
+    }
+}
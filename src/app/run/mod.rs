@@ -2,6 +2,7 @@ mod input;
 mod lifecycle;
 mod reverie;
 mod streaming;
+mod summarize;
 mod tool_cleanup;
 mod tool_pipeline;
 mod watchers;
@@ -5,6 +5,8 @@ use std::sync::mpsc;
 use crate::app::App;
 use crate::app::reverie::{streaming, tools};
 use crate::infra::api::StreamEvent;
+use crate::infra::constants::DEFAULT_WORKER_ID;
+use crate::state::persistence::ledger::{self, Usage};
 use crate::state::persistence::save_state;
 
 impl App {
@@ -18,6 +20,12 @@ impl App {
             return;
         }
 
+        // Defer while the secondary provider's rate-limit budget is nearly spent:
+        // what's left belongs to the main worker. The reverie starts once it refills.
+        if self.state.rate_limit_low(self.state.secondary_provider) {
+            return;
+        }
+
         let (tx, rx) = mpsc::channel();
         streaming::start_reverie_stream(&mut self.state, tx);
         self.reverie_stream = Some(super::super::ReverieStream { rx, pending_tools: Vec::new(), report_called: false });
@@ -51,6 +59,8 @@ impl App {
                                 timestamp_ms: crate::app::panels::now_ms(),
                                 tool_uses: Vec::new(),
                                 tool_results: Vec::new(),
+                                thinking_blocks: Vec::new(),
+                                thinking_token_count: 0,
                             });
                         }
                         if let Some(msg) = rev.messages.last_mut() {
@@ -58,6 +68,15 @@ impl App {
                         }
                     }
                 }
+                StreamEvent::Thinking { .. } | StreamEvent::RedactedThinking { .. } => {
+                    // Reverie runs without a thinking budget
+                }
+                StreamEvent::Failover { .. } => {
+                    // Reverie streams have no failover chain
+                }
+                StreamEvent::RateLimit(info) => {
+                    self.state.record_rate_limit(info);
+                }
                 StreamEvent::ToolUse(tool) => {
                     // Queue the tool for dispatch
                     if let Some(stream) = self.reverie_stream.as_mut() {
@@ -65,12 +84,20 @@ impl App {
                     }
                 }
                 StreamEvent::Done {
-                    input_tokens: _,
-                    output_tokens: _,
-                    cache_hit_tokens: _,
-                    cache_miss_tokens: _,
+                    input_tokens,
+                    output_tokens,
+                    cache_hit_tokens,
+                    cache_miss_tokens,
                     stop_reason: _,
                 } => {
+                    ledger::record(
+                        &self.state,
+                        self.state.secondary_provider,
+                        &self.state.secondary_model(),
+                        DEFAULT_WORKER_ID,
+                        true,
+                        Usage { input_tokens, output_tokens, cache_hit_tokens, cache_miss_tokens },
+                    );
                     // Mark assistant message as complete
                     if let Some(rev) = self.state.reverie.as_mut() {
                         if let Some(msg) = rev.messages.last_mut() {
@@ -178,6 +205,8 @@ impl App {
                         input: tool.input.clone(),
                     }],
                     tool_results: Vec::new(),
+                    thinking_blocks: Vec::new(),
+                    thinking_token_count: 0,
                 });
                 // Add tool result as a new message
                 // NOTE: message_type MUST be ToolResult so assemble_prompt()
@@ -199,6 +228,8 @@ impl App {
                         content: result.content.clone(),
                         is_error: result.is_error,
                     }],
+                    thinking_blocks: Vec::new(),
+                    thinking_token_count: 0,
                 });
             }
             tool_results.push(result);
@@ -282,6 +313,8 @@ impl App {
                 timestamp_ms: crate::app::panels::now_ms(),
                 tool_uses: Vec::new(),
                 tool_results: Vec::new(),
+                thinking_blocks: Vec::new(),
+                thinking_token_count: 0,
             });
         }
 
@@ -3,9 +3,12 @@ use std::sync::mpsc::{Receiver, Sender};
 use crate::app::actions::{Action, ActionResult, apply_action};
 use crate::infra::api::{StreamEvent, StreamParams, start_streaming};
 use crate::infra::constants::{DEFAULT_WORKER_ID, MAX_API_RETRIES};
+use crate::llms::LlmProvider;
+use crate::state::ThinkingBlock;
+use crate::state::persistence::ledger::{self, Usage};
 
 use crate::app::App;
-use crate::app::context::{get_active_agent_content, prepare_stream_context};
+use crate::app::context::{StreamContext, get_active_agent_content, prepare_stream_context};
 
 impl App {
     pub(super) fn process_stream_events(&mut self, rx: &Receiver<StreamEvent>) {
@@ -22,20 +25,52 @@ impl App {
                 StreamEvent::ToolUse(tool) => {
                     self.pending_tools.push(tool);
                 }
+                StreamEvent::Thinking { thinking, signature } => {
+                    apply_action(
+                        &mut self.state,
+                        Action::AppendThinking(ThinkingBlock::Thinking { thinking, signature }),
+                    );
+                }
+                StreamEvent::RedactedThinking { data } => {
+                    apply_action(&mut self.state, Action::AppendThinking(ThinkingBlock::RedactedThinking { data }));
+                }
                 StreamEvent::Done { input_tokens, output_tokens, cache_hit_tokens, cache_miss_tokens, stop_reason } => {
+                    let billed = crate::llms::billed_prompt_tokens(
+                        self.state.stream_target().provider,
+                        input_tokens,
+                        cache_hit_tokens,
+                        cache_miss_tokens,
+                    );
+                    cp_base::tokenizer::calibrate(self.state.prompt_token_estimate, billed);
+                    let target = self.state.stream_target();
+                    ledger::record(
+                        &self.state,
+                        target.provider,
+                        &target.model,
+                        DEFAULT_WORKER_ID,
+                        false,
+                        Usage { input_tokens, output_tokens, cache_hit_tokens, cache_miss_tokens },
+                    );
                     self.typewriter.mark_done();
                     self.pending_done =
                         Some((input_tokens, output_tokens, cache_hit_tokens, cache_miss_tokens, stop_reason));
                     // API call succeeded — reset retry counter immediately at tick level
                     self.state.api_retry_count = 0;
                 }
+                StreamEvent::Failover { fallback, provider, model, error } => {
+                    self.handle_failover(fallback, provider, model, error);
+                }
+                StreamEvent::RateLimit(info) => {
+                    self.state.record_rate_limit(info);
+                }
                 StreamEvent::Error(e) => {
                     self.typewriter.reset();
                     // Log every error to disk for debugging
                     let attempt = self.state.api_retry_count + 1;
                     let will_retry = attempt <= MAX_API_RETRIES;
-                    let provider = format!("{:?}", self.state.llm_provider);
-                    let model = self.state.current_model();
+                    let target = self.state.stream_target();
+                    let provider = format!("{:?}", target.provider);
+                    let model = target.model;
                     let log_msg = format!(
                         "Attempt {}/{} ({})\n\
                          Provider: {} | Model: {}\n\
@@ -68,7 +103,46 @@ impl App {
         }
     }
 
+    /// The stream thread gave up on the current model and re-sent the request to a
+    /// failover model: drop the failed attempt's output and route later streams there.
+    fn handle_failover(&mut self, fallback: usize, provider: LlmProvider, model: String, error: String) {
+        let failed = self.state.stream_target();
+        crate::state::persistence::log_error(&format!(
+            "Failover\n\
+             Provider: {:?} | Model: {}\n\
+             Switching to: {:?} | Model: {}\n\n\
+             {}\n",
+            failed.provider, failed.model, provider, model, error
+        ));
+
+        self.typewriter.reset();
+        self.pending_tools.clear();
+        self.pending_done = None;
+        if let Some(msg) = self.state.messages.last_mut()
+            && msg.role == "assistant"
+        {
+            msg.content.clear();
+            msg.thinking_blocks.clear();
+            msg.thinking_token_count = 0;
+        }
+        self.state.active_fallback = Some(fallback);
+        self.state.api_retry_count = 0;
+
+        let nid = cp_mod_spine::SpineState::create_notification(
+            &mut self.state,
+            cp_mod_spine::NotificationType::Custom,
+            "failover".to_string(),
+            format!("{:?} {} failed ({}); switched to {:?} {}", failed.provider, failed.model, error, provider, model),
+        );
+        // A record of the switch, not something to auto-continue on
+        cp_mod_spine::SpineState::mark_notification_processed(&mut self.state, &nid);
+    }
+
     pub(super) fn handle_retry(&mut self, tx: &Sender<StreamEvent>) {
+        // Hold the retry for as long as the server asked (retry-after / budget reset)
+        if self.pending_retry_error.is_some() && self.state.retry_wait_ms(self.state.stream_target().provider) > 0 {
+            return;
+        }
         if let Some(_error) = self.pending_retry_error.take() {
             // Still streaming, retry the request
             if self.state.is_streaming {
@@ -77,25 +151,13 @@ impl App {
                     && msg.role == "assistant"
                 {
                     msg.content.clear();
+                    msg.thinking_blocks.clear();
+                    msg.thinking_token_count = 0;
                 }
                 let ctx = prepare_stream_context(&mut self.state, true, None);
-                let system_prompt = get_active_agent_content(&self.state);
                 self.typewriter.reset();
                 self.pending_done = None;
-                start_streaming(
-                    StreamParams {
-                        provider: self.state.llm_provider,
-                        model: self.state.current_model(),
-                        max_output_tokens: self.state.current_max_output_tokens(),
-                        messages: ctx.messages,
-                        context_items: ctx.context_items,
-                        tools: ctx.tools,
-                        system_prompt: system_prompt.clone(),
-                        seed_content: Some(system_prompt),
-                        worker_id: DEFAULT_WORKER_ID.to_string(),
-                    },
-                    tx.clone(),
-                );
+                start_streaming(self.main_stream_params(ctx, get_active_agent_content(&self.state)), tx.clone());
                 self.state.dirty = true;
             }
         }
@@ -123,27 +185,89 @@ impl App {
         }
     }
 
+    /// Query the local server for its models while the config overlay shows the Local provider.
+    pub(super) fn maybe_discover_local_models(&mut self) {
+        let uses_local =
+            self.state.llm_provider == LlmProvider::Local || self.state.secondary_provider == LlmProvider::Local;
+        if !self.state.config_view || !uses_local || self.local_models_rx.is_some() {
+            return;
+        }
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.local_models_rx = Some(rx);
+        self.state.local_models_error = None;
+        crate::llms::local::start_model_discovery(tx);
+    }
+
+    pub(super) fn process_local_model_discovery(&mut self) {
+        let Some(rx) = &self.local_models_rx else { return };
+        let Ok(result) = rx.try_recv() else { return };
+        self.local_models_rx = None;
+        self.state.dirty = true;
+        match result {
+            Ok(models) if models.is_empty() => {
+                self.state.local_models.clear();
+                self.state.local_models_error = Some("Server is running but has no models".to_string());
+            }
+            Ok(models) => {
+                self.state.local_models_error = None;
+                // Default empty selections to the first discovered model
+                let first = models[0].clone();
+                let mut changed = false;
+                if self.state.local_model.is_empty() {
+                    self.state.local_model = first.clone();
+                    changed = true;
+                }
+                if self.state.secondary_local_model.is_empty() {
+                    self.state.secondary_local_model = first;
+                    changed = true;
+                }
+                self.state.local_models = models;
+                if changed && self.state.llm_provider == LlmProvider::Local {
+                    let (api_tx, api_rx) = std::sync::mpsc::channel();
+                    self.api_check_rx = Some(api_rx);
+                    self.state.api_check_in_progress = true;
+                    crate::llms::start_api_check(self.state.llm_provider, self.state.current_model(), api_tx);
+                }
+                if changed {
+                    self.save_state_async();
+                }
+            }
+            Err(e) => {
+                self.state.local_models.clear();
+                self.state.local_models_error = Some(e);
+            }
+        }
+    }
+
     /// Continue streaming after tool execution (called when panels are ready).
     pub(super) fn continue_streaming(&mut self, tx: &Sender<StreamEvent>) {
         self.state.is_tooling = false;
         let ctx = prepare_stream_context(&mut self.state, true, None);
-        let system_prompt = get_active_agent_content(&self.state);
         self.typewriter.reset();
         self.pending_done = None;
-        start_streaming(
-            StreamParams {
-                provider: self.state.llm_provider,
-                model: self.state.current_model(),
-                max_output_tokens: self.state.current_max_output_tokens(),
-                messages: ctx.messages,
-                context_items: ctx.context_items,
-                tools: ctx.tools,
-                system_prompt: system_prompt.clone(),
-                seed_content: Some(system_prompt),
-                worker_id: DEFAULT_WORKER_ID.to_string(),
-            },
-            tx.clone(),
-        );
+        start_streaming(self.main_stream_params(ctx, get_active_agent_content(&self.state)), tx.clone());
+    }
+
+    /// Stream parameters for the main worker, aimed at the failover model if one is
+    /// standing in. The rest of the failover chain is attached on the last retry only.
+    pub(super) fn main_stream_params(&self, ctx: StreamContext, system_prompt: String) -> StreamParams {
+        let target = self.state.stream_target();
+        let fallbacks =
+            if self.state.api_retry_count >= MAX_API_RETRIES { self.state.remaining_fallbacks() } else { Vec::new() };
+        StreamParams {
+            provider: target.provider,
+            model: target.model,
+            max_output_tokens: target.max_output_tokens,
+            thinking_budget: target.thinking_budget,
+            messages: ctx.messages,
+            context_items: ctx.context_items,
+            tools: ctx.tools,
+            system_prompt: system_prompt.clone(),
+            seed_content: Some(system_prompt),
+            worker_id: DEFAULT_WORKER_ID.to_string(),
+            fallbacks,
+            inspect: true,
+        }
     }
 
     pub(super) fn finalize_stream(&mut self) {
@@ -172,6 +296,10 @@ impl App {
         {
             self.state.dirty = true;
             let stop_reason = stop_reason.clone();
+            if let Some(text) = self.state.messages.last().filter(|m| m.role == "assistant").map(|m| m.content.clone())
+            {
+                crate::app::eviction::note_references(&mut self.state, &text);
+            }
             match apply_action(
                 &mut self.state,
                 Action::StreamDone {
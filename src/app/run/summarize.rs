@@ -0,0 +1,154 @@
+//! History summaries — condenses detached conversation chunks in the background.
+//!
+//! Runs on the secondary model like the reverie, but as a single tool-less
+//! request per chunk. The summary lands in the panel's metadata; the verbatim
+//! messages stay on disk so `Expand_conversation_history` can bring them back.
+
+use std::sync::mpsc;
+
+use crate::app::App;
+use crate::app::panels::now_ms;
+use crate::infra::api::{StreamEvent, StreamParams, start_streaming};
+use crate::modules::conversation_history::summary;
+use crate::state::persistence::ledger::{self, Usage};
+use crate::state::{Message, MessageStatus, MessageType};
+
+/// Worker name recorded in the cost ledger for summary requests
+const SUMMARIZER_WORKER: &str = "history_summarizer";
+
+const SUMMARIZER_SYSTEM_PROMPT: &str = "You write concise, factual summaries of archived coding conversations.";
+
+impl App {
+    /// Start summarizing the oldest unsummarized history chunk, if enabled and idle.
+    pub(super) fn maybe_start_history_summary(&mut self) {
+        if !self.state.summarize_history || self.history_summary.is_some() || self.reverie_stream.is_some() {
+            return;
+        }
+        // Summaries are optional — never spend the last of the secondary provider's budget on them
+        if self.state.rate_limit_low(self.state.secondary_provider) {
+            return;
+        }
+        let Some((panel_uid, prompt)) = self.state.context.iter().find(|c| summary::needs_summary(c)).and_then(|c| {
+            let uid = c.uid.clone()?;
+            Some((uid, summary::summarization_prompt(c.history_messages.as_deref()?)))
+        }) else {
+            return;
+        };
+
+        let request = Message {
+            id: "U1".to_string(),
+            uid: None,
+            role: "user".to_string(),
+            message_type: MessageType::TextMessage,
+            content: prompt,
+            content_token_count: 0,
+            status: MessageStatus::Full,
+            tool_uses: Vec::new(),
+            tool_results: Vec::new(),
+            thinking_blocks: Vec::new(),
+            thinking_token_count: 0,
+            input_tokens: 0,
+            timestamp_ms: now_ms(),
+        };
+
+        let (tx, rx) = mpsc::channel();
+        start_streaming(
+            StreamParams {
+                provider: self.state.secondary_provider,
+                model: self.state.secondary_model(),
+                max_output_tokens: self.state.secondary_max_output_tokens(),
+                thinking_budget: None,
+                messages: vec![request],
+                context_items: Vec::new(),
+                tools: Vec::new(),
+                system_prompt: SUMMARIZER_SYSTEM_PROMPT.to_string(),
+                seed_content: None,
+                worker_id: SUMMARIZER_WORKER.to_string(),
+                fallbacks: Vec::new(),
+                inspect: false,
+            },
+            tx,
+        );
+        self.history_summary = Some(super::super::HistorySummaryStream { rx, panel_uid, text: String::new() });
+    }
+
+    /// Poll the summary stream; on completion store the summary and swap the panel to it.
+    pub(super) fn process_history_summary_events(&mut self) {
+        let events: Vec<StreamEvent> = match self.history_summary.as_ref() {
+            Some(s) => s.rx.try_iter().collect(),
+            None => return,
+        };
+
+        for evt in events {
+            match evt {
+                StreamEvent::Chunk(text) => {
+                    if let Some(stream) = self.history_summary.as_mut() {
+                        stream.text.push_str(&text);
+                    }
+                }
+                StreamEvent::RateLimit(info) => self.state.record_rate_limit(info),
+                StreamEvent::Thinking { .. }
+                | StreamEvent::RedactedThinking { .. }
+                | StreamEvent::ToolUse(_)
+                | StreamEvent::Failover { .. } => {
+                    // No thinking budget, tools or failover chain on summary requests
+                }
+                StreamEvent::Done { input_tokens, output_tokens, cache_hit_tokens, cache_miss_tokens, .. } => {
+                    ledger::record(
+                        &self.state,
+                        self.state.secondary_provider,
+                        &self.state.secondary_model(),
+                        SUMMARIZER_WORKER,
+                        true,
+                        Usage { input_tokens, output_tokens, cache_hit_tokens, cache_miss_tokens },
+                    );
+                    if let Some(stream) = self.history_summary.take() {
+                        let text = stream.text.trim().to_string();
+                        if text.is_empty() {
+                            self.fail_history_summary(&stream.panel_uid, "empty response");
+                        } else {
+                            self.apply_history_summary(&stream.panel_uid, &text);
+                        }
+                    }
+                    return;
+                }
+                StreamEvent::Error(e) => {
+                    if let Some(stream) = self.history_summary.take() {
+                        self.fail_history_summary(&stream.panel_uid, &e);
+                    }
+                    return;
+                }
+            }
+        }
+    }
+
+    fn apply_history_summary(&mut self, panel_uid: &str, text: &str) {
+        // The panel may have been closed while the summary was streaming
+        let Some(ctx) = self.state.context.iter_mut().find(|c| c.uid.as_deref() == Some(panel_uid)) else {
+            return;
+        };
+        ctx.set_meta(summary::SUMMARY_META, &text);
+        summary::refresh_content(ctx);
+        // Content changed: sort to the end so earlier panels keep their prompt cache hits
+        ctx.last_refresh_ms = now_ms();
+        self.state.dirty = true;
+        self.save_state_async();
+    }
+
+    fn fail_history_summary(&mut self, panel_uid: &str, error: &str) {
+        let Some(ctx) = self.state.context.iter_mut().find(|c| c.uid.as_deref() == Some(panel_uid)) else {
+            return;
+        };
+        // Keep the verbatim chunk and don't retry it on every tick
+        ctx.set_meta(summary::FAILED_META, &true);
+        let message = format!("Could not summarize {}: {}", ctx.id, error);
+        let nid = cp_mod_spine::SpineState::create_notification(
+            &mut self.state,
+            cp_mod_spine::NotificationType::Custom,
+            "history".to_string(),
+            message,
+        );
+        cp_mod_spine::SpineState::mark_notification_processed(&mut self.state, &nid);
+        self.save_state_async();
+    }
+}
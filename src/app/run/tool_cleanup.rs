@@ -208,6 +208,8 @@ impl App {
             status: MessageStatus::Full,
             tool_uses: Vec::new(),
             tool_results: tool_result_records,
+            thinking_blocks: Vec::new(),
+            thinking_token_count: 0,
             input_tokens: 0,
             timestamp_ms: crate::app::panels::now_ms(),
         };
@@ -234,6 +236,8 @@ impl App {
             status: MessageStatus::Full,
             tool_uses: Vec::new(),
             tool_results: Vec::new(),
+            thinking_blocks: Vec::new(),
+            thinking_token_count: 0,
             input_tokens: 0,
             timestamp_ms: crate::app::panels::now_ms(),
         };
@@ -243,15 +247,7 @@ impl App {
 
         // Accumulate token stats from intermediate stream
         if let Some((_, output_tokens, cache_hit_tokens, cache_miss_tokens, _)) = self.pending_done {
-            self.state.tick_cache_hit_tokens = cache_hit_tokens;
-            self.state.tick_cache_miss_tokens = cache_miss_tokens;
-            self.state.tick_output_tokens = output_tokens;
-            self.state.stream_cache_hit_tokens += cache_hit_tokens;
-            self.state.stream_cache_miss_tokens += cache_miss_tokens;
-            self.state.stream_output_tokens += output_tokens;
-            self.state.cache_hit_tokens += cache_hit_tokens;
-            self.state.cache_miss_tokens += cache_miss_tokens;
-            self.state.total_output_tokens += output_tokens;
+            self.state.record_usage(cache_hit_tokens, cache_miss_tokens, output_tokens);
         }
 
         self.save_state_async();
@@ -327,6 +323,8 @@ impl App {
             status: MessageStatus::Full,
             tool_uses: Vec::new(),
             tool_results: tool_result_records,
+            thinking_blocks: Vec::new(),
+            thinking_token_count: 0,
             input_tokens: 0,
             timestamp_ms: now_ms(),
         };
@@ -71,6 +71,8 @@ impl App {
             return;
         }
         let _guard = crate::profile!("app::tool_exec");
+        // The turn checkpoint must not catch this turn's own edits
+        crate::app::checkpoints::finish_turn_checkpoint(true);
 
         self.state.dirty = true;
         self.state.is_tooling = true;
@@ -85,6 +87,8 @@ impl App {
             msg.content = clean_llm_id_prefix(&msg.content);
             let op = build_message_op(msg);
             self.writer.send_message(op);
+            let text = msg.content.clone();
+            crate::app::eviction::note_references(&mut self.state, &text);
         }
 
         // Create tool call messages
@@ -108,6 +112,8 @@ impl App {
                     input: tool.input.clone(),
                 }],
                 tool_results: Vec::new(),
+                thinking_blocks: Vec::new(),
+                thinking_token_count: 0,
                 input_tokens: 0,
                 timestamp_ms: now_ms(),
             };
@@ -115,6 +121,8 @@ impl App {
             self.state.messages.push(tool_msg);
 
             let result = execute_tool(tool, &mut self.state);
+            // After execution, so panels the tool just opened count as referenced too
+            crate::app::eviction::note_references(&mut self.state, &tool.input.to_string());
             tool_results.push(result);
         }
 
@@ -146,7 +154,7 @@ impl App {
 
         // === CALLBACK TRIGGER ===
         // After all tools executed, check if any file edits match active callbacks.
-        // Only collect files from SUCCESSFUL Edit/Write tools (skip failed ones).
+        // Only collect files from SUCCESSFUL file-changing tools (skip failed ones).
         let successful_tools: Vec<_> =
             tools.iter().zip(tool_results.iter()).filter(|(_, r)| !r.is_error).map(|(t, _)| t.clone()).collect();
         let changed_files = callback_trigger::collect_changed_files(&successful_tools);
@@ -157,7 +165,7 @@ impl App {
             if !skip_warnings.is_empty() {
                 let warning_note = format!("\n\n[skip_callbacks warnings: {}]", skip_warnings.join("; "));
                 for tr in tool_results.iter_mut().rev() {
-                    if tr.tool_name == "Edit" || tr.tool_name == "Write" {
+                    if callback_trigger::FILE_TOOLS.contains(&tr.tool_name.as_str()) {
                         tr.content.push_str(&warning_note);
                         break;
                     }
@@ -170,12 +178,12 @@ impl App {
                 // Fire non-blocking callbacks immediately (they run async via watchers)
                 if !async_cbs.is_empty() {
                     let summaries = callback_firing::fire_async_callbacks(&mut self.state, &async_cbs);
-                    // Append compact callback summary to the last Edit/Write tool result
+                    // Append compact callback summary to the last file tool result
                     if !summaries.is_empty() {
                         let note = format!("\nCallbacks:\n{}", summaries.join("\n"));
-                        // Find the last Edit/Write tool result and append the note
+                        // Find the last file tool result and append the note
                         for tr in tool_results.iter_mut().rev() {
-                            if tr.tool_name == "Edit" || tr.tool_name == "Write" {
+                            if callback_trigger::FILE_TOOLS.contains(&tr.tool_name.as_str()) {
                                 tr.content.push_str(&note);
                                 break;
                             }
@@ -186,7 +194,7 @@ impl App {
                 // Fire blocking callbacks — these hold the pipeline until completion.
                 // CONSTRAINT: each tool_call must have exactly 1 tool_result.
                 // We do NOT create a synthetic tool_use/tool_result pair.
-                // Instead, we tag the last Edit/Write tool result with a sentinel
+                // Instead, we tag the last file tool result with a sentinel
                 // and defer all results until the callback watcher completes.
                 if !blocking_cbs.is_empty() {
                     // Generate a unique sentinel ID for the blocking watcher
@@ -196,10 +204,10 @@ impl App {
                     let _summaries =
                         callback_firing::fire_blocking_callbacks(&mut self.state, &blocking_cbs, &sentinel_id);
 
-                    // Tag the last Edit/Write tool result with sentinel so pipeline knows to wait.
+                    // Tag the last file tool result with sentinel so pipeline knows to wait.
                     // Store original content so we can reconstruct: original + callback output.
                     for tr in tool_results.iter_mut().rev() {
-                        if tr.tool_name == "Edit" || tr.tool_name == "Write" {
+                        if callback_trigger::FILE_TOOLS.contains(&tr.tool_name.as_str()) {
                             tr.content = format!("{}{}{}", CONSOLE_WAIT_BLOCKING_SENTINEL, sentinel_id, tr.content,);
                             break;
                         }
@@ -241,6 +249,8 @@ impl App {
             status: MessageStatus::Full,
             tool_uses: Vec::new(),
             tool_results: tool_result_records,
+            thinking_blocks: Vec::new(),
+            thinking_token_count: 0,
             input_tokens: 0,
             timestamp_ms: now_ms(),
         };
@@ -268,6 +278,8 @@ impl App {
             status: MessageStatus::Full,
             tool_uses: Vec::new(),
             tool_results: Vec::new(),
+            thinking_blocks: Vec::new(),
+            thinking_token_count: 0,
             input_tokens: 0,
             timestamp_ms: now_ms(),
         };
@@ -277,15 +289,7 @@ impl App {
 
         // Accumulate token stats from intermediate stream before discarding pending_done
         if let Some((_, output_tokens, cache_hit_tokens, cache_miss_tokens, _)) = self.pending_done {
-            self.state.tick_cache_hit_tokens = cache_hit_tokens;
-            self.state.tick_cache_miss_tokens = cache_miss_tokens;
-            self.state.tick_output_tokens = output_tokens;
-            self.state.stream_cache_hit_tokens += cache_hit_tokens;
-            self.state.stream_cache_miss_tokens += cache_miss_tokens;
-            self.state.stream_output_tokens += output_tokens;
-            self.state.cache_hit_tokens += cache_hit_tokens;
-            self.state.cache_miss_tokens += cache_miss_tokens;
-            self.state.total_output_tokens += output_tokens;
+            self.state.record_usage(cache_hit_tokens, cache_miss_tokens, output_tokens);
         }
 
         self.save_state_async();
@@ -409,6 +413,8 @@ impl App {
             status: MessageStatus::Full,
             tool_uses: Vec::new(),
             tool_results: tool_result_records,
+            thinking_blocks: Vec::new(),
+            thinking_token_count: 0,
             input_tokens: 0,
             timestamp_ms: now_ms(),
         };
@@ -435,6 +441,8 @@ impl App {
             status: MessageStatus::Full,
             tool_uses: Vec::new(),
             tool_results: Vec::new(),
+            thinking_blocks: Vec::new(),
+            thinking_token_count: 0,
             input_tokens: 0,
             timestamp_ms: now_ms(),
         };
@@ -444,15 +452,7 @@ impl App {
 
         // Accumulate token stats from intermediate stream
         if let Some((_, output_tokens, cache_hit_tokens, cache_miss_tokens, _)) = self.pending_done {
-            self.state.tick_cache_hit_tokens = cache_hit_tokens;
-            self.state.tick_cache_miss_tokens = cache_miss_tokens;
-            self.state.tick_output_tokens = output_tokens;
-            self.state.stream_cache_hit_tokens += cache_hit_tokens;
-            self.state.stream_cache_miss_tokens += cache_miss_tokens;
-            self.state.stream_output_tokens += output_tokens;
-            self.state.cache_hit_tokens += cache_hit_tokens;
-            self.state.cache_miss_tokens += cache_miss_tokens;
-            self.state.total_output_tokens += output_tokens;
+            self.state.record_usage(cache_hit_tokens, cache_miss_tokens, output_tokens);
         }
 
         self.save_state_async();
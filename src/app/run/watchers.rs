@@ -1,6 +1,7 @@
 use std::sync::mpsc::Receiver;
 
-use crate::app::panels::now_ms;
+use crate::app::panels::{apply_token_budget, now_ms};
+use crate::infra::config::constants::TAIL_BUDGET_PANEL_TYPES;
 use crate::infra::watcher::WatchEvent;
 use crate::state::cache::{CacheRequest, CacheUpdate, process_cache_request};
 use crate::state::{ContextType, State};
@@ -105,6 +106,10 @@ impl App {
             let panel = crate::app::panels::get_panel(&ctx.context_type);
             // apply_cache_update calls update_if_changed which sets last_refresh_ms on change
             let _changed = panel.apply_cache_update(update, &mut ctx, state);
+            if let Some(budget) = state.panel_budget(&ctx) {
+                let keep_tail = TAIL_BUDGET_PANEL_TYPES.contains(&ctx.context_type.as_str());
+                apply_token_budget(&mut ctx, budget, keep_tail);
+            }
             ctx.cache_in_flight = false;
             state.context.insert(idx, ctx);
             state.dirty = true;
@@ -132,6 +137,7 @@ impl App {
             let (path, is_dir_event) = match event {
                 WatchEvent::FileChanged(p) => (p, false),
                 WatchEvent::DirChanged(p) => (p, true),
+                WatchEvent::TreeFile(p) => (p, false),
             };
 
             for (i, ctx) in self.state.context.iter_mut().enumerate() {
@@ -147,7 +153,7 @@ impl App {
                 }
             }
 
-            if !is_dir_event {
+            if let WatchEvent::FileChanged(path) = event {
                 rewatch_paths.push(path.clone());
             }
         }
@@ -290,8 +296,8 @@ impl App {
                         }
                     }
                     WatchSpec::DirRecursive(path) => {
-                        if !self.watched_dir_paths.contains(&path) && watcher.watch_dir_recursive(&path).is_ok() {
-                            self.watched_dir_paths.insert(path);
+                        if !self.watched_recursive_paths.contains(&path) && watcher.watch_dir_recursive(&path).is_ok() {
+                            self.watched_recursive_paths.insert(path);
                         }
                     }
                 }
@@ -102,6 +102,21 @@ pub const STORE_DIR: &str = "./.context-pilot";
 /// Messages subdirectory
 pub const MESSAGES_DIR: &str = "messages";
 
+/// Conversation branches file
+pub const BRANCHES_FILE: &str = "branches.json";
+
+/// Checkpoints file (working tree + conversation position + panels)
+pub const CHECKPOINTS_FILE: &str = "checkpoints.json";
+
+/// Private git index used to build checkpoint trees (never the user's index)
+pub const CHECKPOINT_INDEX_FILE: &str = "checkpoint.index";
+
+/// Ref namespace holding checkpoint commits, so git gc keeps their objects
+pub const CHECKPOINT_REF_PREFIX: &str = "refs/context-pilot/checkpoints/";
+
+/// Automatic (per user turn) checkpoints kept before the oldest is dropped
+pub const MAX_AUTO_CHECKPOINTS: usize = 20;
+
 /// Shared config file name (new multi-worker format)
 pub const CONFIG_FILE: &str = "config.json";
 
@@ -191,6 +206,7 @@ pub mod chars {
     pub const BLOCK_FULL: &str = "█";
     pub const BLOCK_LIGHT: &str = "░";
     pub const ARROW_RIGHT: &str = "▸";
+    pub const PIN: &str = "•";
     pub const ARROW_UP: &str = "↑";
     pub const ARROW_DOWN: &str = "↓";
     pub const CROSS: &str = "✗";
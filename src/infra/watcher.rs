@@ -14,6 +14,9 @@ pub enum WatchEvent {
     FileChanged(String),
     /// A watched directory changed (file added/removed)
     DirChanged(String),
+    /// A file somewhere under a recursively watched directory changed
+    /// (path relative to the project, e.g. "src/app/mod.rs")
+    TreeFile(String),
 }
 
 /// File watcher that monitors open files and directories
@@ -23,6 +26,8 @@ pub struct FileWatcher {
     watched_files: Arc<Mutex<HashMap<PathBuf, String>>>,
     /// Maps canonical path -> original path
     watched_dirs: Arc<Mutex<HashMap<PathBuf, String>>>,
+    /// Recursively watched roots: canonical path -> original path
+    recursive_dirs: Arc<Mutex<HashMap<PathBuf, String>>>,
     event_rx: Receiver<WatchEvent>,
 }
 
@@ -31,9 +36,11 @@ impl FileWatcher {
         let (tx, rx) = mpsc::channel();
         let watched_files: Arc<Mutex<HashMap<PathBuf, String>>> = Arc::new(Mutex::new(HashMap::new()));
         let watched_dirs: Arc<Mutex<HashMap<PathBuf, String>>> = Arc::new(Mutex::new(HashMap::new()));
+        let recursive_dirs: Arc<Mutex<HashMap<PathBuf, String>>> = Arc::new(Mutex::new(HashMap::new()));
 
         let files_clone = watched_files.clone();
         let dirs_clone = watched_dirs.clone();
+        let recursive_clone = recursive_dirs.clone();
 
         let watcher = RecommendedWatcher::new(
             move |res: Result<Event, notify::Error>| {
@@ -57,13 +64,24 @@ impl FileWatcher {
                         {
                             let _ = tx.send(WatchEvent::DirChanged(original_path.clone()));
                         }
+
+                        // Check if it's anywhere under a recursively watched directory
+                        if let Ok(roots) = recursive_clone.lock()
+                            && let Some((rel, original_root)) = roots
+                                .iter()
+                                .find_map(|(root, original)| canonical.strip_prefix(root).ok().map(|r| (r, original)))
+                            && !rel.as_os_str().is_empty()
+                        {
+                            let path = PathBuf::from(original_root).join(rel);
+                            let _ = tx.send(WatchEvent::TreeFile(path.to_string_lossy().to_string()));
+                        }
                     }
                 }
             },
             Config::default(),
         )?;
 
-        Ok(Self { watcher, watched_files, watched_dirs, event_rx: rx })
+        Ok(Self { watcher, watched_files, watched_dirs, recursive_dirs, event_rx: rx })
     }
 
     /// Watch a file for changes
@@ -104,7 +122,8 @@ impl FileWatcher {
         Ok(())
     }
 
-    /// Watch a directory recursively (for .git/refs/ subdirs)
+    /// Watch a directory recursively (for .git/refs/ subdirs, source trees).
+    /// Immediate children report `DirChanged`; every file below reports `TreeFile`.
     pub fn watch_dir_recursive(&mut self, path: &str) -> notify::Result<()> {
         let path_buf = PathBuf::from(path);
         if !path_buf.is_dir() {
@@ -113,10 +132,13 @@ impl FileWatcher {
 
         let canonical = path_buf.canonicalize().unwrap_or_else(|_| path_buf.clone());
 
-        if let Ok(mut dirs) = self.watched_dirs.lock()
-            && !dirs.contains_key(&canonical)
+        if let Ok(mut roots) = self.recursive_dirs.lock()
+            && !roots.contains_key(&canonical)
         {
-            dirs.insert(canonical.clone(), path.to_string());
+            roots.insert(canonical.clone(), path.to_string());
+            if let Ok(mut dirs) = self.watched_dirs.lock() {
+                dirs.entry(canonical.clone()).or_insert_with(|| path.to_string());
+            }
             self.watcher.watch(&canonical, RecursiveMode::Recursive)?;
         }
         Ok(())
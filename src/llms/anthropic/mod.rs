@@ -9,7 +9,10 @@ use std::io::{BufRead, BufReader};
 use std::sync::mpsc::Sender;
 
 use super::error::LlmError;
-use super::{ApiMessage, ContentBlock, LlmClient, LlmRequest, StreamEvent};
+use super::{
+    ApiMessage, ContentBlock, LlmClient, LlmProvider, LlmRequest, StreamEvent, ThinkingAccumulator, ratelimit,
+    thinking_config,
+};
 use crate::infra::constants::{API_ENDPOINT, API_VERSION, library};
 use crate::infra::tools::ToolUse;
 use crate::infra::tools::build_api_tools;
@@ -42,6 +45,8 @@ struct AnthropicRequest {
     system: String,
     messages: Vec<ApiMessage>,
     tools: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking: Option<Value>,
     stream: bool,
 }
 
@@ -51,6 +56,8 @@ struct StreamContentBlock {
     block_type: Option<String>,
     id: Option<String>,
     name: Option<String>,
+    /// Encrypted payload of a `redacted_thinking` block
+    data: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -59,6 +66,8 @@ struct StreamDelta {
     delta_type: Option<String>,
     text: Option<String>,
     partial_json: Option<String>,
+    thinking: Option<String>,
+    signature: Option<String>,
     stop_reason: Option<String>,
 }
 
@@ -134,6 +143,7 @@ impl LlmClient for AnthropicClient {
             system: system_prompt,
             messages: api_messages,
             tools: build_api_tools(&request.tools),
+            thinking: request.thinking_budget.map(thinking_config),
             stream: true,
         };
 
@@ -152,6 +162,7 @@ impl LlmClient for AnthropicClient {
             .header("content-type", "application/json")
             .json(&api_request)
             .send()?;
+        ratelimit::report(LlmProvider::Anthropic, response.headers(), &tx);
 
         if !response.status().is_success() {
             let status = response.status().as_u16();
@@ -163,6 +174,7 @@ impl LlmClient for AnthropicClient {
         let mut input_tokens = 0;
         let mut output_tokens = 0;
         let mut current_tool: Option<(String, String, String)> = None;
+        let mut thinking = ThinkingAccumulator::default();
         let mut stop_reason: Option<String> = None;
         let mut total_bytes: usize = 0;
         let mut line_count: usize = 0;
@@ -208,11 +220,22 @@ impl LlmClient for AnthropicClient {
             if let Ok(event) = serde_json::from_str::<StreamMessage>(json_str) {
                 match event.event_type.as_str() {
                     "content_block_start" => {
-                        if let Some(block) = event.content_block
-                            && block.block_type.as_deref() == Some("tool_use")
-                        {
-                            current_tool =
-                                Some((block.id.unwrap_or_default(), block.name.unwrap_or_default(), String::new()));
+                        if let Some(block) = event.content_block {
+                            match block.block_type.as_deref() {
+                                Some("tool_use") => {
+                                    current_tool = Some((
+                                        block.id.unwrap_or_default(),
+                                        block.name.unwrap_or_default(),
+                                        String::new(),
+                                    ));
+                                }
+                                Some("thinking") => thinking.start(),
+                                Some("redacted_thinking") => {
+                                    let _ =
+                                        tx.send(StreamEvent::RedactedThinking { data: block.data.unwrap_or_default() });
+                                }
+                                _ => {}
+                            }
                         }
                     }
                     "content_block_delta" => {
@@ -230,11 +253,25 @@ impl LlmClient for AnthropicClient {
                                         input.push_str(&json);
                                     }
                                 }
+
+                                Some("thinking_delta") => {
+                                    if let Some(text) = delta.thinking {
+                                        thinking.push_thinking(&text);
+                                    }
+                                }
+                                Some("signature_delta") => {
+                                    if let Some(signature) = delta.signature {
+                                        thinking.push_signature(&signature);
+                                    }
+                                }
                                 _ => {}
                             }
                         }
                     }
                     "content_block_stop" => {
+                        if let Some(evt) = thinking.finish() {
+                            let _ = tx.send(evt);
+                        }
                         if let Some((id, name, input_json)) = current_tool.take() {
                             let input: Value = serde_json::from_str(&input_json)
                                 .unwrap_or_else(|_| Value::Object(serde_json::Map::new()));
@@ -19,7 +19,8 @@ use serde::Deserialize;
 use serde_json::Value;
 
 use super::error::LlmError;
-use super::{ApiCheckResult, LlmClient, LlmRequest, StreamEvent};
+use super::{ApiCheckResult, LlmClient, LlmProvider, LlmRequest, StreamEvent};
+use crate::infra::config::models::MODELS;
 
 /// API endpoint with beta flag required for Claude 4.5 access
 const CLAUDE_CODE_ENDPOINT: &str = "https://api.anthropic.com/v1/messages?beta=true";
@@ -34,15 +35,9 @@ const BILLING_HEADER: &str = "x-anthropic-billing-header: cc_version=2.1.37.fbe;
 const SYSTEM_REMINDER: &str =
     "<system-reminder>\nThe following skills are available for use with the Skill tool:\n</system-reminder>";
 
-/// Map model names to full API model identifiers
+/// Map model names and aliases to full API model identifiers (see yamls/models.yaml)
 fn map_model_name(model: &str) -> &str {
-    match model {
-        "claude-opus-4-6" => "claude-opus-4-6",
-        "claude-opus-4-5" => "claude-opus-4-6",
-        "claude-sonnet-4-5" => "claude-sonnet-4-5-20250929",
-        "claude-haiku-4-5" => "claude-haiku-4-5-20251001",
-        _ => model,
-    }
+    MODELS.canonical_api_name(LlmProvider::Anthropic, model)
 }
 
 /// Inject the system-reminder text block into the first non-tool-result user message.
@@ -254,6 +249,8 @@ struct StreamContentBlock {
     block_type: Option<String>,
     id: Option<String>,
     name: Option<String>,
+    /// Encrypted payload of a `redacted_thinking` block
+    data: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -262,6 +259,8 @@ struct StreamDelta {
     delta_type: Option<String>,
     text: Option<String>,
     partial_json: Option<String>,
+    thinking: Option<String>,
+    signature: Option<String>,
     stop_reason: Option<String>,
 }
 
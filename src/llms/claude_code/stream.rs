@@ -12,7 +12,9 @@ use super::{
 use crate::infra::constants::{API_VERSION, library};
 use crate::infra::tools::{ToolUse, build_api_tools};
 use crate::llms::error::LlmError;
-use crate::llms::{LlmRequest, StreamEvent, api_messages_to_cc_json};
+use crate::llms::{
+    LlmProvider, LlmRequest, StreamEvent, ThinkingAccumulator, api_messages_to_cc_json, ratelimit, thinking_config,
+};
 
 impl ClaudeCodeClient {
     pub(super) fn do_stream(&self, request: LlmRequest, tx: Sender<StreamEvent>) -> Result<(), LlmError> {
@@ -67,7 +69,7 @@ impl ClaudeCodeClient {
         inject_system_reminder(&mut json_messages);
 
         // Build final request (cache_control breakpoints are on panel tool_results above)
-        let api_request = serde_json::json!({
+        let mut api_request = serde_json::json!({
             "model": map_model_name(&request.model),
             "max_tokens": request.max_output_tokens,
             "system": [
@@ -78,6 +80,9 @@ impl ClaudeCodeClient {
             "tools": build_api_tools(&request.tools),
             "stream": true
         });
+        if let Some(budget) = request.thinking_budget {
+            api_request["thinking"] = thinking_config(budget);
+        }
 
         // Always dump last request for debugging (overwritten each call)
         dump_last_request(&request.worker_id, &api_request);
@@ -101,6 +106,7 @@ impl ClaudeCodeClient {
             .header("x-stainless-runtime-version", "v24.3.0")
             .json(&api_request)
             .send()?;
+        ratelimit::report(LlmProvider::ClaudeCode, response.headers(), &tx);
 
         if !response.status().is_success() {
             let status = response.status().as_u16();
@@ -122,6 +128,7 @@ impl ClaudeCodeClient {
         let mut cache_hit_tokens = 0;
         let mut cache_miss_tokens = 0;
         let mut current_tool: Option<(String, String, String)> = None;
+        let mut thinking = ThinkingAccumulator::default();
         let mut stop_reason: Option<String> = None;
         let mut total_bytes: usize = 0;
         let mut line_count: usize = 0;
@@ -191,11 +198,22 @@ impl ClaudeCodeClient {
             if let Ok(event) = serde_json::from_str::<StreamMessage>(json_str) {
                 match event.event_type.as_str() {
                     "content_block_start" => {
-                        if let Some(block) = event.content_block
-                            && block.block_type.as_deref() == Some("tool_use")
-                        {
-                            current_tool =
-                                Some((block.id.unwrap_or_default(), block.name.unwrap_or_default(), String::new()));
+                        if let Some(block) = event.content_block {
+                            match block.block_type.as_deref() {
+                                Some("tool_use") => {
+                                    current_tool = Some((
+                                        block.id.unwrap_or_default(),
+                                        block.name.unwrap_or_default(),
+                                        String::new(),
+                                    ));
+                                }
+                                Some("thinking") => thinking.start(),
+                                Some("redacted_thinking") => {
+                                    let _ =
+                                        tx.send(StreamEvent::RedactedThinking { data: block.data.unwrap_or_default() });
+                                }
+                                _ => {}
+                            }
                         }
                     }
                     "content_block_delta" => {
@@ -213,11 +231,25 @@ impl ClaudeCodeClient {
                                         input.push_str(&json);
                                     }
                                 }
+
+                                Some("thinking_delta") => {
+                                    if let Some(text) = delta.thinking {
+                                        thinking.push_thinking(&text);
+                                    }
+                                }
+                                Some("signature_delta") => {
+                                    if let Some(signature) = delta.signature {
+                                        thinking.push_signature(&signature);
+                                    }
+                                }
                                 _ => {}
                             }
                         }
                     }
                     "content_block_stop" => {
+                        if let Some(evt) = thinking.finish() {
+                            let _ = tx.send(evt);
+                        }
                         if let Some((id, name, input_json)) = current_tool.take() {
                             let input: Value = serde_json::from_str(&input_json)
                                 .unwrap_or_else(|_| Value::Object(serde_json::Map::new()));
@@ -2,6 +2,9 @@
 
 use serde_json::Value;
 
+use crate::infra::config::models::MODELS;
+use crate::llms::LlmProvider;
+
 /// System reminder injected into first user message for Claude Code validation
 pub const SYSTEM_REMINDER: &str =
     "<system-reminder>\nThe following skills are available for use with the Skill tool:\n</system-reminder>";
@@ -18,15 +21,9 @@ pub const BILLING_HEADER: &str = "x-anthropic-billing-header: cc_version=2.1.44.
 /// Directory for last-request debug dumps
 pub const LAST_REQUESTS_DIR: &str = ".context-pilot/last_requests";
 
-/// Map model names to full API model identifiers
+/// Map model names and aliases to full API model identifiers (see yamls/models.yaml)
 pub fn map_model_name(model: &str) -> &str {
-    match model {
-        "claude-opus-4-6" => "claude-opus-4-6",
-        "claude-opus-4-5" => "claude-opus-4-6",
-        "claude-sonnet-4-5" => "claude-sonnet-4-5-20250929",
-        "claude-haiku-4-5" => "claude-haiku-4-5-20251001",
-        _ => model,
-    }
+    MODELS.canonical_api_name(LlmProvider::Anthropic, model)
 }
 
 /// Inject the system-reminder text block into the first non-tool-result user message.
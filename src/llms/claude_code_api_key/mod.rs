@@ -6,6 +6,8 @@
 mod check;
 pub mod helpers;
 mod streaming;
+
+#[cfg(test)]
 mod tests;
 
 use std::env;
@@ -16,7 +18,10 @@ use secrecy::{ExposeSecret, SecretBox};
 use serde_json::Value;
 
 use super::error::LlmError;
-use super::{ApiCheckResult, LlmClient, LlmRequest, StreamEvent, api_messages_to_cc_json};
+use super::{
+    ApiCheckResult, LlmClient, LlmProvider, LlmRequest, StreamEvent, api_messages_to_cc_json, ratelimit,
+    thinking_config,
+};
 use crate::infra::constants::library;
 use crate::infra::tools::build_api_tools;
 
@@ -92,7 +97,7 @@ impl LlmClient for ClaudeCodeApiKeyClient {
         ensure_message_alternation(&mut json_messages);
         inject_system_reminder(&mut json_messages);
 
-        let api_request = serde_json::json!({
+        let mut api_request = serde_json::json!({
             "model": map_model_name(&request.model),
             "max_tokens": request.max_output_tokens,
             "system": [
@@ -103,6 +108,9 @@ impl LlmClient for ClaudeCodeApiKeyClient {
             "tools": build_api_tools(&request.tools),
             "stream": true
         });
+        if let Some(budget) = request.thinking_budget {
+            api_request["thinking"] = thinking_config(budget);
+        }
 
         dump_last_request(&request.worker_id, &api_request);
 
@@ -110,6 +118,7 @@ impl LlmClient for ClaudeCodeApiKeyClient {
             apply_claude_code_headers(client.post(CLAUDE_CODE_ENDPOINT), api_key.expose_secret(), "text/event-stream")
                 .json(&api_request)
                 .send()?;
+        ratelimit::report(LlmProvider::ClaudeCodeApiKey, response.headers(), &tx);
 
         if !response.status().is_success() {
             let status = response.status().as_u16();
@@ -7,8 +7,8 @@ use serde::Deserialize;
 use serde_json::Value;
 
 use crate::infra::tools::ToolUse;
-use crate::llms::StreamEvent;
 use crate::llms::error::LlmError;
+use crate::llms::{StreamEvent, ThinkingAccumulator};
 
 #[derive(Debug, Deserialize)]
 pub(super) struct StreamContentBlock {
@@ -16,6 +16,8 @@ pub(super) struct StreamContentBlock {
     pub block_type: Option<String>,
     pub id: Option<String>,
     pub name: Option<String>,
+    /// Encrypted payload of a `redacted_thinking` block
+    pub data: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -24,6 +26,8 @@ pub(super) struct StreamDelta {
     pub delta_type: Option<String>,
     pub text: Option<String>,
     pub partial_json: Option<String>,
+    pub thinking: Option<String>,
+    pub signature: Option<String>,
     pub stop_reason: Option<String>,
 }
 
@@ -63,6 +67,7 @@ pub(super) fn parse_sse_stream(
     let mut cache_hit_tokens = 0;
     let mut cache_miss_tokens = 0;
     let mut current_tool: Option<(String, String, String)> = None;
+    let mut thinking = ThinkingAccumulator::default();
     let mut stop_reason: Option<String> = None;
     let mut total_bytes: usize = 0;
     let mut line_count: usize = 0;
@@ -129,11 +134,18 @@ pub(super) fn parse_sse_stream(
         if let Ok(event) = serde_json::from_str::<StreamMessage>(json_str) {
             match event.event_type.as_str() {
                 "content_block_start" => {
-                    if let Some(block) = event.content_block
-                        && block.block_type.as_deref() == Some("tool_use")
-                    {
-                        current_tool =
-                            Some((block.id.unwrap_or_default(), block.name.unwrap_or_default(), String::new()));
+                    if let Some(block) = event.content_block {
+                        match block.block_type.as_deref() {
+                            Some("tool_use") => {
+                                current_tool =
+                                    Some((block.id.unwrap_or_default(), block.name.unwrap_or_default(), String::new()));
+                            }
+                            Some("thinking") => thinking.start(),
+                            Some("redacted_thinking") => {
+                                let _ = tx.send(StreamEvent::RedactedThinking { data: block.data.unwrap_or_default() });
+                            }
+                            _ => {}
+                        }
                     }
                 }
                 "content_block_delta" => {
@@ -151,11 +163,25 @@ pub(super) fn parse_sse_stream(
                                     input.push_str(&json);
                                 }
                             }
+
+                            Some("thinking_delta") => {
+                                if let Some(text) = delta.thinking {
+                                    thinking.push_thinking(&text);
+                                }
+                            }
+                            Some("signature_delta") => {
+                                if let Some(signature) = delta.signature {
+                                    thinking.push_signature(&signature);
+                                }
+                            }
                             _ => {}
                         }
                     }
                 }
                 "content_block_stop" => {
+                    if let Some(evt) = thinking.finish() {
+                        let _ = tx.send(evt);
+                    }
                     if let Some((id, name, input_json)) = current_tool.take() {
                         let input: Value =
                             serde_json::from_str(&input_json).unwrap_or_else(|_| Value::Object(serde_json::Map::new()));
@@ -13,8 +13,8 @@ use secrecy::{ExposeSecret, SecretBox};
 use serde::Serialize;
 
 use super::error::LlmError;
-use super::openai_compat::{self, BuildOptions, OaiMessage, ToolCallAccumulator};
-use super::{LlmClient, LlmRequest, StreamEvent};
+use super::openai_compat::{self, BuildOptions, OaiContent, OaiMessage, ToolCallAccumulator};
+use super::{LlmClient, LlmProvider, LlmRequest, StreamEvent, ratelimit};
 
 const DEEPSEEK_API_ENDPOINT: &str = "https://api.deepseek.com/chat/completions";
 
@@ -46,7 +46,7 @@ impl Default for DeepSeekClient {
 struct DsMessage {
     role: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    content: Option<String>,
+    content: Option<OaiContent>,
     #[serde(skip_serializing_if = "Option::is_none")]
     reasoning_content: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -104,6 +104,7 @@ impl LlmClient for DeepSeekClient {
                 system_suffix: None,
                 extra_context: request.extra_context.clone(),
                 pending_tool_result_ids: pending_tool_ids,
+                vision: openai_compat::supports_vision(LlmProvider::DeepSeek, &request.model),
             },
             &request.api_messages,
         );
@@ -117,7 +118,7 @@ impl LlmClient for DeepSeekClient {
             for result in results {
                 ds_messages.push(DsMessage {
                     role: "tool".to_string(),
-                    content: Some(result.content.clone()),
+                    content: Some(result.content.clone().into()),
                     reasoning_content: None,
                     tool_calls: None,
                     tool_call_id: Some(result.tool_use_id.clone()),
@@ -145,6 +146,7 @@ impl LlmClient for DeepSeekClient {
             .header("Content-Type", "application/json")
             .json(&api_request)
             .send()?;
+        ratelimit::report(LlmProvider::DeepSeek, response.headers(), &tx);
 
         if !response.status().is_success() {
             let status = response.status().as_u16();
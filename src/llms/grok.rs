@@ -13,7 +13,7 @@ use serde::Serialize;
 
 use super::error::LlmError;
 use super::openai_compat::{self, BuildOptions, OaiMessage, ToolCallAccumulator};
-use super::{LlmClient, LlmRequest, StreamEvent};
+use super::{LlmClient, LlmProvider, LlmRequest, StreamEvent, ratelimit};
 
 const GROK_API_ENDPOINT: &str = "https://api.x.ai/v1/chat/completions";
 
@@ -69,6 +69,7 @@ impl LlmClient for GrokClient {
                 system_suffix: None,
                 extra_context: request.extra_context.clone(),
                 pending_tool_result_ids: pending_tool_ids,
+                vision: openai_compat::supports_vision(LlmProvider::Grok, &request.model),
             },
             &request.api_messages,
         );
@@ -78,7 +79,7 @@ impl LlmClient for GrokClient {
             for result in results {
                 messages.push(OaiMessage {
                     role: "tool".to_string(),
-                    content: Some(result.content.clone()),
+                    content: Some(result.content.clone().into()),
                     tool_calls: None,
                     tool_call_id: Some(result.tool_use_id.clone()),
                 });
@@ -105,6 +106,7 @@ impl LlmClient for GrokClient {
             .header("Content-Type", "application/json")
             .json(&api_request)
             .send()?;
+        ratelimit::report(LlmProvider::Grok, response.headers(), &tx);
 
         if !response.status().is_success() {
             let status = response.status().as_u16();
@@ -14,7 +14,7 @@ use serde_json::Value;
 
 use super::error::LlmError;
 use super::openai_compat::{self, BuildOptions, OaiMessage, ToolCallAccumulator};
-use super::{LlmClient, LlmRequest, StreamEvent};
+use super::{LlmClient, LlmProvider, LlmRequest, StreamEvent, ratelimit};
 use crate::infra::tools::ToolDefinition;
 
 const GROQ_API_ENDPOINT: &str = "https://api.groq.com/openai/v1/chat/completions";
@@ -78,6 +78,7 @@ impl LlmClient for GroqClient {
                 system_suffix,
                 extra_context: request.extra_context.clone(),
                 pending_tool_result_ids: pending_tool_ids,
+                vision: openai_compat::supports_vision(LlmProvider::Groq, &request.model),
             },
             &request.api_messages,
         );
@@ -87,7 +88,7 @@ impl LlmClient for GroqClient {
             for result in results {
                 messages.push(OaiMessage {
                     role: "tool".to_string(),
-                    content: Some(result.content.clone()),
+                    content: Some(result.content.clone().into()),
                     tool_calls: None,
                     tool_call_id: Some(result.tool_use_id.clone()),
                 });
@@ -114,6 +115,7 @@ impl LlmClient for GroqClient {
             .header("Content-Type", "application/json")
             .json(&api_request)
             .send()?;
+        ratelimit::report(LlmProvider::Groq, response.headers(), &tx);
 
         if !response.status().is_success() {
             let status = response.status().as_u16();
@@ -0,0 +1,722 @@
+//! Local model provider (Ollama, llama.cpp server, LM Studio, vLLM...).
+//!
+//! Targets any OpenAI-compatible `/chat/completions` endpoint on a configurable
+//! host (`LOCAL_LLM_BASE_URL`). Models are discovered by querying the server
+//! rather than a hard-coded enum.
+//!
+//! Models without native tool calling are handled by emulating tool calls over
+//! a text protocol: tool schemas go into the system prompt, and the model emits
+//! `<tool_call>{"name": ..., "arguments": {...}}</tool_call>` blocks which are
+//! parsed out of the stream and turned into regular `StreamEvent::ToolUse`s.
+
+use std::collections::HashSet;
+use std::env;
+use std::io::{BufRead, BufReader};
+use std::sync::mpsc::Sender;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use reqwest::blocking::{Client, RequestBuilder};
+use secrecy::{ExposeSecret, SecretBox};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::error::LlmError;
+use super::openai_compat::{self, BuildOptions, OaiContent, OaiMessage, ToolCallAccumulator};
+use super::{LlmClient, LlmProvider, LlmRequest, StreamEvent};
+use crate::app::panels::now_ms;
+use crate::infra::tools::{ToolDefinition, ToolUse};
+
+/// Default server: Ollama's OpenAI-compatible API
+const LOCAL_DEFAULT_BASE_URL: &str = "http://localhost:11434/v1";
+
+/// Opening/closing tags of the emulated tool call protocol
+const TOOL_CALL_OPEN: &str = "<tool_call>";
+const TOOL_CALL_CLOSE: &str = "</tool_call>";
+
+/// Models that rejected native `tools` this session — go straight to emulation next time.
+static NO_NATIVE_TOOLS: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// How tool calls are transported to the model
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ToolMode {
+    /// Try native `tools`, fall back to emulation if the server rejects them
+    Auto,
+    /// Always send native `tools`
+    Native,
+    /// Always use the text protocol
+    Emulated,
+}
+
+/// Base URL of the local server (`LOCAL_LLM_BASE_URL`, default Ollama on localhost)
+pub fn base_url() -> String {
+    dotenvy::dotenv().ok();
+    env::var("LOCAL_LLM_BASE_URL")
+        .ok()
+        .filter(|u| !u.trim().is_empty())
+        .map(|u| u.trim_end_matches('/').to_string())
+        .unwrap_or_else(|| LOCAL_DEFAULT_BASE_URL.to_string())
+}
+
+/// Local model client
+pub struct LocalClient {
+    /// Optional bearer token (llama.cpp `--api-key`, vLLM, LM Studio...)
+    api_key: Option<SecretBox<String>>,
+    base_url: String,
+    tool_mode: ToolMode,
+}
+
+impl LocalClient {
+    pub fn new() -> Self {
+        dotenvy::dotenv().ok();
+        let tool_mode = match env::var("LOCAL_LLM_TOOL_MODE").ok().as_deref() {
+            Some("native") => ToolMode::Native,
+            Some("emulated") => ToolMode::Emulated,
+            _ => ToolMode::Auto,
+        };
+        Self {
+            api_key: env::var("LOCAL_LLM_API_KEY").ok().filter(|k| !k.is_empty()).map(|k| SecretBox::new(Box::new(k))),
+            base_url: base_url(),
+            tool_mode,
+        }
+    }
+
+    fn post(&self, client: &Client, path: &str) -> RequestBuilder {
+        self.with_auth(client.post(format!("{}{}", self.base_url, path))).header("Content-Type", "application/json")
+    }
+
+    fn with_auth(&self, builder: RequestBuilder) -> RequestBuilder {
+        match &self.api_key {
+            Some(key) => builder.header("Authorization", format!("Bearer {}", key.expose_secret())),
+            None => builder,
+        }
+    }
+
+    /// List model IDs served by the local server.
+    ///
+    /// Tries the OpenAI-compatible `/models` first, then Ollama's native `/api/tags`.
+    pub fn list_models(&self) -> Result<Vec<String>, String> {
+        let client = Client::builder().timeout(Duration::from_secs(5)).build().map_err(|e| e.to_string())?;
+
+        let openai_err = match self.with_auth(client.get(format!("{}/models", self.base_url))).send() {
+            Ok(resp) if resp.status().is_success() => {
+                let body: Value = resp.json().map_err(|e| e.to_string())?;
+                let models = parse_model_ids(&body);
+                if !models.is_empty() {
+                    return Ok(models);
+                }
+                "server returned no models".to_string()
+            }
+            Ok(resp) => format!("HTTP {}", resp.status().as_u16()),
+            Err(e) => e.to_string(),
+        };
+
+        // Ollama native API lives at the host root, not under /v1
+        let root = self.base_url.strip_suffix("/v1").unwrap_or(&self.base_url);
+        match client.get(format!("{}/api/tags", root)).send() {
+            Ok(resp) if resp.status().is_success() => {
+                let body: Value = resp.json().map_err(|e| e.to_string())?;
+                Ok(parse_model_ids(&body))
+            }
+            _ => Err(format!("Cannot list models at {}: {}", self.base_url, openai_err)),
+        }
+    }
+}
+
+impl Default for LocalClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Start local model discovery in background
+pub fn start_model_discovery(tx: Sender<Result<Vec<String>, String>>) {
+    std::thread::spawn(move || {
+        let _ = tx.send(LocalClient::new().list_models());
+    });
+}
+
+/// Extract model IDs from either `{"data": [{"id": ..}]}` (OpenAI) or `{"models": [{"name": ..}]}` (Ollama).
+fn parse_model_ids(body: &Value) -> Vec<String> {
+    let mut models: Vec<String> = if let Some(data) = body.get("data").and_then(|d| d.as_array()) {
+        data.iter().filter_map(|m| m.get("id").and_then(|v| v.as_str()).map(String::from)).collect()
+    } else if let Some(list) = body.get("models").and_then(|d| d.as_array()) {
+        list.iter()
+            .filter_map(|m| m.get("name").or_else(|| m.get("model")).and_then(|v| v.as_str()).map(String::from))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    models.sort();
+    models.dedup();
+    models
+}
+
+/// Whether an error response means "this model cannot take native tools".
+fn is_tools_unsupported(body: &str) -> bool {
+    let body = body.to_lowercase();
+    ["does not support tools", "tools param requires", "tools are not supported", "tool calls are not supported"]
+        .iter()
+        .any(|needle| body.contains(needle))
+}
+
+#[derive(Debug, Serialize)]
+struct StreamOptions {
+    include_usage: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct LocalRequest {
+    model: String,
+    messages: Vec<OaiMessage>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<openai_compat::OaiTool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<String>,
+    max_tokens: u32,
+    stream: bool,
+    stream_options: StreamOptions,
+}
+
+impl LlmClient for LocalClient {
+    fn stream(&self, request: LlmRequest, tx: Sender<StreamEvent>) -> Result<(), LlmError> {
+        if request.model.is_empty() {
+            return Err(LlmError::Api {
+                status: 0,
+                body: "No local model selected — open the config (Ctrl+H) and pick one".to_string(),
+            });
+        }
+
+        let client = Client::new();
+
+        // Collect pending tool result IDs
+        let pending_tool_ids: Vec<String> = request
+            .tool_results
+            .as_ref()
+            .map(|results| results.iter().map(|r| r.tool_use_id.clone()).collect())
+            .unwrap_or_default();
+
+        // Build messages using shared builder
+        let mut messages = openai_compat::build_messages(
+            &request.messages,
+            &request.context_items,
+            &BuildOptions {
+                system_prompt: request.system_prompt.clone(),
+                system_suffix: None,
+                extra_context: request.extra_context.clone(),
+                pending_tool_result_ids: pending_tool_ids,
+                vision: openai_compat::supports_vision(LlmProvider::Local, &request.model),
+            },
+            &request.api_messages,
+        );
+
+        // Add tool results if present
+        if let Some(results) = &request.tool_results {
+            for result in results {
+                messages.push(OaiMessage {
+                    role: "tool".to_string(),
+                    content: Some(result.content.clone().into()),
+                    tool_calls: None,
+                    tool_call_id: Some(result.tool_use_id.clone()),
+                });
+            }
+        }
+
+        let has_tools = request.tools.iter().any(|t| t.enabled);
+        let known_unsupported = NO_NATIVE_TOOLS.lock().map(|set| set.contains(&request.model)).unwrap_or(false);
+        let emulate = has_tools
+            && match self.tool_mode {
+                ToolMode::Emulated => true,
+                ToolMode::Native => false,
+                ToolMode::Auto => known_unsupported,
+            };
+
+        let response = if emulate {
+            self.send_emulated(&client, &request, messages)?
+        } else {
+            let tools = openai_compat::tools_to_oai(&request.tools);
+            let tool_choice = if tools.is_empty() { None } else { Some("auto".to_string()) };
+            let api_request = LocalRequest {
+                model: request.model.clone(),
+                messages: messages.clone(),
+                tools,
+                tool_choice,
+                max_tokens: request.max_output_tokens,
+                stream: true,
+                stream_options: StreamOptions { include_usage: true },
+            };
+            openai_compat::dump_request(&request.worker_id, "local", &api_request);
+
+            let response = self.post(&client, "/chat/completions").json(&api_request).send()?;
+            if response.status().is_success() {
+                response
+            } else {
+                let status = response.status().as_u16();
+                let body = response.text().unwrap_or_default();
+                if has_tools && self.tool_mode == ToolMode::Auto && is_tools_unsupported(&body) {
+                    // Remember and retry with the text protocol
+                    if let Ok(mut set) = NO_NATIVE_TOOLS.lock() {
+                        set.insert(request.model.clone());
+                    }
+                    self.send_emulated(&client, &request, messages)?
+                } else {
+                    return Err(LlmError::Api { status, body });
+                }
+            }
+        };
+
+        // Stream SSE using shared helpers
+        let reader = BufReader::new(response);
+        let mut input_tokens = 0;
+        let mut output_tokens = 0;
+        let mut stop_reason: Option<String> = None;
+        let mut tool_acc = ToolCallAccumulator::new();
+        let mut text_calls = TextToolCallParser::new();
+
+        for line in reader.lines() {
+            let line = line.map_err(|e| LlmError::StreamRead(e.to_string()))?;
+
+            if let Some(resp) = openai_compat::parse_sse_line(&line) {
+                if let Some(usage) = resp.usage {
+                    if let Some(inp) = usage.prompt_tokens {
+                        input_tokens = inp;
+                    }
+                    if let Some(out) = usage.completion_tokens {
+                        output_tokens = out;
+                    }
+                }
+
+                for choice in resp.choices {
+                    if let Some(delta) = choice.delta {
+                        if let Some(content) = delta.content
+                            && !content.is_empty()
+                        {
+                            let text = if emulate { text_calls.feed(&content) } else { content };
+                            if !text.is_empty() {
+                                let _ = tx.send(StreamEvent::Chunk(text));
+                            }
+                        }
+                        if let Some(calls) = delta.tool_calls {
+                            for call in &calls {
+                                tool_acc.feed(call);
+                            }
+                        }
+                    }
+                    if let Some(ref reason) = choice.finish_reason {
+                        stop_reason = Some(openai_compat::normalize_stop_reason(reason));
+                        for tool_use in tool_acc.drain() {
+                            let _ = tx.send(StreamEvent::ToolUse(tool_use));
+                        }
+                    }
+                }
+            }
+        }
+
+        if emulate {
+            let rest = text_calls.finish();
+            if !rest.is_empty() {
+                let _ = tx.send(StreamEvent::Chunk(rest));
+            }
+            let calls = text_calls.take_calls();
+            if !calls.is_empty() {
+                stop_reason = Some("tool_use".to_string());
+            }
+            for tool_use in calls {
+                let _ = tx.send(StreamEvent::ToolUse(tool_use));
+            }
+        }
+
+        let _ = tx.send(StreamEvent::Done {
+            input_tokens,
+            output_tokens,
+            cache_hit_tokens: 0,
+            cache_miss_tokens: input_tokens,
+            stop_reason,
+        });
+        Ok(())
+    }
+
+    fn check_api(&self, model: &str) -> super::ApiCheckResult {
+        // Test 1: Server reachable and model served
+        let models = match self.list_models() {
+            Ok(models) => models,
+            Err(e) => {
+                return super::ApiCheckResult { auth_ok: false, streaming_ok: false, tools_ok: false, error: Some(e) };
+            }
+        };
+        if model.is_empty() {
+            return super::ApiCheckResult {
+                auth_ok: true,
+                streaming_ok: false,
+                tools_ok: false,
+                error: Some("No local model selected".to_string()),
+            };
+        }
+        if !models.iter().any(|m| m == model) {
+            return super::ApiCheckResult {
+                auth_ok: true,
+                streaming_ok: false,
+                tools_ok: false,
+                error: Some(format!("Model '{}' not found on server", model)),
+            };
+        }
+
+        let client = Client::new();
+
+        // Test 2: Streaming
+        let stream_result = self
+            .post(&client, "/chat/completions")
+            .json(&serde_json::json!({
+                "model": model,
+                "max_tokens": 10,
+                "stream": true,
+                "messages": [{"role": "user", "content": "Say ok"}]
+            }))
+            .send();
+
+        let streaming_ok = stream_result.as_ref().map(|r| r.status().is_success()).unwrap_or(false);
+
+        // Test 3: Tools (native, or emulated via the text protocol)
+        let tools_result = self
+            .post(&client, "/chat/completions")
+            .json(&serde_json::json!({
+                "model": model,
+                "max_tokens": 50,
+                "tools": [{
+                    "type": "function",
+                    "function": {
+                        "name": "test_tool",
+                        "description": "A test tool",
+                        "parameters": {
+                            "type": "object",
+                            "properties": {},
+                            "required": []
+                        }
+                    }
+                }],
+                "messages": [{"role": "user", "content": "Hi"}]
+            }))
+            .send();
+
+        let tools_ok = match tools_result {
+            Ok(resp) if resp.status().is_success() => true,
+            Ok(resp) => {
+                let body = resp.text().unwrap_or_default();
+                // Emulation covers models without native tool calling
+                is_tools_unsupported(&body) && self.tool_mode != ToolMode::Native
+            }
+            Err(_) => false,
+        };
+
+        super::ApiCheckResult { auth_ok: true, streaming_ok, tools_ok, error: None }
+    }
+}
+
+impl LocalClient {
+    /// Send the request using the text tool-call protocol instead of native `tools`.
+    fn send_emulated(
+        &self,
+        client: &Client,
+        request: &LlmRequest,
+        messages: Vec<OaiMessage>,
+    ) -> Result<reqwest::blocking::Response, LlmError> {
+        let api_request = LocalRequest {
+            model: request.model.clone(),
+            messages: to_emulated_messages(messages, &request.tools),
+            tools: Vec::new(),
+            tool_choice: None,
+            max_tokens: request.max_output_tokens,
+            stream: true,
+            stream_options: StreamOptions { include_usage: true },
+        };
+        openai_compat::dump_request(&request.worker_id, "local", &api_request);
+
+        let response = self.post(client, "/chat/completions").json(&api_request).send()?;
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().unwrap_or_default();
+            return Err(LlmError::Api { status, body });
+        }
+        Ok(response)
+    }
+}
+
+// ───────────────────────────────────────────────────────────────────
+// Emulated tool calling (text protocol)
+// ───────────────────────────────────────────────────────────────────
+
+/// Build the system prompt section describing tools and the call protocol.
+fn emulated_tools_prompt(tools: &[ToolDefinition]) -> String {
+    let mut out = String::from(
+        "# Tools\n\n\
+         You can call tools. To call one, output a block exactly like this (JSON, one call per block):\n\
+         <tool_call>{\"name\": \"tool_name\", \"arguments\": {\"param\": \"value\"}}</tool_call>\n\
+         You may output several blocks in one reply. After your tool calls, stop and wait: \
+         results arrive in the next user message inside <tool_result> tags.\n\nAvailable tools:\n",
+    );
+    for tool in tools.iter().filter(|t| t.enabled) {
+        out.push_str(&format!(
+            "\n## {}\n{}\nParameters (JSON schema): {}\n",
+            tool.id,
+            tool.description,
+            serde_json::to_string(&tool.to_json_schema()).unwrap_or_default()
+        ));
+    }
+    out
+}
+
+/// Rewrite native tool_calls / tool messages into the text protocol.
+/// The tool instructions ride on the system message, which is created if the
+/// conversation has none.
+fn to_emulated_messages(messages: Vec<OaiMessage>, tools: &[ToolDefinition]) -> Vec<OaiMessage> {
+    let mut out: Vec<OaiMessage> = Vec::with_capacity(messages.len());
+    let text_message = |role: &str, content: String| OaiMessage {
+        role: role.to_string(),
+        content: Some(OaiContent::Text(content)),
+        tool_calls: None,
+        tool_call_id: None,
+    };
+
+    for msg in messages {
+        match msg.role.as_str() {
+            "system" => {
+                let system = msg.content.map(OaiContent::into_text).unwrap_or_default();
+                let content = format!("{}\n\n{}", system, emulated_tools_prompt(tools));
+                out.push(text_message("system", content));
+            }
+            "tool" => {
+                let block = format!(
+                    "<tool_result id=\"{}\">\n{}\n</tool_result>",
+                    msg.tool_call_id.unwrap_or_default(),
+                    msg.content.map(OaiContent::into_text).unwrap_or_default()
+                );
+                // Consecutive results are merged into one user turn
+                match out.last_mut() {
+                    Some(last) if last.role == "user" && last.text().is_some_and(is_tool_result_turn) => {
+                        if let Some(OaiContent::Text(ref mut content)) = last.content {
+                            content.push_str("\n\n");
+                            content.push_str(&block);
+                        }
+                    }
+                    _ => out.push(text_message("user", block)),
+                }
+            }
+            // Plain messages pass through as they are (image parts included)
+            _ if msg.tool_calls.is_none() => out.push(msg),
+            _ => {
+                let mut content = msg.content.map(OaiContent::into_text).unwrap_or_default();
+                for call in msg.tool_calls.unwrap_or_default() {
+                    let arguments: Value = serde_json::from_str(&call.function.arguments).unwrap_or(Value::Null);
+                    let payload = serde_json::json!({"name": call.function.name, "arguments": arguments});
+                    if !content.is_empty() {
+                        content.push('\n');
+                    }
+                    content.push_str(&format!("{}{}{}", TOOL_CALL_OPEN, payload, TOOL_CALL_CLOSE));
+                }
+                out.push(text_message(&msg.role, content));
+            }
+        }
+    }
+    if !out.iter().any(|m| m.role == "system") {
+        out.insert(0, text_message("system", emulated_tools_prompt(tools)));
+    }
+    out
+}
+
+fn is_tool_result_turn(content: &str) -> bool {
+    content.starts_with("<tool_result ")
+}
+
+/// Incremental parser that splits streamed text into display text and `<tool_call>` blocks.
+///
+/// Text that might be the beginning of an opening tag is held back until the
+/// next chunk disambiguates it, so tags split across SSE chunks are handled.
+#[derive(Default)]
+pub struct TextToolCallParser {
+    buf: String,
+    in_call: bool,
+    calls: Vec<ToolUse>,
+}
+
+impl TextToolCallParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a streamed text chunk. Returns the part that is safe to display.
+    pub fn feed(&mut self, text: &str) -> String {
+        self.buf.push_str(text);
+        let mut display = String::new();
+
+        loop {
+            if self.in_call {
+                let Some(end) = self.buf.find(TOOL_CALL_CLOSE) else { break };
+                let body: String = self.buf[..end].to_string();
+                self.buf.drain(..end + TOOL_CALL_CLOSE.len());
+                self.in_call = false;
+                match self.parse_call(&body) {
+                    Some(call) => self.calls.push(call),
+                    // Unparseable block: show it verbatim so nothing is silently lost
+                    None => display.push_str(&format!("{}{}{}", TOOL_CALL_OPEN, body, TOOL_CALL_CLOSE)),
+                }
+            } else if let Some(start) = self.buf.find(TOOL_CALL_OPEN) {
+                display.push_str(&self.buf[..start]);
+                self.buf.drain(..start + TOOL_CALL_OPEN.len());
+                self.in_call = true;
+            } else {
+                let keep = partial_tag_suffix(&self.buf, TOOL_CALL_OPEN);
+                let emit_to = self.buf.len() - keep;
+                display.push_str(&self.buf[..emit_to]);
+                self.buf.drain(..emit_to);
+                break;
+            }
+        }
+
+        display
+    }
+
+    /// End of stream: flush held-back text (including an unterminated call, verbatim).
+    pub fn finish(&mut self) -> String {
+        let rest = std::mem::take(&mut self.buf);
+        if self.in_call {
+            self.in_call = false;
+            format!("{}{}", TOOL_CALL_OPEN, rest)
+        } else {
+            rest
+        }
+    }
+
+    /// Take all tool calls parsed so far.
+    pub fn take_calls(&mut self) -> Vec<ToolUse> {
+        std::mem::take(&mut self.calls)
+    }
+
+    fn parse_call(&self, body: &str) -> Option<ToolUse> {
+        let value: Value = serde_json::from_str(body.trim()).ok()?;
+        let name = value.get("name")?.as_str()?.to_string();
+        let input = value
+            .get("arguments")
+            .or_else(|| value.get("parameters"))
+            .or_else(|| value.get("input"))
+            .cloned()
+            .filter(|v| v.is_object())
+            .unwrap_or_else(|| Value::Object(serde_json::Map::new()));
+        let id = format!("local_call_{}_{}", now_ms(), self.calls.len());
+        Some(ToolUse { id, name, input })
+    }
+}
+
+/// Length of the longest suffix of `buf` that is a proper prefix of `tag`.
+fn partial_tag_suffix(buf: &str, tag: &str) -> usize {
+    (1..tag.len().min(buf.len() + 1))
+        .rev()
+        .find(|&n| buf.is_char_boundary(buf.len() - n) && buf.ends_with(&tag[..n]))
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser_passes_plain_text_through() {
+        let mut p = TextToolCallParser::new();
+        assert_eq!(p.feed("hello world"), "hello world");
+        assert_eq!(p.finish(), "");
+        assert!(p.take_calls().is_empty());
+    }
+
+    #[test]
+    fn parser_extracts_call_split_across_chunks() {
+        let mut p = TextToolCallParser::new();
+        let mut shown = p.feed("Let me look.<tool");
+        shown.push_str(&p.feed("_call>{\"name\": \"Open\", \"argu"));
+        shown.push_str(&p.feed("ments\": {\"path\": \"src/main.rs\"}}</tool_"));
+        shown.push_str(&p.feed("call> done"));
+        shown.push_str(&p.finish());
+        assert_eq!(shown, "Let me look. done");
+        let calls = p.take_calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "Open");
+        assert_eq!(calls[0].input["path"], "src/main.rs");
+    }
+
+    #[test]
+    fn parser_keeps_invalid_block_as_text() {
+        let mut p = TextToolCallParser::new();
+        let shown = p.feed("<tool_call>not json</tool_call>");
+        assert_eq!(shown, "<tool_call>not json</tool_call>");
+        assert!(p.take_calls().is_empty());
+    }
+
+    #[test]
+    fn parser_flushes_unterminated_call() {
+        let mut p = TextToolCallParser::new();
+        assert_eq!(p.feed("<tool_call>{\"name\""), "");
+        assert_eq!(p.finish(), "<tool_call>{\"name\"");
+    }
+
+    #[test]
+    fn partial_suffix_respects_utf8() {
+        assert_eq!(partial_tag_suffix("abc<to", TOOL_CALL_OPEN), 3);
+        assert_eq!(partial_tag_suffix("héllo", TOOL_CALL_OPEN), 0);
+        assert_eq!(partial_tag_suffix("<", TOOL_CALL_OPEN), 1);
+    }
+
+    #[test]
+    fn model_ids_from_openai_and_ollama_shapes() {
+        let openai = serde_json::json!({"data": [{"id": "qwen2.5-coder:7b"}, {"id": "llama3.1:8b"}]});
+        assert_eq!(parse_model_ids(&openai), vec!["llama3.1:8b", "qwen2.5-coder:7b"]);
+        let ollama = serde_json::json!({"models": [{"name": "mistral:latest"}]});
+        assert_eq!(parse_model_ids(&ollama), vec!["mistral:latest"]);
+    }
+
+    #[test]
+    fn emulated_messages_flatten_tool_calls() {
+        let msgs = vec![
+            OaiMessage {
+                role: "system".into(),
+                content: Some(OaiContent::Text("sys".into())),
+                tool_calls: None,
+                tool_call_id: None,
+            },
+            OaiMessage {
+                role: "assistant".into(),
+                content: None,
+                tool_calls: Some(vec![openai_compat::OaiToolCall {
+                    id: "c1".into(),
+                    call_type: "function".into(),
+                    function: openai_compat::OaiFunction { name: "Open".into(), arguments: "{\"path\":\"a\"}".into() },
+                }]),
+                tool_call_id: None,
+            },
+            OaiMessage {
+                role: "tool".into(),
+                content: Some(OaiContent::Text("ok".into())),
+                tool_calls: None,
+                tool_call_id: Some("c1".into()),
+            },
+        ];
+        let out = to_emulated_messages(msgs, &[]);
+        assert_eq!(out.len(), 3);
+        assert!(out[0].text().unwrap().contains("<tool_call>"));
+        assert!(out[1].text().unwrap().starts_with("<tool_call>{"));
+        assert_eq!(out[2].role, "user");
+        assert!(out.iter().all(|m| m.tool_calls.is_none() && m.tool_call_id.is_none()));
+    }
+
+    #[test]
+    fn emulated_messages_without_system_get_one() {
+        let msgs = vec![OaiMessage {
+            role: "user".into(),
+            content: Some(OaiContent::Text("hi".into())),
+            tool_calls: None,
+            tool_call_id: None,
+        }];
+        let out = to_emulated_messages(msgs, &[]);
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[0].role, "system");
+        assert!(out[0].text().unwrap().contains("<tool_call>"));
+        assert_eq!(out[1].text(), Some("hi"));
+    }
+}
@@ -1,6 +1,7 @@
 //! LLM provider abstraction layer.
 //!
-//! Provides a unified interface for different LLM providers (Anthropic, Grok, Groq, Claude Code OAuth)
+//! Provides a unified interface for different LLM providers (Anthropic, Grok, Groq, Claude Code OAuth, OpenAI,
+//! and local Ollama / llama.cpp servers), plus a cassette-based record/replay provider
 
 pub mod anthropic;
 pub mod claude_code;
@@ -8,7 +9,11 @@ pub mod claude_code_api_key;
 pub mod deepseek;
 pub mod grok;
 pub mod groq;
+pub mod local;
+pub mod openai;
 pub mod openai_compat;
+pub mod ratelimit;
+pub mod replay;
 
 use std::sync::mpsc::Sender;
 
@@ -19,17 +24,19 @@ use crate::app::panels::ContextItem;
 use crate::infra::tools::ToolDefinition;
 use crate::infra::tools::ToolResult;
 use crate::state::Message;
+use cp_base::panels::ImageAttachment;
+use cp_base::state::StreamTarget;
 
 // Re-export LLM types from cp-base so that `crate::llms::LlmProvider` etc. work
-pub use cp_base::llm_types::{
-    AnthropicModel, ApiCheckResult, DeepSeekModel, GrokModel, GroqModel, LlmProvider, ModelInfo, StreamEvent,
-};
+pub use cp_base::llm_types::{ApiCheckResult, LlmProvider, ModelInfo, RateLimitInfo, StreamEvent};
 
 /// Configuration for an LLM request
 #[derive(Debug, Clone)]
 pub struct LlmRequest {
     pub model: String,
     pub max_output_tokens: u32,
+    /// Extended-thinking budget (Anthropic-family providers only; None = disabled)
+    pub thinking_budget: Option<u32>,
     pub messages: Vec<Message>,
     pub context_items: Vec<ContextItem>,
     pub tools: Vec<ToolDefinition>,
@@ -54,15 +61,39 @@ pub trait LlmClient: Send + Sync {
     fn check_api(&self, model: &str) -> ApiCheckResult;
 }
 
-/// Get the appropriate LLM client for the given provider
+/// Get the appropriate LLM client for the given provider.
+/// When `LLM_RECORD_CASSETTE` is set, real providers are wrapped in a recorder.
 pub fn get_client(provider: LlmProvider) -> Box<dyn LlmClient> {
-    match provider {
+    let client: Box<dyn LlmClient> = match provider {
         LlmProvider::Anthropic => Box::new(anthropic::AnthropicClient::new()),
         LlmProvider::ClaudeCode => Box::new(claude_code::ClaudeCodeClient::new()),
         LlmProvider::ClaudeCodeApiKey => Box::new(claude_code_api_key::ClaudeCodeApiKeyClient::new()),
         LlmProvider::Grok => Box::new(grok::GrokClient::new()),
         LlmProvider::Groq => Box::new(groq::GroqClient::new()),
         LlmProvider::DeepSeek => Box::new(deepseek::DeepSeekClient::new()),
+        LlmProvider::OpenAi => Box::new(openai::OpenAiClient::new()),
+        LlmProvider::Local => Box::new(local::LocalClient::new()),
+        LlmProvider::Replay => Box::new(replay::ReplayClient::new()),
+    };
+    match replay::record_path() {
+        Some(path) if provider != LlmProvider::Replay => Box::new(replay::RecordingClient::new(client, path)),
+        _ => client,
+    }
+}
+
+/// Prompt size a provider counted for a request. Anthropic reports cache reads and
+/// writes separately from `input_tokens`; OpenAI-compatible APIs include them in it.
+pub fn billed_prompt_tokens(
+    provider: LlmProvider,
+    input_tokens: usize,
+    cache_hit_tokens: usize,
+    cache_miss_tokens: usize,
+) -> usize {
+    match provider {
+        LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey => {
+            input_tokens + cache_hit_tokens + cache_miss_tokens
+        }
+        _ => input_tokens,
     }
 }
 
@@ -80,12 +111,48 @@ pub struct StreamParams {
     pub provider: LlmProvider,
     pub model: String,
     pub max_output_tokens: u32,
+    pub thinking_budget: Option<u32>,
     pub messages: Vec<Message>,
     pub context_items: Vec<ContextItem>,
     pub tools: Vec<ToolDefinition>,
     pub system_prompt: String,
     pub seed_content: Option<String>,
     pub worker_id: String,
+    /// Failover targets tried in order if the request fails with a retryable error
+    pub fallbacks: Vec<StreamTarget>,
+    /// Record the assembled request for the prompt inspector (main conversation only,
+    /// so reverie and summary requests never show up as "Last")
+    pub inspect: bool,
+}
+
+/// Assemble the prompt (panels + seed + conversation → api_messages) into a request
+fn assemble_request(params: StreamParams) -> (LlmRequest, Vec<StreamTarget>) {
+    let include_tool_uses = false; // No pending tool results on first stream
+    let api_messages = crate::app::prompt_builder::assemble_prompt(
+        &params.messages,
+        &params.context_items,
+        include_tool_uses,
+        params.seed_content.as_deref(),
+    );
+    if params.inspect {
+        crate::app::prompt_inspector::record_sent(&api_messages, &params.system_prompt, &params.tools);
+    }
+
+    let request = LlmRequest {
+        model: params.model,
+        max_output_tokens: params.max_output_tokens,
+        thinking_budget: params.thinking_budget,
+        messages: params.messages,
+        context_items: params.context_items,
+        tools: params.tools,
+        tool_results: None,
+        system_prompt: Some(params.system_prompt),
+        extra_context: None,
+        seed_content: params.seed_content,
+        worker_id: params.worker_id,
+        api_messages,
+    };
+    (request, params.fallbacks)
 }
 
 /// Start streaming with the specified provider and model
@@ -93,35 +160,49 @@ pub fn start_streaming(params: StreamParams, tx: Sender<StreamEvent>) {
     let client = get_client(params.provider);
 
     std::thread::spawn(move || {
-        // Assemble the prompt (panels + seed + conversation → api_messages)
-        let include_tool_uses = false; // No pending tool results on first stream
-        let api_messages = crate::app::prompt_builder::assemble_prompt(
-            &params.messages,
-            &params.context_items,
-            include_tool_uses,
-            params.seed_content.as_deref(),
-        );
-
-        let request = LlmRequest {
-            model: params.model,
-            max_output_tokens: params.max_output_tokens,
-            messages: params.messages,
-            context_items: params.context_items,
-            tools: params.tools,
-            tool_results: None,
-            system_prompt: Some(params.system_prompt),
-            extra_context: None,
-            seed_content: params.seed_content,
-            worker_id: params.worker_id,
-            api_messages,
-        };
-
-        if let Err(e) = client.stream(request, tx.clone()) {
+        let (request, fallbacks) = assemble_request(params);
+        if let Err(e) = stream_with_failover(client.as_ref(), request, &fallbacks, &tx, get_client) {
             let _ = tx.send(StreamEvent::Error(e.to_string()));
         }
     });
 }
 
+/// Stream `request`, re-sending the same assembled messages to each failover
+/// target in turn while the failures are retryable. A `Failover` event marks
+/// every switch so the receiver can discard the failed attempt's output.
+fn stream_with_failover(
+    client: &dyn LlmClient,
+    request: LlmRequest,
+    fallbacks: &[StreamTarget],
+    tx: &Sender<StreamEvent>,
+    connect: impl Fn(LlmProvider) -> Box<dyn LlmClient>,
+) -> Result<(), error::LlmError> {
+    if fallbacks.is_empty() {
+        return client.stream(request, tx.clone());
+    }
+    let mut result = client.stream(request.clone(), tx.clone());
+    for target in fallbacks {
+        let error = match result {
+            Err(e) if e.is_retryable() => e,
+            _ => break,
+        };
+        let _ = tx.send(StreamEvent::Failover {
+            fallback: target.fallback.unwrap_or_default(),
+            provider: target.provider,
+            model: target.model.clone(),
+            error: error.to_string(),
+        });
+        let request = LlmRequest {
+            model: target.model.clone(),
+            max_output_tokens: target.max_output_tokens,
+            thinking_budget: target.thinking_budget,
+            ..request.clone()
+        };
+        result = connect(target.provider).stream(request, tx.clone());
+    }
+    result
+}
+
 // Re-export common types used by providers
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -132,6 +213,38 @@ pub enum ContentBlock {
     ToolUse { id: String, name: String, input: Value },
     #[serde(rename = "tool_result")]
     ToolResult { tool_use_id: String, content: String },
+    #[serde(rename = "thinking")]
+    Thinking { thinking: String, signature: String },
+    #[serde(rename = "redacted_thinking")]
+    RedactedThinking { data: String },
+    /// Vision input. OpenAI-compatible providers send it only to models the
+    /// registry marks `vision`; the others rely on the panel's text description.
+    #[serde(rename = "image")]
+    Image { source: ImageSource },
+}
+
+/// Base64 image payload of an `image` content block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageSource {
+    #[serde(rename = "type")]
+    pub source_type: String,
+    pub media_type: String,
+    pub data: String,
+}
+
+impl ContentBlock {
+    /// Read and base64-encode an attached image. None if the file can't be read.
+    pub fn image_from_attachment(image: &ImageAttachment) -> Option<Self> {
+        use base64::Engine;
+        let bytes = std::fs::read(&image.path).ok()?;
+        Some(ContentBlock::Image {
+            source: ImageSource {
+                source_type: "base64".to_string(),
+                media_type: image.media_type.clone(),
+                data: base64::engine::general_purpose::STANDARD.encode(bytes),
+            },
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -149,6 +262,8 @@ pub struct FakePanelMessage {
     pub timestamp_ms: u64,
     /// Panel content with header
     pub content: String,
+    /// Image attached to the panel (vision input)
+    pub image: Option<ImageAttachment>,
 }
 
 /// Convert milliseconds since UNIX epoch to ISO 8601 format
@@ -291,10 +406,24 @@ pub fn prepare_panel_messages(context_items: &[ContextItem]) -> Vec<FakePanelMes
             panel_id: item.id.clone(),
             timestamp_ms: item.last_refresh_ms,
             content: format!("======= [{}] {} =======\n{}", item.id, item.header, item.content),
+            image: item.image.clone(),
         })
         .collect()
 }
 
+/// Positions (among the panel tool_results, 0-based) that carry a `cache_control`
+/// breakpoint: the panels at 25/50/75/100% of the panel list.
+pub fn panel_cache_breakpoints(panel_count: usize) -> std::collections::BTreeSet<usize> {
+    let mut breakpoints = std::collections::BTreeSet::new();
+    if panel_count > 0 {
+        for quarter in 1..=4usize {
+            let pos = (panel_count * quarter).div_ceil(4);
+            breakpoints.insert(pos.saturating_sub(1));
+        }
+    }
+    breakpoints
+}
+
 /// Convert pre-assembled `Vec<ApiMessage>` into Claude Code's raw JSON format.
 ///
 /// Claude Code requires raw `serde_json::Value` messages (not typed structs).
@@ -316,14 +445,7 @@ pub fn api_messages_to_cc_json(api_messages: &[ApiMessage]) -> Vec<serde_json::V
         .map(|(i, _)| i)
         .collect();
 
-    let panel_count = panel_result_indices.len();
-    let mut cache_breakpoints = std::collections::BTreeSet::new();
-    if panel_count > 0 {
-        for quarter in 1..=4usize {
-            let pos = (panel_count * quarter).div_ceil(4);
-            cache_breakpoints.insert(pos.saturating_sub(1));
-        }
-    }
+    let cache_breakpoints = panel_cache_breakpoints(panel_result_indices.len());
 
     let mut json_messages: Vec<serde_json::Value> = Vec::new();
 
@@ -347,6 +469,13 @@ pub fn api_messages_to_cc_json(api_messages: &[ApiMessage]) -> Vec<serde_json::V
                     }
                     result
                 }
+                ContentBlock::Thinking { thinking, signature } => {
+                    serde_json::json!({"type": "thinking", "thinking": thinking, "signature": signature})
+                }
+                ContentBlock::RedactedThinking { data } => {
+                    serde_json::json!({"type": "redacted_thinking", "data": data})
+                }
+                ContentBlock::Image { source } => serde_json::json!({"type": "image", "source": source}),
             })
             .collect();
 
@@ -391,6 +520,45 @@ pub(crate) fn log_sse_error(
         .and_then(|mut f| f.write_all(entry.as_bytes()));
 }
 
+/// `thinking` request parameter for Anthropic-family providers.
+pub(crate) fn thinking_config(budget_tokens: u32) -> Value {
+    serde_json::json!({"type": "enabled", "budget_tokens": budget_tokens})
+}
+
+/// Accumulator for an Anthropic `thinking` content block.
+/// Thinking text and its signature arrive as separate deltas; the block is
+/// only emitted on `content_block_stop`, once the signature is complete.
+#[derive(Default)]
+pub(crate) struct ThinkingAccumulator {
+    current: Option<(String, String)>,
+}
+
+impl ThinkingAccumulator {
+    /// Begin a new thinking block (`content_block_start` with type "thinking").
+    pub fn start(&mut self) {
+        self.current = Some((String::new(), String::new()));
+    }
+
+    /// Feed a `thinking_delta`.
+    pub fn push_thinking(&mut self, text: &str) {
+        if let Some((thinking, _)) = self.current.as_mut() {
+            thinking.push_str(text);
+        }
+    }
+
+    /// Feed a `signature_delta`.
+    pub fn push_signature(&mut self, text: &str) {
+        if let Some((_, signature)) = self.current.as_mut() {
+            signature.push_str(text);
+        }
+    }
+
+    /// Close the block in progress, if any, into a `Thinking` event.
+    pub fn finish(&mut self) -> Option<StreamEvent> {
+        self.current.take().map(|(thinking, signature)| StreamEvent::Thinking { thinking, signature })
+    }
+}
+
 pub mod error {
     use std::fmt;
 
@@ -414,6 +582,19 @@ pub mod error {
         }
     }
 
+    impl LlmError {
+        /// Whether another provider could plausibly serve the same request:
+        /// network and stream failures, timeouts, rate limits and server errors.
+        /// Auth failures and other 4xx responses are specific to the request or account.
+        pub fn is_retryable(&self) -> bool {
+            match self {
+                LlmError::Auth(_) => false,
+                LlmError::Network(_) | LlmError::StreamRead(_) => true,
+                LlmError::Api { status, .. } => matches!(status, 408 | 429) || *status >= 500,
+            }
+        }
+    }
+
     impl std::error::Error for LlmError {}
 
     impl From<reqwest::Error> for LlmError {
@@ -444,6 +625,16 @@ pub mod error {
             assert_eq!(e.to_string(), "Network error: timeout");
         }
 
+        #[test]
+        fn retryable_classes() {
+            assert!(LlmError::Network("timeout".into()).is_retryable());
+            assert!(LlmError::StreamRead("reset".into()).is_retryable());
+            assert!(LlmError::Api { status: 529, body: "overloaded".into() }.is_retryable());
+            assert!(LlmError::Api { status: 429, body: String::new() }.is_retryable());
+            assert!(!LlmError::Api { status: 400, body: "bad request".into() }.is_retryable());
+            assert!(!LlmError::Auth("key missing".into()).is_retryable());
+        }
+
         #[test]
         fn display_stream_read() {
             let e = LlmError::StreamRead("connection reset".into());
@@ -451,3 +642,138 @@ pub mod error {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::error::LlmError;
+    use super::*;
+
+    /// Streams one chunk, then fails with the given error (or finishes)
+    struct ScriptedClient {
+        chunk: &'static str,
+        error: Option<fn() -> LlmError>,
+    }
+
+    impl LlmClient for ScriptedClient {
+        fn stream(&self, request: LlmRequest, tx: Sender<StreamEvent>) -> Result<(), LlmError> {
+            let _ = tx.send(StreamEvent::Chunk(format!("{}@{}", self.chunk, request.model)));
+            match self.error {
+                Some(error) => Err(error()),
+                None => {
+                    let _ = tx.send(StreamEvent::Done {
+                        input_tokens: 0,
+                        output_tokens: 0,
+                        cache_hit_tokens: 0,
+                        cache_miss_tokens: 0,
+                        stop_reason: None,
+                    });
+                    Ok(())
+                }
+            }
+        }
+
+        fn check_api(&self, _model: &str) -> ApiCheckResult {
+            ApiCheckResult { auth_ok: true, streaming_ok: true, tools_ok: true, error: None }
+        }
+    }
+
+    fn request() -> LlmRequest {
+        LlmRequest {
+            model: "primary".to_string(),
+            max_output_tokens: 1024,
+            thinking_budget: Some(2048),
+            messages: Vec::new(),
+            context_items: Vec::new(),
+            tools: Vec::new(),
+            tool_results: None,
+            system_prompt: None,
+            extra_context: None,
+            seed_content: None,
+            worker_id: "main_worker".to_string(),
+            api_messages: Vec::new(),
+        }
+    }
+
+    fn target(provider: LlmProvider, model: &str, fallback: usize) -> StreamTarget {
+        StreamTarget {
+            provider,
+            model: model.to_string(),
+            max_output_tokens: 4096,
+            thinking_budget: None,
+            fallback: Some(fallback),
+        }
+    }
+
+    fn overloaded() -> LlmError {
+        LlmError::Api { status: 529, body: "overloaded".to_string() }
+    }
+
+    fn connect(provider: LlmProvider) -> Box<dyn LlmClient> {
+        match provider {
+            LlmProvider::Grok => Box::new(ScriptedClient { chunk: "grok", error: Some(overloaded) }),
+            _ => Box::new(ScriptedClient { chunk: "fallback", error: None }),
+        }
+    }
+
+    #[test]
+    fn retryable_failure_walks_the_chain() {
+        let (tx, rx) = mpsc::channel();
+        let primary = ScriptedClient { chunk: "primary", error: Some(overloaded) };
+        let chain = [target(LlmProvider::Grok, "grok-4", 0), target(LlmProvider::DeepSeek, "deepseek-chat", 1)];
+        assert!(stream_with_failover(&primary, request(), &chain, &tx, connect).is_ok());
+        drop(tx);
+
+        let events: Vec<StreamEvent> = rx.into_iter().collect();
+        assert!(matches!(&events[0], StreamEvent::Chunk(t) if t == "primary@primary"));
+        assert!(matches!(&events[1], StreamEvent::Failover { fallback: 0, provider: LlmProvider::Grok, error, .. }
+            if error.contains("529")));
+        assert!(matches!(&events[2], StreamEvent::Chunk(t) if t == "grok@grok-4"));
+        assert!(matches!(&events[3], StreamEvent::Failover { fallback: 1, provider: LlmProvider::DeepSeek, .. }));
+        assert!(matches!(&events[4], StreamEvent::Chunk(t) if t == "fallback@deepseek-chat"));
+        assert!(matches!(&events[5], StreamEvent::Done { .. }));
+    }
+
+    #[test]
+    fn non_retryable_failure_does_not_fail_over() {
+        let (tx, rx) = mpsc::channel();
+        let primary = ScriptedClient { chunk: "primary", error: Some(|| LlmError::Auth("bad key".to_string())) };
+        let chain = [target(LlmProvider::DeepSeek, "deepseek-chat", 0)];
+        let result = stream_with_failover(&primary, request(), &chain, &tx, connect);
+        assert!(matches!(result, Err(LlmError::Auth(_))));
+        drop(tx);
+        assert!(rx.into_iter().all(|e| !matches!(e, StreamEvent::Failover { .. })));
+    }
+
+    fn params(system_prompt: &str, inspect: bool) -> StreamParams {
+        StreamParams {
+            provider: LlmProvider::Anthropic,
+            model: "primary".to_string(),
+            max_output_tokens: 1024,
+            thinking_budget: None,
+            messages: vec![crate::state::message::test_helpers::MessageBuilder::user("hello").build()],
+            context_items: Vec::new(),
+            tools: Vec::new(),
+            system_prompt: system_prompt.to_string(),
+            seed_content: Some(system_prompt.to_string()),
+            worker_id: "main_worker".to_string(),
+            fallbacks: Vec::new(),
+            inspect,
+        }
+    }
+
+    #[test]
+    fn only_inspected_requests_become_last() {
+        use crate::app::prompt_inspector::{InspectMode, captures_for, describe};
+
+        assemble_request(params("main prompt", true));
+        let (summary, _) = assemble_request(params("summarize this", false));
+        let last = captures_for(InspectMode::Last).0.expect("main request recorded");
+        let hashes = |c: &crate::app::prompt_inspector::PromptCapture| -> Vec<String> {
+            c.blocks.iter().map(|b| b.hash.clone()).collect()
+        };
+        // Other tests may record main requests concurrently: only check the summary never became Last
+        assert_ne!(hashes(&last), hashes(&describe(&summary.api_messages, "summarize this", &[])));
+    }
+}
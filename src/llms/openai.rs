@@ -0,0 +1,418 @@
+//! OpenAI API implementation.
+//!
+//! Uses the chat completions endpoint with the shared `openai_compat` message
+//! builder. Reasoning models (o-series) get `reasoning_effort` and a `developer`
+//! role instead of `system`.
+
+use std::env;
+use std::io::{BufRead, BufReader};
+use std::sync::mpsc::Sender;
+
+use reqwest::blocking::Client;
+use secrecy::{ExposeSecret, SecretBox};
+use serde::Serialize;
+
+use super::error::LlmError;
+use super::openai_compat::{self, BuildOptions, OaiMessage, ToolCallAccumulator};
+use super::{LlmClient, LlmProvider, LlmRequest, StreamEvent, ratelimit};
+
+/// Default API base URL (override with `OPENAI_BASE_URL`, e.g. for Azure or a proxy)
+const OPENAI_DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// Default reasoning effort for o-series models (override with `OPENAI_REASONING_EFFORT`)
+const OPENAI_DEFAULT_REASONING_EFFORT: &str = "medium";
+
+/// OpenAI client
+pub struct OpenAiClient {
+    api_key: Option<SecretBox<String>>,
+    base_url: String,
+    reasoning_effort: String,
+}
+
+impl OpenAiClient {
+    pub fn new() -> Self {
+        dotenvy::dotenv().ok();
+        let base_url = env::var("OPENAI_BASE_URL")
+            .ok()
+            .filter(|u| !u.trim().is_empty())
+            .unwrap_or_else(|| OPENAI_DEFAULT_BASE_URL.to_string());
+        let reasoning_effort = env::var("OPENAI_REASONING_EFFORT")
+            .ok()
+            .filter(|e| matches!(e.as_str(), "low" | "medium" | "high"))
+            .unwrap_or_else(|| OPENAI_DEFAULT_REASONING_EFFORT.to_string());
+        Self {
+            api_key: env::var("OPENAI_API_KEY").ok().map(|k| SecretBox::new(Box::new(k))),
+            base_url,
+            reasoning_effort,
+        }
+    }
+
+    fn endpoint(&self) -> String {
+        format!("{}/chat/completions", self.base_url.trim_end_matches('/'))
+    }
+
+    /// Chat completions request body; reasoning models get `reasoning_effort`
+    /// and take instructions through the `developer` role
+    fn build_request(&self, request: &LlmRequest) -> OpenAiRequest {
+        let is_reasoning = is_reasoning_model(&request.model);
+
+        // Collect pending tool result IDs
+        let pending_tool_ids: Vec<String> = request
+            .tool_results
+            .as_ref()
+            .map(|results| results.iter().map(|r| r.tool_use_id.clone()).collect())
+            .unwrap_or_default();
+
+        // Build messages using shared builder
+        let mut messages = openai_compat::build_messages(
+            &request.messages,
+            &request.context_items,
+            &BuildOptions {
+                system_prompt: request.system_prompt.clone(),
+                system_suffix: None,
+                extra_context: request.extra_context.clone(),
+                pending_tool_result_ids: pending_tool_ids,
+                vision: openai_compat::supports_vision(LlmProvider::OpenAi, &request.model),
+            },
+            &request.api_messages,
+        );
+
+        // Reasoning models take instructions via the developer role
+        if is_reasoning {
+            for msg in messages.iter_mut().filter(|m| m.role == "system") {
+                msg.role = "developer".to_string();
+            }
+        }
+
+        // Add tool results if present
+        if let Some(results) = &request.tool_results {
+            for result in results {
+                messages.push(OaiMessage {
+                    role: "tool".to_string(),
+                    content: Some(result.content.clone().into()),
+                    tool_calls: None,
+                    tool_call_id: Some(result.tool_use_id.clone()),
+                });
+            }
+        }
+
+        let tools = openai_compat::tools_to_oai(&request.tools);
+        let tool_choice = if tools.is_empty() { None } else { Some("auto".to_string()) };
+
+        OpenAiRequest {
+            model: request.model.clone(),
+            messages,
+            tools,
+            tool_choice,
+            max_completion_tokens: request.max_output_tokens,
+            reasoning_effort: if is_reasoning { Some(self.reasoning_effort.clone()) } else { None },
+            stream: true,
+            stream_options: StreamOptions { include_usage: true },
+        }
+    }
+}
+
+impl Default for OpenAiClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether a model ID refers to a reasoning model (o1, o3, o4-mini, ...).
+fn is_reasoning_model(model: &str) -> bool {
+    let mut chars = model.chars();
+    chars.next() == Some('o') && chars.next().is_some_and(|c| c.is_ascii_digit())
+}
+
+#[derive(Debug, Serialize)]
+struct StreamOptions {
+    include_usage: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAiRequest {
+    model: String,
+    messages: Vec<OaiMessage>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<openai_compat::OaiTool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<String>,
+    max_completion_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning_effort: Option<String>,
+    stream: bool,
+    stream_options: StreamOptions,
+}
+
+impl LlmClient for OpenAiClient {
+    fn stream(&self, request: LlmRequest, tx: Sender<StreamEvent>) -> Result<(), LlmError> {
+        let api_key = self.api_key.as_ref().ok_or_else(|| LlmError::Auth("OPENAI_API_KEY not set".into()))?;
+
+        let client = Client::new();
+        let api_request = self.build_request(&request);
+
+        openai_compat::dump_request(&request.worker_id, "openai", &api_request);
+
+        let response = client
+            .post(self.endpoint())
+            .header("Authorization", format!("Bearer {}", api_key.expose_secret()))
+            .header("Content-Type", "application/json")
+            .json(&api_request)
+            .send()?;
+        ratelimit::report(LlmProvider::OpenAi, response.headers(), &tx);
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().unwrap_or_default();
+            return Err(LlmError::Api { status, body });
+        }
+
+        // Stream SSE using shared helpers
+        let reader = BufReader::new(response);
+        let mut input_tokens = 0;
+        let mut output_tokens = 0;
+        let mut cached_tokens = 0;
+        let mut stop_reason: Option<String> = None;
+        let mut tool_acc = ToolCallAccumulator::new();
+
+        for line in reader.lines() {
+            let line = line.map_err(|e| LlmError::StreamRead(e.to_string()))?;
+
+            if let Some(resp) = openai_compat::parse_sse_line(&line) {
+                // Usage arrives in a final chunk with empty choices (stream_options.include_usage)
+                if let Some(usage) = resp.usage {
+                    if let Some(inp) = usage.prompt_tokens {
+                        input_tokens = inp;
+                    }
+                    if let Some(out) = usage.completion_tokens {
+                        output_tokens = out;
+                    }
+                    if let Some(cached) = usage.prompt_tokens_details.and_then(|d| d.cached_tokens) {
+                        cached_tokens = cached;
+                    }
+                }
+
+                for choice in resp.choices {
+                    if let Some(delta) = choice.delta {
+                        if let Some(content) = delta.content
+                            && !content.is_empty()
+                        {
+                            let _ = tx.send(StreamEvent::Chunk(content));
+                        }
+                        if let Some(calls) = delta.tool_calls {
+                            for call in &calls {
+                                tool_acc.feed(call);
+                            }
+                        }
+                    }
+                    if let Some(ref reason) = choice.finish_reason {
+                        stop_reason = Some(openai_compat::normalize_stop_reason(reason));
+                        for tool_use in tool_acc.drain() {
+                            let _ = tx.send(StreamEvent::ToolUse(tool_use));
+                        }
+                    }
+                }
+            }
+        }
+
+        let _ = tx.send(StreamEvent::Done {
+            input_tokens,
+            output_tokens,
+            cache_hit_tokens: cached_tokens,
+            cache_miss_tokens: input_tokens.saturating_sub(cached_tokens),
+            stop_reason,
+        });
+        Ok(())
+    }
+
+    fn check_api(&self, model: &str) -> super::ApiCheckResult {
+        let api_key = match self.api_key.as_ref() {
+            Some(k) => k,
+            None => {
+                return super::ApiCheckResult {
+                    auth_ok: false,
+                    streaming_ok: false,
+                    tools_ok: false,
+                    error: Some("OPENAI_API_KEY not set".to_string()),
+                };
+            }
+        };
+
+        let client = Client::new();
+        let endpoint = self.endpoint();
+        // Reasoning models spend tokens thinking before answering — leave headroom
+        let max_tokens = if is_reasoning_model(model) { 256 } else { 10 };
+
+        // Test 1: Basic auth
+        let auth_result = client
+            .post(&endpoint)
+            .header("Authorization", format!("Bearer {}", api_key.expose_secret()))
+            .header("Content-Type", "application/json")
+            .json(&serde_json::json!({
+                "model": model,
+                "max_completion_tokens": max_tokens,
+                "messages": [{"role": "user", "content": "Hi"}]
+            }))
+            .send();
+
+        let auth_ok = auth_result.as_ref().map(|r| r.status().is_success()).unwrap_or(false);
+
+        if !auth_ok {
+            let error = match auth_result {
+                Ok(resp) => Some(format!("Auth failed ({})", resp.status().as_u16())),
+                Err(e) => Some(e.to_string()),
+            };
+            return super::ApiCheckResult { auth_ok: false, streaming_ok: false, tools_ok: false, error };
+        }
+
+        // Test 2: Streaming
+        let stream_result = client
+            .post(&endpoint)
+            .header("Authorization", format!("Bearer {}", api_key.expose_secret()))
+            .header("Content-Type", "application/json")
+            .json(&serde_json::json!({
+                "model": model,
+                "max_completion_tokens": max_tokens,
+                "stream": true,
+                "messages": [{"role": "user", "content": "Say ok"}]
+            }))
+            .send();
+
+        let streaming_ok = stream_result.as_ref().map(|r| r.status().is_success()).unwrap_or(false);
+
+        // Test 3: Tools
+        let tools_result = client
+            .post(&endpoint)
+            .header("Authorization", format!("Bearer {}", api_key.expose_secret()))
+            .header("Content-Type", "application/json")
+            .json(&serde_json::json!({
+                "model": model,
+                "max_completion_tokens": max_tokens.max(50),
+                "tools": [{
+                    "type": "function",
+                    "function": {
+                        "name": "test_tool",
+                        "description": "A test tool",
+                        "parameters": {
+                            "type": "object",
+                            "properties": {},
+                            "required": []
+                        }
+                    }
+                }],
+                "messages": [{"role": "user", "content": "Hi"}]
+            }))
+            .send();
+
+        let tools_ok = tools_result.as_ref().map(|r| r.status().is_success()).unwrap_or(false);
+
+        super::ApiCheckResult { auth_ok, streaming_ok, tools_ok, error: None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llms::{ApiMessage, ContentBlock, ImageSource};
+
+    #[test]
+    fn reasoning_models_detected() {
+        assert!(is_reasoning_model("o3"));
+        assert!(is_reasoning_model("o4-mini"));
+        assert!(is_reasoning_model("o1-preview"));
+    }
+
+    fn client() -> OpenAiClient {
+        OpenAiClient {
+            api_key: None,
+            base_url: OPENAI_DEFAULT_BASE_URL.to_string(),
+            reasoning_effort: "high".to_string(),
+        }
+    }
+
+    fn request(model: &str) -> LlmRequest {
+        LlmRequest {
+            model: model.to_string(),
+            max_output_tokens: 4096,
+            thinking_budget: None,
+            messages: Vec::new(),
+            context_items: Vec::new(),
+            tools: Vec::new(),
+            tool_results: None,
+            system_prompt: Some("Be brief.".to_string()),
+            extra_context: None,
+            seed_content: None,
+            worker_id: "main_worker".to_string(),
+            api_messages: vec![ApiMessage {
+                role: "user".to_string(),
+                content: vec![ContentBlock::Text { text: "Hi".to_string() }],
+            }],
+        }
+    }
+
+    #[test]
+    fn reasoning_request_uses_developer_role_and_effort() {
+        let body = serde_json::to_value(client().build_request(&request("o3"))).unwrap();
+        assert_eq!(body["max_completion_tokens"], 4096);
+        assert!(body.get("max_tokens").is_none());
+        assert_eq!(body["reasoning_effort"], "high");
+        assert_eq!(body["messages"][0]["role"], "developer");
+        assert_eq!(body["messages"][0]["content"], "Be brief.");
+        assert!(body["messages"].as_array().unwrap().iter().all(|m| m["role"] != "system"));
+        assert_eq!(body["stream_options"]["include_usage"], true);
+    }
+
+    #[test]
+    fn chat_request_keeps_system_role() {
+        let body = serde_json::to_value(client().build_request(&request("gpt-4.1"))).unwrap();
+        assert_eq!(body["max_completion_tokens"], 4096);
+        assert!(body.get("reasoning_effort").is_none());
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert!(body.get("tools").is_none());
+    }
+
+    /// A panel's tool_result followed by its image, as the prompt builder emits it
+    fn image_request(model: &str) -> LlmRequest {
+        let mut req = request(model);
+        req.api_messages.push(ApiMessage {
+            role: "user".to_string(),
+            content: vec![
+                ContentBlock::ToolResult { tool_use_id: "panel_P9".to_string(), content: "PNG 1x1".to_string() },
+                ContentBlock::Image {
+                    source: ImageSource {
+                        source_type: "base64".to_string(),
+                        media_type: "image/png".to_string(),
+                        data: "iVBORw==".to_string(),
+                    },
+                },
+            ],
+        });
+        req
+    }
+
+    #[test]
+    fn vision_models_get_image_parts() {
+        let body = serde_json::to_value(client().build_request(&image_request("gpt-4o"))).unwrap();
+        let messages = body["messages"].as_array().unwrap();
+        let last = messages.last().unwrap();
+        assert_eq!(messages[messages.len() - 2]["role"], "tool");
+        assert_eq!(last["role"], "user");
+        assert_eq!(last["content"][0]["type"], "image_url");
+        assert_eq!(last["content"][0]["image_url"]["url"], "data:image/png;base64,iVBORw==");
+    }
+
+    #[test]
+    fn models_without_vision_keep_the_text_fallback() {
+        let body = serde_json::to_value(client().build_request(&image_request("gpt-3.5-turbo"))).unwrap();
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages.last().unwrap()["role"], "tool");
+        assert!(messages.iter().all(|m| m["content"].is_string()));
+    }
+
+    #[test]
+    fn chat_models_not_reasoning() {
+        assert!(!is_reasoning_model("gpt-4o"));
+        assert!(!is_reasoning_model("gpt-4.1"));
+        assert!(!is_reasoning_model("omni-moderation-latest"));
+    }
+}
@@ -1,6 +1,6 @@
 //! Shared OpenAI-compatible message builder.
 //!
-//! Grok, Groq, and DeepSeek all use the OpenAI chat completions format.
+//! Grok, Groq, DeepSeek, and OpenAI all use the OpenAI chat completions format.
 //! This module extracts the common message-building logic so each provider
 //! only needs to handle its own quirks (request struct, endpoint, headers).
 
@@ -9,8 +9,9 @@ use std::collections::HashSet;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use super::{panel_footer_text, panel_header_text, panel_timestamp_text, prepare_panel_messages};
+use super::{LlmProvider, panel_footer_text, panel_header_text, panel_timestamp_text, prepare_panel_messages};
 use crate::app::panels::now_ms;
+use crate::infra::config::models::MODELS;
 use crate::infra::constants::{library, prompts};
 use crate::infra::tools::ToolDefinition;
 use crate::state::{Message, MessageStatus, MessageType};
@@ -24,13 +25,64 @@ use crate::state::{Message, MessageStatus, MessageType};
 pub struct OaiMessage {
     pub role: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub content: Option<String>,
+    pub content: Option<OaiContent>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_calls: Option<Vec<OaiToolCall>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_call_id: Option<String>,
 }
 
+impl OaiMessage {
+    /// Content of a plain-text message
+    pub fn text(&self) -> Option<&str> {
+        match &self.content {
+            Some(OaiContent::Text(text)) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+/// Message content: a plain string, or parts when images are attached.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum OaiContent {
+    Text(String),
+    Parts(Vec<OaiContentPart>),
+}
+
+impl OaiContent {
+    /// Flatten to text, dropping image parts
+    pub fn into_text(self) -> String {
+        match self {
+            OaiContent::Text(text) => text,
+            OaiContent::Parts(parts) => parts
+                .into_iter()
+                .filter_map(|p| if let OaiContentPart::Text { text } = p { Some(text) } else { None })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+impl From<String> for OaiContent {
+    fn from(text: String) -> Self {
+        OaiContent::Text(text)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OaiContentPart {
+    Text { text: String },
+    ImageUrl { image_url: OaiImageUrl },
+}
+
+/// Image given inline as a `data:<media type>;base64,...` URL
+#[derive(Debug, Clone, Serialize)]
+pub struct OaiImageUrl {
+    pub url: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OaiToolCall {
     pub id: String,
@@ -117,6 +169,27 @@ pub struct BuildOptions {
     pub extra_context: Option<String>,
     /// Pending tool result IDs from current tool loop.
     pub pending_tool_result_ids: Vec<String>,
+    /// Send `Image` blocks as `image_url` parts (the model accepts images).
+    /// Otherwise they are dropped and the panel's text description stands in.
+    pub vision: bool,
+}
+
+/// Whether the model registry marks a model as accepting images
+pub fn supports_vision(provider: LlmProvider, model: &str) -> bool {
+    MODELS.get(provider, model).is_some_and(|m| m.vision)
+}
+
+/// `image_url` parts for the `Image` blocks of a message
+fn image_parts(blocks: &[super::ContentBlock]) -> Vec<OaiContentPart> {
+    blocks
+        .iter()
+        .filter_map(|b| match b {
+            super::ContentBlock::Image { source } => Some(OaiContentPart::ImageUrl {
+                image_url: OaiImageUrl { url: format!("data:{};base64,{}", source.media_type, source.data) },
+            }),
+            _ => None,
+        })
+        .collect()
 }
 
 /// Build the full OpenAI-compatible message list.
@@ -150,7 +223,7 @@ fn build_from_api_messages(api_messages: &[super::ApiMessage], opts: &BuildOptio
     }
     out.push(OaiMessage {
         role: "system".to_string(),
-        content: Some(system_content),
+        content: Some(system_content.into()),
         tool_calls: None,
         tool_call_id: None,
     });
@@ -167,12 +240,22 @@ fn build_from_api_messages(api_messages: &[super::ApiMessage], opts: &BuildOptio
                 if let super::ContentBlock::ToolResult { tool_use_id, content } = block {
                     out.push(OaiMessage {
                         role: "tool".to_string(),
-                        content: Some(content.clone()),
+                        content: Some(content.clone().into()),
                         tool_calls: None,
                         tool_call_id: Some(tool_use_id.clone()),
                     });
                 }
             }
+            // Tool messages can't carry images: they follow in a user message
+            let images = if opts.vision { image_parts(&msg.content) } else { Vec::new() };
+            if !images.is_empty() {
+                out.push(OaiMessage {
+                    role: "user".to_string(),
+                    content: Some(OaiContent::Parts(images)),
+                    tool_calls: None,
+                    tool_call_id: None,
+                });
+            }
         } else if has_tool_use {
             // Assistant message with text + tool calls
             let text_parts: Vec<&str> = msg
@@ -203,7 +286,7 @@ fn build_from_api_messages(api_messages: &[super::ApiMessage], opts: &BuildOptio
 
             out.push(OaiMessage {
                 role: msg.role.clone(),
-                content: text,
+                content: text.map(OaiContent::Text),
                 tool_calls: if calls.is_empty() { None } else { Some(calls) },
                 tool_call_id: None,
             });
@@ -215,14 +298,16 @@ fn build_from_api_messages(api_messages: &[super::ApiMessage], opts: &BuildOptio
                 .filter_map(|b| if let super::ContentBlock::Text { text } = b { Some(text.as_str()) } else { None })
                 .collect::<Vec<_>>()
                 .join("\n");
-
-            if !text.is_empty() {
-                out.push(OaiMessage {
-                    role: msg.role.clone(),
-                    content: Some(text),
-                    tool_calls: None,
-                    tool_call_id: None,
-                });
+            let images = if opts.vision { image_parts(&msg.content) } else { Vec::new() };
+
+            let content = if images.is_empty() {
+                Some(text).filter(|t| !t.is_empty()).map(OaiContent::Text)
+            } else {
+                let text = Some(text).filter(|t| !t.is_empty()).map(|text| OaiContentPart::Text { text });
+                Some(OaiContent::Parts(text.into_iter().chain(images).collect()))
+            };
+            if content.is_some() {
+                out.push(OaiMessage { role: msg.role.clone(), content, tool_calls: None, tool_call_id: None });
             }
         }
     }
@@ -231,7 +316,7 @@ fn build_from_api_messages(api_messages: &[super::ApiMessage], opts: &BuildOptio
     if let Some(ref ctx) = opts.extra_context {
         out.push(OaiMessage {
             role: "user".to_string(),
-            content: Some(format!("Please clean up the context to reduce token usage:\n\n{}", ctx)),
+            content: Some(format!("Please clean up the context to reduce token usage:\n\n{}", ctx).into()),
             tool_calls: None,
             tool_call_id: None,
         });
@@ -258,7 +343,7 @@ fn build_from_raw(
 
     out.push(OaiMessage {
         role: "system".to_string(),
-        content: Some(system_content),
+        content: Some(system_content.into()),
         tool_calls: None,
         tool_call_id: None,
     });
@@ -275,7 +360,7 @@ fn build_from_raw(
             // Assistant message with tool_call
             out.push(OaiMessage {
                 role: "assistant".to_string(),
-                content: Some(text),
+                content: Some(text.into()),
                 tool_calls: Some(vec![OaiToolCall {
                     id: format!("panel_{}", panel.panel_id),
                     call_type: "function".to_string(),
@@ -290,7 +375,7 @@ fn build_from_raw(
             // Tool result message
             out.push(OaiMessage {
                 role: "tool".to_string(),
-                content: Some(panel.content.clone()),
+                content: Some(panel.content.clone().into()),
                 tool_calls: None,
                 tool_call_id: Some(format!("panel_{}", panel.panel_id)),
             });
@@ -300,7 +385,7 @@ fn build_from_raw(
         let footer = panel_footer_text(messages, current_ms);
         out.push(OaiMessage {
             role: "assistant".to_string(),
-            content: Some(footer),
+            content: Some(footer.into()),
             tool_calls: Some(vec![OaiToolCall {
                 id: "panel_footer".to_string(),
                 call_type: "function".to_string(),
@@ -313,7 +398,7 @@ fn build_from_raw(
         });
         out.push(OaiMessage {
             role: "tool".to_string(),
-            content: Some(prompts::panel_footer_ack().to_string()),
+            content: Some(prompts::panel_footer_ack().to_string().into()),
             tool_calls: None,
             tool_call_id: Some("panel_footer".to_string()),
         });
@@ -323,7 +408,7 @@ fn build_from_raw(
     if let Some(ref ctx) = opts.extra_context {
         out.push(OaiMessage {
             role: "user".to_string(),
-            content: Some(format!("Please clean up the context to reduce token usage:\n\n{}", ctx)),
+            content: Some(format!("Please clean up the context to reduce token usage:\n\n{}", ctx).into()),
             tool_calls: None,
             tool_call_id: None,
         });
@@ -347,7 +432,7 @@ fn build_from_raw(
                 if included_tool_ids.contains(&result.tool_use_id) {
                     out.push(OaiMessage {
                         role: "tool".to_string(),
-                        content: Some(result.content.clone()),
+                        content: Some(result.content.clone().into()),
                         tool_calls: None,
                         tool_call_id: Some(result.tool_use_id.clone()),
                     });
@@ -403,7 +488,7 @@ fn build_from_raw(
         if !message_content.is_empty() {
             out.push(OaiMessage {
                 role: msg.role.clone(),
-                content: Some(message_content),
+                content: Some(message_content.into()),
                 tool_calls: None,
                 tool_call_id: None,
             });
@@ -476,6 +561,13 @@ pub struct StreamUsage {
     /// DeepSeek-specific cache fields
     pub prompt_cache_hit_tokens: Option<usize>,
     pub prompt_cache_miss_tokens: Option<usize>,
+    /// OpenAI-specific prompt breakdown (cached prefix tokens)
+    pub prompt_tokens_details: Option<PromptTokensDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PromptTokensDetails {
+    pub cached_tokens: Option<usize>,
 }
 
 /// Normalize provider-specific stop reasons to our internal format.
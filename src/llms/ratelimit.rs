@@ -0,0 +1,156 @@
+//! Rate-limit response headers.
+//!
+//! Anthropic sends `anthropic-ratelimit-{requests,tokens}-{limit,remaining,reset}` with
+//! RFC 3339 reset timestamps. OpenAI-compatible APIs send
+//! `x-ratelimit-{limit,remaining,reset}-{requests,tokens}` with durations like `6m0s`.
+//! Both may add `retry-after` (seconds or an HTTP date) to 429 and overloaded responses.
+
+use std::sync::mpsc::Sender;
+
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+
+use super::{LlmProvider, RateLimitInfo, StreamEvent};
+
+/// Parse rate-limit headers and forward them to the app. Call right after `send()`,
+/// before checking the status, so 429 responses carry their `retry-after`.
+pub fn report(provider: LlmProvider, headers: &HeaderMap, tx: &Sender<StreamEvent>) {
+    if let Some(info) = parse(provider, headers, Utc::now()) {
+        let _ = tx.send(StreamEvent::RateLimit(info));
+    }
+}
+
+/// Rate-limit budget from response headers (None when the provider sent none)
+pub fn parse(provider: LlmProvider, headers: &HeaderMap, now: DateTime<Utc>) -> Option<RateLimitInfo> {
+    let get = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::trim);
+    let number = |name: &str| get(name).and_then(|v| v.parse::<u64>().ok());
+    let reset = |name: &str| get(name).and_then(|v| until_ms(v, now));
+
+    let anthropic = |kind: &str, field: &str| format!("anthropic-ratelimit-{}-{}", kind, field);
+    let openai = |field: &str, kind: &str| format!("x-ratelimit-{}-{}", field, kind);
+
+    let (requests_limit, requests_remaining, requests_reset) = (
+        number(&anthropic("requests", "limit")).or_else(|| number(&openai("limit", "requests"))),
+        number(&anthropic("requests", "remaining")).or_else(|| number(&openai("remaining", "requests"))),
+        reset(&anthropic("requests", "reset")).or_else(|| reset(&openai("reset", "requests"))),
+    );
+    // Anthropic splits tokens into input/output on some tiers; input is what prompts spend
+    let tokens_kind = if get(&anthropic("tokens", "limit")).is_some() { "tokens" } else { "input-tokens" };
+    let (tokens_limit, tokens_remaining, tokens_reset) = (
+        number(&anthropic(tokens_kind, "limit")).or_else(|| number(&openai("limit", "tokens"))),
+        number(&anthropic(tokens_kind, "remaining")).or_else(|| number(&openai("remaining", "tokens"))),
+        reset(&anthropic(tokens_kind, "reset")).or_else(|| reset(&openai("reset", "tokens"))),
+    );
+
+    let retry_after_ms = number("retry-after-ms").or_else(|| get("retry-after").and_then(|v| until_ms(v, now)));
+
+    let info = RateLimitInfo {
+        provider,
+        requests_limit,
+        requests_remaining,
+        tokens_limit,
+        tokens_remaining,
+        reset_after_ms: requests_reset.max(tokens_reset),
+        retry_after_ms,
+    };
+    (info != RateLimitInfo { provider, ..Default::default() }).then_some(info)
+}
+
+/// Milliseconds until a header's point in time: seconds (`30`, `1.5`), a Go-style
+/// duration (`6m0s`, `20ms`), an RFC 3339 timestamp or an HTTP date.
+fn until_ms(value: &str, now: DateTime<Utc>) -> Option<u64> {
+    if let Ok(secs) = value.parse::<f64>() {
+        return (secs >= 0.0).then(|| (secs * 1000.0).ceil() as u64);
+    }
+    if let Some(ms) = duration_ms(value) {
+        return Some(ms);
+    }
+    let at = DateTime::parse_from_rfc3339(value).or_else(|_| DateTime::parse_from_rfc2822(value)).ok()?;
+    Some((at.with_timezone(&Utc) - now).num_milliseconds().max(0) as u64)
+}
+
+/// Parse `1h2m3.5s` / `20ms` style durations
+fn duration_ms(value: &str) -> Option<u64> {
+    let mut total = 0.0;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let split = rest.find(|c: char| !(c.is_ascii_digit() || c == '.'))?;
+        let amount: f64 = rest[..split].parse().ok()?;
+        rest = &rest[split..];
+        let unit_len = rest.find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len());
+        let scale = match &rest[..unit_len] {
+            "h" => 3_600_000.0,
+            "m" => 60_000.0,
+            "s" => 1_000.0,
+            "ms" => 1.0,
+            _ => return None,
+        };
+        total += amount * scale;
+        rest = &rest[unit_len..];
+    }
+    Some(total.ceil() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderName, HeaderValue};
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(HeaderName::from_static(name), HeaderValue::from_str(value).unwrap());
+        }
+        map
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2025-06-01T12:00:00Z").unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn anthropic_headers() {
+        let map = headers(&[
+            ("anthropic-ratelimit-requests-limit", "50"),
+            ("anthropic-ratelimit-requests-remaining", "49"),
+            ("anthropic-ratelimit-requests-reset", "2025-06-01T12:00:01Z"),
+            ("anthropic-ratelimit-input-tokens-limit", "40000"),
+            ("anthropic-ratelimit-input-tokens-remaining", "0"),
+            ("anthropic-ratelimit-input-tokens-reset", "2025-06-01T12:00:30Z"),
+            ("retry-after", "30"),
+        ]);
+        let info = parse(LlmProvider::Anthropic, &map, now()).unwrap();
+        assert_eq!(info.requests_remaining, Some(49));
+        assert_eq!(info.tokens_limit, Some(40_000));
+        assert_eq!(info.tokens_remaining, Some(0));
+        assert_eq!(info.reset_after_ms, Some(30_000));
+        assert_eq!(info.retry_after_ms, Some(30_000));
+        assert!(info.is_exhausted());
+    }
+
+    #[test]
+    fn openai_headers() {
+        let map = headers(&[
+            ("x-ratelimit-limit-requests", "500"),
+            ("x-ratelimit-remaining-requests", "20"),
+            ("x-ratelimit-reset-requests", "1m30.5s"),
+            ("x-ratelimit-limit-tokens", "30000"),
+            ("x-ratelimit-remaining-tokens", "29000"),
+            ("x-ratelimit-reset-tokens", "20ms"),
+            ("retry-after-ms", "1500"),
+        ]);
+        let info = parse(LlmProvider::OpenAi, &map, now()).unwrap();
+        assert_eq!(info.requests_limit, Some(500));
+        assert_eq!(info.reset_after_ms, Some(90_500));
+        assert_eq!(info.retry_after_ms, Some(1_500));
+        assert!(info.is_low(0.1), "20 of 500 requests left");
+        assert!(!info.is_exhausted());
+    }
+
+    #[test]
+    fn retry_after_http_date_and_missing_headers() {
+        let map = headers(&[("retry-after", "Sun, 01 Jun 2025 12:00:05 GMT")]);
+        assert_eq!(parse(LlmProvider::Grok, &map, now()).unwrap().retry_after_ms, Some(5_000));
+        assert_eq!(parse(LlmProvider::Grok, &HeaderMap::new(), now()), None);
+    }
+}
@@ -0,0 +1,390 @@
+//! Record/replay provider for deterministic sessions and tests.
+//!
+//! A cassette is a JSON Lines file with one LLM turn per line, each holding the
+//! stream events the provider emitted:
+//!
+//! ```text
+//! {"model":"claude-sonnet-4-5","events":[{"type":"tool_use","id":"t1","name":"Open","input":{"path":"a.rs"}},{"type":"done","input_tokens":812,"output_tokens":40,"stop_reason":"tool_use"}]}
+//! {"events":[{"type":"text","text":"Done."}]}
+//! ```
+//!
+//! `ReplayClient` (the `replay` provider) plays turns back in order, one per
+//! `stream()` call, from `REPLAY_CASSETTE`. Setting `LLM_RECORD_CASSETTE` wraps
+//! every other provider in a `RecordingClient` that appends each stream it
+//! forwards to that file.
+
+use std::collections::HashMap;
+use std::env;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{LazyLock, Mutex};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::error::LlmError;
+use super::{ApiCheckResult, LlmClient, LlmProvider, LlmRequest, RateLimitInfo, StreamEvent};
+use crate::infra::tools::ToolUse;
+
+/// Cassette played by the replay provider when `REPLAY_CASSETTE` is unset
+const DEFAULT_CASSETTE_PATH: &str = ".context-pilot/cassette.jsonl";
+
+/// Next turn to play, per cassette file. Shared across clients since a new
+/// client is created for every stream.
+static CURSORS: LazyLock<Mutex<HashMap<PathBuf, usize>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Serializable mirror of `StreamEvent`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CassetteEvent {
+    Text {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        #[serde(default)]
+        input: Value,
+    },
+    Thinking {
+        thinking: String,
+        signature: String,
+    },
+    RedactedThinking {
+        data: String,
+    },
+    Done {
+        #[serde(default)]
+        input_tokens: usize,
+        #[serde(default)]
+        output_tokens: usize,
+        #[serde(default)]
+        cache_hit_tokens: usize,
+        #[serde(default)]
+        cache_miss_tokens: usize,
+        #[serde(default)]
+        stop_reason: Option<String>,
+    },
+    Failover {
+        fallback: usize,
+        provider: LlmProvider,
+        model: String,
+        error: String,
+    },
+    RateLimit(RateLimitInfo),
+    Error {
+        message: String,
+    },
+}
+
+impl From<&StreamEvent> for CassetteEvent {
+    fn from(event: &StreamEvent) -> Self {
+        match event {
+            StreamEvent::Chunk(text) => CassetteEvent::Text { text: text.clone() },
+            StreamEvent::ToolUse(tool) => {
+                CassetteEvent::ToolUse { id: tool.id.clone(), name: tool.name.clone(), input: tool.input.clone() }
+            }
+            StreamEvent::Thinking { thinking, signature } => {
+                CassetteEvent::Thinking { thinking: thinking.clone(), signature: signature.clone() }
+            }
+            StreamEvent::RedactedThinking { data } => CassetteEvent::RedactedThinking { data: data.clone() },
+            StreamEvent::Done { input_tokens, output_tokens, cache_hit_tokens, cache_miss_tokens, stop_reason } => {
+                CassetteEvent::Done {
+                    input_tokens: *input_tokens,
+                    output_tokens: *output_tokens,
+                    cache_hit_tokens: *cache_hit_tokens,
+                    cache_miss_tokens: *cache_miss_tokens,
+                    stop_reason: stop_reason.clone(),
+                }
+            }
+            StreamEvent::Failover { fallback, provider, model, error } => CassetteEvent::Failover {
+                fallback: *fallback,
+                provider: *provider,
+                model: model.clone(),
+                error: error.clone(),
+            },
+            StreamEvent::RateLimit(info) => CassetteEvent::RateLimit(info.clone()),
+            StreamEvent::Error(message) => CassetteEvent::Error { message: message.clone() },
+        }
+    }
+}
+
+impl From<CassetteEvent> for StreamEvent {
+    fn from(event: CassetteEvent) -> Self {
+        match event {
+            CassetteEvent::Text { text } => StreamEvent::Chunk(text),
+            CassetteEvent::ToolUse { id, name, input } => StreamEvent::ToolUse(ToolUse { id, name, input }),
+            CassetteEvent::Thinking { thinking, signature } => StreamEvent::Thinking { thinking, signature },
+            CassetteEvent::RedactedThinking { data } => StreamEvent::RedactedThinking { data },
+            CassetteEvent::Done { input_tokens, output_tokens, cache_hit_tokens, cache_miss_tokens, stop_reason } => {
+                StreamEvent::Done { input_tokens, output_tokens, cache_hit_tokens, cache_miss_tokens, stop_reason }
+            }
+            CassetteEvent::Failover { fallback, provider, model, error } => {
+                StreamEvent::Failover { fallback, provider, model, error }
+            }
+            CassetteEvent::RateLimit(info) => StreamEvent::RateLimit(info),
+            CassetteEvent::Error { message } => StreamEvent::Error(message),
+        }
+    }
+}
+
+/// One recorded LLM turn
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CassetteTurn {
+    /// Model the turn was recorded with (informational)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub model: String,
+    pub events: Vec<CassetteEvent>,
+}
+
+/// Parse a cassette. Blank lines are ignored.
+pub fn parse_cassette(content: &str) -> Result<Vec<CassetteTurn>, String> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| serde_json::from_str(line).map_err(|e| format!("line {}: {}", i + 1, e)))
+        .collect()
+}
+
+/// Append one turn to a cassette, creating the file (and its directory) if needed
+pub fn append_turn(path: &Path, turn: &CassetteTurn) -> std::io::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let line = serde_json::to_string(turn).map_err(std::io::Error::other)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// Cassette the recorder writes to (`LLM_RECORD_CASSETTE`), if recording is on
+pub fn record_path() -> Option<PathBuf> {
+    dotenvy::dotenv().ok();
+    env::var("LLM_RECORD_CASSETTE").ok().filter(|p| !p.trim().is_empty()).map(PathBuf::from)
+}
+
+/// Plays back a cassette, one turn per `stream()` call
+pub struct ReplayClient {
+    path: PathBuf,
+}
+
+impl ReplayClient {
+    pub fn new() -> Self {
+        dotenvy::dotenv().ok();
+        let path = env::var("REPLAY_CASSETTE")
+            .ok()
+            .filter(|p| !p.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_CASSETTE_PATH.to_string());
+        Self::with_path(path)
+    }
+
+    pub fn with_path(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    fn load(&self) -> Result<Vec<CassetteTurn>, LlmError> {
+        let content = std::fs::read_to_string(&self.path)
+            .map_err(|e| LlmError::StreamRead(format!("cannot read cassette {}: {}", self.path.display(), e)))?;
+        parse_cassette(&content).map_err(|e| LlmError::StreamRead(format!("{}: {}", self.path.display(), e)))
+    }
+
+    /// Take the next unplayed turn
+    fn next_turn(&self) -> Result<CassetteTurn, LlmError> {
+        let mut turns = self.load()?;
+        let mut cursors = CURSORS.lock().unwrap_or_else(|e| e.into_inner());
+        let cursor = cursors.entry(self.path.clone()).or_insert(0);
+        if *cursor >= turns.len() {
+            return Err(LlmError::StreamRead(format!(
+                "cassette {} exhausted after {} turns",
+                self.path.display(),
+                turns.len()
+            )));
+        }
+        let turn = turns.swap_remove(*cursor);
+        *cursor += 1;
+        Ok(turn)
+    }
+}
+
+impl Default for ReplayClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LlmClient for ReplayClient {
+    fn stream(&self, _request: LlmRequest, tx: Sender<StreamEvent>) -> Result<(), LlmError> {
+        let turn = self.next_turn()?;
+        // Hand-written cassettes may leave out the terminating event
+        let terminated =
+            turn.events.iter().any(|e| matches!(e, CassetteEvent::Done { .. } | CassetteEvent::Error { .. }));
+        for event in turn.events {
+            let _ = tx.send(event.into());
+        }
+        if !terminated {
+            let _ = tx.send(StreamEvent::Done {
+                input_tokens: 0,
+                output_tokens: 0,
+                cache_hit_tokens: 0,
+                cache_miss_tokens: 0,
+                stop_reason: Some("end_turn".to_string()),
+            });
+        }
+        Ok(())
+    }
+
+    fn check_api(&self, _model: &str) -> ApiCheckResult {
+        match self.load() {
+            Ok(_) => ApiCheckResult { auth_ok: true, streaming_ok: true, tools_ok: true, error: None },
+            Err(e) => {
+                ApiCheckResult { auth_ok: false, streaming_ok: false, tools_ok: false, error: Some(e.to_string()) }
+            }
+        }
+    }
+}
+
+/// Forwards a real client's stream unchanged while appending it to a cassette
+pub struct RecordingClient {
+    inner: Box<dyn LlmClient>,
+    path: PathBuf,
+}
+
+impl RecordingClient {
+    pub fn new(inner: Box<dyn LlmClient>, path: PathBuf) -> Self {
+        Self { inner, path }
+    }
+}
+
+impl LlmClient for RecordingClient {
+    fn stream(&self, request: LlmRequest, tx: Sender<StreamEvent>) -> Result<(), LlmError> {
+        let model = request.model.clone();
+        let (inner_tx, inner_rx) = mpsc::channel::<StreamEvent>();
+        let forwarder = std::thread::spawn(move || {
+            let mut events = Vec::new();
+            for event in inner_rx {
+                events.push(CassetteEvent::from(&event));
+                let _ = tx.send(event);
+            }
+            events
+        });
+
+        // `inner_tx` is dropped when the inner stream returns, which ends the forwarder
+        let result = self.inner.stream(request, inner_tx);
+        let mut events = forwarder.join().unwrap_or_default();
+        if let Err(e) = &result {
+            // start_streaming turns this into an Error event; replay it the same way
+            events.push(CassetteEvent::Error { message: e.to_string() });
+        }
+        let _ = append_turn(&self.path, &CassetteTurn { model, events });
+        result
+    }
+
+    fn check_api(&self, model: &str) -> ApiCheckResult {
+        self.inner.check_api(model)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> LlmRequest {
+        LlmRequest {
+            model: "test-model".to_string(),
+            max_output_tokens: 1024,
+            thinking_budget: None,
+            messages: Vec::new(),
+            context_items: Vec::new(),
+            tools: Vec::new(),
+            tool_results: None,
+            system_prompt: None,
+            extra_context: None,
+            seed_content: None,
+            worker_id: "main_worker".to_string(),
+            api_messages: Vec::new(),
+        }
+    }
+
+    fn cassette(name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("cp_replay_{}_{}.jsonl", name, std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn play(client: &dyn LlmClient) -> Vec<StreamEvent> {
+        let (tx, rx) = mpsc::channel();
+        if let Err(e) = client.stream(request(), tx.clone()) {
+            let _ = tx.send(StreamEvent::Error(e.to_string()));
+        }
+        drop(tx);
+        rx.into_iter().collect()
+    }
+
+    #[test]
+    fn multi_turn_tool_flow_replays_in_order() {
+        let path = cassette(
+            "tool_flow",
+            concat!(
+                r#"{"events":[{"type":"text","text":"Opening."},{"type":"tool_use","id":"t1","name":"Open","input":{"path":"a.rs"}},{"type":"done","input_tokens":10,"output_tokens":5,"stop_reason":"tool_use"}]}"#,
+                "\n\n",
+                r#"{"events":[{"type":"text","text":"Done."}]}"#,
+                "\n",
+            ),
+        );
+        let client = ReplayClient::with_path(&path);
+
+        let first = play(&client);
+        assert!(matches!(&first[0], StreamEvent::Chunk(t) if t == "Opening."));
+        assert!(matches!(&first[1], StreamEvent::ToolUse(t) if t.name == "Open" && t.input["path"] == "a.rs"));
+        assert!(matches!(&first[2], StreamEvent::Done { stop_reason: Some(r), .. } if r == "tool_use"));
+
+        // A fresh client (as get_client creates per stream) continues where the last left off
+        let second = play(&ReplayClient::with_path(&path));
+        assert!(matches!(&second[0], StreamEvent::Chunk(t) if t == "Done."));
+        assert!(matches!(&second[1], StreamEvent::Done { stop_reason: Some(r), .. } if r == "end_turn"));
+
+        let third = play(&client);
+        assert!(matches!(&third[..], [StreamEvent::Error(e)] if e.contains("exhausted after 2 turns")));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn parse_errors_name_the_line() {
+        let err = parse_cassette("{\"events\":[]}\n{\"events\": [{\"type\":\"bogus\"}]}").unwrap_err();
+        assert!(err.starts_with("line 2:"), "{}", err);
+    }
+
+    struct FakeClient;
+
+    impl LlmClient for FakeClient {
+        fn stream(&self, _request: LlmRequest, tx: Sender<StreamEvent>) -> Result<(), LlmError> {
+            let _ = tx.send(StreamEvent::Chunk("hi".to_string()));
+            let _ = tx.send(StreamEvent::Thinking { thinking: "hm".to_string(), signature: "sig".to_string() });
+            Err(LlmError::Network("connection reset".to_string()))
+        }
+
+        fn check_api(&self, _model: &str) -> ApiCheckResult {
+            ApiCheckResult { auth_ok: true, streaming_ok: true, tools_ok: true, error: None }
+        }
+    }
+
+    #[test]
+    fn recording_round_trips_through_replay() {
+        let path = std::env::temp_dir().join(format!("cp_replay_record_{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let recorded = play(&RecordingClient::new(Box::new(FakeClient), path.clone()));
+        assert_eq!(recorded.len(), 3);
+
+        let turns = parse_cassette(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(turns.len(), 1);
+        assert_eq!(turns[0].model, "test-model");
+
+        let replayed = play(&ReplayClient::with_path(&path));
+        let as_cassette = |events: &[StreamEvent]| events.iter().map(CassetteEvent::from).collect::<Vec<_>>();
+        assert_eq!(as_cassette(&replayed), as_cassette(&recorded));
+        let _ = std::fs::remove_file(path);
+    }
+}
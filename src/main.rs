@@ -25,13 +25,15 @@ fn main() -> io::Result<()> {
     let args: Vec<String> = std::env::args().collect();
     let resume_stream = args.iter().any(|a| a == "--resume-stream");
 
-    // Handle typst subcommands (used by callback scripts)
+    // Handle subcommands (typst ones are used by callback scripts)
     if args.len() >= 2 {
         match args[1].as_str() {
             // Compile a .typ → .pdf in the same directory
             "typst-compile" => return run_typst_compile(&args[2..]),
             // Recompile watched documents whose dependencies changed
             "typst-recompile-watched" => return run_typst_recompile_watched(&args[2..]),
+            // Aggregate the cost ledger
+            "costs" => return run_costs(&args[2..]),
             _ => {}
         }
     }
@@ -70,6 +72,9 @@ fn main() -> io::Result<()> {
     // Set callback hooks for extracted module crates
     state.highlight_fn = Some(ui::helpers::highlight_file);
 
+    // Count tokens with the vocabulary of the selected model family
+    cp_base::tokenizer::select_for_model(state.llm_provider, &state.current_model());
+
     // Validate module dependencies at startup
     modules::validate_dependencies(&state.active_modules);
 
@@ -191,3 +196,97 @@ fn run_typst_recompile_watched(args: &[String]) -> io::Result<()> {
     }
     Ok(())
 }
+
+/// Print spend from the cost ledger, grouped by day, month, model, task or project.
+/// Extra ledger paths (e.g. other projects' `.context-pilot/ledger.jsonl`) are merged in.
+/// Usage: cpilot costs [--by day|month|model|task|project] [--since YYYY-MM-DD] [--until YYYY-MM-DD] [--csv] [ledger ...]
+fn run_costs(args: &[String]) -> io::Result<()> {
+    use state::persistence::ledger::{self, GroupBy};
+
+    const USAGE: &str = "Usage: cpilot costs [--by day|month|model|task|project] [--since YYYY-MM-DD] \
+                         [--until YYYY-MM-DD] [--csv] [ledger ...]";
+    let fail = |msg: &str| -> ! {
+        eprintln!("{}\n{}", msg, USAGE);
+        std::process::exit(2);
+    };
+    let date = |value: Option<&String>| {
+        let value = value.unwrap_or_else(|| fail("Missing date"));
+        chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap_or_else(|_| fail(&format!("Bad date '{}'", value)))
+    };
+
+    let mut by = GroupBy::Day;
+    let (mut since, mut until, mut csv) = (None, None, false);
+    let mut paths: Vec<std::path::PathBuf> = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--by" => {
+                let value = iter.next().map(String::as_str).unwrap_or("");
+                by = GroupBy::parse(value).unwrap_or_else(|| fail(&format!("Unknown grouping '{}'", value)));
+            }
+            "--since" => since = Some(date(iter.next())),
+            "--until" => until = Some(date(iter.next())),
+            "--csv" => csv = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            flag if flag.starts_with("--") => fail(&format!("Unknown option '{}'", flag)),
+            path => paths.push(path.into()),
+        }
+    }
+    if paths.is_empty() {
+        paths.push(ledger::ledger_path());
+    }
+
+    let mut entries = Vec::new();
+    for path in &paths {
+        match ledger::load(path) {
+            Ok(loaded) => entries.extend(loaded),
+            Err(e) => {
+                eprintln!("Cannot read {}: {}", path.display(), e);
+                std::process::exit(1);
+            }
+        }
+    }
+    let rows = ledger::aggregate(&ledger::filter_dates(entries, since, until), by);
+
+    if csv {
+        print!("{}", ledger::to_csv(by, &rows));
+        return Ok(());
+    }
+    if rows.is_empty() {
+        println!("No spend recorded.");
+        return Ok(());
+    }
+    let width = rows.iter().map(|r| r.key.chars().count()).max().unwrap_or(0).max(5);
+    println!(
+        "{:<width$}  {:>6}  {:>12}  {:>12}  {:>12}  {:>10}  {:>10}",
+        "", "calls", "cache hit", "cache miss", "input", "output", "cost"
+    );
+    for row in &rows {
+        println!(
+            "{:<width$}  {:>6}  {:>12}  {:>12}  {:>12}  {:>10}  {:>10}",
+            row.key,
+            row.calls,
+            row.cache_hit_tokens,
+            row.cache_miss_tokens,
+            row.input_tokens,
+            row.output_tokens,
+            format!("${:.2}", row.cost)
+        );
+    }
+    let total: f64 = rows.iter().map(|r| r.cost).sum();
+    let calls: usize = rows.iter().map(|r| r.calls).sum();
+    println!(
+        "{:<width$}  {:>6}  {:>12}  {:>12}  {:>12}  {:>10}  {:>10}",
+        "total",
+        calls,
+        "",
+        "",
+        "",
+        "",
+        format!("${:.2}", total)
+    );
+    Ok(())
+}
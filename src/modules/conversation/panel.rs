@@ -31,12 +31,19 @@ impl ConversationPanel {
         };
         let tool_uses_len = msg.tool_uses.len();
         let tool_results_len = msg.tool_results.len();
+        let thinking_len = msg.thinking_blocks.len();
 
         hash_values(&[
             msg.content.as_str(),
             &format!(
-                "{}{}{}{}{}{}",
-                status_num, viewport_width, dev_mode as u8, tool_uses_len, tool_results_len, msg.input_tokens
+                "{}{}{}{}{}{}{}",
+                status_num,
+                viewport_width,
+                dev_mode as u8,
+                tool_uses_len,
+                tool_results_len,
+                thinking_len,
+                msg.input_tokens
             ),
         ])
     }
@@ -68,6 +75,7 @@ impl ConversationPanel {
             std::hash::Hash::hash(&msg.status, &mut hasher);
             std::hash::Hash::hash(&msg.tool_uses.len(), &mut hasher);
             std::hash::Hash::hash(&msg.tool_results.len(), &mut hasher);
+            std::hash::Hash::hash(&msg.thinking_blocks.len(), &mut hasher);
             std::hash::Hash::hash(&msg.input_tokens, &mut hasher);
         }
 
@@ -1,7 +1,7 @@
 use ratatui::prelude::*;
 
 use crate::infra::constants::icons;
-use crate::state::{Message, MessageStatus, MessageType};
+use crate::state::{Message, MessageStatus, MessageType, ThinkingBlock};
 use crate::ui::{helpers::wrap_text, markdown::*, theme};
 
 use std::collections::HashMap;
@@ -152,6 +152,11 @@ pub(crate) fn render_message(
     let prefix_width = prefix.chars().count();
     let wrap_width = (viewport_width as usize).saturating_sub(prefix_width + 2).max(20);
 
+    // Extended thinking stays collapsed: one summary line above the response
+    if !msg.thinking_blocks.is_empty() {
+        lines.push(thinking_summary_line(msg, prefix_width, base_style));
+    }
+
     if content.trim().is_empty() {
         if msg.role == "assistant" && is_streaming_this {
             lines.push(Line::from(vec![
@@ -278,4 +283,21 @@ pub(crate) fn render_message(
     lines
 }
 
+/// Collapsed summary of a message's thinking blocks, e.g. "▸ Thinking (1.2K tokens, 1 redacted)"
+fn thinking_summary_line(msg: &Message, prefix_width: usize, base_style: Style) -> Line<'static> {
+    let redacted = msg.thinking_blocks.iter().filter(|b| matches!(b, ThinkingBlock::RedactedThinking { .. })).count();
+    let tokens = msg.thinking_token_count;
+    let tokens_str =
+        if tokens >= 1_000 { format!("{:.1}K tokens", tokens as f64 / 1_000.0) } else { format!("{} tokens", tokens) };
+    let detail = match (tokens, redacted) {
+        (_, 0) => tokens_str,
+        (0, n) => format!("{} redacted", n),
+        (_, n) => format!("{}, {} redacted", tokens_str, n),
+    };
+    Line::from(vec![
+        Span::styled(" ".repeat(prefix_width), base_style),
+        Span::styled(format!("▸ Thinking ({})", detail), Style::default().fg(theme::text_muted()).italic()),
+    ])
+}
+
 pub(super) use super::render_input::render_input;
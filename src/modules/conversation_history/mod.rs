@@ -1,7 +1,8 @@
 mod panel;
+pub mod summary;
 
 use crate::app::panels::Panel;
-use crate::infra::tools::{ToolDefinition, ToolResult, ToolUse};
+use crate::infra::tools::{ParamType, ToolDefinition, ToolParam, ToolResult, ToolUse};
 use crate::state::{ContextType, ContextTypeMeta, State};
 
 use self::panel::ConversationHistoryPanel;
@@ -67,10 +68,55 @@ impl Module for ConversationHistoryModule {
     }
 
     fn tool_definitions(&self) -> Vec<ToolDefinition> {
-        vec![]
+        vec![ToolDefinition {
+            id: "Expand_conversation_history".to_string(),
+            name: "Expand Conversation History".to_string(),
+            short_desc: "Show a summarized history chunk verbatim".to_string(),
+            description: "Swaps a summarized conversation history panel back to its full, verbatim messages. \
+                Use when the summary lacks a detail you need. Pass collapse=true to return to the summary \
+                once you are done, so the chunk stops costing its full token weight."
+                .to_string(),
+            params: vec![
+                ToolParam::new("id", ParamType::String)
+                    .desc("ID of the conversation history panel (e.g., 'P12')")
+                    .required(),
+                ToolParam::new("collapse", ParamType::Boolean)
+                    .desc("Collapse back to the summary instead of expanding (default: false)"),
+            ],
+            enabled: true,
+            reverie_allowed: true,
+            category: "Context".to_string(),
+        }]
     }
 
-    fn execute_tool(&self, _tool: &ToolUse, _state: &mut State) -> Option<ToolResult> {
-        None
+    fn execute_tool(&self, tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+        match tool.name.as_str() {
+            "Expand_conversation_history" => Some(execute_expand(tool, state)),
+            _ => None,
+        }
     }
 }
+
+fn execute_expand(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let Some(id) = tool.input.get("id").and_then(|v| v.as_str()) else {
+        return ToolResult::new(tool.id.clone(), "Missing 'id' parameter".to_string(), true);
+    };
+    let collapse = tool.input.get("collapse").and_then(|v| v.as_bool()).unwrap_or(false);
+
+    let Some(ctx) =
+        state.context.iter_mut().find(|c| c.id == id && c.context_type == ContextType::CONVERSATION_HISTORY)
+    else {
+        return ToolResult::new(tool.id.clone(), format!("Conversation history panel '{}' not found", id), true);
+    };
+    if !ctx.metadata.contains_key(summary::SUMMARY_META) {
+        return ToolResult::new(tool.id.clone(), format!("{} has no summary — it is already verbatim", id), true);
+    }
+
+    ctx.set_meta(summary::EXPANDED_META, &!collapse);
+    summary::refresh_content(ctx);
+    ctx.last_refresh_ms = crate::app::panels::now_ms();
+    let view = if collapse { "summary" } else { "verbatim messages" };
+    let message = format!("{} now shows its {} ({} tokens)", id, view, ctx.token_count);
+    state.dirty = true;
+    ToolResult::new(tool.id.clone(), message, false)
+}
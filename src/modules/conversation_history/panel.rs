@@ -6,7 +6,8 @@ use crate::state::{ContextType, State};
 use crate::ui::theme;
 
 /// Panel for frozen conversation history chunks.
-/// Content is set once at creation (via detach_conversation_chunks) and never refreshed.
+/// Content is set at creation (via detach_conversation_chunks) and only swapped
+/// between the verbatim chunk and its summary (see `summary::refresh_content`).
 pub struct ConversationHistoryPanel;
 
 impl Panel for ConversationHistoryPanel {
@@ -48,6 +49,17 @@ impl Panel for ConversationHistoryPanel {
             }
         };
 
+        // Summary the LLM sees in place of the messages (unless expanded)
+        if let Some(summary) = ctx.get_meta_str(super::summary::SUMMARY_META) {
+            let expanded = ctx.get_meta::<bool>(super::summary::EXPANDED_META) == Some(true);
+            let header = if expanded { "Summary (expanded — LLM sees the messages)" } else { "Summary (LLM view)" };
+            lines.push(Line::from(vec![Span::styled(header.to_string(), Style::default().fg(theme::accent()).bold())]));
+            for line in summary.lines() {
+                lines.push(Line::from(vec![Span::styled(line.to_string(), base_style.fg(theme::text()))]));
+            }
+            lines.push(Line::from(""));
+        }
+
         // Prefer rendering from history_messages (full formatting with icons/markdown)
         if let Some(ref msgs) = ctx.history_messages {
             for msg in msgs {
@@ -0,0 +1,172 @@
+//! Summary view of detached history chunks.
+//!
+//! When history summaries are enabled, the secondary model condenses each chunk
+//! into a short recap stored in the panel's `history_summary` metadata. The panel
+//! then shows the recap (plus which tools ran and which files changed) instead of
+//! the verbatim messages, which stay on disk and can be re-expanded on demand.
+
+use std::collections::BTreeMap;
+
+use cp_mod_callback::trigger::tool_file_paths;
+
+use crate::state::{
+    ContextElement, ContextType, Message, MessageType, compute_total_pages, estimate_tokens, format_messages_to_chunk,
+};
+
+/// Metadata key holding the LLM-written summary
+pub const SUMMARY_META: &str = "history_summary";
+/// Metadata flag: show the verbatim chunk even though a summary exists
+pub const EXPANDED_META: &str = "history_expanded";
+/// Metadata flag: summarization failed, don't retry this chunk
+pub const FAILED_META: &str = "history_summary_failed";
+
+/// What happened in a chunk, derived from its tool calls
+#[derive(Debug, Default, PartialEq)]
+pub struct ChunkActivity {
+    /// Tool name → number of calls, sorted by name
+    pub tools: BTreeMap<String, usize>,
+    /// Files modified by successful edit/write calls, in first-touched order
+    pub files: Vec<String>,
+}
+
+impl ChunkActivity {
+    pub fn from_messages(messages: &[Message]) -> Self {
+        let failed: Vec<&str> = messages
+            .iter()
+            .flat_map(|m| &m.tool_results)
+            .filter(|r| r.is_error)
+            .map(|r| r.tool_use_id.as_str())
+            .collect();
+
+        let mut activity = Self::default();
+        for tool_use in messages.iter().filter(|m| m.message_type == MessageType::ToolCall).flat_map(|m| &m.tool_uses) {
+            *activity.tools.entry(tool_use.name.clone()).or_default() += 1;
+            if failed.contains(&tool_use.id.as_str()) {
+                continue;
+            }
+            for path in tool_file_paths(&tool_use.name, &tool_use.input) {
+                if !activity.files.iter().any(|f| f == path) {
+                    activity.files.push(path.to_string());
+                }
+            }
+        }
+        activity
+    }
+
+    /// "Tools run" / "Files changed" lines appended to a summary
+    pub fn render(&self) -> String {
+        let tools = if self.tools.is_empty() {
+            "none".to_string()
+        } else {
+            self.tools.iter().map(|(name, n)| format!("{} ×{}", name, n)).collect::<Vec<_>>().join(", ")
+        };
+        let files = if self.files.is_empty() { "none".to_string() } else { self.files.join(", ") };
+        format!("Tools run: {}\nFiles changed: {}\n", tools, files)
+    }
+}
+
+/// Whether a history panel still needs a summary
+pub fn needs_summary(ctx: &ContextElement) -> bool {
+    ctx.context_type == ContextType::CONVERSATION_HISTORY
+        && ctx.history_messages.as_ref().is_some_and(|m| !m.is_empty())
+        && !ctx.metadata.contains_key(SUMMARY_META)
+        && !ctx.metadata.contains_key(FAILED_META)
+}
+
+/// Text shown to the LLM when the chunk is collapsed to its summary
+fn summary_view(ctx: &ContextElement, summary: &str, messages: &[Message]) -> String {
+    format!(
+        "Summary of {} archived messages (call Expand_conversation_history with id {} to read them verbatim)\n\n{}\n\n{}",
+        messages.len(),
+        ctx.id,
+        summary.trim(),
+        ChunkActivity::from_messages(messages).render()
+    )
+}
+
+/// Rebuild a history panel's LLM-facing content from its messages: the summary
+/// when one exists and the panel isn't expanded, the verbatim chunk otherwise.
+pub fn refresh_content(ctx: &mut ContextElement) {
+    let Some(messages) = ctx.history_messages.as_deref() else {
+        return;
+    };
+    let content = match ctx.get_meta_str(SUMMARY_META) {
+        Some(summary) if ctx.get_meta::<bool>(EXPANDED_META) != Some(true) => summary_view(ctx, summary, messages),
+        _ => format_messages_to_chunk(messages),
+    };
+    let token_count = estimate_tokens(&content);
+    ctx.total_pages = compute_total_pages(token_count);
+    ctx.current_page = ctx.current_page.min(ctx.total_pages.saturating_sub(1));
+    ctx.token_count = token_count;
+    ctx.full_token_count = token_count;
+    ctx.cached_content = Some(content);
+}
+
+/// Prompt asking the secondary model to summarize a chunk
+pub fn summarization_prompt(messages: &[Message]) -> String {
+    format!(
+        "Summarize the following archived conversation excerpt between a user and a coding assistant. \
+         Keep it under 200 words. Capture the user's requests and preferences, decisions made, \
+         what was done and its outcome, and anything left unfinished. Name files, functions and \
+         commands precisely. Reply with the summary only.\n\n<conversation>\n{}</conversation>",
+        format_messages_to_chunk(messages)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::message::test_helpers::MessageBuilder;
+    use serde_json::json;
+
+    fn history_panel(messages: Vec<Message>) -> ContextElement {
+        let mut ctx = crate::modules::make_default_context_element(
+            "P9",
+            ContextType::new(ContextType::CONVERSATION_HISTORY),
+            "Chat",
+            false,
+        );
+        ctx.history_messages = Some(messages);
+        ctx
+    }
+
+    #[test]
+    fn activity_counts_tools_and_successful_file_changes() {
+        let edit = MessageBuilder::tool_call("Edit", json!({"file_path": "src/a.rs"})).build();
+        let write = MessageBuilder::tool_call("Write", json!({"file_path": "src/b.rs"})).build();
+        let again = MessageBuilder::tool_call("Edit", json!({"file_path": "src/a.rs"})).build();
+        let open = MessageBuilder::tool_call("Open", json!({"path": "src/c.rs"})).build();
+        let mut failed = MessageBuilder::tool_result(&write.tool_uses[0].id, "no such dir").build();
+        failed.tool_results[0].is_error = true;
+
+        let activity = ChunkActivity::from_messages(&[edit, write, failed, again, open]);
+        assert_eq!(activity.tools.get("Edit"), Some(&2));
+        assert_eq!(activity.tools.get("Open"), Some(&1));
+        assert_eq!(activity.files, vec!["src/a.rs".to_string()]);
+        assert_eq!(activity.render(), "Tools run: Edit ×2, Open ×1, Write ×1\nFiles changed: src/a.rs\n");
+    }
+
+    #[test]
+    fn content_switches_between_summary_and_verbatim() {
+        let mut ctx = history_panel(vec![
+            MessageBuilder::user("please rename the flag everywhere").build(),
+            MessageBuilder::assistant("done, renamed in twelve places").build(),
+        ]);
+        assert!(needs_summary(&ctx));
+
+        refresh_content(&mut ctx);
+        assert!(ctx.cached_content.as_deref().unwrap().contains("[user]: please rename"));
+
+        ctx.set_meta(SUMMARY_META, &"Renamed the flag.");
+        assert!(!needs_summary(&ctx));
+        refresh_content(&mut ctx);
+        let summary = ctx.cached_content.clone().unwrap();
+        assert!(summary.contains("Renamed the flag.") && summary.contains("id P9"));
+        assert!(!summary.contains("[user]:"));
+        assert_eq!(ctx.token_count, estimate_tokens(&summary));
+
+        ctx.set_meta(EXPANDED_META, &true);
+        refresh_content(&mut ctx);
+        assert!(ctx.cached_content.as_deref().unwrap().contains("[user]: please rename"));
+    }
+}
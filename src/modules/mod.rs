@@ -22,7 +22,9 @@ pub use cp_mod_memory::MemoryModule;
 pub use cp_mod_preset::PresetModule;
 pub use cp_mod_prompt::PromptModule;
 pub use cp_mod_scratchpad::ScratchpadModule;
+pub use cp_mod_search::SearchModule;
 pub use cp_mod_spine::SpineModule;
+pub use cp_mod_symbols::SymbolsModule;
 pub use cp_mod_todo::TodoModule;
 pub use cp_mod_tree::TreeModule;
 pub use cp_mod_typst::TypstModule;
@@ -80,6 +82,8 @@ pub fn all_modules() -> Vec<Box<dyn Module>> {
         Box::new(PromptModule),
         Box::new(FilesModule),
         Box::new(TreeModule),
+        Box::new(SymbolsModule),
+        Box::new(SearchModule),
         Box::new(GitModule),
         Box::new(GithubModule),
         Box::new(ConsoleModule),
@@ -137,6 +141,9 @@ pub fn dispatch_tool(tool: &ToolUse, state: &mut State, active_modules: &HashSet
         {
             // Ensure tool_name is set for visualization dispatch
             result.tool_name = tool.name.clone();
+            if !result.is_error {
+                notify_path_moves(tool, state, active_modules);
+            }
             return result;
         }
     }
@@ -149,6 +156,17 @@ pub fn dispatch_tool(tool: &ToolUse, state: &mut State, active_modules: &HashSet
     }
 }
 
+/// Let modules that keep paths in their state follow what a tool moved or deleted
+fn notify_path_moves(tool: &ToolUse, state: &mut State, active_modules: &HashSet<String>) {
+    for (from, to) in cp_mod_files::paths::moved_paths(&tool.name, &tool.input) {
+        for module in all_modules() {
+            if active_modules.contains(module.id()) {
+                module.on_path_moved(&from, to.as_deref(), state);
+            }
+        }
+    }
+}
+
 /// Create a panel for the given context type by asking all modules.
 pub fn create_panel(context_type: &ContextType) -> Option<Box<dyn Panel>> {
     for module in all_modules() {
@@ -338,6 +356,27 @@ fn execute_module_toggle(tool: &ToolUse, state: &mut State) -> ToolResult {
     }
 }
 
+/// Rebuild the tools list from active modules and preserved disabled_tools.
+fn rebuild_tools(state: &mut State) {
+    // Preserve currently disabled tool IDs
+    let disabled: HashSet<String> = state.tools.iter().filter(|t| !t.enabled).map(|t| t.id.clone()).collect();
+
+    // Get fresh tool definitions from active modules
+    let mut tools = active_tool_definitions(&state.active_modules);
+
+    // Add the reverie's optimize_context tool (always available for main AI)
+    tools.push(crate::app::reverie::tools::optimize_context_tool_definition());
+
+    // Re-apply disabled state
+    for tool in &mut tools {
+        if tool.id != "tool_manage" && tool.id != "module_toggle" && disabled.contains(&tool.id) {
+            tool.enabled = false;
+        }
+    }
+
+    state.tools = tools;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -380,24 +419,3 @@ mod tests {
         assert!(result.is_ok());
     }
 }
-
-/// Rebuild the tools list from active modules and preserved disabled_tools.
-fn rebuild_tools(state: &mut State) {
-    // Preserve currently disabled tool IDs
-    let disabled: HashSet<String> = state.tools.iter().filter(|t| !t.enabled).map(|t| t.id.clone()).collect();
-
-    // Get fresh tool definitions from active modules
-    let mut tools = active_tool_definitions(&state.active_modules);
-
-    // Add the reverie's optimize_context tool (always available for main AI)
-    tools.push(crate::app::reverie::tools::optimize_context_tool_definition());
-
-    // Re-apply disabled state
-    for tool in &mut tools {
-        if tool.id != "tool_manage" && tool.id != "module_toggle" && disabled.contains(&tool.id) {
-            tool.enabled = false;
-        }
-    }
-
-    state.tools = tools;
-}
@@ -1,3 +1,4 @@
+use crate::app::eviction::{self, Priority};
 use crate::modules::all_modules;
 use crate::state::{State, estimate_tokens, get_context_type_meta};
 
@@ -30,6 +31,13 @@ pub fn estimate_tool_definitions_tokens(state: &State) -> usize {
     total
 }
 
+/// Estimated size of the full prompt: system prompt (sent twice), tool definitions and panels
+pub fn estimate_prompt_tokens(state: &State) -> usize {
+    let system_prompt = cp_mod_prompt::seed::get_active_agent_content(state);
+    let panel_tokens: usize = state.context.iter().map(|c| c.token_count).sum();
+    estimate_tokens(&system_prompt) * 2 + estimate_tool_definitions_tokens(state) + panel_tokens
+}
+
 /// Generates the plain-text/markdown context content sent to the LLM.
 /// This is separate from the TUI rendering (overview_render.rs).
 pub fn generate_context_content(state: &State) -> String {
@@ -52,6 +60,16 @@ pub fn generate_context_content(state: &State) -> String {
         total_tokens, threshold, budget, usage_pct
     );
 
+    let (tokenizer, calibration) = cp_base::tokenizer::describe();
+    if calibration.samples > 0 {
+        output.push_str(&format!(
+            "Tokenizer: {} (×{:.2} calibrated over {} requests)\n\n",
+            tokenizer, calibration.factor, calibration.samples
+        ));
+    } else {
+        output.push_str(&format!("Tokenizer: {} (uncalibrated)\n\n", tokenizer));
+    }
+
     let mut accumulated = 0usize;
 
     // --- Non-panel entries first: system prompt and tool definitions ---
@@ -89,15 +107,25 @@ pub fn generate_context_content(state: &State) -> String {
 
         accumulated += ctx.token_count;
 
+        let mut tags = match (eviction::is_pinned(ctx), eviction::priority(ctx)) {
+            (true, _) => " [pinned]".to_string(),
+            (false, Priority::Normal) => String::new(),
+            (false, p) => format!(" [{} priority]", p.as_str()),
+        };
+        if let Some(budget) = state.panel_budget(ctx) {
+            let overflow = ctx.full_token_count.saturating_sub(ctx.token_count);
+            tags.push_str(&format!(" [budget {}, {} over]", budget, overflow));
+        }
+
         if details.is_empty() {
             output.push_str(&format!(
-                "  {} {}: {} tokens {} {} (acc: {})\n",
-                ctx.id, type_name, ctx.token_count, cost, hit_miss, accumulated
+                "  {} {}{}: {} tokens {} {} (acc: {})\n",
+                ctx.id, type_name, tags, ctx.token_count, cost, hit_miss, accumulated
             ));
         } else {
             output.push_str(&format!(
-                "  {} {} ({}): {} tokens {} {} (acc: {})\n",
-                ctx.id, type_name, details, ctx.token_count, cost, hit_miss, accumulated
+                "  {} {} ({}){}: {} tokens {} {} (acc: {})\n",
+                ctx.id, type_name, details, tags, ctx.token_count, cost, hit_miss, accumulated
             ));
         }
     }
@@ -112,6 +140,32 @@ pub fn generate_context_content(state: &State) -> String {
         assistant_msgs
     ));
 
+    // Conversation branches (only once the conversation has been forked)
+    let branches = crate::state::persistence::branch::load_branches();
+    if !branches.branches.is_empty() {
+        output.push_str(&format!("\nConversation branches (live: {}):\n", branches.active));
+        for (depth, branch) in branches.tree() {
+            let forked_at =
+                branch.fork_message_id.as_deref().map(|id| format!(" (forked at {})", id)).unwrap_or_default();
+            output.push_str(&format!("  {}- {}{}\n", "  ".repeat(depth), branch.name, forked_at));
+        }
+    }
+
+    // Checkpoints (newest first), so restore targets can be named by ID
+    let checkpoints = crate::state::persistence::checkpoint::load_checkpoints();
+    if !checkpoints.checkpoints.is_empty() {
+        output.push_str("\nCheckpoints (newest first):\n");
+        for checkpoint in checkpoints.checkpoints.iter().rev().take(10) {
+            output.push_str(&format!(
+                "  {} {} ({} messages, {} panels)\n",
+                checkpoint.id,
+                checkpoint.label,
+                checkpoint.message_count,
+                checkpoint.panels.len()
+            ));
+        }
+    }
+
     // Module-specific overview sections (todos, memories, git status, etc.)
     for module in &modules {
         if let Some(section) = module.overview_context_section(state) {
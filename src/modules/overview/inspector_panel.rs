@@ -0,0 +1,176 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+
+use crate::app::actions::Action;
+use crate::app::panels::{ContextItem, Panel};
+use crate::app::prompt_inspector::{
+    BlockChange, BlockKind, INSPECT_MODE_META, InspectMode, PROMPT_INSPECTOR_TYPE, PromptCapture, captures_for, diff,
+};
+use crate::infra::constants::{SCROLL_ARROW_AMOUNT, SCROLL_PAGE_AMOUNT};
+use crate::state::State;
+use crate::ui::{
+    helpers::{Cell, format_time_ago, render_table},
+    theme,
+};
+
+pub struct PromptInspectorPanel;
+
+fn mode(state: &State) -> InspectMode {
+    state
+        .context
+        .iter()
+        .find(|c| c.context_type.as_str() == PROMPT_INSPECTOR_TYPE)
+        .and_then(|c| c.get_meta_str(INSPECT_MODE_META))
+        .and_then(InspectMode::parse)
+        .unwrap_or(InspectMode::Last)
+}
+
+fn kind_color(kind: BlockKind) -> Color {
+    match kind {
+        BlockKind::System | BlockKind::Seed => theme::accent(),
+        BlockKind::Tools => theme::accent_dim(),
+        BlockKind::Panel | BlockKind::PanelFooter => theme::text(),
+        BlockKind::Conversation => theme::success(),
+    }
+}
+
+fn heading(title: &str, detail: String, base_style: Style) -> Vec<Line<'static>> {
+    vec![
+        Line::from(vec![
+            Span::styled(" ".to_string(), base_style),
+            Span::styled(title.to_string(), Style::default().fg(theme::text_muted()).bold()),
+            Span::styled(format!("  {}", detail), Style::default().fg(theme::text_muted())),
+        ]),
+        Line::from(""),
+    ]
+}
+
+fn render_blocks(capture: &PromptCapture) -> Vec<Line<'static>> {
+    let header = [
+        Cell::right("#", Style::default()),
+        Cell::new("Block", Style::default()),
+        Cell::right("Tokens", Style::default()),
+        Cell::right("Msgs", Style::default()),
+        Cell::new("Cache", Style::default()),
+    ];
+    let rows: Vec<Vec<Cell>> = capture
+        .blocks
+        .iter()
+        .enumerate()
+        .map(|(i, b)| {
+            let cache = if b.cache_breakpoint { "◆ breakpoint" } else { "" };
+            vec![
+                Cell::right((i + 1).to_string(), Style::default().fg(theme::text_muted())),
+                Cell::new(b.label.clone(), Style::default().fg(kind_color(b.kind))),
+                Cell::right(b.tokens.to_string(), Style::default().fg(theme::accent())),
+                Cell::right(if b.messages > 0 { b.messages.to_string() } else { String::new() }, Style::default()),
+                Cell::new(cache, Style::default().fg(theme::warning()).bold()),
+            ]
+        })
+        .collect();
+    let footer = [
+        Cell::new("", Style::default()),
+        Cell::new("Total", Style::default().bold()),
+        Cell::right(capture.total_tokens().to_string(), Style::default().fg(theme::accent()).bold()),
+        Cell::new("", Style::default()),
+        Cell::new("", Style::default()),
+    ];
+    render_table(&header, &rows, Some(&footer), 1)
+}
+
+fn render_diff(previous: &PromptCapture, current: &PromptCapture, base_style: Style) -> Vec<Line<'static>> {
+    let (changes, common_prefix) = diff(previous, current);
+    let prefix_tokens: usize = current.blocks.iter().take(common_prefix).map(|b| b.tokens).sum();
+    let mut text = heading(
+        "LAST REQUEST vs PREVIOUS",
+        format!(
+            "unchanged prefix: {} of {} blocks (~{} tokens cacheable)",
+            common_prefix,
+            current.blocks.len(),
+            prefix_tokens
+        ),
+        base_style,
+    );
+
+    let header = [
+        Cell::new("", Style::default()),
+        Cell::new("Block", Style::default()),
+        Cell::right("Tokens", Style::default()),
+        Cell::right("Δ", Style::default()),
+    ];
+    let rows: Vec<Vec<Cell>> = changes
+        .iter()
+        .map(|(change, block, delta)| {
+            let (marker, color) = match change {
+                BlockChange::Unchanged => ("=", theme::text_muted()),
+                BlockChange::Changed => ("~", theme::warning()),
+                BlockChange::Added => ("+", theme::success()),
+                BlockChange::Removed => ("-", theme::error()),
+            };
+            let delta = if *delta == 0 { String::new() } else { format!("{:+}", delta) };
+            vec![
+                Cell::new(marker, Style::default().fg(color).bold()),
+                Cell::new(block.label.clone(), Style::default().fg(color)),
+                Cell::right(block.tokens.to_string(), Style::default().fg(theme::accent())),
+                Cell::right(delta, Style::default().fg(color)),
+            ]
+        })
+        .collect();
+    text.extend(render_table(&header, &rows, None, 1));
+    text
+}
+
+impl Panel for PromptInspectorPanel {
+    fn handle_key(&self, key: &KeyEvent, _state: &State) -> Option<Action> {
+        match key.code {
+            KeyCode::Up => Some(Action::ScrollUp(SCROLL_ARROW_AMOUNT)),
+            KeyCode::Down => Some(Action::ScrollDown(SCROLL_ARROW_AMOUNT)),
+            KeyCode::PageUp => Some(Action::ScrollUp(SCROLL_PAGE_AMOUNT)),
+            KeyCode::PageDown => Some(Action::ScrollDown(SCROLL_PAGE_AMOUNT)),
+            _ => None,
+        }
+    }
+
+    fn title(&self, state: &State) -> String {
+        format!("Prompt Inspector ({})", mode(state).as_str())
+    }
+
+    /// Debugging view only — it must not add itself to the prompt it inspects
+    fn context(&self, _state: &State) -> Vec<ContextItem> {
+        Vec::new()
+    }
+
+    fn content(&self, state: &State, base_style: Style) -> Vec<Line<'static>> {
+        let mode = mode(state);
+        let (capture, previous) = captures_for(mode);
+        let Some(capture) = capture else {
+            let hint = match mode {
+                InspectMode::Next => "Nothing previewed yet. Run \"Inspect Next Prompt\" from the palette.",
+                _ => "No request sent yet in this session. \"Inspect Next Prompt\" previews one.",
+            };
+            return vec![Line::from(Span::styled(format!(" {}", hint), Style::default().fg(theme::text_muted())))];
+        };
+
+        if mode == InspectMode::Diff {
+            let Some(previous) = previous else {
+                return vec![Line::from(Span::styled(
+                    " Only one request sent so far — nothing to compare yet.".to_string(),
+                    Style::default().fg(theme::text_muted()),
+                ))];
+            };
+            return render_diff(&previous, &capture, base_style);
+        }
+
+        let title = if mode == InspectMode::Next { "NEXT REQUEST (preview)" } else { "LAST REQUEST" };
+        let age = format_time_ago(crate::app::panels::now_ms().saturating_sub(capture.captured_ms));
+        let mut text = heading(title, format!("captured {}", age), base_style);
+        text.extend(render_blocks(&capture));
+        text.push(Line::from(""));
+        text.push(Line::from(Span::styled(
+            " ◆ prompt-cache breakpoint: the prefix up to this block is cached and reused while it stays identical"
+                .to_string(),
+            Style::default().fg(theme::text_muted()),
+        )));
+        text
+    }
+}
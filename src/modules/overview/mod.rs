@@ -1,8 +1,9 @@
 pub mod context;
+mod inspector_panel;
 mod panel;
 mod render;
 mod render_details;
-mod tools;
+pub mod tools;
 mod tools_panel;
 
 use serde_json::json;
@@ -13,6 +14,7 @@ use crate::infra::tools::{ToolResult, ToolUse};
 use crate::modules::ToolVisualizer;
 use crate::state::{ContextType, ContextTypeMeta, State};
 
+use self::inspector_panel::PromptInspectorPanel;
 use self::panel::OverviewPanel;
 use self::tools_panel::ToolsPanel;
 use super::Module;
@@ -57,12 +59,19 @@ impl Module for OverviewModule {
             "grok_model": state.grok_model,
             "groq_model": state.groq_model,
             "deepseek_model": state.deepseek_model,
+            "openai_model": state.openai_model,
+            "local_model": state.local_model,
             "secondary_provider": state.secondary_provider,
             "secondary_anthropic_model": state.secondary_anthropic_model,
             "secondary_grok_model": state.secondary_grok_model,
             "secondary_groq_model": state.secondary_groq_model,
             "secondary_deepseek_model": state.secondary_deepseek_model,
+            "secondary_openai_model": state.secondary_openai_model,
+            "secondary_local_model": state.secondary_local_model,
             "reverie_enabled": state.reverie_enabled,
+            "summarize_history": state.summarize_history,
+            "auto_checkpoints": state.auto_checkpoints,
+            "thinking_budget": state.thinking_budget,
             "cleaning_threshold": state.cleaning_threshold,
             "cleaning_target_proportion": state.cleaning_target_proportion,
             "context_budget": state.context_budget,
@@ -70,6 +79,7 @@ impl Module for OverviewModule {
             "cache_hit_tokens": state.cache_hit_tokens,
             "cache_miss_tokens": state.cache_miss_tokens,
             "total_output_tokens": state.total_output_tokens,
+            "fallback_usage": state.fallback_usage,
             "disabled_tools": state.tools.iter().filter(|t| !t.enabled).map(|t| &t.id).collect::<Vec<_>>(),
         })
     }
@@ -115,6 +125,14 @@ impl Module for OverviewModule {
         {
             state.deepseek_model = m;
         }
+        if let Some(v) = data.get("openai_model")
+            && let Ok(m) = serde_json::from_value(v.clone())
+        {
+            state.openai_model = m;
+        }
+        if let Some(v) = data.get("local_model").and_then(|v| v.as_str()) {
+            state.local_model = v.to_string();
+        }
         if let Some(v) = data.get("secondary_provider")
             && let Ok(p) = serde_json::from_value(v.clone())
         {
@@ -140,9 +158,26 @@ impl Module for OverviewModule {
         {
             state.secondary_deepseek_model = m;
         }
+        if let Some(v) = data.get("secondary_openai_model")
+            && let Ok(m) = serde_json::from_value(v.clone())
+        {
+            state.secondary_openai_model = m;
+        }
+        if let Some(v) = data.get("secondary_local_model").and_then(|v| v.as_str()) {
+            state.secondary_local_model = v.to_string();
+        }
         if let Some(v) = data.get("reverie_enabled").and_then(|v| v.as_bool()) {
             state.reverie_enabled = v;
         }
+        if let Some(v) = data.get("summarize_history").and_then(|v| v.as_bool()) {
+            state.summarize_history = v;
+        }
+        if let Some(v) = data.get("auto_checkpoints").and_then(|v| v.as_bool()) {
+            state.auto_checkpoints = v;
+        }
+        if let Some(v) = data.get("thinking_budget").and_then(|v| v.as_u64()) {
+            state.thinking_budget = v as u32;
+        }
         if let Some(v) = data.get("cleaning_threshold").and_then(|v| v.as_f64()) {
             state.cleaning_threshold = v as f32;
         }
@@ -164,6 +199,9 @@ impl Module for OverviewModule {
         if let Some(v) = data.get("total_output_tokens").and_then(|v| v.as_u64()) {
             state.total_output_tokens = v as usize;
         }
+        if let Some(v) = data.get("fallback_usage").and_then(|v| serde_json::from_value(v.clone()).ok()) {
+            state.fallback_usage = v;
+        }
         if let Some(arr) = data.get("disabled_tools").and_then(|v| v.as_array()) {
             let disabled: Vec<String> = arr.iter().filter_map(|v| v.as_str().map(String::from)).collect();
             // Build tools from active_modules (must be loaded already) and apply disabled state
@@ -218,6 +256,16 @@ impl Module for OverviewModule {
                 short_name: "tools",
                 needs_async_wait: false,
             },
+            ContextTypeMeta {
+                context_type: crate::app::prompt_inspector::PROMPT_INSPECTOR_TYPE,
+                icon_id: "overview",
+                is_fixed: false,
+                needs_cache: false,
+                fixed_order: None,
+                display_name: "prompt inspector",
+                short_name: "prompt",
+                needs_async_wait: false,
+            },
         ]
     }
 
@@ -225,6 +273,7 @@ impl Module for OverviewModule {
         match context_type.as_str() {
             ContextType::OVERVIEW => Some(Box::new(OverviewPanel)),
             ContextType::TOOLS => Some(Box::new(ToolsPanel)),
+            crate::app::prompt_inspector::PROMPT_INSPECTOR_TYPE => Some(Box::new(PromptInspectorPanel)),
             _ => None,
         }
     }
@@ -246,6 +295,78 @@ impl Module for OverviewModule {
                 reverie_allowed: true,
                 category: "Context".to_string(),
             },
+            ToolDefinition {
+                id: "panel_pin".to_string(),
+                name: "Pin Panels".to_string(),
+                short_desc: "Pin panels or set eviction priority".to_string(),
+                description: "Pins panels so they are never evicted when the context grows past the cleaning threshold, \
+                    or sets their eviction priority. Unpinned panels are closed lowest priority first, then least \
+                    recently referenced (mentioning a panel's ID or file path counts as a reference). High-priority \
+                    panels are only closed by the context optimizer. Called with just ids, pins them."
+                    .to_string(),
+                params: vec![
+                    ToolParam::new("ids", ParamType::Array(Box::new(ParamType::String)))
+                        .desc("Panel IDs to update (e.g., ['P8', 'P12'])")
+                        .required(),
+                    ToolParam::new("pinned", ParamType::Boolean).desc("true to pin, false to unpin"),
+                    ToolParam::new("priority", ParamType::String)
+                        .desc("Eviction priority")
+                        .enum_vals(&["low", "normal", "high"]),
+                ],
+                enabled: true,
+                reverie_allowed: true,
+                category: "Context".to_string(),
+            },
+            ToolDefinition {
+                id: "panel_set_budget".to_string(),
+                name: "Set Panel Budget".to_string(),
+                short_desc: "Cap a panel's token size".to_string(),
+                description: "Sets a token budget on panels, overriding their type's default. Over-budget console \
+                    output keeps only its newest lines; other panels are split into pages of about the budget size \
+                    (navigate with panel_goto_page). Omit tokens to fall back to the type's budget; 0 means unlimited."
+                    .to_string(),
+                params: vec![
+                    ToolParam::new("ids", ParamType::Array(Box::new(ParamType::String)))
+                        .desc("Panel IDs to update (e.g., ['P8', 'P12'])")
+                        .required(),
+                    ToolParam::new("tokens", ParamType::Integer).desc("Token budget (0 = unlimited)"),
+                ],
+                enabled: true,
+                reverie_allowed: true,
+                category: "Context".to_string(),
+            },
+            ToolDefinition {
+                id: "checkpoint_create".to_string(),
+                name: "Create Checkpoint".to_string(),
+                short_desc: "Save a restorable point".to_string(),
+                description: "Captures the working tree (tracked and untracked files, ignored files excluded), the \
+                    conversation position and the open panels as a checkpoint. Create one before a risky multi-file \
+                    change. A checkpoint is also taken automatically at the start of every user turn."
+                    .to_string(),
+                params: vec![
+                    ToolParam::new("label", ParamType::String).desc("Short description (e.g., 'before auth refactor')"),
+                ],
+                enabled: true,
+                reverie_allowed: false,
+                category: "Context".to_string(),
+            },
+            ToolDefinition {
+                id: "checkpoint_restore".to_string(),
+                name: "Restore Checkpoint".to_string(),
+                short_desc: "Roll files and panels back".to_string(),
+                description: "Restores a checkpoint: rewrites changed files, recreates deleted ones, deletes files \
+                    created since, and reopens the panel set. With preview, only lists what would change (file \
+                    statuses and diffstat). The conversation is not rolled back by this tool; the user can do that \
+                    from the command palette (Undo Last Turn / Restore Checkpoint)."
+                    .to_string(),
+                params: vec![
+                    ToolParam::new("id", ParamType::String).desc("Checkpoint ID (e.g., CP4)").required(),
+                    ToolParam::new("preview", ParamType::Boolean).desc("Only describe the changes (default false)"),
+                ],
+                enabled: true,
+                reverie_allowed: false,
+                category: "Context".to_string(),
+            },
 
             // System tools (reload stays in core)
             ToolDefinition {
@@ -312,6 +433,10 @@ impl Module for OverviewModule {
             // Context tools
             "Close_panel" => Some(self::tools::close_context::execute(tool, state)),
             "panel_goto_page" => Some(self::tools::panel_goto_page::execute(tool, state)),
+            "panel_pin" => Some(self::tools::pin_panel::execute(tool, state)),
+            "panel_set_budget" => Some(self::tools::set_budget::execute(tool, state)),
+            "checkpoint_create" => Some(self::tools::checkpoint::execute_create(tool, state)),
+            "checkpoint_restore" => Some(self::tools::checkpoint::execute_restore(tool, state)),
 
             // System tools (reload stays in core)
             "system_reload" => Some(crate::infra::tools::execute_reload_tui(tool, state)),
@@ -330,6 +455,10 @@ impl Module for OverviewModule {
             ("tool_manage", visualize_core_output as ToolVisualizer),
             ("system_reload", visualize_core_output as ToolVisualizer),
             ("panel_goto_page", visualize_core_output as ToolVisualizer),
+            ("panel_pin", visualize_core_output as ToolVisualizer),
+            ("panel_set_budget", visualize_core_output as ToolVisualizer),
+            ("checkpoint_create", visualize_core_output as ToolVisualizer),
+            ("checkpoint_restore", visualize_core_output as ToolVisualizer),
         ]
     }
 }
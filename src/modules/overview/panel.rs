@@ -76,6 +76,30 @@ impl Panel for OverviewPanel {
 
         text.extend(render::render_statistics(state, base_style));
 
+        let branches_section = render::render_branches(base_style);
+        if !branches_section.is_empty() {
+            text.extend(render::separator());
+            text.extend(branches_section);
+        }
+
+        let snapshots_section = render::render_snapshots(base_style);
+        if !snapshots_section.is_empty() {
+            text.extend(render::separator());
+            text.extend(snapshots_section);
+        }
+
+        let checkpoints_section = render::render_checkpoints(base_style);
+        if !checkpoints_section.is_empty() {
+            text.extend(render::separator());
+            text.extend(checkpoints_section);
+        }
+
+        let spend_section = render::render_spend(base_style);
+        if !spend_section.is_empty() {
+            text.extend(render::separator());
+            text.extend(spend_section);
+        }
+
         text
     }
 }
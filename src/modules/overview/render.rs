@@ -216,6 +216,7 @@ pub fn render_context_elements(state: &State, base_style: Style) -> Vec<Line<'st
         Cell::new("Type", Style::default()),
         Cell::right("Tokens", Style::default()),
         Cell::right("Acc", Style::default()),
+        Cell::right("Budget", Style::default()),
         Cell::right("Cost", Style::default()),
         Cell::new("Hit", Style::default()),
         Cell::new("Refreshed", Style::default()),
@@ -238,6 +239,7 @@ pub fn render_context_elements(state: &State, base_style: Style) -> Vec<Line<'st
         Cell::right(format_number(system_prompt_tokens), Style::default().fg(theme::accent())),
         Cell::right(format_number(accumulated), Style::default().fg(theme::text_muted())),
         Cell::right("—", Style::default().fg(theme::text_muted())),
+        Cell::right("—", Style::default().fg(theme::text_muted())),
         Cell::new("—", Style::default().fg(theme::text_muted())),
         Cell::new("—", Style::default().fg(theme::text_muted())),
         Cell::new("", Style::default()),
@@ -253,6 +255,7 @@ pub fn render_context_elements(state: &State, base_style: Style) -> Vec<Line<'st
         Cell::right(format_number(tool_def_tokens), Style::default().fg(theme::accent())),
         Cell::right(format_number(accumulated), Style::default().fg(theme::text_muted())),
         Cell::right("—", Style::default().fg(theme::text_muted())),
+        Cell::right("—", Style::default().fg(theme::text_muted())),
         Cell::new("—", Style::default().fg(theme::text_muted())),
         Cell::new("—", Style::default().fg(theme::text_muted())),
         Cell::new("", Style::default()),
@@ -297,11 +300,28 @@ pub fn render_context_elements(state: &State, base_style: Style) -> Vec<Line<'st
 
         accumulated += ctx.token_count;
 
+        // Budget, with the tokens it keeps out of the context (hidden pages or truncated head)
+        let budget_cell = match state.panel_budget(ctx) {
+            None => Cell::right("—", Style::default().fg(theme::text_muted())),
+            Some(budget) => {
+                let overflow = ctx.full_token_count.saturating_sub(ctx.token_count);
+                if overflow > 0 {
+                    Cell::right(
+                        format!("{} +{}", format_number(budget), format_number(overflow)),
+                        Style::default().fg(theme::warning()),
+                    )
+                } else {
+                    Cell::right(format_number(budget), Style::default().fg(theme::text_muted()))
+                }
+            }
+        };
+
         rows.push(vec![
             Cell::new(id_with_icon, Style::default().fg(theme::accent_dim())),
             Cell::new(type_name, Style::default().fg(theme::text_secondary())),
             Cell::right(format_number(ctx.token_count), Style::default().fg(theme::accent())),
             Cell::right(format_number(accumulated), Style::default().fg(theme::text_muted())),
+            budget_cell,
             Cell::right(cost_str, Style::default().fg(theme::text_muted())),
             Cell::new(hit_str, Style::default().fg(hit_color)),
             Cell::new(refreshed, Style::default().fg(theme::text_muted())),
@@ -314,4 +334,6 @@ pub fn render_context_elements(state: &State, base_style: Style) -> Vec<Line<'st
     text
 }
 
-pub use super::render_details::render_statistics;
+pub use super::render_details::{
+    render_branches, render_checkpoints, render_snapshots, render_spend, render_statistics,
+};
@@ -80,6 +80,53 @@ pub fn render_statistics(state: &State, base_style: Style) -> Vec<Line<'static>>
     text
 }
 
+/// Render the SPEND section: this project's cost ledger (survives Ctrl+O).
+pub fn render_spend(base_style: Style) -> Vec<Line<'static>> {
+    let mut text: Vec<Line> = Vec::new();
+
+    let spend = crate::state::persistence::ledger::summary();
+    if spend.total == 0.0 {
+        return text;
+    }
+
+    text.push(Line::from(vec![
+        Span::styled(" ".to_string(), base_style),
+        Span::styled("SPEND".to_string(), Style::default().fg(theme::text_muted()).bold()),
+        Span::styled("  (cpilot costs for reports)".to_string(), Style::default().fg(theme::text_muted())),
+    ]));
+    text.push(Line::from(""));
+
+    let mut totals = vec![Span::styled(" ".to_string(), base_style)];
+    for (label, cost) in [("Today: ", spend.today), ("  Month: ", spend.month), ("  All time: ", spend.total)] {
+        totals.push(Span::styled(label.to_string(), Style::default().fg(theme::text_secondary())));
+        totals.push(Span::styled(format!("${:.2}", cost), Style::default().fg(theme::text()).bold()));
+    }
+    text.push(Line::from(totals));
+
+    if !spend.month_by_model.is_empty() {
+        text.push(Line::from(""));
+        let header = [
+            Cell::new("Model (this month)", Style::default()),
+            Cell::right("Calls", Style::default()),
+            Cell::right("Cost", Style::default()),
+        ];
+        let rows: Vec<Vec<Cell>> = spend
+            .month_by_model
+            .iter()
+            .map(|row| {
+                vec![
+                    Cell::new(row.key.clone(), Style::default().fg(theme::text())),
+                    Cell::right(row.calls.to_string(), Style::default().fg(theme::text_muted())),
+                    Cell::right(format!("${:.2}", row.cost), Style::default().fg(theme::accent())),
+                ]
+            })
+            .collect();
+        text.extend(render_table(&header, &rows, None, 1));
+    }
+
+    text
+}
+
 /// Render the AGENTS section (system prompts table).
 pub fn render_seeds(state: &State, base_style: Style) -> Vec<Line<'static>> {
     let mut text: Vec<Line> = Vec::new();
@@ -256,6 +303,134 @@ pub fn render_presets(base_style: Style) -> Vec<Line<'static>> {
     text
 }
 
+/// Render the SNAPSHOTS section (parked working sets, newest first).
+pub fn render_snapshots(base_style: Style) -> Vec<Line<'static>> {
+    let mut text: Vec<Line> = Vec::new();
+
+    let snapshots = cp_mod_preset::snapshot::list_snapshots();
+    if snapshots.is_empty() {
+        return text;
+    }
+
+    text.push(Line::from(vec![
+        Span::styled(" ".to_string(), base_style),
+        Span::styled("SNAPSHOTS".to_string(), Style::default().fg(theme::text_muted()).bold()),
+        Span::styled(format!("  ({} saved)", snapshots.len()), Style::default().fg(theme::text_muted())),
+    ]));
+    text.push(Line::from(""));
+
+    let header = [
+        Cell::new("Name", Style::default()),
+        Cell::right("Panels", Style::default()),
+        Cell::right("Todos", Style::default()),
+        Cell::new("Saved", Style::default()),
+    ];
+
+    let now_ms = crate::app::panels::now_ms();
+    let rows: Vec<Vec<Cell>> = snapshots
+        .iter()
+        .map(|s| {
+            vec![
+                Cell::new(s.name.clone(), Style::default().fg(theme::text())),
+                Cell::right(s.panels.to_string(), Style::default().fg(theme::accent())),
+                Cell::right(s.todos.to_string(), Style::default().fg(theme::accent())),
+                Cell::new(
+                    crate::ui::helpers::format_time_ago(now_ms.saturating_sub(s.created_ms)),
+                    Style::default().fg(theme::text_muted()),
+                ),
+            ]
+        })
+        .collect();
+
+    text.extend(render_table(&header, &rows, None, 1));
+
+    text
+}
+
+/// Render the BRANCHES section (conversation branch tree, live branch highlighted).
+pub fn render_branches(base_style: Style) -> Vec<Line<'static>> {
+    let mut text: Vec<Line> = Vec::new();
+
+    let store = crate::state::persistence::branch::load_branches();
+    if store.branches.is_empty() {
+        return text;
+    }
+
+    text.push(Line::from(vec![
+        Span::styled(" ".to_string(), base_style),
+        Span::styled("BRANCHES".to_string(), Style::default().fg(theme::text_muted()).bold()),
+        Span::styled(format!("  (on {})", store.active), Style::default().fg(theme::text_muted())),
+    ]));
+    text.push(Line::from(""));
+
+    for (depth, branch) in store.tree() {
+        let is_active = branch.name == store.active;
+        let marker = if is_active { "● " } else { "○ " };
+        let name_style =
+            if is_active { Style::default().fg(theme::accent()).bold() } else { Style::default().fg(theme::text()) };
+        let forked_at = branch.fork_message_id.as_deref().map(|id| format!("  from {}", id)).unwrap_or_default();
+        let count = if is_active { String::new() } else { format!("  {} msgs", branch.message_uids.len()) };
+        text.push(Line::from(vec![
+            Span::styled(format!("  {}{}", "  ".repeat(depth), marker), Style::default().fg(theme::accent_dim())),
+            Span::styled(branch.name.clone(), name_style),
+            Span::styled(format!("{}{}", forked_at, count), Style::default().fg(theme::text_muted())),
+        ]));
+    }
+
+    text
+}
+
+/// Render the CHECKPOINTS section (restorable points, newest first).
+pub fn render_checkpoints(base_style: Style) -> Vec<Line<'static>> {
+    let mut text: Vec<Line> = Vec::new();
+
+    let store = crate::state::persistence::checkpoint::load_checkpoints();
+    if store.checkpoints.is_empty() {
+        return text;
+    }
+
+    text.push(Line::from(vec![
+        Span::styled(" ".to_string(), base_style),
+        Span::styled("CHECKPOINTS".to_string(), Style::default().fg(theme::text_muted()).bold()),
+        Span::styled(format!("  ({} kept)", store.checkpoints.len()), Style::default().fg(theme::text_muted())),
+    ]));
+    text.push(Line::from(""));
+
+    let header = [
+        Cell::new("ID", Style::default()),
+        Cell::new("Label", Style::default()),
+        Cell::right("Msgs", Style::default()),
+        Cell::right("Panels", Style::default()),
+        Cell::new("Taken", Style::default()),
+    ];
+
+    let now_ms = crate::app::panels::now_ms();
+    let rows: Vec<Vec<Cell>> = store
+        .checkpoints
+        .iter()
+        .rev()
+        .take(10)
+        .map(|c| {
+            let label_style =
+                if c.auto { Style::default().fg(theme::text_muted()) } else { Style::default().fg(theme::text()) };
+            vec![
+                Cell::new(c.id.clone(), Style::default().fg(theme::accent_dim())),
+                Cell::new(c.label.clone(), label_style),
+                Cell::right(c.message_count.to_string(), Style::default().fg(theme::accent())),
+                Cell::right(c.panels.len().to_string(), Style::default().fg(theme::accent())),
+                Cell::new(
+                    crate::ui::helpers::format_time_ago(now_ms.saturating_sub(c.created_ms)),
+                    Style::default().fg(theme::text_muted()),
+                ),
+            ]
+        })
+        .collect();
+
+    text.extend(render_table(&header, &rows, None, 1));
+
+    text
+}
+
 /// Render the TOOLS section (grouped by category).
 pub fn render_tools(state: &State, base_style: Style) -> Vec<Line<'static>> {
     let mut text: Vec<Line> = Vec::new();
@@ -0,0 +1,27 @@
+use crate::app::checkpoints;
+use crate::infra::tools::{ToolResult, ToolUse};
+use crate::state::State;
+
+pub fn execute_create(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let label = tool.input.get("label").and_then(|v| v.as_str()).filter(|l| !l.trim().is_empty());
+    match checkpoints::create(state, label.map(str::trim), false) {
+        Ok(msg) => ToolResult::new(tool.id.clone(), msg, false),
+        Err(e) => ToolResult::new(tool.id.clone(), format!("Error: {}", e), true),
+    }
+}
+
+pub fn execute_restore(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let Some(id) = tool.input.get("id").and_then(|v| v.as_str()) else {
+        return ToolResult::new(tool.id.clone(), "Missing 'id' parameter".to_string(), true);
+    };
+    // The conversation is mid-turn while a tool runs, so only the user rolls it back
+    let result = if tool.input.get("preview").and_then(|v| v.as_bool()).unwrap_or(false) {
+        checkpoints::preview(state, id, false)
+    } else {
+        checkpoints::restore(state, id, false)
+    };
+    match result {
+        Ok(msg) => ToolResult::new(tool.id.clone(), msg, false),
+        Err(e) => ToolResult::new(tool.id.clone(), format!("Error: {}", e), true),
+    }
+}
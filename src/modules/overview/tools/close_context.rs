@@ -1,5 +1,6 @@
+use crate::app::eviction;
 use crate::infra::tools::{ToolResult, ToolUse};
-use crate::modules::all_modules;
+use crate::modules::{Module, all_modules};
 use crate::state::State;
 
 pub fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
@@ -41,34 +42,15 @@ pub fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
             continue;
         }
 
-        // Take the context element out so modules can mutate state without borrow conflicts
-        let ctx = state.context.remove(idx);
-
-        // Ask modules for special close handling
-        let mut close_result: Option<Result<String, String>> = None;
-        for module in &modules {
-            if let Some(result) = module.on_close_context(&ctx, state) {
-                close_result = Some(result);
-                break;
-            }
+        // Pinned panels stay until someone unpins them
+        if eviction::is_pinned(&state.context[idx]) {
+            skipped.push(format!("{} (pinned — unpin it with panel_pin first)", id));
+            continue;
         }
 
-        match close_result {
-            Some(Ok(desc)) => {
-                // Context already removed
-                closed.push(format!("{} ({})", id, desc));
-            }
-            Some(Err(msg)) => {
-                // Put it back — close was rejected
-                state.context.insert(idx, ctx);
-                skipped.push(msg);
-            }
-            None => {
-                // Default: use context_detail for description
-                let detail = modules.iter().find_map(|m| m.context_detail(&ctx)).unwrap_or_else(|| ctx.name.clone());
-                // Context already removed
-                closed.push(format!("{} ({})", id, detail));
-            }
+        match close_at(state, idx, &modules) {
+            Ok(desc) => closed.push(format!("{} ({})", id, desc)),
+            Err(msg) => skipped.push(msg),
         }
     }
 
@@ -102,3 +84,24 @@ pub fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
 
     ToolResult::new(tool.id.clone(), output, closed.is_empty() && skipped.is_empty())
 }
+
+/// Close the panel at `idx`, letting modules veto or describe the close.
+/// Returns a short description of what was closed, or the veto message.
+pub fn close_at(state: &mut State, idx: usize, modules: &[Box<dyn Module>]) -> Result<String, String> {
+    // Take the context element out so modules can mutate state without borrow conflicts
+    let ctx = state.context.remove(idx);
+
+    // Ask modules for special close handling
+    let close_result = modules.iter().find_map(|m| m.on_close_context(&ctx, state));
+
+    match close_result {
+        Some(Ok(desc)) => Ok(desc),
+        Some(Err(msg)) => {
+            // Put it back — close was rejected
+            state.context.insert(idx, ctx);
+            Err(msg)
+        }
+        // Default: use context_detail for description
+        None => Ok(modules.iter().find_map(|m| m.context_detail(&ctx)).unwrap_or_else(|| ctx.name.clone())),
+    }
+}
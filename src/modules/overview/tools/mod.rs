@@ -1,3 +1,6 @@
+pub mod checkpoint;
 pub mod close_context;
 pub mod manage_tools;
 pub mod panel_goto_page;
+pub mod pin_panel;
+pub mod set_budget;
@@ -0,0 +1,61 @@
+use crate::app::eviction::{self, PINNED_META, PRIORITY_META, Priority};
+use crate::infra::tools::{ToolResult, ToolUse};
+use crate::state::State;
+
+pub fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let ids = match tool.input.get("ids").and_then(|v| v.as_array()) {
+        Some(arr) if !arr.is_empty() => arr,
+        _ => {
+            return ToolResult::new(tool.id.clone(), "Missing or empty 'ids' array parameter".to_string(), true);
+        }
+    };
+    let pinned = tool.input.get("pinned").and_then(|v| v.as_bool());
+    let priority = match tool.input.get("priority").and_then(|v| v.as_str()) {
+        Some(p) => match Priority::parse(p) {
+            Some(p) => Some(p),
+            None => {
+                return ToolResult::new(
+                    tool.id.clone(),
+                    format!("Invalid priority '{}' (expected low, normal or high)", p),
+                    true,
+                );
+            }
+        },
+        None => None,
+    };
+    // Bare call pins
+    let pinned = pinned.or(priority.is_none().then_some(true));
+
+    let mut updated = Vec::new();
+    let mut skipped = Vec::new();
+    for id in ids.iter().filter_map(|v| v.as_str()) {
+        let Some(ctx) = state.context.iter_mut().find(|c| c.id == id) else {
+            skipped.push(format!("{} (not found)", id));
+            continue;
+        };
+        if ctx.uid.is_none() || ctx.context_type.is_fixed() {
+            skipped.push(format!("{} (fixed panel — never evicted)", id));
+            continue;
+        }
+        if let Some(pinned) = pinned {
+            ctx.set_meta(PINNED_META, &pinned);
+        }
+        if let Some(priority) = priority {
+            ctx.set_meta(PRIORITY_META, &priority.as_str());
+        }
+        let pin = if eviction::is_pinned(ctx) { "pinned" } else { "unpinned" };
+        updated.push(format!("{} {}, priority {}", id, pin, eviction::priority(ctx).as_str()));
+    }
+
+    if !updated.is_empty() {
+        state.dirty = true;
+    }
+    let mut output = updated.join("\n");
+    if !skipped.is_empty() {
+        if !output.is_empty() {
+            output.push_str("\n\n");
+        }
+        output.push_str(&format!("Skipped: {}", skipped.join(", ")));
+    }
+    ToolResult::new(tool.id.clone(), output, updated.is_empty())
+}
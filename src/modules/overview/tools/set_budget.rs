@@ -0,0 +1,54 @@
+use crate::infra::config::constants::PANEL_BUDGET_META;
+use crate::infra::tools::{ToolResult, ToolUse};
+use crate::state::State;
+
+pub fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let ids = match tool.input.get("ids").and_then(|v| v.as_array()) {
+        Some(arr) if !arr.is_empty() => arr,
+        _ => {
+            return ToolResult::new(tool.id.clone(), "Missing or empty 'ids' array parameter".to_string(), true);
+        }
+    };
+    // Omitted → back to the panel type's budget; 0 → unlimited
+    let tokens = tool.input.get("tokens").and_then(|v| v.as_u64()).map(|t| t as usize);
+
+    let mut updated = Vec::new();
+    let mut skipped = Vec::new();
+    for id in ids.iter().filter_map(|v| v.as_str()) {
+        let Some(ctx) = state.context.iter_mut().find(|c| c.id == id) else {
+            skipped.push(format!("{} (not found)", id));
+            continue;
+        };
+        if ctx.uid.is_none() || ctx.context_type.is_fixed() {
+            skipped.push(format!("{} (fixed panel)", id));
+            continue;
+        }
+        match tokens {
+            Some(tokens) => ctx.set_meta(PANEL_BUDGET_META, &tokens),
+            None => {
+                ctx.metadata.remove(PANEL_BUDGET_META);
+            }
+        }
+        // Re-fetch so the new budget applies to fresh content
+        ctx.cache_deprecated = true;
+        updated.push(id.to_string());
+    }
+
+    let mut output = String::new();
+    if !updated.is_empty() {
+        let budget_desc = match tokens {
+            Some(0) => "unlimited".to_string(),
+            Some(t) => format!("{} tokens", t),
+            None => "the panel type's default".to_string(),
+        };
+        output.push_str(&format!("Budget of {} set to {}", updated.join(", "), budget_desc));
+        state.dirty = true;
+    }
+    if !skipped.is_empty() {
+        if !output.is_empty() {
+            output.push_str("\n\n");
+        }
+        output.push_str(&format!("Skipped: {}", skipped.join(", ")));
+    }
+    ToolResult::new(tool.id.clone(), output, updated.is_empty())
+}
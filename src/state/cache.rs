@@ -62,6 +62,11 @@ impl CachePool {
 /// Global cache pool instance
 static CACHE_POOL: std::sync::LazyLock<CachePool> = std::sync::LazyLock::new(CachePool::new);
 
+/// Process a cache request in the background via the bounded thread pool.
+pub fn process_cache_request(request: CacheRequest, tx: Sender<CacheUpdate>) {
+    CACHE_POOL.submit(request, tx);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,8 +100,3 @@ mod tests {
         assert_eq!(hash_content("anything").len(), 64);
     }
 }
-
-/// Process a cache request in the background via the bounded thread pool.
-pub fn process_cache_request(request: CacheRequest, tx: Sender<CacheUpdate>) {
-    CACHE_POOL.submit(request, tx);
-}
@@ -0,0 +1,146 @@
+//! Conversation branch persistence
+//! Handles loading and saving branches.json (named forks of the conversation)
+//!
+//! A branch is a list of message UIDs. Branches share the messages before their
+//! fork point, so a message file stays on disk as long as any branch lists it.
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use cp_mod_preset::snapshot::SnapshotPanel;
+
+use crate::infra::constants::{BRANCHES_FILE, STORE_DIR};
+
+/// Name of the branch every conversation starts on
+pub const MAIN_BRANCH: &str = "main";
+
+/// A named line of conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Branch {
+    pub name: String,
+    /// Branch this one was forked from (None for main)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// Display ID of the last message shared with the parent (e.g. "A12")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fork_message_id: Option<String>,
+    pub created_ms: u64,
+    /// Message UIDs, in order. Stale for the active branch (state.messages is authoritative).
+    #[serde(default)]
+    pub message_uids: Vec<String>,
+    /// Dynamic panels open when the branch was last left (or forked)
+    #[serde(default)]
+    pub panels: Vec<SnapshotPanel>,
+}
+
+/// All branches of the conversation and which one is live
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchStore {
+    pub active: String,
+    #[serde(default)]
+    pub branches: Vec<Branch>,
+}
+
+impl Default for BranchStore {
+    fn default() -> Self {
+        Self { active: MAIN_BRANCH.to_string(), branches: Vec::new() }
+    }
+}
+
+impl BranchStore {
+    pub fn get(&self, name: &str) -> Option<&Branch> {
+        self.branches.iter().find(|b| b.name == name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Branch> {
+        self.branches.iter_mut().find(|b| b.name == name)
+    }
+
+    /// Message UIDs listed by branches other than the active one
+    pub fn uids_outside_active(&self) -> HashSet<String> {
+        self.branches.iter().filter(|b| b.name != self.active).flat_map(|b| b.message_uids.iter().cloned()).collect()
+    }
+
+    /// Branches in tree order (parents before children, siblings by creation) with
+    /// their depth. Branches whose parent is missing are shown as roots.
+    pub fn tree(&self) -> Vec<(usize, &Branch)> {
+        fn visit<'a>(store: &'a BranchStore, parent: Option<&str>, depth: usize, out: &mut Vec<(usize, &'a Branch)>) {
+            let mut children: Vec<&Branch> = store
+                .branches
+                .iter()
+                .filter(|b| match parent {
+                    Some(parent) => b.parent.as_deref() == Some(parent),
+                    None => b.parent.as_deref().and_then(|p| store.get(p)).is_none(),
+                })
+                .collect();
+            children.sort_by_key(|b| b.created_ms);
+            for child in children {
+                out.push((depth, child));
+                visit(store, Some(&child.name), depth + 1, out);
+            }
+        }
+        let mut out = Vec::new();
+        visit(self, None, 0, &mut out);
+        out
+    }
+}
+
+fn branches_path() -> PathBuf {
+    PathBuf::from(STORE_DIR).join(BRANCHES_FILE)
+}
+
+/// Load branches.json (a single implicit main branch when absent)
+pub fn load_branches() -> BranchStore {
+    fs::read_to_string(branches_path()).ok().and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default()
+}
+
+/// Save branches.json
+pub fn save_branches(store: &BranchStore) -> Result<(), String> {
+    fs::create_dir_all(STORE_DIR).map_err(|e| format!("Failed to create store directory: {}", e))?;
+    let json = serde_json::to_string_pretty(store).map_err(|e| format!("Failed to serialize branches: {}", e))?;
+    fs::write(branches_path(), json).map_err(|e| format!("Failed to write branches: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn branch(name: &str, parent: Option<&str>, created_ms: u64, uids: &[&str]) -> Branch {
+        Branch {
+            name: name.to_string(),
+            parent: parent.map(|p| p.to_string()),
+            fork_message_id: None,
+            created_ms,
+            message_uids: uids.iter().map(|u| u.to_string()).collect(),
+            panels: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn tree_orders_parents_before_children() {
+        let store = BranchStore {
+            active: "b".to_string(),
+            branches: vec![
+                branch("c", Some("main"), 30, &[]),
+                branch("main", None, 10, &[]),
+                branch("b", Some("main"), 20, &[]),
+                branch("b2", Some("b"), 40, &[]),
+            ],
+        };
+        let tree: Vec<(usize, &str)> = store.tree().into_iter().map(|(d, b)| (d, b.name.as_str())).collect();
+        assert_eq!(tree, vec![(0, "main"), (1, "b"), (2, "b2"), (1, "c")]);
+    }
+
+    #[test]
+    fn shared_uids_exclude_the_active_branch() {
+        let store = BranchStore {
+            active: "main".to_string(),
+            branches: vec![branch("main", None, 10, &["U1", "U2"]), branch("b", Some("main"), 20, &["U1", "U9"])],
+        };
+        let shared = store.uids_outside_active();
+        assert!(shared.contains("U1") && shared.contains("U9"));
+        assert!(!shared.contains("U2"));
+    }
+}
@@ -0,0 +1,141 @@
+//! Checkpoint persistence
+//! Handles loading and saving checkpoints.json (restorable points of the session)
+//!
+//! The working tree itself lives in git, as a commit under a private ref
+//! namespace; the manifest only records its hash plus the conversation
+//! position and the open panels.
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use cp_mod_preset::snapshot::SnapshotPanel;
+
+use crate::infra::constants::{CHECKPOINTS_FILE, MAX_AUTO_CHECKPOINTS, STORE_DIR};
+
+/// A restorable point: files, conversation position and panel set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Display ID (e.g. "CP4")
+    pub id: String,
+    pub label: String,
+    pub created_ms: u64,
+    /// Taken automatically at the start of a user turn
+    #[serde(default)]
+    pub auto: bool,
+    /// Shadow commit holding the working tree
+    pub commit: String,
+    /// Number of live messages at checkpoint time
+    pub message_count: usize,
+    /// UID of the last live message (None when the conversation was empty)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_message_uid: Option<String>,
+    /// Dynamic panels open at checkpoint time
+    #[serde(default)]
+    pub panels: Vec<SnapshotPanel>,
+}
+
+/// All checkpoints, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointStore {
+    pub next_id: usize,
+    #[serde(default)]
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+impl Default for CheckpointStore {
+    fn default() -> Self {
+        Self { next_id: 1, checkpoints: Vec::new() }
+    }
+}
+
+impl CheckpointStore {
+    pub fn get(&self, id: &str) -> Option<&Checkpoint> {
+        self.checkpoints.iter().find(|c| c.id == id)
+    }
+
+    /// Most recent automatic checkpoint
+    pub fn last_auto(&self) -> Option<&Checkpoint> {
+        self.checkpoints.iter().rev().find(|c| c.auto)
+    }
+
+    /// Append a checkpoint and assign its ID
+    pub fn push(&mut self, mut checkpoint: Checkpoint) -> String {
+        checkpoint.id = format!("CP{}", self.next_id);
+        self.next_id += 1;
+        let id = checkpoint.id.clone();
+        self.checkpoints.push(checkpoint);
+        id
+    }
+
+    pub fn remove(&mut self, id: &str) -> Option<Checkpoint> {
+        let idx = self.checkpoints.iter().position(|c| c.id == id)?;
+        Some(self.checkpoints.remove(idx))
+    }
+
+    /// Drop the oldest automatic checkpoints beyond the retention limit.
+    /// Manual checkpoints are kept until removed explicitly.
+    pub fn prune_auto(&mut self) -> Vec<Checkpoint> {
+        let auto_count = self.checkpoints.iter().filter(|c| c.auto).count();
+        let mut excess = auto_count.saturating_sub(MAX_AUTO_CHECKPOINTS);
+        let mut pruned = Vec::new();
+        self.checkpoints.retain(|c| {
+            if c.auto && excess > 0 {
+                excess -= 1;
+                pruned.push(c.clone());
+                false
+            } else {
+                true
+            }
+        });
+        pruned
+    }
+}
+
+fn checkpoints_path() -> PathBuf {
+    PathBuf::from(STORE_DIR).join(CHECKPOINTS_FILE)
+}
+
+/// Load checkpoints.json (empty when absent)
+pub fn load_checkpoints() -> CheckpointStore {
+    fs::read_to_string(checkpoints_path()).ok().and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default()
+}
+
+/// Save checkpoints.json
+pub fn save_checkpoints(store: &CheckpointStore) -> Result<(), String> {
+    fs::create_dir_all(STORE_DIR).map_err(|e| format!("Failed to create store directory: {}", e))?;
+    let json = serde_json::to_string_pretty(store).map_err(|e| format!("Failed to serialize checkpoints: {}", e))?;
+    fs::write(checkpoints_path(), json).map_err(|e| format!("Failed to write checkpoints: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkpoint(auto: bool) -> Checkpoint {
+        Checkpoint {
+            id: String::new(),
+            label: String::new(),
+            created_ms: 0,
+            auto,
+            commit: "0".repeat(40),
+            message_count: 0,
+            last_message_uid: None,
+            panels: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn pruning_drops_only_the_oldest_auto_checkpoints() {
+        let mut store = CheckpointStore::default();
+        let manual = store.push(checkpoint(false));
+        for _ in 0..MAX_AUTO_CHECKPOINTS + 2 {
+            store.push(checkpoint(true));
+        }
+        let pruned: Vec<String> = store.prune_auto().into_iter().map(|c| c.id).collect();
+        assert_eq!(pruned, vec!["CP2".to_string(), "CP3".to_string()]);
+        assert!(store.get(&manual).is_some());
+        assert_eq!(store.checkpoints.len(), MAX_AUTO_CHECKPOINTS + 1);
+        assert_eq!(store.last_auto().map(|c| c.id.as_str()), Some(format!("CP{}", MAX_AUTO_CHECKPOINTS + 3).as_str()));
+    }
+}
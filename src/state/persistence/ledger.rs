@@ -0,0 +1,343 @@
+//! Append-only cost ledger (`.context-pilot/ledger.jsonl`).
+//!
+//! One JSON line per completed API call, priced at the model that served it.
+//! Unlike the session counters, the ledger is never reset (Ctrl+O leaves it alone),
+//! so it can be aggregated by day, month, model, task or project for budgeting —
+//! in the Statistics panel or with `cpilot costs`.
+
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use cp_base::config::models::{MODELS, registry_key};
+use cp_mod_todo::{TodoState, TodoStatus};
+
+use crate::infra::constants::STORE_DIR;
+use crate::llms::{LlmProvider, ModelInfo};
+use crate::state::State;
+
+/// Ledger file name inside the store directory
+const LEDGER_FILE: &str = "ledger.jsonl";
+
+/// One API call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    /// Unix time in ms
+    pub timestamp_ms: u64,
+    /// Name of the project directory
+    pub project: String,
+    pub provider: LlmProvider,
+    pub model: String,
+    pub worker: String,
+    #[serde(default)]
+    pub reverie: bool,
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    pub cache_hit_tokens: usize,
+    pub cache_miss_tokens: usize,
+    /// Cost in USD at the model's prices when the call was made
+    pub cost: f64,
+    /// Todo in progress when the call was made
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+}
+
+/// Token usage of one call, as reported in `StreamEvent::Done`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Usage {
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    pub cache_hit_tokens: usize,
+    pub cache_miss_tokens: usize,
+}
+
+/// This project's ledger, loaded on first use and kept in step with `record`
+static ENTRIES: LazyLock<Mutex<Vec<LedgerEntry>>> =
+    LazyLock::new(|| Mutex::new(load(&ledger_path()).unwrap_or_default()));
+
+/// Path of this project's ledger
+pub fn ledger_path() -> PathBuf {
+    PathBuf::from(STORE_DIR).join(LEDGER_FILE)
+}
+
+/// Append one call to the ledger. Replayed calls cost nothing and are skipped.
+pub fn record(state: &State, provider: LlmProvider, model: &str, worker: &str, reverie: bool, usage: Usage) {
+    if provider == LlmProvider::Replay {
+        return;
+    }
+    let entry = LedgerEntry {
+        timestamp_ms: crate::app::panels::now_ms(),
+        project: project_name(),
+        provider,
+        model: model.to_string(),
+        worker: worker.to_string(),
+        reverie,
+        input_tokens: usage.input_tokens,
+        output_tokens: usage.output_tokens,
+        cache_hit_tokens: usage.cache_hit_tokens,
+        cache_miss_tokens: usage.cache_miss_tokens,
+        cost: price(provider, model, usage),
+        task: TodoState::get(state).todos.iter().find(|t| t.status == TodoStatus::InProgress).map(|t| t.name.clone()),
+    };
+    if let Err(e) = append(&ledger_path(), &entry) {
+        super::log_error(&format!("Failed to append to cost ledger: {}", e));
+    }
+    ENTRIES.lock().unwrap_or_else(|e| e.into_inner()).push(entry);
+}
+
+/// Cost in USD of a call at the model's registry prices (0 for unknown models)
+fn price(provider: LlmProvider, model: &str, usage: Usage) -> f64 {
+    MODELS.get(provider, model).map_or(0.0, |m| {
+        State::token_cost(usage.cache_hit_tokens, m.cache_hit_price_per_mtok())
+            + State::token_cost(usage.cache_miss_tokens, m.cache_miss_price_per_mtok())
+            + State::token_cost(usage.output_tokens, m.output_price_per_mtok())
+    })
+}
+
+fn project_name() -> String {
+    std::env::current_dir()
+        .ok()
+        .and_then(|d| d.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn append(path: &Path, entry: &LedgerEntry) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let line = serde_json::to_string(entry).map_err(std::io::Error::other)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// Read a ledger file. A missing file is an empty ledger; unparseable lines
+/// (e.g. a write cut short by a crash) are skipped.
+pub fn load(path: &Path) -> std::io::Result<Vec<LedgerEntry>> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// How report rows are grouped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    Day,
+    Month,
+    Model,
+    Task,
+    Project,
+}
+
+impl GroupBy {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "day" => Some(Self::Day),
+            "month" => Some(Self::Month),
+            "model" => Some(Self::Model),
+            "task" => Some(Self::Task),
+            "project" => Some(Self::Project),
+            _ => None,
+        }
+    }
+
+    fn key(self, entry: &LedgerEntry) -> String {
+        match self {
+            Self::Day => local_date(entry.timestamp_ms).format("%Y-%m-%d").to_string(),
+            Self::Month => local_date(entry.timestamp_ms).format("%Y-%m").to_string(),
+            Self::Model => format!("{}/{}", registry_key(entry.provider), entry.model),
+            Self::Task => match (&entry.task, entry.reverie) {
+                (_, true) => "(reverie)".to_string(),
+                (Some(task), false) => task.clone(),
+                (None, false) => "(no task)".to_string(),
+            },
+            Self::Project => entry.project.clone(),
+        }
+    }
+}
+
+fn local_date(timestamp_ms: u64) -> chrono::NaiveDate {
+    Local.timestamp_millis_opt(timestamp_ms as i64).single().map(|t| t.date_naive()).unwrap_or_default()
+}
+
+/// Aggregated spend of one group
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReportRow {
+    pub key: String,
+    pub calls: usize,
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    pub cache_hit_tokens: usize,
+    pub cache_miss_tokens: usize,
+    pub cost: f64,
+}
+
+/// Sum entries per group. Dates come out chronologically; everything else by cost, highest first.
+pub fn aggregate(entries: &[LedgerEntry], by: GroupBy) -> Vec<ReportRow> {
+    let mut groups: BTreeMap<String, ReportRow> = BTreeMap::new();
+    for entry in entries {
+        let key = by.key(entry);
+        let row = groups.entry(key.clone()).or_insert_with(|| ReportRow { key, ..Default::default() });
+        row.calls += 1;
+        row.input_tokens += entry.input_tokens;
+        row.output_tokens += entry.output_tokens;
+        row.cache_hit_tokens += entry.cache_hit_tokens;
+        row.cache_miss_tokens += entry.cache_miss_tokens;
+        row.cost += entry.cost;
+    }
+    let mut rows: Vec<ReportRow> = groups.into_values().collect();
+    if !matches!(by, GroupBy::Day | GroupBy::Month) {
+        rows.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+    }
+    rows
+}
+
+/// Keep entries from `since` to `until` (local dates, inclusive)
+pub fn filter_dates(
+    entries: Vec<LedgerEntry>,
+    since: Option<chrono::NaiveDate>,
+    until: Option<chrono::NaiveDate>,
+) -> Vec<LedgerEntry> {
+    entries
+        .into_iter()
+        .filter(|e| {
+            let date = local_date(e.timestamp_ms);
+            since.is_none_or(|s| date >= s) && until.is_none_or(|u| date <= u)
+        })
+        .collect()
+}
+
+/// Report rows as CSV, header first
+pub fn to_csv(by: GroupBy, rows: &[ReportRow]) -> String {
+    let group = match by {
+        GroupBy::Day => "day",
+        GroupBy::Month => "month",
+        GroupBy::Model => "model",
+        GroupBy::Task => "task",
+        GroupBy::Project => "project",
+    };
+    let mut out = format!("{},calls,input_tokens,output_tokens,cache_hit_tokens,cache_miss_tokens,cost_usd\n", group);
+    for row in rows {
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{:.6}\n",
+            csv_field(&row.key),
+            row.calls,
+            row.input_tokens,
+            row.output_tokens,
+            row.cache_hit_tokens,
+            row.cache_miss_tokens,
+            row.cost
+        ));
+    }
+    out
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Spend from this project's ledger, for the Statistics panel
+#[derive(Debug, Clone, Default)]
+pub struct SpendSummary {
+    pub today: f64,
+    pub month: f64,
+    pub total: f64,
+    /// This month's spend per model, highest first
+    pub month_by_model: Vec<ReportRow>,
+}
+
+pub fn summary() -> SpendSummary {
+    let entries = ENTRIES.lock().unwrap_or_else(|e| e.into_inner());
+    let today = Local::now().date_naive();
+    let month = today.format("%Y-%m").to_string();
+    let this_month: Vec<LedgerEntry> =
+        entries.iter().filter(|e| local_date(e.timestamp_ms).format("%Y-%m").to_string() == month).cloned().collect();
+    SpendSummary {
+        today: this_month.iter().filter(|e| local_date(e.timestamp_ms) == today).map(|e| e.cost).sum(),
+        month: this_month.iter().map(|e| e.cost).sum(),
+        total: entries.iter().map(|e| e.cost).sum(),
+        month_by_model: aggregate(&this_month, GroupBy::Model),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(day: u32, model: &str, task: Option<&str>, cost: f64) -> LedgerEntry {
+        let timestamp = Local.with_ymd_and_hms(2025, 3, day, 12, 0, 0).unwrap();
+        LedgerEntry {
+            timestamp_ms: timestamp.timestamp_millis() as u64,
+            project: "demo".to_string(),
+            provider: LlmProvider::Anthropic,
+            model: model.to_string(),
+            worker: "main_worker".to_string(),
+            reverie: false,
+            input_tokens: 10,
+            output_tokens: 5,
+            cache_hit_tokens: 100,
+            cache_miss_tokens: 20,
+            cost,
+            task: task.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn aggregates_by_day_and_model() {
+        let entries = vec![
+            entry(1, "claude-opus-4-5", Some("parser"), 1.0),
+            entry(1, "claude-haiku-4-5", Some("parser"), 0.25),
+            entry(2, "claude-opus-4-5", None, 2.0),
+        ];
+
+        let days = aggregate(&entries, GroupBy::Day);
+        assert_eq!(days.iter().map(|r| r.key.as_str()).collect::<Vec<_>>(), ["2025-03-01", "2025-03-02"]);
+        assert_eq!(days[0].calls, 2);
+        assert_eq!(days[0].cache_hit_tokens, 200);
+        assert!((days[0].cost - 1.25).abs() < 1e-9);
+
+        let models = aggregate(&entries, GroupBy::Model);
+        assert_eq!(models[0].key, "anthropic/claude-opus-4-5");
+        assert!((models[0].cost - 3.0).abs() < 1e-9);
+
+        let tasks = aggregate(&entries, GroupBy::Task);
+        assert_eq!(tasks[0].key, "(no task)");
+        assert_eq!(tasks[1].key, "parser");
+
+        let march_2 = chrono::NaiveDate::from_ymd_opt(2025, 3, 2);
+        assert_eq!(filter_dates(entries, march_2, None).len(), 1);
+    }
+
+    #[test]
+    fn csv_quotes_awkward_keys() {
+        let rows = aggregate(&[entry(1, "m", Some("fix \"a, b\""), 0.5)], GroupBy::Task);
+        let csv = to_csv(GroupBy::Task, &rows);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("task,calls,input_tokens,output_tokens,cache_hit_tokens,cache_miss_tokens,cost_usd")
+        );
+        assert_eq!(lines.next(), Some("\"fix \"\"a, b\"\"\",1,10,5,100,20,0.500000"));
+    }
+
+    #[test]
+    fn load_skips_torn_lines() {
+        let path = std::env::temp_dir().join(format!("cp_ledger_{}.jsonl", std::process::id()));
+        append(&path, &entry(1, "m", None, 0.1)).unwrap();
+        std::fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"{\"timestamp_ms\":").unwrap();
+        let loaded = load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(loaded, vec![entry(1, "m", None, 0.1)]);
+        assert!(load(Path::new("/nonexistent/ledger.jsonl")).unwrap().is_empty());
+    }
+}
@@ -5,7 +5,13 @@
 //! - WorkerState (states/{worker}.json) - Worker-specific state
 //! - PanelData (panels/{uid}.json) - Dynamic panel metadata
 //! - Messages (messages/{uid}.yaml) - Conversation messages
+//! - Cost ledger (ledger.jsonl) - Every API call's usage and cost, append-only
+//! - Branches (branches.json) - Named forks of the conversation
+//! - Checkpoints (checkpoints.json) - Restorable working tree + context positions
+pub mod branch;
+pub mod checkpoint;
 pub mod config;
+pub mod ledger;
 pub mod message;
 pub mod panel;
 pub mod worker;
@@ -124,17 +130,12 @@ fn load_state_new() -> State {
                 let mut elem = panel_to_context(&p, local_id);
 
                 // For ConversationHistory panels, load history messages and rebuild cached content
+                // (the summary view when one was generated)
                 if p.panel_type == ContextType::CONVERSATION_HISTORY && !p.message_uids.is_empty() {
                     let msgs: Vec<Message> = p.message_uids.iter().filter_map(|uid| load_message(uid)).collect();
                     if !msgs.is_empty() {
-                        let content = crate::state::format_messages_to_chunk(&msgs);
-                        let token_count = crate::state::estimate_tokens(&content);
-                        let total_pages = crate::state::compute_total_pages(token_count);
-                        elem.cached_content = Some(content);
                         elem.history_messages = Some(msgs);
-                        elem.token_count = token_count;
-                        elem.total_pages = total_pages;
-                        elem.full_token_count = token_count;
+                        crate::modules::conversation_history::summary::refresh_content(&mut elem);
                         elem.cache_deprecated = false;
                     }
                 }
@@ -190,6 +191,7 @@ fn load_state_new() -> State {
         input: shared_config.draft_input,
         input_cursor: shared_config.draft_cursor,
         active_theme: shared_config.active_theme.clone(),
+        panel_budgets: shared_config.panel_budgets.clone(),
         ..State::default()
     };
 
@@ -272,6 +274,7 @@ pub fn build_save_batch(state: &State) -> WriteBatch {
         selected_context: state.selected_context,
         draft_input: state.input.clone(),
         draft_cursor: state.input_cursor,
+        panel_budgets: state.panel_budgets.clone(),
         modules: global_modules,
     };
     if let Ok(json) = serde_json::to_string_pretty(&shared_config) {
@@ -1,4 +1,24 @@
-use crate::state::{ContextType, State};
+use crate::state::{ContextType, MessageType, State};
+
+/// Command ID prefix of "restore this snapshot" entries
+pub const RESTORE_SNAPSHOT_PREFIX: &str = "restore-snapshot:";
+/// Command ID prefix of "fork after this message" entries
+pub const FORK_AT_PREFIX: &str = "fork-at:";
+/// Command ID prefixes of "switch to this branch" entries (messages only / with panels)
+pub const SWITCH_BRANCH_PREFIX: &str = "switch-branch:";
+pub const SWITCH_BRANCH_PANELS_PREFIX: &str = "switch-branch-panels:";
+/// Command ID prefixes of checkpoint entries (preview / restore)
+pub const PREVIEW_CHECKPOINT_PREFIX: &str = "preview-checkpoint:";
+pub const RESTORE_CHECKPOINT_PREFIX: &str = "restore-checkpoint:";
+
+/// Command ID prefix of the prompt inspector entries (followed by the mode)
+pub const INSPECT_PROMPT_PREFIX: &str = "inspect-prompt:";
+
+/// How many recent checkpoints get palette entries
+const LISTED_CHECKPOINTS: usize = 10;
+
+/// How many recent messages get a "Fork at" palette entry
+const FORKABLE_MESSAGES: usize = 30;
 
 /// A command that can be executed from the palette
 #[derive(Debug, Clone)]
@@ -130,5 +150,110 @@ pub fn get_available_commands(state: &State) -> Vec<PaletteCommand> {
         );
     }
 
+    // Context snapshots: save the working set, or resume a parked one
+    commands.push(
+        PaletteCommand::new(
+            "snapshot",
+            "Snapshot Context",
+            "Park panels, todos, scratchpad and skills under a new name",
+        )
+        .with_keywords(vec!["save", "park", "task", "context"]),
+    );
+    for snap in cp_mod_preset::snapshot::list_snapshots() {
+        commands.push(
+            PaletteCommand::new(
+                format!("{}{}", RESTORE_SNAPSHOT_PREFIX, snap.name),
+                format!("Restore Snapshot {}", snap.name),
+                format!("{} panels, {} todos", snap.panels, snap.todos),
+            )
+            .with_keywords(vec!["restore", "resume", "snapshot", "task"]),
+        );
+    }
+
+    // Conversation branches: switch to another branch, or fork at a recent message
+    let store = crate::state::persistence::branch::load_branches();
+    for (_, branch) in store.tree().into_iter().filter(|(_, b)| b.name != store.active) {
+        let forked_at = branch.fork_message_id.as_deref().map(|id| format!(", forked at {}", id)).unwrap_or_default();
+        commands.push(
+            PaletteCommand::new(
+                format!("{}{}", SWITCH_BRANCH_PREFIX, branch.name),
+                format!("Switch Branch {}", branch.name),
+                format!("{} messages{}", branch.message_uids.len(), forked_at),
+            )
+            .with_keywords(vec!["branch", "switch", "conversation"]),
+        );
+        commands.push(
+            PaletteCommand::new(
+                format!("{}{}", SWITCH_BRANCH_PANELS_PREFIX, branch.name),
+                format!("Switch Branch {} + Panels", branch.name),
+                format!("Also restore its {} panels", branch.panels.len()),
+            )
+            .with_keywords(vec!["branch", "switch", "panels", "restore"]),
+        );
+    }
+    let forkable = state
+        .messages
+        .iter()
+        .rev()
+        .filter(|m| m.message_type == MessageType::TextMessage && (m.role == "user" || m.role == "assistant"))
+        .take(FORKABLE_MESSAGES);
+    for msg in forkable {
+        let first_line = msg.content.lines().next().unwrap_or("");
+        let preview = if first_line.len() > 50 {
+            format!("{}...", &first_line[..first_line.floor_char_boundary(47)])
+        } else {
+            first_line.to_string()
+        };
+        commands.push(
+            PaletteCommand::new(format!("{}{}", FORK_AT_PREFIX, msg.id), format!("Fork at {}", msg.id), preview)
+                .with_keywords(vec!["fork", "branch", "retry"]),
+        );
+    }
+
+    // Prompt inspector: what the model sees, block by block
+    for (mode, label, description) in [
+        ("next", "Inspect Next Prompt", "Preview the request the current context would send"),
+        ("last", "Inspect Last Prompt", "Blocks, token counts and cache breakpoints of the last request"),
+        ("diff", "Diff Last Prompt", "What changed between the last two requests"),
+    ] {
+        commands.push(
+            PaletteCommand::new(format!("{}{}", INSPECT_PROMPT_PREFIX, mode), label, description)
+                .with_keywords(vec!["prompt", "inspect", "request", "debug", "cache"]),
+        );
+    }
+
+    // Checkpoints: undo the last turn, or roll back to an earlier point
+    commands.push(
+        PaletteCommand::new("checkpoint", "Create Checkpoint", "Save files, conversation position and panels")
+            .with_keywords(vec!["checkpoint", "save", "undo"]),
+    );
+    let checkpoints = crate::state::persistence::checkpoint::load_checkpoints();
+    if let Some(last) = checkpoints.last_auto() {
+        commands.push(
+            PaletteCommand::new("undo-turn", "Undo Last Turn", format!("Restore {} ({})", last.id, last.label))
+                .with_keywords(vec!["undo", "rollback", "revert", "checkpoint"]),
+        );
+    }
+    let now_ms = crate::app::panels::now_ms();
+    for checkpoint in checkpoints.checkpoints.iter().rev().take(LISTED_CHECKPOINTS) {
+        let age = crate::ui::helpers::format_time_ago(now_ms.saturating_sub(checkpoint.created_ms));
+        commands.push(
+            PaletteCommand::new(
+                format!("{}{}", PREVIEW_CHECKPOINT_PREFIX, checkpoint.id),
+                format!("Preview Checkpoint {}", checkpoint.id),
+                format!("What restoring '{}' ({}) would change", checkpoint.label, age),
+            )
+            .with_keywords(vec!["checkpoint", "diff", "preview"]),
+        );
+        commands.push(
+            PaletteCommand::new(
+                format!("{}{}", RESTORE_CHECKPOINT_PREFIX, checkpoint.id),
+                format!("Restore Checkpoint {}", checkpoint.id),
+                format!("Roll back to '{}' ({})", checkpoint.label, age),
+            )
+            .with_keywords(vec!["checkpoint", "restore", "rollback", "undo"]),
+        );
+    }
+
     commands
 }
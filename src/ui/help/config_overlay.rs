@@ -9,7 +9,7 @@ use crate::state::State;
 pub fn render_config_overlay(frame: &mut Frame, state: &State, area: Rect) {
     // Center the overlay, clamped to available area
     let overlay_width = 56u16.min(area.width);
-    let overlay_height = 38u16.min(area.height); // Reduced from 50
+    let overlay_height = 45u16.min(area.height); // Reduced from 50
     let x = area.x + area.width.saturating_sub(overlay_width) / 2;
     let y = area.y + area.height.saturating_sub(overlay_height) / 2;
     let overlay_area = Rect::new(x, y, overlay_width, overlay_height);
@@ -48,7 +48,7 @@ pub fn render_config_overlay(frame: &mut Frame, state: &State, area: Rect) {
     // Help text
     lines.push(Line::from(vec![
         Span::styled("  ", Style::default()),
-        Span::styled("1-6", Style::default().fg(theme::warning())),
+        Span::styled("1-9", Style::default().fg(theme::warning())),
         Span::styled(" provider  ", Style::default().fg(theme::text_muted())),
         Span::styled("a-d", Style::default().fg(theme::warning())),
         Span::styled(" model  ", Style::default().fg(theme::text_muted())),
@@ -98,6 +98,9 @@ fn render_provider_section(lines: &mut Vec<Line>, state: &State) {
         (LlmProvider::Grok, "3", "Grok (xAI)"),
         (LlmProvider::Groq, "4", "Groq"),
         (LlmProvider::DeepSeek, "5", "DeepSeek"),
+        (LlmProvider::OpenAi, "7", "OpenAI"),
+        (LlmProvider::Local, "8", "Local (Ollama / llama.cpp)"),
+        (LlmProvider::Replay, "9", "Replay (cassette)"),
     ];
 
     for (provider, key, name) in providers {
@@ -117,42 +120,10 @@ fn render_provider_section(lines: &mut Vec<Line>, state: &State) {
 }
 
 fn render_model_section(lines: &mut Vec<Line>, state: &State) {
-    use crate::llms::{AnthropicModel, DeepSeekModel, GrokModel, GroqModel, LlmProvider};
-
     lines.push(Line::from(vec![Span::styled("  Model", Style::default().fg(theme::text_secondary()).bold())]));
     lines.push(Line::from(""));
 
-    match state.llm_provider {
-        LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey => {
-            for (model, key) in [
-                (AnthropicModel::ClaudeOpus45, "a"),
-                (AnthropicModel::ClaudeSonnet45, "b"),
-                (AnthropicModel::ClaudeHaiku45, "c"),
-            ] {
-                render_model_line_with_info(lines, state.anthropic_model == model, key, &model);
-            }
-        }
-        LlmProvider::Grok => {
-            for (model, key) in [(GrokModel::Grok41Fast, "a"), (GrokModel::Grok4Fast, "b")] {
-                render_model_line_with_info(lines, state.grok_model == model, key, &model);
-            }
-        }
-        LlmProvider::Groq => {
-            for (model, key) in [
-                (GroqModel::GptOss120b, "a"),
-                (GroqModel::GptOss20b, "b"),
-                (GroqModel::Llama33_70b, "c"),
-                (GroqModel::Llama31_8b, "d"),
-            ] {
-                render_model_line_with_info(lines, state.groq_model == model, key, &model);
-            }
-        }
-        LlmProvider::DeepSeek => {
-            for (model, key) in [(DeepSeekModel::DeepseekChat, "a"), (DeepSeekModel::DeepseekReasoner, "b")] {
-                render_model_line_with_info(lines, state.deepseek_model == model, key, &model);
-            }
-        }
-    }
+    render_models(lines, state, state.llm_provider, state.current_model_id());
 }
 
 fn render_api_check(lines: &mut Vec<Line>, state: &State) {
@@ -283,6 +254,67 @@ fn render_budget_bars(lines: &mut Vec<Line>, state: &State) {
         Span::styled(cost_label, Style::default().fg(theme::text()).bold()),
         Span::styled("  (guard rail)", Style::default().fg(theme::text_muted())),
     ]));
+
+    // 5. Extended Thinking Budget
+    use crate::infra::config::constants::THINKING_BUDGET_MAX;
+    let thinking = state.thinking_budget;
+    let thinking_pct = (thinking as u64 * 100 / THINKING_BUDGET_MAX as u64) as usize;
+    let thinking_filled = (thinking as usize * bar_width / THINKING_BUDGET_MAX as usize).min(bar_width);
+    let thinking_extra = if thinking == 0 {
+        " (off)"
+    } else if state.effective_thinking_budget().is_none() {
+        " (Anthropic only)"
+    } else {
+        ""
+    };
+    render_bar(
+        lines,
+        BarConfig {
+            selected,
+            idx: 4,
+            label: "Thinking Budget",
+            pct: thinking_pct,
+            filled: thinking_filled,
+            bar_width,
+            tokens_str: &format_tokens(thinking as usize),
+            bar_color: theme::accent(),
+            extra: Some(thinking_extra),
+        },
+    );
+
+    // 6. Per-type panel token budget (p cycles the type)
+    use crate::infra::config::constants::BUDGETED_PANEL_TYPES;
+    let panel_type = BUDGETED_PANEL_TYPES[state.config_budget_type % BUDGETED_PANEL_TYPES.len()];
+    let panel_budget = state.panel_budgets.get(panel_type).copied().unwrap_or(0);
+    let budget_display = 50_000usize;
+    let budget_filled = (panel_budget * bar_width / budget_display).min(bar_width);
+    let budget_label =
+        if panel_budget == 0 { "unlimited".to_string() } else { format!("{} tok", format_tokens(panel_budget)) };
+    let is_selected = selected == 5;
+    let indicator = if is_selected { ">" } else { " " };
+    let label_style = if is_selected {
+        Style::default().fg(theme::accent()).bold()
+    } else {
+        Style::default().fg(theme::text_secondary()).bold()
+    };
+    let arrow_color = if is_selected { theme::accent() } else { theme::text_muted() };
+
+    lines.push(Line::from(vec![
+        Span::styled(format!(" {} ", indicator), Style::default().fg(theme::accent())),
+        Span::styled("Panel Budget: ".to_string(), label_style),
+        Span::styled(panel_type.to_string(), Style::default().fg(theme::text()).bold()),
+        Span::styled("  (p: next type)", Style::default().fg(theme::text_muted())),
+    ]));
+    lines.push(Line::from(vec![
+        Span::styled("   ◀ ", Style::default().fg(arrow_color)),
+        Span::styled(chars::BLOCK_FULL.repeat(budget_filled), Style::default().fg(theme::warning())),
+        Span::styled(
+            chars::BLOCK_LIGHT.repeat(bar_width.saturating_sub(budget_filled)),
+            Style::default().fg(theme::bg_elevated()),
+        ),
+        Span::styled(" ▶ ", Style::default().fg(arrow_color)),
+        Span::styled(budget_label, Style::default().fg(theme::text()).bold()),
+    ]));
 }
 
 struct BarConfig<'a> {
@@ -389,48 +421,111 @@ fn render_toggles_section(lines: &mut Vec<Line>, state: &State) {
         Span::styled("r", Style::default().fg(theme::warning())),
         Span::styled(" to toggle)", Style::default().fg(theme::text_muted())),
     ]));
+
+    // History summaries toggle
+    let (check, status, color) =
+        if state.summarize_history { ("[x]", "ON", theme::success()) } else { ("[ ]", "OFF", theme::text_muted()) };
+    lines.push(Line::from(vec![
+        Span::styled("  Summaries:     ", Style::default().fg(theme::text_secondary()).bold()),
+        Span::styled(format!("{} ", check), Style::default().fg(color).bold()),
+        Span::styled(status, Style::default().fg(color).bold()),
+        Span::styled("  (press ", Style::default().fg(theme::text_muted())),
+        Span::styled("h", Style::default().fg(theme::warning())),
+        Span::styled(" to toggle)", Style::default().fg(theme::text_muted())),
+    ]));
+
+    // Automatic turn checkpoints toggle
+    let (check, status, color) =
+        if state.auto_checkpoints { ("[x]", "ON", theme::success()) } else { ("[ ]", "OFF", theme::text_muted()) };
+    lines.push(Line::from(vec![
+        Span::styled("  Checkpoints:   ", Style::default().fg(theme::text_secondary()).bold()),
+        Span::styled(format!("{} ", check), Style::default().fg(color).bold()),
+        Span::styled(status, Style::default().fg(color).bold()),
+        Span::styled("  (press ", Style::default().fg(theme::text_muted())),
+        Span::styled("k", Style::default().fg(theme::warning())),
+        Span::styled(" to toggle)", Style::default().fg(theme::text_muted())),
+    ]));
 }
 
 fn render_secondary_model_section(lines: &mut Vec<Line>, state: &State) {
-    use crate::llms::{AnthropicModel, DeepSeekModel, GrokModel, GroqModel, LlmProvider};
-
     lines.push(Line::from(vec![Span::styled(
         "  Secondary Model (Reverie)",
         Style::default().fg(theme::text_secondary()).bold(),
     )]));
     lines.push(Line::from(""));
 
-    match state.secondary_provider {
-        LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey => {
-            for (model, key) in [
-                (AnthropicModel::ClaudeOpus45, "a"),
-                (AnthropicModel::ClaudeSonnet45, "b"),
-                (AnthropicModel::ClaudeHaiku45, "c"),
-            ] {
-                render_model_line_with_info(lines, state.secondary_anthropic_model == model, key, &model);
-            }
-        }
-        LlmProvider::Grok => {
-            for (model, key) in [(GrokModel::Grok41Fast, "a"), (GrokModel::Grok4Fast, "b")] {
-                render_model_line_with_info(lines, state.secondary_grok_model == model, key, &model);
-            }
-        }
-        LlmProvider::Groq => {
-            for (model, key) in [
-                (GroqModel::GptOss120b, "a"),
-                (GroqModel::GptOss20b, "b"),
-                (GroqModel::Llama33_70b, "c"),
-                (GroqModel::Llama31_8b, "d"),
-            ] {
-                render_model_line_with_info(lines, state.secondary_groq_model == model, key, &model);
-            }
-        }
-        LlmProvider::DeepSeek => {
-            for (model, key) in [(DeepSeekModel::DeepseekChat, "a"), (DeepSeekModel::DeepseekReasoner, "b")] {
-                render_model_line_with_info(lines, state.secondary_deepseek_model == model, key, &model);
-            }
-        }
+    render_models(lines, state, state.secondary_provider, state.secondary_model_id());
+}
+
+/// Render the registry models of a provider (a-d for the first four, m to cycle)
+fn render_models(lines: &mut Vec<Line>, state: &State, provider: crate::llms::LlmProvider, selected: &str) {
+    use crate::infra::config::models::MODELS;
+
+    if let Some(err) = &MODELS.override_error {
+        lines.push(Line::from(vec![Span::styled(format!("  {}", err), Style::default().fg(theme::error()))]));
+    }
+    if provider == crate::llms::LlmProvider::Local {
+        render_local_models(lines, state, selected);
+        return;
+    }
+
+    let models = MODELS.models(provider);
+    let selected = MODELS.resolve(provider, selected).map(|m| m.id.as_str()).unwrap_or("");
+    for (idx, model) in models.iter().take(4).enumerate() {
+        let key = ((b'a' + idx as u8) as char).to_string();
+        render_model_line_with_info(lines, model.id == selected, &key, model);
+    }
+    if let Some(model) = models.iter().skip(4).find(|m| m.id == selected) {
+        render_model_line_with_info(lines, true, "m", model);
+    }
+    if models.len() > 4 {
+        render_cycle_hint(lines, models.len());
+    }
+}
+
+fn render_cycle_hint(lines: &mut Vec<Line>, available: usize) {
+    lines.push(Line::from(vec![
+        Span::styled("  ", Style::default()),
+        Span::styled("m", Style::default().fg(theme::warning())),
+        Span::styled(format!(" next model ({} available)", available), Style::default().fg(theme::text_muted())),
+    ]));
+}
+
+/// Render models discovered on the local server (a-d for the first four, m to cycle)
+fn render_local_models(lines: &mut Vec<Line>, state: &State, selected: &str) {
+    if state.local_models.is_empty() {
+        let msg = match &state.local_models_error {
+            Some(err) => err.clone(),
+            None => "Discovering models...".to_string(),
+        };
+        lines.push(Line::from(vec![Span::styled(format!("  {}", msg), Style::default().fg(theme::text_muted()))]));
+        return;
+    }
+
+    for (idx, model) in state.local_models.iter().take(4).enumerate() {
+        let is_selected = model == selected;
+        let indicator = if is_selected { ">" } else { " " };
+        let check = if is_selected { "[x]" } else { "[ ]" };
+        let style =
+            if is_selected { Style::default().fg(theme::accent()).bold() } else { Style::default().fg(theme::text()) };
+        let key = (b'a' + idx as u8) as char;
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {} ", indicator), Style::default().fg(theme::accent())),
+            Span::styled(format!("{} ", key), Style::default().fg(theme::warning())),
+            Span::styled(format!("{} ", check), style),
+            Span::styled(model.clone(), style),
+        ]));
+    }
+
+    // Selected model beyond the first four, reached via 'm'
+    if !selected.is_empty() && !state.local_models.iter().take(4).any(|m| m == selected) {
+        lines.push(Line::from(vec![
+            Span::styled("  > ", Style::default().fg(theme::accent())),
+            Span::styled("m ", Style::default().fg(theme::warning())),
+            Span::styled(format!("[x] {}", selected), Style::default().fg(theme::accent()).bold()),
+        ]));
     }
+    render_cycle_hint(lines, state.local_models.len());
 }
 
 fn render_model_line_with_info<M: crate::llms::ModelInfo>(
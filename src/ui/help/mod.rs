@@ -2,4 +2,8 @@ mod commands;
 pub mod config_overlay;
 mod palette;
 
+pub use commands::{
+    FORK_AT_PREFIX, INSPECT_PROMPT_PREFIX, PREVIEW_CHECKPOINT_PREFIX, RESTORE_CHECKPOINT_PREFIX,
+    RESTORE_SNAPSHOT_PREFIX, SWITCH_BRANCH_PANELS_PREFIX, SWITCH_BRANCH_PREFIX,
+};
 pub use palette::CommandPalette;
@@ -19,6 +19,9 @@ pub struct CommandPalette {
     pub cursor: usize,
     /// Currently selected index in filtered results
     pub selected: usize,
+    /// Commands built when the palette opened (snapshots, branches and checkpoints
+    /// come from disk, so they are read once per open rather than per keystroke)
+    all_commands: Vec<PaletteCommand>,
     /// Cached filtered commands
     filtered_commands: Vec<PaletteCommand>,
 }
@@ -34,7 +37,8 @@ impl CommandPalette {
         self.query.clear();
         self.cursor = 0;
         self.selected = 0;
-        self.update_filtered(state);
+        self.all_commands = get_available_commands(state);
+        self.update_filtered();
     }
 
     /// Close the palette
@@ -43,20 +47,21 @@ impl CommandPalette {
         self.query.clear();
         self.cursor = 0;
         self.selected = 0;
+        self.all_commands.clear();
         self.filtered_commands.clear();
     }
 
     /// Update the filtered commands based on query
-    pub fn update_filtered(&mut self, state: &State) {
-        let all_commands = get_available_commands(state);
-
+    fn update_filtered(&mut self) {
         if self.query.is_empty() {
-            self.filtered_commands = all_commands;
+            self.filtered_commands = self.all_commands.clone();
         } else {
             // Filter and sort by match score
-            let mut matched: Vec<_> = all_commands
-                .into_iter()
+            let mut matched: Vec<_> = self
+                .all_commands
+                .iter()
                 .filter(|cmd| cmd.matches(&self.query))
+                .cloned()
                 .map(|cmd| {
                     let score = cmd.match_score(&self.query);
                     (cmd, score)
@@ -64,7 +69,7 @@ impl CommandPalette {
                 .collect();
 
             // Sort by score (descending)
-            matched.sort_by(|a, b| b.1.cmp(&a.1));
+            matched.sort_by_key(|m| std::cmp::Reverse(m.1));
 
             self.filtered_commands = matched.into_iter().map(|(cmd, _)| cmd).collect();
         }
@@ -78,31 +83,31 @@ impl CommandPalette {
     }
 
     /// Insert a character at cursor position
-    pub fn insert_char(&mut self, c: char, state: &State) {
+    pub fn insert_char(&mut self, c: char) {
         self.query.insert(self.cursor, c);
         self.cursor += c.len_utf8();
         self.selected = 0; // Reset selection on query change
-        self.update_filtered(state);
+        self.update_filtered();
     }
 
     /// Delete character before cursor
-    pub fn backspace(&mut self, state: &State) {
+    pub fn backspace(&mut self) {
         if self.cursor > 0 {
             // Find the previous character boundary
             let prev_boundary = self.query[..self.cursor].char_indices().last().map(|(i, _)| i).unwrap_or(0);
             self.query.remove(prev_boundary);
             self.cursor = prev_boundary;
             self.selected = 0;
-            self.update_filtered(state);
+            self.update_filtered();
         }
     }
 
     /// Delete character at cursor
-    pub fn delete(&mut self, state: &State) {
+    pub fn delete(&mut self) {
         if self.cursor < self.query.len() {
             self.query.remove(self.cursor);
             self.selected = 0;
-            self.update_filtered(state);
+            self.update_filtered();
         }
     }
 
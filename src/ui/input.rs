@@ -3,7 +3,10 @@ use ratatui::{
     widgets::{Block, BorderType, Borders, Clear, Paragraph},
 };
 
-use super::{helpers::spinner, theme};
+use super::{
+    helpers::{format_number, spinner},
+    theme,
+};
 use crate::llms::{LlmProvider, ModelInfo};
 use crate::state::State;
 
@@ -57,10 +60,13 @@ pub fn render_status_bar(frame: &mut Frame, state: &State, area: Rect) {
     }
     spans.push(Span::styled(" ", base_style));
 
-    // Show retry count when retrying after API errors (secondary badge)
+    // Show retry count when retrying after API errors (secondary badge),
+    // with the time left when the server asked us to wait
     if state.api_retry_count > 0 {
+        let wait_ms = state.retry_wait_ms(state.stream_target().provider);
+        let wait = if wait_ms > 0 { format!(" · {}s", wait_ms.div_ceil(1000)) } else { String::new() };
         spans.push(Span::styled(
-            format!(" RETRY {}/{} ", state.api_retry_count, crate::infra::constants::MAX_API_RETRIES),
+            format!(" RETRY {}/{}{} ", state.api_retry_count, crate::infra::constants::MAX_API_RETRIES, wait),
             Style::default().fg(theme::bg_base()).bg(theme::error()).bold(),
         ));
         spans.push(Span::styled(" ", base_style));
@@ -78,14 +84,28 @@ pub fn render_status_bar(frame: &mut Frame, state: &State, area: Rect) {
         spans.push(Span::styled(" ", base_style));
     }
 
-    // Show current LLM provider and model
-    let (provider_name, model_name) = match state.llm_provider {
-        LlmProvider::Anthropic => ("Claude", state.anthropic_model.display_name()),
-        LlmProvider::ClaudeCode => ("OAuth", state.anthropic_model.display_name()),
-        LlmProvider::ClaudeCodeApiKey => ("APIKey", state.anthropic_model.display_name()),
-        LlmProvider::Grok => ("Grok", state.grok_model.display_name()),
-        LlmProvider::Groq => ("Groq", state.groq_model.display_name()),
-        LlmProvider::DeepSeek => ("DeepSeek", state.deepseek_model.display_name()),
+    // Show current LLM provider and model (or the failover model standing in for it)
+    let target = state.stream_target();
+    if target.fallback.is_some() {
+        spans.push(Span::styled(" FAILOVER ", Style::default().fg(theme::bg_base()).bg(theme::warning()).bold()));
+        spans.push(Span::styled(" ", base_style));
+    }
+    let provider_name = match target.provider {
+        LlmProvider::Anthropic => "Claude",
+        LlmProvider::ClaudeCode => "OAuth",
+        LlmProvider::ClaudeCodeApiKey => "APIKey",
+        LlmProvider::Grok => "Grok",
+        LlmProvider::Groq => "Groq",
+        LlmProvider::DeepSeek => "DeepSeek",
+        LlmProvider::OpenAi => "OpenAI",
+        LlmProvider::Local => "Local",
+        LlmProvider::Replay => "Replay",
+    };
+    let model_name = match state.current_model_spec() {
+        _ if target.fallback.is_some() => target.model.as_str(),
+        Some(spec) => spec.display_name(),
+        None if state.current_model_id().is_empty() => "no model",
+        None => state.current_model_id(),
     };
     spans.push(Span::styled(
         format!(" {} ", provider_name),
@@ -95,6 +115,24 @@ pub fn render_status_bar(frame: &mut Frame, state: &State, area: Rect) {
     spans.push(Span::styled(format!(" {} ", model_name), Style::default().fg(theme::text()).bg(theme::bg_elevated())));
     spans.push(Span::styled(" ", base_style));
 
+    // Remaining rate-limit budget reported by the provider
+    if let Some(status) = state.rate_limits.get(&target.provider) {
+        let info = &status.info;
+        let budget: Vec<String> = [(info.requests_remaining, "req"), (info.tokens_remaining, "tok")]
+            .into_iter()
+            .filter_map(|(remaining, unit)| remaining.map(|n| format!("{} {}", format_number(n as usize), unit)))
+            .collect();
+        if !budget.is_empty() {
+            let style = if status.nearly_exhausted(crate::app::panels::now_ms()) {
+                Style::default().fg(theme::bg_base()).bg(theme::warning()).bold()
+            } else {
+                Style::default().fg(theme::text_muted()).bg(theme::bg_elevated())
+            };
+            spans.push(Span::styled(format!(" {} ", budget.join(" · ")), style));
+            spans.push(Span::styled(" ", base_style));
+        }
+    }
+
     // Stop reason from last stream (highlight max_tokens as warning)
     if !state.is_streaming
         && let Some(ref reason) = state.last_stop_reason
@@ -322,9 +322,17 @@ pub fn render_sidebar(frame: &mut Frame, state: &State, area: Rect) {
             ])
         };
 
+        // Cost rows price the main model's share; failover usage is priced separately
+        let fb = &state.fallback_usage;
+        let stream_fb = &state.stream_fallback_usage;
+
         // tot row
         rows.push(counts_row("tot", state.cache_hit_tokens, state.cache_miss_tokens, state.total_output_tokens));
-        if let Some(row) = costs_row(state.cache_hit_tokens, state.cache_miss_tokens, state.total_output_tokens) {
+        if let Some(row) = costs_row(
+            state.cache_hit_tokens.saturating_sub(fb.cache_hit_tokens),
+            state.cache_miss_tokens.saturating_sub(fb.cache_miss_tokens),
+            state.total_output_tokens.saturating_sub(fb.output_tokens),
+        ) {
             rows.push(row);
         }
 
@@ -336,9 +344,11 @@ pub fn render_sidebar(frame: &mut Frame, state: &State, area: Rect) {
                 state.stream_cache_miss_tokens,
                 state.stream_output_tokens,
             ));
-            if let Some(row) =
-                costs_row(state.stream_cache_hit_tokens, state.stream_cache_miss_tokens, state.stream_output_tokens)
-            {
+            if let Some(row) = costs_row(
+                state.stream_cache_hit_tokens.saturating_sub(stream_fb.cache_hit_tokens),
+                state.stream_cache_miss_tokens.saturating_sub(stream_fb.cache_miss_tokens),
+                state.stream_output_tokens.saturating_sub(stream_fb.output_tokens),
+            ) {
                 rows.push(row);
             }
         }
@@ -351,8 +361,10 @@ pub fn render_sidebar(frame: &mut Frame, state: &State, area: Rect) {
                 state.tick_cache_miss_tokens,
                 state.tick_output_tokens,
             ));
-            if let Some(row) =
-                costs_row(state.tick_cache_hit_tokens, state.tick_cache_miss_tokens, state.tick_output_tokens)
+            // A tick served by a failover model is already in the failover total
+            if state.active_fallback.is_none()
+                && let Some(row) =
+                    costs_row(state.tick_cache_hit_tokens, state.tick_cache_miss_tokens, state.tick_output_tokens)
             {
                 rows.push(row);
             }
@@ -360,10 +372,14 @@ pub fn render_sidebar(frame: &mut Frame, state: &State, area: Rect) {
 
         lines.extend(render_table(&header, &rows, None, 1));
 
-        // Total cost below the table
-        let total_cost = crate::state::State::token_cost(state.cache_hit_tokens, hit_price)
-            + crate::state::State::token_cost(state.cache_miss_tokens, miss_price)
-            + crate::state::State::token_cost(state.total_output_tokens, out_price);
+        // Failover and total cost below the table
+        if fb.cost >= 0.001 {
+            lines.push(Line::from(vec![Span::styled(
+                format!(" failover: ${:.3}", fb.cost),
+                Style::default().fg(theme::text_muted()),
+            )]));
+        }
+        let total_cost = state.session_cost();
         if total_cost >= 0.001 {
             let total_str =
                 if total_cost < 0.01 { format!("${:.3}", total_cost) } else { format!("${:.2}", total_cost) };
@@ -390,6 +406,11 @@ pub fn render_sidebar(frame: &mut Frame, state: &State, area: Rect) {
             Span::styled("↑↓", Style::default().fg(theme::accent())),
             Span::styled(" scroll", Style::default().fg(theme::text_muted())),
         ]),
+        Line::from(vec![
+            Span::styled("  ", base_style),
+            Span::styled("Ctrl+K", Style::default().fg(theme::accent())),
+            Span::styled(" pin panel", Style::default().fg(theme::text_muted())),
+        ]),
         Line::from(vec![
             Span::styled("  ", base_style),
             Span::styled("Ctrl+P", Style::default().fg(theme::accent())),
@@ -437,6 +458,7 @@ fn render_context_line(
     };
 
     let indicator = if is_selected { chars::ARROW_RIGHT } else { " " };
+    let pin = if crate::app::eviction::is_pinned(ctx) { chars::PIN } else { " " };
 
     // Selected element: orange text, no background change
     // Loading elements: dimmed
@@ -452,7 +474,8 @@ fn render_context_line(
 
     lines.push(Line::from(vec![
         Span::styled(format!(" {}", indicator), Style::default().fg(indicator_color)),
-        Span::styled(format!(" {} ", shortcut), Style::default().fg(theme::text_muted())),
+        Span::styled(format!(" {}", shortcut), Style::default().fg(theme::text_muted())),
+        Span::styled(pin, Style::default().fg(theme::accent_dim())),
         Span::styled(icon, Style::default().fg(if is_selected { theme::accent() } else { theme::text_muted() })),
         Span::styled(format!("{:<18}", name), Style::default().fg(name_color)),
         Span::styled(format!("{:>6}", tokens_or_spinner), Style::default().fg(tokens_color)),